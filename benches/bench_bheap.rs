@@ -1,7 +1,6 @@
 //! Benchmarks
 
 #[macro_use]
-
 extern crate bencher;
 extern crate priq;
 
@@ -1,7 +1,6 @@
 //! Benchmarks
 
 #[macro_use]
-
 extern crate bencher;
 extern crate priq;
 
@@ -122,6 +121,66 @@ fn pq_pop_1mil(b: &mut Bencher) {
 }
 
 
+/// Benchmark pop-ing 100k elements from a 4-ary heap
+fn dary4_pop_100k(b: &mut Bencher) {
+    let mut pq: DaryHeap<usize, usize, 4> = DaryHeap::new();
+    let n = 100_000_usize;
+    (0..n).for_each(|i| { pq.put(i, i * 2); });
+    b.iter(|| {
+        (0..n).for_each(|_| { pq.pop(); });
+    });
+}
+
+/// Benchmark pop-ing 100k elements from an 8-ary heap
+fn dary8_pop_100k(b: &mut Bencher) {
+    let mut pq: DaryHeap<usize, usize, 8> = DaryHeap::new();
+    let n = 100_000_usize;
+    (0..n).for_each(|i| { pq.put(i, i * 2); });
+    b.iter(|| {
+        (0..n).for_each(|_| { pq.pop(); });
+    });
+}
+
+/// Benchmark pop-ing 100k elements from a cache-aware B-heap
+fn bheap_pop_100k(b: &mut Bencher) {
+    let mut pq: BHeap<usize, usize> = BHeap::new();
+    let n = 100_000_usize;
+    (0..n).for_each(|i| { pq.put(i, i * 2); });
+    b.iter(|| {
+        (0..n).for_each(|_| { pq.pop(); });
+    });
+}
+
+/// Benchmark pop-ing 100k elements with 256 byte items from a priority queue
+fn pq_pop_100k_large(b: &mut Bencher) {
+    let mut pq: PriorityQueue<usize, [u64; 32]> = PriorityQueue::new();
+    let n = 100_000_usize;
+    (0..n).for_each(|i| { pq.put(i, [i as u64; 32]); });
+    b.iter(|| {
+        (0..n).for_each(|_| { pq.pop(); });
+    });
+}
+
+/// Benchmark pop-ing 100k elements with 256 byte items from a priority
+/// queue which stores scores and items apart
+fn soa_pop_100k_large(b: &mut Bencher) {
+    let mut pq: SoaPriorityQueue<usize, [u64; 32]> = SoaPriorityQueue::new();
+    let n = 100_000_usize;
+    (0..n).for_each(|i| { pq.put(i, [i as u64; 32]); });
+    b.iter(|| {
+        (0..n).for_each(|_| { pq.pop(); });
+    });
+}
+
+/// Benchmark putting 1mil elements in a priority queue with segmented storage
+fn segmented_put_1mil(b: &mut Bencher) {
+    let n = 1_000_000_usize;
+    b.iter(|| {
+        let mut pq: SegmentedPriorityQueue<usize, usize> = SegmentedPriorityQueue::new();
+        (0..n).for_each(|i| { pq.put(n - i, i); });
+    });
+}
+
 
 benchmark_group!(
     benches,
@@ -137,5 +196,11 @@ benchmark_group!(
     pq_pop_1mil,
     pq_put_100k_wcap,
     pq_put_1mil_wcap,
+    dary4_pop_100k,
+    dary8_pop_100k,
+    bheap_pop_100k,
+    pq_pop_100k_large,
+    soa_pop_100k_large,
+    segmented_put_1mil,
 );
 benchmark_main!(benches);
@@ -0,0 +1,62 @@
+/*
+ * C interface of the priq crate, a priority queue of `double` scores and
+ * opaque pointers. The library is built with the `ffi` feature enabled:
+ *
+ *     cargo rustc --release --features ffi --crate-type staticlib
+ *     cargo rustc --release --features ffi --crate-type cdylib
+ *
+ * which leave `libpriq.a` and `libpriq.so` (`.dylib`, `.dll`) in
+ * `target/release`.
+ *
+ * The queue never dereferences nor frees the items, the caller stays
+ * responsible for the memory they point to. A queue must not be used by
+ * more than one thread at the same time.
+ */
+
+#ifndef PRIQ_H
+#define PRIQ_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* An element was put, popped or peeked. */
+#define PRIQ_OK 0
+/* `priq_pop` or `priq_peek` was called on an empty queue. */
+#define PRIQ_EMPTY 1
+/* The queue pointer is null or the operation failed. */
+#define PRIQ_ERROR (-1)
+
+typedef struct PriqQueue PriqQueue;
+
+/* Creates an empty queue where the lowest score is on top. */
+PriqQueue *priq_new(void);
+
+/* Creates an empty queue where the highest score is on top. */
+PriqQueue *priq_new_max(void);
+
+/* Inserts an element. NaN scores are kept after all the other ones. */
+int priq_put(PriqQueue *pq, double score, void *item);
+
+/* Removes the top element, writing it through the non-null pointers. */
+int priq_pop(PriqQueue *pq, double *score, void **item);
+
+/* Writes the top element through the non-null pointers without removing it. */
+int priq_peek(const PriqQueue *pq, double *score, void **item);
+
+/* Returns the number of elements, or zero if `pq` is null. */
+size_t priq_len(const PriqQueue *pq);
+
+/* Removes all the elements. The items are not freed. */
+void priq_clear(PriqQueue *pq);
+
+/* Destroys the queue. The items are not freed. Does nothing for null. */
+void priq_free(PriqQueue *pq);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* PRIQ_H */
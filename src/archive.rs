@@ -0,0 +1,155 @@
+use std::slice;
+
+use rkyv::bytecheck::CheckBytes;
+use rkyv::rancor::{Fallible, Source};
+use rkyv::ser::{Allocator, Writer};
+use rkyv::vec::{ArchivedVec, VecResolver};
+use rkyv::{Archive, Archived, Deserialize, DeserializeUnsized, Place, Portable, Serialize};
+
+use crate::{Order, PriorityQueue};
+
+/// An archived [`PriorityQueue`], produced by serializing a queue with
+/// [`rkyv`](https://docs.rs/rkyv).
+///
+/// The elements are stored in the order of the underlying array, so the
+/// archive keeps the heap property and the top of the queue can be looked
+/// at without deserializing anything. Settings like a custom comparator,
+/// [`NanPolicy`] or growth policy are not archived.
+///
+/// # Examples
+///
+/// ```
+/// use priq::{ArchivedPriorityQueue, PriorityQueue};
+/// use rkyv::rancor::Error;
+///
+/// let pq: PriorityQueue<u32, u64> = (0..100).map(|i| (i, i as u64 * 2)).collect();
+/// let bytes = rkyv::to_bytes::<Error>(&pq).unwrap();
+///
+/// let archived = rkyv::access::<ArchivedPriorityQueue<u32, u64>, Error>(&bytes).unwrap();
+/// assert_eq!(100, archived.len());
+/// assert_eq!(0, archived.peek().unwrap().1);
+///
+/// let mut pq: PriorityQueue<u32, u64> = rkyv::deserialize::<_, Error>(archived).unwrap();
+/// assert_eq!(Some((0, 0)), pq.pop());
+/// ```
+///
+/// [`NanPolicy`]: crate::NanPolicy
+#[derive(Portable)]
+#[rkyv(crate = rkyv)]
+#[repr(transparent)]
+pub struct ArchivedPriorityQueue<S: Archive, T: Archive> {
+    data: ArchivedVec<Archived<(S, T)>>,
+}
+
+impl<S: Archive, T: Archive> ArchivedPriorityQueue<S, T> {
+    /// Returns the number of elements in the archived queue.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` is there are no elements in the archived queue.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns a reference to the archived top element, if there is one.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    #[inline]
+    pub fn peek(&self) -> Option<&Archived<(S, T)>> {
+        self.data.first()
+    }
+
+    /// Returns the archived elements in the order of the underlying array
+    /// (NOT sorted).
+    #[inline]
+    pub fn as_slice(&self) -> &[Archived<(S, T)>] {
+        self.data.as_slice()
+    }
+
+    /// Returns an iterator over the archived elements in the order of the
+    /// underlying array (NOT sorted).
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, Archived<(S, T)>> {
+        self.data.iter()
+    }
+}
+
+impl<'a, S: Archive, T: Archive> IntoIterator for &'a ArchivedPriorityQueue<S, T> {
+    type Item = &'a Archived<(S, T)>;
+    type IntoIter = slice::Iter<'a, Archived<(S, T)>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// SAFETY: `ArchivedPriorityQueue` is a transparent wrapper around the
+// archived vector, so it's valid whenever the vector is.
+unsafe impl<S, T, C> CheckBytes<C> for ArchivedPriorityQueue<S, T>
+where
+    S: Archive,
+    T: Archive,
+    C: Fallible + ?Sized,
+    ArchivedVec<Archived<(S, T)>>: CheckBytes<C>,
+{
+    unsafe fn check_bytes(value: *const Self, context: &mut C) -> Result<(), C::Error> {
+        ArchivedVec::check_bytes(value.cast(), context)
+    }
+}
+
+impl<S, T, O> Archive for PriorityQueue<S, T, O>
+where
+    S: PartialOrd + Archive,
+    T: Archive,
+    O: Order,
+{
+    type Archived = ArchivedPriorityQueue<S, T>;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        // SAFETY: the wrapper is transparent, so its place is the vector's.
+        let out = unsafe { out.cast_unchecked::<ArchivedVec<Archived<(S, T)>>>() };
+        ArchivedVec::resolve_from_len(self.len(), resolver, out);
+    }
+}
+
+impl<S, T, O, Se> Serialize<Se> for PriorityQueue<S, T, O>
+where
+    S: PartialOrd + Serialize<Se>,
+    T: Serialize<Se>,
+    O: Order,
+    Se: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(&self, serializer: &mut Se) -> Result<Self::Resolver, Se::Error> {
+        ArchivedVec::serialize_from_slice(&self[..], serializer)
+    }
+}
+
+impl<S, T, O, D> Deserialize<PriorityQueue<S, T, O>, D> for ArchivedPriorityQueue<S, T>
+where
+    S: PartialOrd + Archive,
+    T: Archive,
+    O: Order,
+    [Archived<(S, T)>]: DeserializeUnsized<[(S, T)], D>,
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    /// Deserializes the elements and puts them in a new queue, so the heap
+    /// property is restored even if the archive was produced by a queue with
+    /// a different order.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)***
+    fn deserialize(&self, deserializer: &mut D) -> Result<PriorityQueue<S, T, O>, D::Error> {
+        let data: Vec<(S, T)> = self.data.deserialize(deserializer)?;
+        let mut pq = PriorityQueue::default();
+        pq.put_many(data);
+        Ok(pq)
+    }
+}
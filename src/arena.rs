@@ -0,0 +1,38 @@
+use bumpalo::Bump;
+
+use crate::{Min, PriorityQueue};
+
+/// A [`PriorityQueue`] which lives in a [`Bump`] arena from the
+/// [`bumpalo`](https://docs.rs/bumpalo) crate.
+///
+/// Memory of the queue is taken from the arena and never freed one by one:
+/// dropping the queue only runs destructors of its elements, while all the
+/// memory is released at once when the arena is reset or dropped. This suits
+/// short-lived queues built and thrown away every frame or every request.
+///
+/// The arena can't move the memory it handed out, so a queue which grows
+/// leaves its old array behind until the reset. Reserving the capacity up
+/// front with [`with_capacity_in`] avoids that.
+///
+/// [`with_capacity_in`]: PriorityQueue::with_capacity_in
+///
+/// # Examples
+///
+/// ```
+/// use bumpalo::Bump;
+/// use priq::BumpPriorityQueue;
+///
+/// let mut arena = Bump::new();
+/// for frame in 0..3 {
+///     let mut pq: BumpPriorityQueue<'_, u32, &str> =
+///         BumpPriorityQueue::with_capacity_in(16, &arena);
+///     pq.put(frame + 2, "draw");
+///     pq.put(frame, "input");
+///     pq.put(frame + 1, "physics");
+///     assert_eq!(Some((frame, "input")), pq.pop());
+///
+///     drop(pq);
+///     arena.reset();
+/// }
+/// ```
+pub type BumpPriorityQueue<'bump, S, T, O = Min> = PriorityQueue<S, T, O, &'bump Bump>;
@@ -0,0 +1,282 @@
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+
+use crate::{
+    BHeap, BinomialHeap, DaryHeap, FibonacciHeap, LeftistHeap, Order, PairingHeap,
+    PriorityQueue, Radix, RadixHeap, SegmentedPriorityQueue, SmallPriorityQueue,
+    SoaPriorityQueue, StablePriorityQueue, WeakHeap,
+};
+
+/// Common interface of the heaps of this crate. The array heap of
+/// [`PriorityQueue`] and every other heap implement the trait, so code
+/// written against it can switch between them with a type parameter.
+///
+/// Backend is not a type parameter of [`PriorityQueue`] itself: its surface
+/// (ranges of the underlying array, `Deref` to a slice, in-place mapping)
+/// is specific to the array storage. Other heaps are separate types which
+/// share the ordering of scores with it and, for the array based ones, the
+/// sift routines, whatever the layout their elements are stored in.
+///
+/// Only the core operations have to be implemented, the rest of the surface
+/// like [`drain_sorted`] or [`into_sorted_vec`] is shared by all the
+/// backends. Heaps which can do it faster, like [`PriorityQueue`] sorting
+/// its array in place, override it.
+///
+/// [`drain_sorted`]: HeapBackend::drain_sorted
+/// [`into_sorted_vec`]: HeapBackend::into_sorted_vec
+///
+/// # Examples
+///
+/// ```
+/// use priq::{HeapBackend, PairingHeap, PriorityQueue};
+///
+/// fn schedule<B: HeapBackend<u32, &'static str>>(mut jobs: B) -> Vec<&'static str> {
+///     jobs.put(3, "deploy");
+///     jobs.put(1, "build");
+///     jobs.put(2, "test");
+///     jobs.into_sorted_iter().map(|(_, job)| job).collect()
+/// }
+///
+/// assert_eq!(vec!["build", "test", "deploy"], schedule(PriorityQueue::new()));
+/// assert_eq!(vec!["build", "test", "deploy"], schedule(PairingHeap::new()));
+/// ```
+pub trait HeapBackend<S, T> {
+    /// Inserts an element.
+    fn put(&mut self, score: S, item: T);
+
+    /// Get the top priority element.
+    fn pop(&mut self) -> Option<(S, T)>;
+
+    /// Check what is a top element, by getting the references to its score
+    /// and item.
+    fn peek(&self) -> Option<(&S, &T)>;
+
+    /// Returns the number of elements.
+    fn len(&self) -> usize;
+
+    /// Remove all the elements.
+    fn clear(&mut self);
+
+    /// Returns `true` is there are no elements.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the reference to the score of a top element.
+    fn peek_score<'a>(&'a self) -> Option<&'a S>
+    where
+        T: 'a,
+    {
+        self.peek().map(|(s, _)| s)
+    }
+
+    /// Get the reference to the item of a top element.
+    fn peek_item<'a>(&'a self) -> Option<&'a T>
+    where
+        S: 'a,
+    {
+        self.peek().map(|(_, e)| e)
+    }
+
+    /// Get the item of the top priority element, dropping its score.
+    fn pop_item(&mut self) -> Option<T> {
+        self.pop().map(|(_, e)| e)
+    }
+
+    /// Clears the heap, returning an iterator over the removed elements in
+    /// the order of their priority. Elements which are not iterated over
+    /// are dropped together with the iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::{HeapBackend, WeakHeap};
+    ///
+    /// let mut pq: WeakHeap<_, _> = [(2, 'b'), (3, 'c'), (1, 'a')].into_iter().collect();
+    /// assert_eq!(Some((1, 'a')), pq.drain_sorted().next());
+    /// assert!(pq.is_empty());
+    /// ```
+    fn drain_sorted(&mut self) -> DrainSorted<'_, S, T, Self>
+    where
+        Self: Sized,
+    {
+        DrainSorted { heap: self, _elem: PhantomData }
+    }
+
+    /// Consumes the heap and returns an iterator over its elements in the
+    /// order of their priority.
+    fn into_sorted_iter(self) -> IntoSorted<S, T, Self>
+    where
+        Self: Sized,
+    {
+        IntoSorted { heap: self, _elem: PhantomData }
+    }
+
+    /// Consumes the heap and returns elements sorted by their priority, the
+    /// top one first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::{HeapBackend, PairingHeap};
+    ///
+    /// let pq: PairingHeap<_, _> = [(2, 'b'), (3, 'c'), (1, 'a')].into_iter().collect();
+    /// assert_eq!(vec![(1, 'a'), (2, 'b'), (3, 'c')], pq.into_sorted_vec());
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n log(n))*** for the heaps which pop in ***O(log(n))***.
+    fn into_sorted_vec(self) -> Vec<(S, T)>
+    where
+        Self: Sized,
+    {
+        self.into_sorted_iter().collect()
+    }
+}
+
+/// Draining iterator over the elements of a heap in the order of their
+/// priority, created by [`HeapBackend::drain_sorted`].
+pub struct DrainSorted<'a, S, T, B>
+where
+    B: HeapBackend<S, T>,
+{
+    heap: &'a mut B,
+    _elem: PhantomData<(S, T)>,
+}
+
+impl<S, T, B> Iterator for DrainSorted<'_, S, T, B>
+where
+    B: HeapBackend<S, T>,
+{
+    type Item = (S, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.heap.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.len(), Some(self.heap.len()))
+    }
+}
+
+impl<S, T, B> ExactSizeIterator for DrainSorted<'_, S, T, B>
+where
+    B: HeapBackend<S, T>,
+{}
+
+impl<S, T, B> FusedIterator for DrainSorted<'_, S, T, B>
+where
+    B: HeapBackend<S, T>,
+{}
+
+impl<S, T, B> Drop for DrainSorted<'_, S, T, B>
+where
+    B: HeapBackend<S, T>,
+{
+    fn drop(&mut self) {
+        self.heap.clear();
+    }
+}
+
+/// Consuming iterator over the elements of a heap in the order of their
+/// priority, created by [`HeapBackend::into_sorted_iter`].
+pub struct IntoSorted<S, T, B>
+where
+    B: HeapBackend<S, T>,
+{
+    heap: B,
+    _elem: PhantomData<(S, T)>,
+}
+
+impl<S, T, B> Iterator for IntoSorted<S, T, B>
+where
+    B: HeapBackend<S, T>,
+{
+    type Item = (S, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.heap.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.len(), Some(self.heap.len()))
+    }
+}
+
+impl<S, T, B> ExactSizeIterator for IntoSorted<S, T, B>
+where
+    B: HeapBackend<S, T>,
+{}
+
+impl<S, T, B> FusedIterator for IntoSorted<S, T, B>
+where
+    B: HeapBackend<S, T>,
+{}
+
+/// Implements the backend for a heap which has the core operations as
+/// inherent methods. Heaps which sort their elements faster than by popping
+/// them one by one pass their inherent `into_sorted_vec` to be used instead.
+macro_rules! backend_impl {
+    ($heap:ty, [$($param:tt)*], [$($bound:tt)*], |$pq:ident| $peek:expr $(, $sorted:ident)?) => {
+        impl<$($param)*> HeapBackend<S, T> for $heap
+        where
+            $($bound)*
+        {
+            fn put(&mut self, score: S, item: T) {
+                <$heap>::put(self, score, item);
+            }
+
+            fn pop(&mut self) -> Option<(S, T)> {
+                <$heap>::pop(self)
+            }
+
+            fn peek(&self) -> Option<(&S, &T)> {
+                let $pq = self;
+                $peek
+            }
+
+            fn len(&self) -> usize {
+                <$heap>::len(self)
+            }
+
+            fn clear(&mut self) {
+                <$heap>::clear(self);
+            }
+
+            $(
+            fn $sorted(self) -> Vec<(S, T)> {
+                <$heap>::$sorted(self)
+            }
+            )?
+        }
+    };
+}
+
+backend_impl!(PriorityQueue<S, T, O>, [S, T, O], [S: PartialOrd, O: Order],
+              |pq| pq.peek().map(|(s, e)| (s, e)), into_sorted_vec);
+backend_impl!(StablePriorityQueue<S, T, O>, [S, T, O], [S: PartialOrd, O: Order],
+              |pq| pq.peek(), into_sorted_vec);
+backend_impl!(DaryHeap<S, T, D, O>, [S, T, const D: usize, O], [S: PartialOrd, O: Order],
+              |pq| pq.peek().map(|(s, e)| (s, e)));
+backend_impl!(BHeap<S, T, O>, [S, T, O], [S: PartialOrd, O: Order],
+              |pq| pq.peek().map(|(s, e)| (s, e)));
+backend_impl!(WeakHeap<S, T, O>, [S, T, O], [S: PartialOrd, O: Order],
+              |pq| pq.peek().map(|(s, e)| (s, e)));
+backend_impl!(SegmentedPriorityQueue<S, T, O>, [S, T, O], [S: PartialOrd, O: Order],
+              |pq| pq.peek().map(|(s, e)| (s, e)));
+backend_impl!(SmallPriorityQueue<S, T, N, O>, [S, T, const N: usize, O], [S: PartialOrd, O: Order],
+              |pq| pq.peek().map(|(s, e)| (s, e)));
+backend_impl!(SoaPriorityQueue<S, T, O>, [S, T, O], [S: PartialOrd, O: Order],
+              |pq| pq.peek());
+backend_impl!(BinomialHeap<S, T, O>, [S, T, O], [S: PartialOrd, O: Order],
+              |pq| pq.peek().map(|(s, e)| (s, e)));
+backend_impl!(PairingHeap<S, T, O>, [S, T, O], [S: PartialOrd, O: Order],
+              |pq| pq.peek().map(|(s, e)| (s, e)));
+backend_impl!(FibonacciHeap<S, T, O>, [S, T, O], [S: PartialOrd, O: Order],
+              |pq| pq.peek().map(|(s, e)| (s, e)));
+backend_impl!(LeftistHeap<S, T, O>, [S, T, O], [S: PartialOrd + Clone, T: Clone, O: Order],
+              |pq| pq.peek().map(|(s, e)| (s, e)));
+backend_impl!(RadixHeap<S, T>, [S, T], [S: Radix],
+              |pq| pq.peek().map(|(s, e)| (s, e)));
@@ -0,0 +1,209 @@
+use alloc::vec::Vec;
+
+use crate::{Max, Min, Order, PriorityQueue};
+
+/// Decides from which band [`BandedPriorityQueue`] pops the next element.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ServicePolicy {
+    /// Elements are always popped from the first non-empty band, so lower
+    /// bands are served only when all the higher ones are empty.
+    Strict,
+
+    /// Bands are served in a round, every band giving away up to its weight
+    /// of elements before the next one is served. Weights are given for
+    /// every band in order, starting with band `0`.
+    Weighted(Vec<usize>),
+}
+
+/// A priority queue split into a fixed number of priority classes (bands),
+/// each backed by its own heap. Band `0` is the highest class. Elements are
+/// ordered by their scores only within a band, while [`ServicePolicy`]
+/// decides which band is served next.
+///
+/// # Examples
+///
+/// ```
+/// use priq::{BandedPriorityQueue, ServicePolicy};
+///
+/// let mut pq = BandedPriorityQueue::new(2, ServicePolicy::Weighted(vec![2, 1]));
+/// pq.put(0, 1, "interactive 1");
+/// pq.put(0, 2, "interactive 2");
+/// pq.put(0, 3, "interactive 3");
+/// pq.put(1, 1, "batch 1");
+///
+/// assert_eq!("interactive 1", pq.pop().unwrap().1);
+/// assert_eq!("interactive 2", pq.pop().unwrap().1);
+/// assert_eq!("batch 1", pq.pop().unwrap().1);
+/// assert_eq!("interactive 3", pq.pop().unwrap().1);
+/// ```
+#[derive(Debug)]
+pub struct BandedPriorityQueue<S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    bands: Vec<PriorityQueue<S, T, O>>,
+    policy: ServicePolicy,
+    cursor: usize,
+    credit: usize,
+}
+
+impl<S, T> BandedPriorityQueue<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `BandedPriorityQueue` with the given number of bands,
+    /// where the lowest score of a band is on its top.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no bands, or if weights are not given for every
+    /// band or some of them are zero.
+    #[must_use]
+    pub fn new(bands: usize, policy: ServicePolicy) -> Self {
+        BandedPriorityQueue::with_bands(bands, policy)
+    }
+}
+
+impl<S, T> BandedPriorityQueue<S, T, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `BandedPriorityQueue` with the given number of bands,
+    /// where the highest score of a band is on its top.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no bands, or if weights are not given for every
+    /// band or some of them are zero.
+    #[must_use]
+    pub fn new_max(bands: usize, policy: ServicePolicy) -> Self {
+        BandedPriorityQueue::with_bands(bands, policy)
+    }
+}
+
+impl<S, T, O> BandedPriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn with_bands(bands: usize, policy: ServicePolicy) -> Self {
+        assert!(bands > 0, "`BandedPriorityQueue` needs at least one band");
+        if let ServicePolicy::Weighted(weights) = &policy {
+            assert_eq!(bands, weights.len(), "weight should be given for every band");
+            assert!(weights.iter().all(|&w| w > 0), "weights should be positive");
+        }
+        BandedPriorityQueue {
+            bands: (0..bands).map(|_| PriorityQueue::default()).collect(),
+            credit: Self::weight(&policy, 0),
+            policy,
+            cursor: 0,
+        }
+    }
+
+    /// Inserts an element in the given band.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `band` is out of bounds.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(n))*** for `n` elements in the band.
+    pub fn put(&mut self, band: usize, score: S, item: T) {
+        self.bands[band].put(score, item);
+    }
+
+    /// Get the top priority element from the band which is served next
+    /// according to the [`ServicePolicy`].
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(log(n) + b)*** for `b` bands.
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        let band = self.next_band()?;
+        self.credit -= 1;
+        self.bands[band].pop()
+    }
+
+    /// Check what is the element [`pop`] would return next, together with
+    /// its band.
+    ///
+    /// [`pop`]: BandedPriorityQueue::pop
+    pub fn peek(&self) -> Option<(usize, &(S, T))> {
+        let band = match self.policy {
+            ServicePolicy::Strict => self.bands.iter().position(|b| !b.is_empty())?,
+            ServicePolicy::Weighted(_) => {
+                if self.credit > 0 && !self.bands[self.cursor].is_empty() {
+                    self.cursor
+                } else {
+                    (1..=self.bands.len())
+                        .map(|i| (self.cursor + i) % self.bands.len())
+                        .find(|&b| !self.bands[b].is_empty())?
+                }
+            }
+        };
+        self.bands[band].peek().map(|elem| (band, elem))
+    }
+
+    /// Returns the priority queue of the given band.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `band` is out of bounds.
+    pub fn band(&self, band: usize) -> &PriorityQueue<S, T, O> {
+        &self.bands[band]
+    }
+
+    /// Returns the number of bands.
+    pub fn bands(&self) -> usize {
+        self.bands.len()
+    }
+
+    /// Returns the [`ServicePolicy`] of the queue.
+    pub fn policy(&self) -> &ServicePolicy {
+        &self.policy
+    }
+
+    /// Returns the number of elements in all the bands.
+    pub fn len(&self) -> usize {
+        self.bands.iter().map(PriorityQueue::len).sum()
+    }
+
+    /// Returns `true` is there are no elements in any of the bands.
+    pub fn is_empty(&self) -> bool {
+        self.bands.iter().all(PriorityQueue::is_empty)
+    }
+
+    /// Remove all the elements from all the bands and start serving bands
+    /// from the first one.
+    pub fn clear(&mut self) {
+        self.bands.iter_mut().for_each(PriorityQueue::clear);
+        self.cursor = 0;
+        self.credit = Self::weight(&self.policy, 0);
+    }
+
+    /// Finds the band to pop from, moving on to the next bands in a round
+    /// if the current one is empty or has used up its weight.
+    fn next_band(&mut self) -> Option<usize> {
+        if let ServicePolicy::Strict = self.policy {
+            self.credit = 1;
+            return self.bands.iter().position(|b| !b.is_empty())
+        }
+        if self.is_empty() {
+            return None
+        }
+        while self.credit == 0 || self.bands[self.cursor].is_empty() {
+            self.cursor = (self.cursor + 1) % self.bands.len();
+            self.credit = Self::weight(&self.policy, self.cursor);
+        }
+        Some(self.cursor)
+    }
+
+    fn weight(policy: &ServicePolicy, band: usize) -> usize {
+        match policy {
+            ServicePolicy::Strict => 1,
+            ServicePolicy::Weighted(weights) => weights[band],
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::{self, MaybeUninit};
+use core::ptr;
+
+use crate::{Max, Min, Order};
+use crate::sift;
+
+/// Size of the memory block the subtrees are fit in.
+const CACHE_LINE: usize = 64;
+
+/// A binary heap with a cache-aware memory layout. The tree is cut into
+/// small complete subtrees which fit a cache line, and every subtree is
+/// stored contiguously, so walking down or up the tree touches a new cache
+/// line only every few levels instead of on every level. This matters for
+/// very large queues which don't fit in the cache.
+///
+/// Subtrees are laid out level by level, so while the tree grows a new level
+/// of subtrees, the storage can be up to a few times larger than the number
+/// of elements. Elements are ordered by their scores the same way as in
+/// [`PriorityQueue`], including the direction given by the [`Order`] type
+/// parameter.
+///
+/// [`PriorityQueue`]: crate::PriorityQueue
+///
+/// # Examples
+///
+/// ```
+/// use priq::BHeap;
+///
+/// let mut pq = BHeap::new();
+/// pq.put(3, "c");
+/// pq.put(1, "a");
+/// pq.put(2, "b");
+///
+/// assert_eq!(Some((1, "a")), pq.pop());
+/// assert_eq!(Some((2, "b")), pq.pop());
+/// assert_eq!(Some((3, "c")), pq.pop());
+/// ```
+pub struct BHeap<S, T, O = Min> {
+    /// Slots of the subtrees, the ones of the first `len` nodes of the tree
+    /// in level order are initialized.
+    slots: Vec<MaybeUninit<(S, T)>>,
+    len: usize,
+    _order: PhantomData<O>,
+}
+
+impl<S, T> BHeap<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `BHeap` where the lowest score is on top.
+    #[must_use]
+    pub fn new() -> Self {
+        BHeap::default()
+    }
+
+    /// Create an empty `BHeap` with space for at least `cap` elements.
+    #[must_use]
+    pub fn with_capacity(cap: usize) -> Self {
+        let mut pq = BHeap::default();
+        pq.slots.reserve_exact(cap);
+        pq
+    }
+}
+
+impl<S, T> BHeap<S, T, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `BHeap` where the highest score is on top.
+    #[must_use]
+    pub fn new_max() -> Self {
+        BHeap::default()
+    }
+}
+
+impl<S, T, O> BHeap<S, T, O> {
+    /// Height of the subtrees, the highest one which fits a cache line.
+    const HEIGHT: u32 = {
+        let size = mem::size_of::<(S, T)>();
+        if size == 0 || size * 3 > CACHE_LINE { 1 } else { (CACHE_LINE / size + 1).ilog2() }
+    };
+
+    /// Number of nodes in a subtree.
+    const SUBTREE: usize = (1 << Self::HEIGHT) - 1;
+
+    /// Finds the slot of a node given by its position in the level order of
+    /// the whole tree.
+    #[inline]
+    fn slot(node: usize) -> usize {
+        let depth = (node + 1).ilog2();
+        let pos = node + 1 - (1 << depth);
+        let (level, depth) = (depth / Self::HEIGHT, depth % Self::HEIGHT);
+        // there are `(1 << HEIGHT)^level` subtrees on this level
+        let before = ((1 << (Self::HEIGHT * level)) - 1) / ((1 << Self::HEIGHT) - 1);
+        let subtree = before + (pos >> depth);
+        let local = (1 << depth) - 1 + (pos & ((1 << depth) - 1));
+        subtree * Self::SUBTREE + local
+    }
+
+    #[inline]
+    fn get(&self, node: usize) -> &(S, T) {
+        debug_assert!(node < self.len);
+        // SAFETY: slots of the first `len` nodes are initialized
+        unsafe { self.slots[Self::slot(node)].assume_init_ref() }
+    }
+}
+
+impl<S, T, O> BHeap<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Inserts an element in the heap.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(n))***.
+    pub fn put(&mut self, score: S, item: T) {
+        let slot = Self::slot(self.len);
+        if slot >= self.slots.len() {
+            self.slots.resize_with(slot + 1, MaybeUninit::uninit);
+        }
+        self.slots[slot].write((score, item));
+        self.len += 1;
+        sift::sift_up_nodes(self, self.len - 1, 2);
+    }
+
+    /// Get the top priority element from `BHeap`.
+    ///
+    /// # Time Complexity
+    ///
+    /// Worst case is ***O(log(n))***.
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        if self.len == 0 {
+            return None
+        }
+        self.len -= 1;
+        self.slots.swap(Self::slot(0), Self::slot(self.len));
+        // SAFETY: the last node was initialized and is no longer counted
+        //      in `len`, so it's never read again
+        let top = unsafe { self.slots[Self::slot(self.len)].assume_init_read() };
+        if self.len > 1 { sift::sift_down_nodes(self, 0, 2); }
+        Some(top)
+    }
+
+    /// Check what is a top element in `BHeap`, by getting the reference.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek(&self) -> Option<&(S, T)> {
+        if self.len == 0 {
+            return None
+        }
+        Some(self.get(0))
+    }
+
+    /// Returns the number of elements in the `BHeap`
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` is there are no elements in `BHeap`
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all the elements from `BHeap`
+    pub fn clear(&mut self) {
+        self.drop_nodes();
+        self.slots.clear();
+    }
+}
+
+/// Nodes are addressed by their index in the binary heap, and moved between
+/// the slots of the blocks they are laid out in.
+impl<S, T, O> sift::Nodes for BHeap<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    #[inline]
+    fn count(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn before(&self, a: usize, b: usize) -> bool {
+        sift::precedes::<S, O>(&self.get(a).0, &self.get(b).0)
+    }
+
+    #[inline]
+    fn exchange(&mut self, a: usize, b: usize) {
+        self.slots.swap(Self::slot(a), Self::slot(b));
+    }
+}
+
+impl<S, T, O> BHeap<S, T, O> {
+    /// Drops all the elements, leaving the heap empty.
+    fn drop_nodes(&mut self) {
+        let len = mem::take(&mut self.len);
+        for node in 0..len {
+            // SAFETY: slots of the first `len` nodes are initialized, and
+            //      `len` is already reset so they are never read again
+            unsafe { ptr::drop_in_place(self.slots[Self::slot(node)].as_mut_ptr()); }
+        }
+    }
+}
+
+impl<S, T, O> Drop for BHeap<S, T, O> {
+    fn drop(&mut self) {
+        self.drop_nodes();
+    }
+}
+
+impl<S, T, O> Default for BHeap<S, T, O> {
+    fn default() -> Self {
+        BHeap { slots: Vec::new(), len: 0, _order: PhantomData }
+    }
+}
+
+impl<S, T, O> Clone for BHeap<S, T, O>
+where
+    S: Clone,
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        let mut pq = BHeap::default();
+        pq.slots.resize_with(self.slots.len(), MaybeUninit::uninit);
+        for node in 0..self.len {
+            pq.slots[Self::slot(node)].write(self.get(node).clone());
+            pq.len += 1;
+        }
+        pq
+    }
+}
+
+impl<S, T> FromIterator<(S, T)> for BHeap<S, T>
+where
+    S: PartialOrd,
+{
+    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
+        let mut pq = BHeap::new();
+        pq.extend(iter);
+        pq
+    }
+}
+
+impl<S, T, O> Extend<(S, T)> for BHeap<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(s, e)| self.put(s, e));
+    }
+}
+
+impl<S, T, O> fmt::Debug for BHeap<S, T, O>
+where
+    S: fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BHeap")
+         .field("data", &(0..self.len).map(|n| self.get(n)).collect::<Vec<_>>())
+         .finish()
+    }
+}
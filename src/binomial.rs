@@ -0,0 +1,273 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::{Max, Min, Order};
+use crate::sift;
+
+/// Binomial tree, where the child `k` is a tree of rank `k`.
+#[derive(Clone)]
+struct Tree<S, T> {
+    elem: (S, T),
+    children: Vec<Box<Tree<S, T>>>,
+}
+
+/// A binomial heap, a forest of binomial trees with at most one tree of
+/// every rank. Two heaps are melded like binary numbers are added, in
+/// ***O(log(n))***, which makes it a good fit for combining many queues,
+/// where merging array based heaps takes linear time.
+///
+/// Elements are ordered by their scores the same way as in
+/// [`PriorityQueue`], including the direction given by the [`Order`] type
+/// parameter.
+///
+/// [`PriorityQueue`]: crate::PriorityQueue
+///
+/// # Examples
+///
+/// ```
+/// use priq::BinomialHeap;
+///
+/// let partitions: Vec<BinomialHeap<u32, &str>> = vec![
+///     [(4, "d"), (1, "a")].into_iter().collect(),
+///     [(3, "c")].into_iter().collect(),
+///     [(2, "b"), (5, "e")].into_iter().collect(),
+/// ];
+/// let mut pq = partitions.into_iter().fold(BinomialHeap::new(), BinomialHeap::meld);
+///
+/// assert_eq!(5, pq.len());
+/// assert_eq!(Some((1, "a")), pq.pop());
+/// assert_eq!(Some((2, "b")), pq.pop());
+/// ```
+#[derive(Clone)]
+pub struct BinomialHeap<S, T, O = Min> {
+    /// Trees by their rank.
+    roots: Vec<Option<Box<Tree<S, T>>>>,
+    len: usize,
+    _order: PhantomData<O>,
+}
+
+impl<S, T> BinomialHeap<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `BinomialHeap` where the lowest score is on top.
+    #[must_use]
+    pub fn new() -> Self {
+        BinomialHeap::default()
+    }
+}
+
+impl<S, T> BinomialHeap<S, T, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `BinomialHeap` where the highest score is on top.
+    #[must_use]
+    pub fn new_max() -> Self {
+        BinomialHeap::default()
+    }
+}
+
+impl<S, T, O> BinomialHeap<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Inserts an element in the heap.
+    ///
+    /// # Time Complexity
+    ///
+    /// Amortized ***O(1)***, worst case is ***O(log(n))***.
+    pub fn put(&mut self, score: S, item: T) {
+        let mut tree = Box::new(Tree { elem: (score, item), children: Vec::new() });
+        self.len += 1;
+        for rank in 0.. {
+            if rank == self.roots.len() {
+                self.roots.push(None);
+            }
+            match self.roots[rank].take() {
+                Some(other) => tree = self.link(tree, other),
+                None => {
+                    self.roots[rank] = Some(tree);
+                    return
+                },
+            }
+        }
+    }
+
+    /// Get the top priority element from `BinomialHeap`.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(log(n))***
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        let rank = self.top_rank()?;
+        let tree = self.roots[rank].take().expect("top tree exists");
+        while let Some(None) = self.roots.last() {
+            self.roots.pop();
+        }
+        self.len -= 1;
+        let Tree { elem, children } = *tree;
+        self.add_trees(children.into_iter().map(Some).collect());
+        Some(elem)
+    }
+
+    /// Check what is a top element in `BinomialHeap`, by getting the
+    /// reference.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(log(n))***
+    pub fn peek(&self) -> Option<&(S, T)> {
+        let rank = self.top_rank()?;
+        self.roots[rank].as_ref().map(|tree| &tree.elem)
+    }
+
+    /// Combines two heaps into one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::{BinomialHeap, HeapBackend};
+    ///
+    /// let pq1: BinomialHeap<_, _> = [(2, 'b'), (4, 'd')].into_iter().collect();
+    /// let pq2: BinomialHeap<_, _> = [(1, 'a'), (3, 'c')].into_iter().collect();
+    /// let pq = pq1.meld(pq2);
+    ///
+    /// assert_eq!(vec![(1, 'a'), (2, 'b'), (3, 'c'), (4, 'd')], pq.into_sorted_vec());
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(log(n + m))***
+    #[must_use]
+    pub fn meld(mut self, other: Self) -> Self {
+        self.len += other.len;
+        self.add_trees(other.roots);
+        self
+    }
+
+    /// Returns the number of elements in the `BinomialHeap`
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` is there are no elements in `BinomialHeap`
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all the elements from `BinomialHeap`
+    pub fn clear(&mut self) {
+        self.roots.clear();
+        self.len = 0;
+    }
+
+    /// Checks if `lhs` should be placed closer to the root than `rhs`.
+    /// Scores that can't be compared even with themselves sink to the back.
+    #[inline]
+    fn less(&self, lhs: &S, rhs: &S) -> bool {
+        sift::precedes::<S, O>(lhs, rhs)
+    }
+
+    /// Finds the rank of the tree with the top priority root.
+    fn top_rank(&self) -> Option<usize> {
+        let mut top: Option<(usize, &S)> = None;
+        for (rank, tree) in self.roots.iter().enumerate() {
+            if let Some(tree) = tree {
+                match top {
+                    Some((_, score)) if !self.less(&tree.elem.0, score) => {},
+                    _ => top = Some((rank, &tree.elem.0)),
+                }
+            }
+        }
+        top.map(|(rank, _)| rank)
+    }
+
+    /// Links two trees of the same rank into a tree of the next rank.
+    fn link(&self, a: Box<Tree<S, T>>, b: Box<Tree<S, T>>) -> Box<Tree<S, T>> {
+        let (mut parent, child) = if self.less(&b.elem.0, &a.elem.0) { (b, a) } else { (a, b) };
+        parent.children.push(child);
+        parent
+    }
+
+    /// Adds trees given by their rank, carrying linked trees over to the
+    /// next rank.
+    fn add_trees(&mut self, others: Vec<Option<Box<Tree<S, T>>>>) {
+        if self.roots.len() < others.len() {
+            self.roots.resize_with(others.len(), || None);
+        }
+        let mut others = others.into_iter();
+        let mut carry = None;
+        for rank in 0.. {
+            let other = others.next().flatten();
+            if other.is_none() && carry.is_none() && rank >= self.roots.len() {
+                break
+            }
+            if rank == self.roots.len() {
+                self.roots.push(None);
+            }
+            let trees = [self.roots[rank].take(), other, carry.take()];
+            let mut trees = trees.into_iter().flatten();
+            match (trees.next(), trees.next(), trees.next()) {
+                (Some(a), Some(b), Some(c)) => {
+                    self.roots[rank] = Some(a);
+                    carry = Some(self.link(b, c));
+                },
+                (Some(a), Some(b), None) => carry = Some(self.link(a, b)),
+                (a, _, _) => self.roots[rank] = a,
+            }
+        }
+        while let Some(None) = self.roots.last() {
+            self.roots.pop();
+        }
+    }
+}
+
+impl<S, T, O> Default for BinomialHeap<S, T, O> {
+    fn default() -> Self {
+        BinomialHeap { roots: Vec::new(), len: 0, _order: PhantomData }
+    }
+}
+
+impl<S, T> FromIterator<(S, T)> for BinomialHeap<S, T>
+where
+    S: PartialOrd,
+{
+    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
+        let mut pq = BinomialHeap::new();
+        pq.extend(iter);
+        pq
+    }
+}
+
+impl<S, T, O> Extend<(S, T)> for BinomialHeap<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(s, e)| self.put(s, e));
+    }
+}
+
+impl<S, T, O> fmt::Debug for BinomialHeap<S, T, O>
+where
+    S: fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut elems = Vec::with_capacity(self.len);
+        let mut stack: Vec<&Tree<S, T>> = self.roots.iter().flatten().map(|t| &**t).collect();
+        while let Some(tree) = stack.pop() {
+            elems.push(&tree.elem);
+            stack.extend(tree.children.iter().map(|t| &**t));
+        }
+        f.debug_struct("BinomialHeap")
+         .field("len", &self.len)
+         .field("data", &elems)
+         .finish()
+    }
+}
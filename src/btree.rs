@@ -0,0 +1,329 @@
+use core::cmp::Ordering;
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::{Bound, RangeBounds};
+
+use crate::{HeapBackend, Max, Min, Order};
+use crate::sift;
+
+/// Score ordered totally in the order of the queue, so the top score is the
+/// first key of the map. Scores that can't be compared even with themselves
+/// go after all the others and are equal to each other.
+#[repr(transparent)]
+struct OrdWrapper<S, O> {
+    score: S,
+    _order: PhantomData<O>,
+}
+
+impl<S, O> OrdWrapper<S, O> {
+    fn new(score: S) -> Self {
+        OrdWrapper { score, _order: PhantomData }
+    }
+
+    fn from_ref(score: &S) -> &Self {
+        // SAFETY: `OrdWrapper` is a transparent wrapper around the score
+        unsafe { &*(score as *const S as *const Self) }
+    }
+}
+
+impl<S: PartialOrd, O: Order> Ord for OrdWrapper<S, O> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        sift::order::<S, O>(&self.score, &other.score)
+    }
+}
+
+impl<S: PartialOrd, O: Order> PartialOrd for OrdWrapper<S, O> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: PartialOrd, O: Order> PartialEq for OrdWrapper<S, O> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S: PartialOrd, O: Order> Eq for OrdWrapper<S, O> {}
+
+impl<S: Clone, O> Clone for OrdWrapper<S, O> {
+    fn clone(&self) -> Self {
+        OrdWrapper::new(self.score.clone())
+    }
+}
+
+/// A priority queue kept in a [`BTreeMap`] from scores to the FIFO queues of
+/// their items. It's slower to put and pop than the array heap of
+/// [`PriorityQueue`], but in exchange:
+///
+/// * Elements with equal scores are popped in the order they were put.
+/// * Elements are iterated in the order of their priority without
+///   draining the queue.
+/// * Elements within a range of scores are found in ***O(log(n))***.
+///
+/// Scores that can't be compared even with themselves, like `NAN`, are put
+/// after all the others. Other scores are expected to be totally ordered,
+/// scores which are incomparable with each other are treated as equal.
+/// Equal scores share a single key, which is cloned when their elements are
+/// popped, so scores have to be [`Clone`].
+///
+/// Scores are ordered by the same rules as in the other heaps and the queue
+/// implements [`HeapBackend`], so code generic over the trait can switch
+/// to it from the array heap of [`PriorityQueue`] with a type parameter.
+///
+/// [`PriorityQueue`]: crate::PriorityQueue
+///
+/// # Examples
+///
+/// ```
+/// use priq::BTreePriorityQueue;
+///
+/// let mut pq = BTreePriorityQueue::new();
+/// pq.put(2, "b");
+/// pq.put(1, "a");
+/// pq.put(2, "c");
+/// pq.put(5, "e");
+///
+/// let scored: Vec<_> = pq.range(2..5).map(|(_, e)| *e).collect();
+/// assert_eq!(vec!["b", "c"], scored);
+///
+/// assert_eq!(Some((1, "a")), pq.pop());
+/// assert_eq!(Some((2, "b")), pq.pop());
+/// assert_eq!(Some((2, "c")), pq.pop());
+/// ```
+pub struct BTreePriorityQueue<S, T, O = Min> {
+    map: BTreeMap<OrdWrapper<S, O>, VecDeque<T>>,
+    len: usize,
+}
+
+impl<S, T> BTreePriorityQueue<S, T>
+where
+    S: PartialOrd + Clone,
+{
+    /// Create an empty `BTreePriorityQueue` where the lowest score is on top.
+    #[must_use]
+    pub fn new() -> Self {
+        BTreePriorityQueue::default()
+    }
+}
+
+impl<S, T> BTreePriorityQueue<S, T, Max>
+where
+    S: PartialOrd + Clone,
+{
+    /// Create an empty `BTreePriorityQueue` where the highest score is on
+    /// top.
+    #[must_use]
+    pub fn new_max() -> Self {
+        BTreePriorityQueue::default()
+    }
+}
+
+impl<S, T, O> BTreePriorityQueue<S, T, O>
+where
+    S: PartialOrd + Clone,
+    O: Order,
+{
+    /// Inserts an element in the queue after all the elements with the
+    /// equal score.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(log(n))***
+    pub fn put(&mut self, score: S, item: T) {
+        self.map.entry(OrdWrapper::new(score)).or_default().push_back(item);
+        self.len += 1;
+    }
+
+    /// Get the top priority element, the oldest one among equal scores.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(log(n))***
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        let mut entry = self.map.first_entry()?;
+        let item = entry.get_mut().pop_front().expect("items of a score are not empty");
+        let score = if entry.get().is_empty() {
+            entry.remove_entry().0.score
+        } else {
+            entry.key().score.clone()
+        };
+        self.len -= 1;
+        Some((score, item))
+    }
+
+    /// Check what is a top element in `BTreePriorityQueue`, by getting
+    /// references to its score and item.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(log(n))***
+    pub fn peek(&self) -> Option<(&S, &T)> {
+        let (key, items) = self.map.first_key_value()?;
+        Some((&key.score, items.front().expect("items of a score are not empty")))
+    }
+
+    /// Returns an iterator over the elements in the order of their priority,
+    /// without removing them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::BTreePriorityQueue;
+    ///
+    /// let pq: BTreePriorityQueue<_, _> = [(3, 'c'), (1, 'a'), (2, 'b')].into_iter().collect();
+    /// let items: String = pq.iter().map(|(_, e)| e).collect();
+    /// assert_eq!("abc", items);
+    /// assert_eq!(3, pq.len());
+    /// ```
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&S, &T)> + '_ {
+        self.map.iter().flat_map(|(key, items)| items.iter().map(move |e| (&key.score, e)))
+    }
+
+    /// Returns an iterator over the elements with scores within the range,
+    /// in the order of their priority, without removing them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end, or they are
+    /// equal and both excluded.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(log(n))*** to find the first element.
+    pub fn range<R>(&self, range: R) -> impl DoubleEndedIterator<Item = (&S, &T)> + '_
+    where
+        R: RangeBounds<S>,
+    {
+        let start = range.start_bound().map(OrdWrapper::from_ref);
+        let end = range.end_bound().map(OrdWrapper::from_ref);
+        let bounds = if O::TOP == Ordering::Less { (start, end) } else { (end, start) };
+        self.map.range::<OrdWrapper<S, O>, (Bound<_>, Bound<_>)>(bounds)
+                .flat_map(|(key, items)| items.iter().map(move |e| (&key.score, e)))
+    }
+
+    /// Returns the number of distinct scores in the `BTreePriorityQueue`
+    pub fn score_count(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns the number of elements in the `BTreePriorityQueue`
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` is there are no elements in `BTreePriorityQueue`
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all the elements from `BTreePriorityQueue`
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.len = 0;
+    }
+
+    /// Consumes the queue and returns elements sorted by their priority, the
+    /// top one first.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)***
+    pub fn into_sorted_vec(self) -> Vec<(S, T)> {
+        let mut res = Vec::with_capacity(self.len);
+        for (key, items) in self.map {
+            let mut items = items.into_iter();
+            let last = items.next_back();
+            res.extend(items.map(|e| (key.score.clone(), e)));
+            res.extend(last.map(|e| (key.score, e)));
+        }
+        res
+    }
+}
+
+impl<S, T, O> HeapBackend<S, T> for BTreePriorityQueue<S, T, O>
+where
+    S: PartialOrd + Clone,
+    O: Order,
+{
+    fn put(&mut self, score: S, item: T) {
+        BTreePriorityQueue::put(self, score, item);
+    }
+
+    fn pop(&mut self) -> Option<(S, T)> {
+        BTreePriorityQueue::pop(self)
+    }
+
+    fn peek(&self) -> Option<(&S, &T)> {
+        BTreePriorityQueue::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn clear(&mut self) {
+        BTreePriorityQueue::clear(self);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn into_sorted_vec(self) -> Vec<(S, T)> {
+        BTreePriorityQueue::into_sorted_vec(self)
+    }
+}
+
+impl<S, T, O> Default for BTreePriorityQueue<S, T, O> {
+    fn default() -> Self {
+        BTreePriorityQueue { map: BTreeMap::new(), len: 0 }
+    }
+}
+
+impl<S, T, O> Clone for BTreePriorityQueue<S, T, O>
+where
+    S: PartialOrd + Clone,
+    T: Clone,
+    O: Order,
+{
+    fn clone(&self) -> Self {
+        BTreePriorityQueue { map: self.map.clone(), len: self.len }
+    }
+}
+
+impl<S, T> FromIterator<(S, T)> for BTreePriorityQueue<S, T>
+where
+    S: PartialOrd + Clone,
+{
+    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
+        let mut pq = BTreePriorityQueue::new();
+        pq.extend(iter);
+        pq
+    }
+}
+
+impl<S, T, O> Extend<(S, T)> for BTreePriorityQueue<S, T, O>
+where
+    S: PartialOrd + Clone,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(s, e)| self.put(s, e));
+    }
+}
+
+impl<S, T, O> fmt::Debug for BTreePriorityQueue<S, T, O>
+where
+    S: fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = self.map.iter().map(|(key, items)| (&key.score, items));
+        f.debug_struct("BTreePriorityQueue")
+         .field("data", &data.collect::<Vec<_>>())
+         .finish()
+    }
+}
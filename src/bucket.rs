@@ -0,0 +1,175 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::{Max, Min, Order};
+
+/// A priority queue for a small number of integer priority levels, such as
+/// a task scheduler with a handful of priorities. Every level is a FIFO
+/// bucket, so elements with the same score are popped in insertion order,
+/// and both put and pop take ***O(1)*** time instead of ***O(log(n))***.
+///
+/// Scores are levels in `0..levels`, given when the queue is created.
+///
+/// # Examples
+///
+/// ```
+/// use priq::BucketQueue;
+///
+/// let mut pq = BucketQueue::new(4);
+/// pq.put(2, "idle");
+/// pq.put(0, "urgent");
+/// pq.put(2, "idle again");
+///
+/// assert_eq!(Some((0, "urgent")), pq.pop());
+/// assert_eq!(Some((2, "idle")), pq.pop());
+/// assert_eq!(Some((2, "idle again")), pq.pop());
+/// ```
+#[derive(Clone)]
+pub struct BucketQueue<T, O = Min> {
+    /// Buckets from the top priority level down.
+    buckets: Vec<VecDeque<T>>,
+    /// No bucket before this one has elements.
+    cursor: usize,
+    len: usize,
+    _order: PhantomData<O>,
+}
+
+impl<T> BucketQueue<T> {
+    /// Create an empty `BucketQueue` with scores in `0..levels`, where the
+    /// lowest score is on top.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no levels.
+    #[must_use]
+    pub fn new(levels: usize) -> Self {
+        BucketQueue::with_levels(levels)
+    }
+}
+
+impl<T> BucketQueue<T, Max> {
+    /// Create an empty `BucketQueue` with scores in `0..levels`, where the
+    /// highest score is on top.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no levels.
+    #[must_use]
+    pub fn new_max(levels: usize) -> Self {
+        BucketQueue::with_levels(levels)
+    }
+}
+
+impl<T, O: Order> BucketQueue<T, O> {
+    fn with_levels(levels: usize) -> Self {
+        assert!(levels > 0, "`BucketQueue` needs at least one level");
+        BucketQueue {
+            buckets: (0..levels).map(|_| VecDeque::new()).collect(),
+            cursor: levels,
+            len: 0,
+            _order: PhantomData,
+        }
+    }
+
+    /// Converts between scores and the positions of buckets, which is the
+    /// same both ways.
+    #[inline]
+    fn position(&self, score: usize) -> usize {
+        if O::TOP.is_lt() { score } else { self.buckets.len() - 1 - score }
+    }
+
+    /// Inserts an element after all the elements with the same score.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the score is not below the number of levels.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn put(&mut self, score: usize, item: T) {
+        assert!(score < self.buckets.len(), "score is out of the levels");
+        let pos = self.position(score);
+        self.buckets[pos].push_back(item);
+        self.cursor = self.cursor.min(pos);
+        self.len += 1;
+    }
+
+    /// Get the earliest put element with the top priority score from
+    /// `BucketQueue`.
+    ///
+    /// # Time Complexity
+    ///
+    /// Amortized ***O(1)***, worst case is ***O(levels)***.
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        while self.cursor < self.buckets.len() {
+            if let Some(item) = self.buckets[self.cursor].pop_front() {
+                self.len -= 1;
+                return Some((self.position(self.cursor), item))
+            }
+            self.cursor += 1;
+        }
+        None
+    }
+
+    /// Check what is a top element in `BucketQueue`, by getting the
+    /// reference to the item together with its score.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(levels)***
+    pub fn peek(&self) -> Option<(usize, &T)> {
+        self.buckets.iter().enumerate().skip(self.cursor)
+            .find_map(|(pos, bucket)| bucket.front().map(|item| (self.position(pos), item)))
+    }
+
+    /// Returns the number of priority levels.
+    pub fn levels(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Returns the number of elements with the given score.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the score is not below the number of levels.
+    pub fn level_len(&self, score: usize) -> usize {
+        assert!(score < self.buckets.len(), "score is out of the levels");
+        self.buckets[self.position(score)].len()
+    }
+
+    /// Returns the number of elements in the `BucketQueue`
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` is there are no elements in `BucketQueue`
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all the elements from `BucketQueue`
+    pub fn clear(&mut self) {
+        self.buckets.iter_mut().for_each(VecDeque::clear);
+        self.cursor = self.buckets.len();
+        self.len = 0;
+    }
+}
+
+impl<T, O: Order> Extend<(usize, T)> for BucketQueue<T, O> {
+    fn extend<I: IntoIterator<Item = (usize, T)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(s, e)| self.put(s, e));
+    }
+}
+
+impl<T: fmt::Debug, O: Order> fmt::Debug for BucketQueue<T, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let levels = (0..self.levels()).map(|s| (s, &self.buckets[self.position(s)]));
+        f.debug_struct("BucketQueue")
+         .field("len", &self.len)
+         .field("levels", &levels.filter(|(_, b)| !b.is_empty()).collect::<Vec<_>>())
+         .finish()
+    }
+}
@@ -0,0 +1,174 @@
+use core::cmp::Ordering;
+use core::marker::PhantomData;
+
+use crate::{Float, GrowthPolicy, Max, Min, NanPolicy, Order, PriorityQueue, ShrinkPolicy};
+use crate::compare::Comparator;
+
+/// Configures and creates a [`PriorityQueue`]. Created with
+/// [`PriorityQueue::builder`], every setter can be chained and the queue is
+/// made with the [`build`] call at the end.
+///
+/// [`build`]: Builder::build
+///
+/// # Examples
+///
+/// ```
+/// use priq::PriorityQueue;
+///
+/// let mut pq: PriorityQueue<usize, String> = PriorityQueue::builder()
+///     .capacity(100)
+///     .growth_factor(1.5)
+///     .build();
+///
+/// assert!(pq.capacity() >= 100);
+/// assert_eq!(1.5, pq.growth_policy().factor());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Builder<S, T, O = Min> {
+    capacity: usize,
+    growth: GrowthPolicy,
+    shrink: ShrinkPolicy,
+    cmp: Option<Comparator<S>>,
+    tiebreak: Option<Comparator<T>>,
+    nan: NanPolicy,
+    _marker: PhantomData<fn() -> (S, T)>,
+    _order: PhantomData<O>,
+}
+
+impl<S, T, O> Builder<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    pub(crate) fn new() -> Self {
+        Builder {
+            capacity: 0,
+            growth: GrowthPolicy::default(),
+            shrink: ShrinkPolicy::default(),
+            cmp: None,
+            tiebreak: None,
+            nan: NanPolicy::default(),
+            _marker: PhantomData,
+            _order: PhantomData,
+        }
+    }
+
+    /// Builds the queue with the highest score on top, see [`Max`].
+    pub fn max(self) -> Builder<S, T, Max> {
+        Builder {
+            capacity: self.capacity,
+            growth: self.growth,
+            shrink: self.shrink,
+            cmp: self.cmp,
+            tiebreak: self.tiebreak,
+            nan: self.nan,
+            _marker: PhantomData,
+            _order: PhantomData,
+        }
+    }
+
+    /// Space for at least `cap` elements is allocated when the queue is
+    /// built. By default nothing is allocated until the first element is put.
+    pub fn capacity(mut self, cap: usize) -> Self {
+        self.capacity = cap;
+        self
+    }
+
+    /// Sets the [`GrowthPolicy`] used when the underlying array is full.
+    pub fn growth_policy(mut self, policy: GrowthPolicy) -> Self {
+        self.growth = policy;
+        self
+    }
+
+    /// Sets the number of elements allocated when the empty array grows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cap` is zero.
+    pub fn initial_capacity(mut self, cap: usize) -> Self {
+        self.growth = GrowthPolicy::new(cap, self.growth.factor());
+        self
+    }
+
+    /// Sets how many times capacity is multiplied when the array grows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is not a finite number greater than one.
+    pub fn growth_factor(mut self, factor: f64) -> Self {
+        self.growth = GrowthPolicy::new(self.growth.initial_capacity(), factor);
+        self
+    }
+
+    /// Sets the [`ShrinkPolicy`] applied when elements are popped.
+    pub fn shrink_policy(mut self, policy: ShrinkPolicy) -> Self {
+        self.shrink = policy;
+        self
+    }
+
+    /// Scores are compared with the given function instead of their
+    /// `PartialOrd` implementation, see [`PriorityQueue::with_comparator`].
+    pub fn comparator<F>(mut self, cmp: F) -> Self
+    where
+        F: Fn(&S, &S) -> Option<Ordering> + Send + Sync + 'static,
+    {
+        self.cmp = Some(Comparator::new(cmp));
+        self
+    }
+
+    /// Elements with equal scores are ordered by their items with the given
+    /// function, see [`PriorityQueue::with_tiebreak`].
+    pub fn tiebreak<F>(mut self, tiebreak: F) -> Self
+    where
+        F: Fn(&T, &T) -> Ordering + Send + Sync + 'static,
+    {
+        self.tiebreak = Some(Comparator::new(move |a, b| Some(tiebreak(a, b))));
+        self
+    }
+
+    /// Sets the [`NanPolicy`] applied to incomparable scores.
+    pub fn nan_policy(mut self, policy: NanPolicy) -> Self {
+        self.nan = policy;
+        self
+    }
+
+    /// Float scores which differ by no more than `epsilon` are compared as
+    /// equal, see [`PriorityQueue::with_epsilon`]. Replaces the comparator.
+    pub fn epsilon(self, epsilon: S) -> Self
+    where
+        S: Float,
+    {
+        self.comparator(move |a: &S, b: &S| {
+            if a.approx_eq(b, epsilon) {
+                Some(Ordering::Equal)
+            } else {
+                a.partial_cmp(b)
+            }
+        })
+    }
+
+    /// Creates an empty [`PriorityQueue`] with the given configuration.
+    #[must_use]
+    pub fn build(self) -> PriorityQueue<S, T, O> {
+        let mut pq = PriorityQueue::default();
+        pq.reserve_exact(self.capacity);
+        pq.set_growth_policy(self.growth);
+        pq.set_shrink_policy(self.shrink);
+        if let Some(cmp) = self.cmp {
+            pq.cmp = cmp;
+        }
+        pq.tiebreak = self.tiebreak;
+        pq.nan = self.nan;
+        pq
+    }
+}
+
+impl<S, T, O> Default for Builder<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn default() -> Self {
+        Builder::new()
+    }
+}
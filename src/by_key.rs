@@ -0,0 +1,257 @@
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+use alloc::vec::{self, Vec};
+
+use crate::{Max, Min, Order};
+use crate::sift;
+
+/// A priority queue which stores only items and derives the score of every
+/// item with a key function, e.g. when priority is a field of the item.
+/// Nothing is duplicated into a separate score, key is computed every time
+/// items are compared, so it should be cheap.
+///
+/// Ordering of keys follows the same rules as the scores in [`PriorityQueue`],
+/// including the direction given by the [`Order`] type parameter.
+///
+/// [`PriorityQueue`]: crate::PriorityQueue
+///
+/// # Examples
+///
+/// ```
+/// use priq::PriorityQueueBy;
+///
+/// struct Task { deadline: u64, name: &'static str }
+///
+/// let mut pq = PriorityQueueBy::new(|t: &Task| t.deadline);
+/// pq.put(Task { deadline: 30, name: "deploy" });
+/// pq.put(Task { deadline: 10, name: "build" });
+/// pq.put(Task { deadline: 20, name: "test" });
+///
+/// assert_eq!("build", pq.pop().unwrap().name);
+/// assert_eq!("test", pq.pop().unwrap().name);
+/// assert_eq!("deploy", pq.pop().unwrap().name);
+/// ```
+pub struct PriorityQueueBy<T, K, F, O = Min> {
+    data: Vec<T>,
+    key: F,
+    _marker: PhantomData<fn() -> (K, O)>,
+}
+
+impl<T, K, F> PriorityQueueBy<T, K, F>
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    /// Create an empty `PriorityQueueBy` where the item with the lowest key
+    /// is on top.
+    #[must_use]
+    pub fn new(key: F) -> Self {
+        PriorityQueueBy::with_key(key)
+    }
+
+    /// Create an empty `PriorityQueueBy` with space for at least `cap` items.
+    #[must_use]
+    pub fn with_capacity(cap: usize, key: F) -> Self {
+        let mut pq = PriorityQueueBy::with_key(key);
+        pq.data.reserve_exact(cap);
+        pq
+    }
+}
+
+impl<T, K, F> PriorityQueueBy<T, K, F, Max>
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    /// Create an empty `PriorityQueueBy` where the item with the highest key
+    /// is on top.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueueBy;
+    ///
+    /// let mut pq = PriorityQueueBy::new_max(|s: &&str| s.len());
+    /// pq.extend(["a", "ccc", "bb"]);
+    ///
+    /// assert_eq!(Some("ccc"), pq.pop());
+    /// ```
+    #[must_use]
+    pub fn new_max(key: F) -> Self {
+        PriorityQueueBy::with_key(key)
+    }
+}
+
+impl<T, K, F, O> PriorityQueueBy<T, K, F, O>
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+    O: Order,
+{
+    fn with_key(key: F) -> Self {
+        PriorityQueueBy {
+            data: Vec::new(),
+            key,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Inserts an item in the heap.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(n))***.
+    pub fn put(&mut self, item: T) {
+        self.data.push(item);
+        self.heapify_up(self.data.len() - 1);
+    }
+
+    /// Get the top priority item from `PriorityQueueBy`.
+    ///
+    /// # Time Complexity
+    ///
+    /// Worst case is ***O(log(n))***.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.data.is_empty() {
+            return None
+        }
+        let top = self.data.swap_remove(0);
+        if self.data.len() > 1 { self.heapify_down(0); }
+        Some(top)
+    }
+
+    /// Check what is a top item in `PriorityQueueBy`, by getting the reference.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// Computes the key of a top item in `PriorityQueueBy`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueueBy;
+    ///
+    /// let mut pq = PriorityQueueBy::new(|s: &String| s.len());
+    /// pq.put(String::from("Odo"));
+    /// pq.put(String::from("Vaal"));
+    ///
+    /// assert_eq!(Some(3), pq.peek_key());
+    /// ```
+    pub fn peek_key(&self) -> Option<K> {
+        self.peek().map(&self.key)
+    }
+
+    /// Returns the number of items in the `PriorityQueueBy`
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` is there are no items in `PriorityQueueBy`
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Remove all the items from `PriorityQueueBy`
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Consumes the priority queue and returns the items in the heap order
+    /// (NOT sorted).
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    /// Consumes the priority queue and returns items sorted by their
+    /// priority, the top one first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueueBy;
+    ///
+    /// let mut pq = PriorityQueueBy::new(|e: &(char, u8)| e.1);
+    /// pq.extend([('c', 3), ('a', 1), ('b', 2)]);
+    ///
+    /// assert_eq!(vec![('a', 1), ('b', 2), ('c', 3)], pq.into_sorted_vec());
+    /// ```
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut res = Vec::with_capacity(self.len());
+        while let Some(item) = self.pop() {
+            res.push(item);
+        }
+        res
+    }
+
+    fn heapify_up(&mut self, index: usize) {
+        let key = &self.key;
+        sift::sift_up(&mut self.data, index, 2, |a, b| sift::precedes::<K, O>(&key(a), &key(b)));
+    }
+
+    fn heapify_down(&mut self, index: usize) {
+        let key = &self.key;
+        sift::sift_down(&mut self.data, index, 2, |a, b| sift::precedes::<K, O>(&key(a), &key(b)));
+    }
+}
+
+impl<T, K, F, O> Deref for PriorityQueueBy<T, K, F, O> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T, K, F, O> Extend<T> for PriorityQueueBy<T, K, F, O>
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.data.reserve(iter.size_hint().0);
+        iter.for_each(|item| self.put(item));
+    }
+}
+
+impl<T, K, F, O> IntoIterator for PriorityQueueBy<T, K, F, O> {
+    type Item = T;
+    type IntoIter = vec::IntoIter<T>;
+
+    /// Consumes the priority queue returning items in arbitrary order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<T, K, F, O> Clone for PriorityQueueBy<T, K, F, O>
+where
+    T: Clone,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        PriorityQueueBy {
+            data: self.data.clone(),
+            key: self.key.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, K, F, O> fmt::Debug for PriorityQueueBy<T, K, F, O>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PriorityQueueBy")
+         .field("data", &self.data)
+         .finish_non_exhaustive()
+    }
+}
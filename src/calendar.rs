@@ -0,0 +1,246 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::float;
+
+/// Fewest buckets the calendar is shrunk to.
+const MIN_BUCKETS: usize = 2;
+
+/// Number of the earliest events used to estimate the bucket width.
+const SAMPLE: usize = 25;
+
+/// A calendar queue, a priority queue of events ordered by their `f64`
+/// times, for discrete event simulation. Events are spread over buckets like
+/// days of a year, each covering a fixed width of time. With roughly uniform
+/// inter-event times every bucket holds only a few events, so put and pop
+/// take ***O(1)*** on average.
+///
+/// The number of buckets follows the number of events, and bucket width is
+/// re-estimated from the earliest events whenever the calendar is resized.
+/// Events at the same time are popped in the order they were put.
+///
+/// # Examples
+///
+/// ```
+/// use priq::CalendarQueue;
+///
+/// let mut events = CalendarQueue::new();
+/// events.put(2.5, "send");
+/// events.put(0.5, "start");
+/// events.put(2.5, "receive");
+///
+/// assert_eq!(Some((0.5, "start")), events.pop());
+/// assert_eq!(Some((2.5, "send")), events.pop());
+/// assert_eq!(Some((2.5, "receive")), events.pop());
+/// ```
+#[derive(Clone)]
+pub struct CalendarQueue<T> {
+    /// Events of every bucket, sorted with the earliest at the end.
+    buckets: Vec<Vec<(f64, T)>>,
+    width: f64,
+    /// Number of the bucket being served, counted from time zero and not
+    /// wrapped around the calendar.
+    current: i64,
+    len: usize,
+}
+
+impl<T> CalendarQueue<T> {
+    /// Create an empty `CalendarQueue`.
+    #[must_use]
+    pub fn new() -> Self {
+        CalendarQueue::with_bucket_width(1.0)
+    }
+
+    /// Create an empty `CalendarQueue` with the initial width of buckets,
+    /// which should be about three times the average time between events.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the width is not positive and finite.
+    #[must_use]
+    pub fn with_bucket_width(width: f64) -> Self {
+        assert!(width > 0.0 && width.is_finite(), "bucket width should be positive");
+        CalendarQueue {
+            buckets: (0..MIN_BUCKETS).map(|_| Vec::new()).collect(),
+            width,
+            current: 0,
+            len: 0,
+        }
+    }
+
+    /// Number of the bucket covering the time, not wrapped around.
+    #[inline]
+    fn day(&self, time: f64) -> i64 {
+        float::floor(time / self.width) as i64
+    }
+
+    #[inline]
+    fn bucket(&self, day: i64) -> usize {
+        day.rem_euclid(self.buckets.len() as i64) as usize
+    }
+
+    /// Inserts an event after all the events at the same time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the time is `NAN`.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)*** on average.
+    pub fn put(&mut self, time: f64, item: T) {
+        assert!(!time.is_nan(), "event time is NAN");
+        self.insert(time, item);
+        if self.len > 2 * self.buckets.len() {
+            self.resize(2 * self.buckets.len());
+        }
+    }
+
+    /// Get the earliest event from `CalendarQueue`.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)*** on average.
+    pub fn pop(&mut self) -> Option<(f64, T)> {
+        let bucket = self.next_bucket()?;
+        let event = self.buckets[bucket].pop();
+        self.len -= 1;
+        if self.len < self.buckets.len() / 2 && self.buckets.len() > MIN_BUCKETS {
+            self.resize(self.buckets.len() / 2);
+        }
+        event
+    }
+
+    /// Check what is the earliest event in `CalendarQueue`, by getting the
+    /// reference to the item together with its time.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)*** on average.
+    pub fn peek(&self) -> Option<(f64, &T)> {
+        let (bucket, _) = self.find_next()?;
+        self.buckets[bucket].last().map(|(t, item)| (*t, item))
+    }
+
+    /// Returns the current width of the buckets.
+    pub fn bucket_width(&self) -> f64 {
+        self.width
+    }
+
+    /// Returns the current number of the buckets.
+    pub fn bucket_count(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Returns the number of events in the `CalendarQueue`
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` is there are no events in `CalendarQueue`
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all the events from `CalendarQueue`
+    pub fn clear(&mut self) {
+        self.buckets.truncate(MIN_BUCKETS);
+        self.buckets.iter_mut().for_each(Vec::clear);
+        self.len = 0;
+    }
+
+    /// Moves on to the bucket with the earliest event and returns it.
+    fn next_bucket(&mut self) -> Option<usize> {
+        let (bucket, day) = self.find_next()?;
+        self.current = day;
+        Some(bucket)
+    }
+
+    /// Finds the bucket with the earliest event, together with the day of
+    /// the event. Looks through the buckets of one year starting from the
+    /// current day, and searches all the buckets directly if the next event
+    /// is further away.
+    fn find_next(&self) -> Option<(usize, i64)> {
+        if self.len == 0 {
+            return None
+        }
+        for day in self.current..self.current.saturating_add(self.buckets.len() as i64) {
+            let bucket = self.bucket(day);
+            if let Some((t, _)) = self.buckets[bucket].last() {
+                if self.day(*t) <= day {
+                    return Some((bucket, day))
+                }
+            }
+        }
+        self.buckets.iter().enumerate()
+            .filter_map(|(bucket, events)| events.last().map(|(t, _)| (bucket, *t)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(bucket, t)| (bucket, self.day(t)))
+    }
+
+    /// Redistributes the events over the new number of buckets, with the
+    /// width estimated from the average time between the earliest events.
+    fn resize(&mut self, buckets: usize) {
+        let mut events: Vec<(f64, T)> = self.buckets.iter_mut().flat_map(|b| b.drain(..)).collect();
+        let mut times: Vec<f64> = events.iter().map(|(t, _)| *t).collect();
+        let sample = times.len().min(SAMPLE);
+        if sample > 1 {
+            times.select_nth_unstable_by(sample - 1, f64::total_cmp);
+            let (earliest, _) = times.split_at_mut(sample);
+            earliest.sort_unstable_by(f64::total_cmp);
+            let gap = (earliest[sample - 1] - earliest[0]) / (sample - 1) as f64;
+            if gap > 0.0 && gap.is_finite() {
+                self.width = 3.0 * gap;
+            }
+        }
+        self.buckets.resize_with(buckets, Vec::new);
+        self.len = 0;
+        // events within a bucket were latest first, so reversing keeps the
+        // insertion order of the events at the same time
+        events.reverse();
+        events.into_iter().for_each(|(t, e)| self.insert(t, e));
+    }
+
+    /// Inserts an event without resizing the calendar.
+    fn insert(&mut self, time: f64, item: T) {
+        let day = self.day(time);
+        if self.len == 0 || day < self.current {
+            self.current = day;
+        }
+        let bucket = self.bucket(day);
+        let events = &mut self.buckets[bucket];
+        let pos = events.partition_point(|(t, _)| *t > time);
+        events.insert(pos, (time, item));
+        self.len += 1;
+    }
+}
+
+impl<T> Default for CalendarQueue<T> {
+    fn default() -> Self {
+        CalendarQueue::new()
+    }
+}
+
+impl<T> FromIterator<(f64, T)> for CalendarQueue<T> {
+    fn from_iter<I: IntoIterator<Item = (f64, T)>>(iter: I) -> Self {
+        let mut pq = CalendarQueue::new();
+        pq.extend(iter);
+        pq
+    }
+}
+
+impl<T> Extend<(f64, T)> for CalendarQueue<T> {
+    fn extend<I: IntoIterator<Item = (f64, T)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(t, e)| self.put(t, e));
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CalendarQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CalendarQueue")
+         .field("len", &self.len)
+         .field("bucket_width", &self.width)
+         .field("buckets", &self.buckets)
+         .finish()
+    }
+}
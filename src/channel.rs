@@ -0,0 +1,541 @@
+//! Multi-producer, single-consumer channels which deliver the messages in
+//! the order of their priority instead of the order they were sent.
+//!
+//! Messages can be received by blocking the thread with [`Receiver::recv`],
+//! or from an async task with [`Receiver::recv_async`], which works with
+//! any executor.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::thread;
+//! use priq::channel;
+//!
+//! let (tx, rx) = channel::channel();
+//! let producers: Vec<_> = (0..3).map(|i| {
+//!     let tx = tx.clone();
+//!     thread::spawn(move || tx.send(i, format!("job {}", i)).unwrap())
+//! }).collect();
+//! drop(tx);
+//! producers.into_iter().for_each(|p| p.join().unwrap());
+//!
+//! let jobs: Vec<_> = rx.iter().map(|(_, job)| job).collect();
+//! assert_eq!(vec!["job 0", "job 1", "job 2"], jobs);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::future;
+use std::mem;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+use crate::{Max, Min, Order, PriorityQueue};
+
+/// State shared by both halves of a channel.
+struct Shared<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    state: Mutex<State<S, T, O>>,
+    nonempty: Condvar,
+}
+
+struct State<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    pq: PriorityQueue<S, T, O>,
+    senders: usize,
+    receiver: bool,
+    /// Task of the receiver waiting for a message, if it's async.
+    waker: Option<Waker>,
+}
+
+impl<S, T, O> Shared<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn lock(&self) -> MutexGuard<'_, State<S, T, O>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Wakes up the receiver, whether it's waiting on a thread or in a task.
+    fn wake(&self, mut state: MutexGuard<'_, State<S, T, O>>) {
+        let waker = state.waker.take();
+        drop(state);
+        self.nonempty.notify_all();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// Creates a channel where the message with the lowest score is received
+/// first.
+pub fn channel<S, T>() -> (Sender<S, T>, Receiver<S, T>)
+where
+    S: PartialOrd,
+{
+    with_queue(PriorityQueue::new())
+}
+
+/// Creates a channel where the message with the highest score is received
+/// first.
+pub fn channel_max<S, T>() -> (Sender<S, T, Max>, Receiver<S, T, Max>)
+where
+    S: PartialOrd,
+{
+    with_queue(PriorityQueue::new_max())
+}
+
+/// Creates a channel which keeps the messages in the given priority queue,
+/// along with the elements it already holds and its settings.
+pub fn with_queue<S, T, O>(pq: PriorityQueue<S, T, O>) -> (Sender<S, T, O>, Receiver<S, T, O>)
+where
+    S: PartialOrd,
+    O: Order,
+{
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State { pq, senders: 1, receiver: true, waker: None }),
+        nonempty: Condvar::new(),
+    });
+    (Sender { shared: Arc::clone(&shared) }, Receiver { shared })
+}
+
+/// The sending half of a channel, which can be cloned to send from many
+/// threads.
+pub struct Sender<S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    shared: Arc<Shared<S, T, O>>,
+}
+
+impl<S, T, O> Sender<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Sends a message with the given score. Fails, giving the message
+    /// back, if the receiver was dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the score is incomparable and the [`NanPolicy`] of the
+    /// queue is `Reject` or `Panic`.
+    ///
+    /// [`NanPolicy`]: crate::NanPolicy
+    pub fn send(&self, score: S, item: T) -> Result<(), SendError<(S, T)>> {
+        let mut state = self.shared.lock();
+        if !state.receiver {
+            return Err(SendError((score, item)))
+        }
+        state.pq.put(score, item);
+        self.shared.wake(state);
+        Ok(())
+    }
+
+    /// Sends clones of all the messages of a batch under a single lock.
+    /// Fails if the receiver was dropped, in which case none of them is
+    /// sent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a score is incomparable and the [`NanPolicy`] of the
+    /// queue is `Reject` or `Panic`.
+    ///
+    /// [`NanPolicy`]: crate::NanPolicy
+    pub fn send_batch(&self, batch: &[(S, T)]) -> Result<(), SendError<()>>
+    where
+        S: Clone,
+        T: Clone,
+    {
+        let mut state = self.shared.lock();
+        if !state.receiver {
+            return Err(SendError(()))
+        }
+        state.pq.extend_from_slice(batch);
+        self.shared.wake(state);
+        Ok(())
+    }
+}
+
+impl<S, T, O> Clone for Sender<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        Sender { shared: Arc::clone(&self.shared) }
+    }
+}
+
+impl<S, T, O> Drop for Sender<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            self.shared.wake(state);
+        }
+    }
+}
+
+impl<S, T, O> fmt::Debug for Sender<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+/// The receiving half of a channel, which gets the messages in the order of
+/// their priority.
+pub struct Receiver<S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    shared: Arc<Shared<S, T, O>>,
+}
+
+impl<S, T, O> Receiver<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Gets the top priority message, waiting until one is sent if there is
+    /// none. Messages which were sent before all the senders were dropped
+    /// are still received, after that it fails.
+    pub fn recv(&self) -> Result<(S, T), RecvError> {
+        let mut state = self.shared.nonempty
+            .wait_while(self.shared.lock(), |s| s.pq.is_empty() && s.senders > 0)
+            .unwrap_or_else(PoisonError::into_inner);
+        state.pq.pop().ok_or(RecvError)
+    }
+
+    /// Gets the top priority message if there is one, without waiting.
+    pub fn try_recv(&self) -> Result<(S, T), TryRecvError> {
+        let mut state = self.shared.lock();
+        match state.pq.pop() {
+            Some(elem) => Ok(elem),
+            None if state.senders == 0 => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Gets the top priority message, waiting at most `timeout` until one is
+    /// sent if there is none. Timeout too long to be represented as a point
+    /// in time waits without a limit, same as [`recv`](Receiver::recv).
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<(S, T), RecvTimeoutError> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.recv_deadline(deadline),
+            None => self.recv().map_err(|RecvError| RecvTimeoutError::Disconnected),
+        }
+    }
+
+    /// Gets the top priority message, waiting until `deadline` at most for
+    /// one to be sent if there is none.
+    pub fn recv_deadline(&self, deadline: Instant) -> Result<(S, T), RecvTimeoutError> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        let (mut state, _) = self.shared.nonempty
+            .wait_timeout_while(self.shared.lock(), timeout,
+                                |s| s.pq.is_empty() && s.senders > 0)
+            .unwrap_or_else(PoisonError::into_inner);
+        match state.pq.pop() {
+            Some(elem) => Ok(elem),
+            None if state.senders == 0 => Err(RecvTimeoutError::Disconnected),
+            None => Err(RecvTimeoutError::Timeout),
+        }
+    }
+
+    /// Gets the top priority message, waiting without blocking the thread
+    /// until one is sent if there is none. Fails the same way as
+    /// [`recv`](Receiver::recv).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::future::Future;
+    /// # use std::pin::pin;
+    /// # use std::sync::Arc;
+    /// # use std::task::{Context, Poll, Wake};
+    /// # struct Noop;
+    /// # impl Wake for Noop { fn wake(self: Arc<Self>) {} }
+    /// use priq::channel;
+    ///
+    /// let (tx, rx) = channel::channel();
+    /// tx.send(2, "later").unwrap();
+    /// tx.send(1, "first").unwrap();
+    ///
+    /// let waker = Arc::new(Noop).into();
+    /// let recv = pin!(rx.recv_async());
+    /// let received = recv.poll(&mut Context::from_waker(&waker));
+    /// assert_eq!(Poll::Ready(Ok((1, "first"))), received);
+    /// ```
+    pub async fn recv_async(&self) -> Result<(S, T), RecvError> {
+        future::poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Attempts to get the top priority message. If there is none, the task
+    /// of the context is woken up when a message is sent or all the senders
+    /// are dropped. Only the task of the last call is woken up.
+    pub fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<Result<(S, T), RecvError>> {
+        let mut state = self.shared.lock();
+        match state.pq.pop() {
+            Some(elem) => Poll::Ready(Ok(elem)),
+            None if state.senders == 0 => Poll::Ready(Err(RecvError)),
+            None => {
+                match &mut state.waker {
+                    Some(waker) => waker.clone_from(cx.waker()),
+                    waker @ None => *waker = Some(cx.waker().clone()),
+                }
+                Poll::Pending
+            },
+        }
+    }
+
+    /// Returns an iterator which waits for the messages, and ends when all
+    /// the senders are dropped and all the messages are received.
+    pub fn iter(&self) -> Iter<'_, S, T, O> {
+        Iter { rx: self }
+    }
+
+    /// Returns an iterator over the messages which were already sent,
+    /// without waiting for new ones.
+    pub fn try_iter(&self) -> TryIter<'_, S, T, O> {
+        TryIter { rx: self }
+    }
+
+    /// Returns the number of messages waiting to be received.
+    pub fn len(&self) -> usize {
+        self.shared.lock().pq.len()
+    }
+
+    /// Returns `true` is there are no messages waiting to be received.
+    pub fn is_empty(&self) -> bool {
+        self.shared.lock().pq.is_empty()
+    }
+}
+
+impl<S, T, O> Drop for Receiver<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.receiver = false;
+        // nobody is going to receive the messages. They are dropped after
+        // the lock is released, as a message can own a sender of this
+        // channel, which takes the lock when dropped.
+        let pending = (mem::take(&mut state.pq), state.waker.take());
+        drop(state);
+        drop(pending);
+    }
+}
+
+impl<S, T, O> fmt::Debug for Receiver<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+impl<'a, S, T, O> IntoIterator for &'a Receiver<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    type Item = (S, T);
+    type IntoIter = Iter<'a, S, T, O>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<S, T, O> IntoIterator for Receiver<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    type Item = (S, T);
+    type IntoIter = IntoIter<S, T, O>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { rx: self }
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<S, T, O> futures_core::Stream for Receiver<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    type Item = (S, T);
+
+    /// Yields the messages in the order of their priority, and ends when
+    /// all the senders are dropped and all the messages are received.
+    fn poll_next(self: std::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_recv(cx).map(Result::ok)
+    }
+}
+
+/// Blocking iterator over the messages of a [`Receiver`], created by
+/// [`Receiver::iter`].
+#[derive(Debug)]
+pub struct Iter<'a, S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    rx: &'a Receiver<S, T, O>,
+}
+
+impl<S, T, O> Iterator for Iter<'_, S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    type Item = (S, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rx.recv().ok()
+    }
+}
+
+/// Iterator over the messages of a [`Receiver`] which were already sent,
+/// created by [`Receiver::try_iter`].
+#[derive(Debug)]
+pub struct TryIter<'a, S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    rx: &'a Receiver<S, T, O>,
+}
+
+impl<S, T, O> Iterator for TryIter<'_, S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    type Item = (S, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rx.try_recv().ok()
+    }
+}
+
+/// Blocking iterator which owns a [`Receiver`].
+#[derive(Debug)]
+pub struct IntoIter<S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    rx: Receiver<S, T, O>,
+}
+
+impl<S, T, O> Iterator for IntoIter<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    type Item = (S, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rx.recv().ok()
+    }
+}
+
+/// The error returned from [`Sender::send`] when the receiver was dropped.
+/// Gives back the message which couldn't be sent.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SendError<E>(pub E);
+
+impl<E> fmt::Debug for SendError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendError").finish_non_exhaustive()
+    }
+}
+
+impl<E> fmt::Display for SendError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("sending on a closed channel")
+    }
+}
+
+impl<E> Error for SendError<E> {}
+
+/// The error returned from [`Receiver::recv`] when all the senders were
+/// dropped and there are no messages left.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecvError;
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("receiving on a closed channel")
+    }
+}
+
+impl Error for RecvError {}
+
+/// The reason why [`Receiver::try_recv`] didn't get a message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// There are no messages at the moment.
+    Empty,
+
+    /// All the senders were dropped and there are no messages left.
+    Disconnected,
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => f.write_str("receiving on an empty channel"),
+            TryRecvError::Disconnected => f.write_str("receiving on a closed channel"),
+        }
+    }
+}
+
+impl Error for TryRecvError {}
+
+/// The reason why [`Receiver::recv_timeout`] didn't get a message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecvTimeoutError {
+    /// No message was sent before the time was up.
+    Timeout,
+
+    /// All the senders were dropped and there are no messages left.
+    Disconnected,
+}
+
+impl fmt::Display for RecvTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecvTimeoutError::Timeout => f.write_str("timed out waiting on a channel"),
+            RecvTimeoutError::Disconnected => f.write_str("receiving on a closed channel"),
+        }
+    }
+}
+
+impl Error for RecvTimeoutError {}
@@ -0,0 +1,56 @@
+use core::cmp::Ordering;
+use core::fmt;
+use alloc::sync::Arc;
+
+type CompareFn<S> = dyn Fn(&S, &S) -> Option<Ordering> + Send + Sync;
+
+/// Function comparing scores of the queue. It's either the `PartialOrd`
+/// implementation of a score, or a custom function stored in the queue.
+pub(crate) enum Comparator<S> {
+    Partial(fn(&S, &S) -> Option<Ordering>),
+    Custom(Arc<CompareFn<S>>),
+}
+
+impl<S> Comparator<S> {
+    pub(crate) fn new<F>(cmp: F) -> Self
+    where
+        F: Fn(&S, &S) -> Option<Ordering> + Send + Sync + 'static,
+    {
+        Comparator::Custom(Arc::new(cmp))
+    }
+
+    /// Checks if the scores are compared with a custom function.
+    #[inline]
+    pub(crate) fn is_custom(&self) -> bool {
+        matches!(self, Comparator::Custom(_))
+    }
+
+    #[inline]
+    pub(crate) fn compare(&self, lhs: &S, rhs: &S) -> Option<Ordering> {
+        match self {
+            Comparator::Partial(cmp) => cmp(lhs, rhs),
+            Comparator::Custom(cmp) => cmp(lhs, rhs),
+        }
+    }
+}
+
+impl<S: PartialOrd> Default for Comparator<S> {
+    fn default() -> Self {
+        Comparator::Partial(S::partial_cmp)
+    }
+}
+
+impl<S> Clone for Comparator<S> {
+    fn clone(&self) -> Self {
+        match self {
+            Comparator::Partial(cmp) => Comparator::Partial(*cmp),
+            Comparator::Custom(cmp) => Comparator::Custom(Arc::clone(cmp)),
+        }
+    }
+}
+
+impl<S> fmt::Debug for Comparator<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Comparator")
+    }
+}
@@ -0,0 +1,412 @@
+//! Lock-free priority queues.
+
+use std::cell::UnsafeCell;
+use std::cmp::Ordering;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{self, AtomicBool, AtomicU64, AtomicUsize};
+
+use crossbeam_epoch::{self as epoch, Atomic, Guard, Owned, Shared};
+
+use crate::{Max, Min, Order};
+use crate::sift;
+
+/// Number of levels of the skiplist.
+const LEVELS: usize = 32;
+
+/// How many logically deleted nodes are left at the front of the list before
+/// they are unlinked, so popping threads don't all contend for the head.
+const BOUND_OFFSET: usize = 32;
+
+const ACQ: atomic::Ordering = atomic::Ordering::Acquire;
+const ACQ_REL: atomic::Ordering = atomic::Ordering::AcqRel;
+
+/// Pointers to the next nodes on every level, shared by the nodes and the
+/// head of the list. On the lowest level a marked pointer means that the
+/// next node is deleted.
+struct Links<S, T> {
+    next: Box<[Atomic<Node<S, T>>]>,
+    /// Set while the node is being linked on the upper levels.
+    inserting: AtomicBool,
+}
+
+impl<S, T> Links<S, T> {
+    fn new(height: usize, inserting: bool) -> Self {
+        Links {
+            next: (0..height).map(|_| Atomic::null()).collect(),
+            inserting: AtomicBool::new(inserting),
+        }
+    }
+
+    /// Checks if the next node on the lowest level is deleted, which means
+    /// that this one is deleted too, unless it's the head.
+    fn next_deleted(&self, guard: &Guard) -> bool {
+        self.next[0].load(ACQ, guard).tag() == 1
+    }
+}
+
+struct Node<S, T> {
+    links: Links<S, T>,
+    score: S,
+    /// Sequence number of the put, which orders equal scores.
+    seq: u64,
+    /// Moved out by the thread which deleted the node.
+    item: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// Nodes around the place of a key in the list.
+struct Position<'g, S, T> {
+    preds: [&'g Links<S, T>; LEVELS],
+    succs: [Shared<'g, Node<S, T>>; LEVELS],
+    /// Last deleted node passed on the lowest level.
+    deleted: Shared<'g, Node<S, T>>,
+}
+
+/// A lock-free priority queue for many producers and many consumers, which
+/// keeps working when some threads are preempted in the middle of an
+/// operation, unlike a queue behind a global mutex.
+///
+/// It's a skiplist ordered by scores where popped nodes are only marked as
+/// deleted, and they are unlinked from the front of the list in batches,
+/// after some of them pile up. Both `put` and `pop` are linearizable: every
+/// `pop` returns the top element at some moment during the call. Unlinked
+/// nodes are freed with epoch-based reclamation, once no thread can read
+/// them.
+///
+/// Elements with equal scores are popped in the order they were put, and
+/// scores that can't be compared even with themselves, like `NAN`, are
+/// popped after all the others. As other threads can still read the score
+/// of a popped element, it's returned as a clone, and the original is
+/// dropped later, possibly by another thread.
+///
+/// Requires the `concurrent` feature.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use priq::concurrent::ConcurrentPriorityQueue;
+///
+/// let pq = Arc::new(ConcurrentPriorityQueue::new());
+/// let producers: Vec<_> = (0..4).map(|t| {
+///     let pq = Arc::clone(&pq);
+///     thread::spawn(move || (0..100).for_each(|i| pq.put(i * 4 + t, t)))
+/// }).collect();
+/// producers.into_iter().for_each(|p| p.join().unwrap());
+///
+/// assert_eq!(400, pq.len());
+/// assert_eq!(Some((0, 0)), pq.pop());
+/// ```
+pub struct ConcurrentPriorityQueue<S, T, O = Min> {
+    head: Links<S, T>,
+    seq: AtomicU64,
+    len: AtomicUsize,
+    _order: PhantomData<O>,
+}
+
+// SAFETY: elements are moved between threads, and scores are read by all of
+//      them, while an item is only ever accessed by the thread which put it
+//      or the one which popped it
+unsafe impl<S: Send + Sync, T: Send, O> Send for ConcurrentPriorityQueue<S, T, O> {}
+unsafe impl<S: Send + Sync, T: Send, O> Sync for ConcurrentPriorityQueue<S, T, O> {}
+
+impl<S, T> ConcurrentPriorityQueue<S, T>
+where
+    S: PartialOrd + Clone + Send + 'static,
+{
+    /// Create an empty `ConcurrentPriorityQueue` where the lowest score is
+    /// on top.
+    #[must_use]
+    pub fn new() -> Self {
+        ConcurrentPriorityQueue::default()
+    }
+}
+
+impl<S, T> ConcurrentPriorityQueue<S, T, Max>
+where
+    S: PartialOrd + Clone + Send + 'static,
+{
+    /// Create an empty `ConcurrentPriorityQueue` where the highest score is
+    /// on top.
+    #[must_use]
+    pub fn new_max() -> Self {
+        ConcurrentPriorityQueue::default()
+    }
+}
+
+impl<S, T, O> ConcurrentPriorityQueue<S, T, O>
+where
+    S: PartialOrd + Clone + Send + 'static,
+    O: Order,
+{
+    /// Inserts an element in the queue.
+    ///
+    /// # Time Complexity
+    ///
+    /// Expected ***O(log(n))*** without contention.
+    pub fn put(&self, score: S, item: T) {
+        self.put_pinned(score, item, &epoch::pin());
+    }
+
+    /// Inserts clones of all the elements of a batch, pinning the thread
+    /// for the epoch-based reclamation only once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::concurrent::ConcurrentPriorityQueue;
+    ///
+    /// let pq = ConcurrentPriorityQueue::new();
+    /// pq.put_batch(&[(3, 'c'), (1, 'a'), (2, 'b')]);
+    /// assert_eq!(Some((1, 'a')), pq.pop());
+    /// ```
+    pub fn put_batch(&self, batch: &[(S, T)])
+    where
+        T: Clone,
+    {
+        let guard = &epoch::pin();
+        batch.iter().for_each(|(s, e)| self.put_pinned(s.clone(), e.clone(), guard));
+    }
+
+    fn put_pinned(&self, score: S, item: T, guard: &Guard) {
+        let seq = self.seq.fetch_add(1, ACQ_REL);
+        let height = (rand::random::<u32>().trailing_ones() as usize + 1).min(LEVELS);
+        let node = Owned::new(Node {
+            links: Links::new(height, true),
+            score,
+            seq,
+            item: UnsafeCell::new(MaybeUninit::new(item)),
+        }).into_shared(guard);
+        // SAFETY: the node is alive until it's unlinked after a pop
+        let new = unsafe { node.deref() };
+        // counted before it can be popped, so the length never underflows
+        self.len.fetch_add(1, ACQ_REL);
+
+        let mut pos = loop {
+            let pos = self.locate(new, guard);
+            new.links.next[0].store(pos.succs[0], atomic::Ordering::Relaxed);
+            // fails if the successor of the predecessor got deleted as well
+            if pos.preds[0].next[0]
+                .compare_exchange(pos.succs[0], node, ACQ_REL, ACQ, guard)
+                .is_ok()
+            {
+                break pos
+            }
+        };
+
+        let mut level = 1;
+        while level < height {
+            let succ = pos.succs[level];
+            new.links.next[level].store(succ, atomic::Ordering::Release);
+            // SAFETY: successors are protected by the guard
+            let succ_deleted = unsafe { succ.as_ref() }
+                .is_some_and(|succ| succ.links.next_deleted(guard));
+            if new.links.next_deleted(guard) || succ_deleted || pos.deleted == succ {
+                break
+            }
+            if pos.preds[level].next[level]
+                .compare_exchange(succ, node, ACQ_REL, ACQ, guard)
+                .is_ok()
+            {
+                level += 1;
+            } else {
+                pos = self.locate(new, guard);
+                if pos.succs[0] != node {
+                    // the new node was already popped
+                    break
+                }
+            }
+        }
+        new.links.inserting.store(false, atomic::Ordering::Release);
+    }
+
+    /// Get the top priority element from `ConcurrentPriorityQueue`, or
+    /// `None` if it's empty.
+    ///
+    /// # Time Complexity
+    ///
+    /// Expected ***O(1)*** without contention.
+    pub fn pop(&self) -> Option<(S, T)> {
+        let guard = &epoch::pin();
+        let observed = self.head.next[0].load(ACQ, guard);
+        let (mut links, mut cur) = (&self.head, Shared::null());
+        let mut offset = 0;
+        let mut new_head = None;
+        let node = loop {
+            if links.next[0].load(ACQ, guard).with_tag(0).is_null() {
+                return None
+            }
+            // nodes still being linked can't be unlinked yet
+            if new_head.is_none() && links.inserting.load(ACQ) {
+                new_head = Some(cur);
+            }
+            let next = links.next[0].fetch_or(1, ACQ_REL, guard);
+            offset += 1;
+            cur = next.with_tag(0);
+            if next.tag() == 0 {
+                break cur
+            }
+            // SAFETY: deleted nodes are protected by the guard
+            links = unsafe { &cur.deref().links };
+        };
+        self.len.fetch_sub(1, ACQ_REL);
+        // SAFETY: the node is protected by the guard, and only this thread
+        //      managed to mark it as deleted, so the item is moved out once
+        let elem = unsafe {
+            let node = node.deref();
+            (node.score.clone(), (*node.item.get()).assume_init_read())
+        };
+
+        if offset > BOUND_OFFSET {
+            // the first node which is still inserted, or the popped one,
+            // becomes the first node after the head
+            let new_head = new_head.unwrap_or(node);
+            if self.head.next[0]
+                .compare_exchange(observed, new_head.with_tag(1), ACQ_REL, ACQ, guard)
+                .is_ok()
+            {
+                self.restructure(guard);
+                let mut cur = observed.with_tag(0);
+                while cur != new_head {
+                    // SAFETY: nodes before the new first node are unlinked
+                    //      from all the levels, and only this thread managed
+                    //      to unlink them
+                    unsafe {
+                        let next = cur.deref().links.next[0].load(ACQ, guard);
+                        guard.defer_destroy(cur);
+                        cur = next.with_tag(0);
+                    }
+                }
+            }
+        }
+        Some(elem)
+    }
+
+    /// Returns the number of elements in the `ConcurrentPriorityQueue`.
+    /// Other threads can change it at any moment, so it's only a snapshot.
+    pub fn len(&self) -> usize {
+        self.len.load(ACQ)
+    }
+
+    /// Returns `true` is there are no elements in `ConcurrentPriorityQueue`.
+    /// Other threads can change it at any moment, so it's only a snapshot.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if `node` goes before the node with the given key. Equal
+    /// scores are ordered by their sequence numbers, so every node is unique.
+    #[inline]
+    fn before(node: &Node<S, T>, key: &Node<S, T>) -> bool {
+        sift::order::<S, O>(&node.score, &key.score).then(node.seq.cmp(&key.seq))
+            == Ordering::Less
+    }
+
+    /// Finds the predecessors and successors of a node on every level,
+    /// skipping all the deleted nodes.
+    fn locate<'g>(&'g self, key: &Node<S, T>, guard: &'g Guard) -> Position<'g, S, T> {
+        let mut pos = Position {
+            preds: [&self.head; LEVELS],
+            succs: [Shared::null(); LEVELS],
+            deleted: Shared::null(),
+        };
+        let mut links = &self.head;
+        for level in (0..LEVELS).rev() {
+            let mut next = links.next[level].load(ACQ, guard);
+            // SAFETY: nodes reachable from the head are protected by the
+            //      guard
+            while let Some(node) = unsafe { next.with_tag(0).as_ref() } {
+                let deleted = level == 0 && next.tag() == 1;
+                if !deleted
+                    && !node.links.next_deleted(guard)
+                    && !Self::before(node, key)
+                {
+                    break
+                }
+                if deleted {
+                    pos.deleted = next.with_tag(0);
+                }
+                links = &node.links;
+                next = links.next[level].load(ACQ, guard);
+            }
+            pos.preds[level] = links;
+            pos.succs[level] = next.with_tag(0);
+        }
+        pos
+    }
+
+    /// Moves the upper levels of the head past the deleted nodes.
+    fn restructure(&self, guard: &Guard) {
+        let mut pred = &self.head;
+        let mut level = LEVELS - 1;
+        while level > 0 {
+            let first = self.head.next[level].load(ACQ, guard);
+            // SAFETY: nodes reachable from the head are protected by the
+            //      guard
+            let Some(node) = (unsafe { first.as_ref() }) else {
+                level -= 1;
+                continue
+            };
+            if !node.links.next_deleted(guard) {
+                level -= 1;
+                continue
+            }
+            let mut cur = pred.next[level].load(ACQ, guard);
+            while let Some(node) = unsafe { cur.as_ref() } {
+                if !node.links.next_deleted(guard) {
+                    break
+                }
+                pred = &node.links;
+                cur = pred.next[level].load(ACQ, guard);
+            }
+            if self.head.next[level]
+                .compare_exchange(first, cur, ACQ_REL, ACQ, guard)
+                .is_ok()
+            {
+                level -= 1;
+            }
+        }
+    }
+}
+
+impl<S, T, O> Default for ConcurrentPriorityQueue<S, T, O> {
+    fn default() -> Self {
+        ConcurrentPriorityQueue {
+            head: Links::new(LEVELS, false),
+            seq: AtomicU64::new(0),
+            len: AtomicUsize::new(0),
+            _order: PhantomData,
+        }
+    }
+}
+
+impl<S, T, O> Drop for ConcurrentPriorityQueue<S, T, O> {
+    fn drop(&mut self) {
+        // SAFETY: no other thread can access the queue, so the nodes still
+        //      linked on the lowest level are freed here and only here
+        unsafe {
+            let guard = epoch::unprotected();
+            let mut deleted = self.head.next_deleted(guard);
+            let mut cur = self.head.next[0].load(ACQ, guard).with_tag(0);
+            while !cur.is_null() {
+                let node = cur.into_owned();
+                if !deleted {
+                    (*node.item.get()).assume_init_drop();
+                }
+                let next = node.links.next[0].load(ACQ, guard);
+                deleted = next.tag() == 1;
+                cur = next.with_tag(0);
+            }
+        }
+    }
+}
+
+impl<S, T, O> fmt::Debug for ConcurrentPriorityQueue<S, T, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConcurrentPriorityQueue")
+         .field("len", &self.len.load(ACQ))
+         .finish()
+    }
+}
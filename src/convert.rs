@@ -0,0 +1,295 @@
+use alloc::collections::{BTreeMap, BinaryHeap};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+#[cfg(feature = "std")]
+use core::hash::BuildHasher;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::{Max, Min, Order, PriorityQueue};
+
+impl<T, O> PriorityQueue<T, T, O>
+where
+    T: Ord + Clone,
+    O: Order,
+{
+    /// Create `PriorityQueue` from values which are their own scores, the
+    /// way [`BinaryHeap`] orders its elements. Each value is cloned to be
+    /// used as its score.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq: PriorityQueue<&str, &str> =
+    ///     PriorityQueue::from_values(["pear", "apple", "fig"]);
+    /// assert_eq!(Some(("apple", "apple")), pq.pop());
+    /// ```
+    ///
+    /// [`BinaryHeap`]: std::collections::BinaryHeap
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)***
+    pub fn from_values<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut pq = PriorityQueue::default();
+        pq.put_many(iter.into_iter().map(|value| (value.clone(), value)));
+        pq
+    }
+}
+
+impl<S, T, O> PriorityQueue<S, T, O>
+where
+    S: Ord,
+    O: Order,
+{
+    /// Consumes the priority queue and groups the items by their scores.
+    /// Groups come in the order they'd be popped from the queue and items of
+    /// each group are in the order they'd be popped too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq = PriorityQueue::from([(2, 'b'), (1, 'a'), (2, 'c'), (1, 'd')]);
+    /// let groups = pq.into_grouped_vec();
+    ///
+    /// assert_eq!(2, groups.len());
+    /// assert_eq!(1, groups[0].0);
+    /// assert_eq!(2, groups[0].1.len());
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n log(n))***
+    pub fn into_grouped_vec(self) -> Vec<(S, Vec<T>)> {
+        let mut groups: Vec<(S, Vec<T>)> = Vec::new();
+        for (score, item) in self.into_sorted_vec() {
+            match groups.last_mut() {
+                Some((last, items)) if *last == score => items.push(item),
+                _ => groups.push((score, vec![item])),
+            }
+        }
+        groups
+    }
+
+    /// Consumes the priority queue and groups the items by their scores into
+    /// a `BTreeMap`, which is ordered by ascending scores whatever the order
+    /// of the queue. Items of each group are in the order they'd be popped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq = PriorityQueue::from([(2, 'b'), (1, 'a'), (2, 'c'), (1, 'd')]);
+    /// let groups = pq.into_btree_map();
+    ///
+    /// assert_eq!(vec![&1, &2], groups.keys().collect::<Vec<_>>());
+    /// assert_eq!(2, groups[&2].len());
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n log(n))***
+    pub fn into_btree_map(self) -> BTreeMap<S, Vec<T>> {
+        self.into_grouped_vec().into_iter().collect()
+    }
+}
+
+impl<S, T> PriorityQueue<S, T, Max>
+where
+    S: Ord,
+    T: Ord,
+{
+    /// Consumes the priority queue and returns a [`BinaryHeap`] of its
+    /// elements, which pops them in the same order. Elements with equal
+    /// scores are ordered by their items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::new_max();
+    /// pq.extend([(1, 'a'), (3, 'c'), (2, 'b')]);
+    ///
+    /// let mut heap = pq.into_binary_heap();
+    /// assert_eq!(Some((3, 'c')), heap.pop());
+    /// ```
+    ///
+    /// [`BinaryHeap`]: std::collections::BinaryHeap
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)***
+    pub fn into_binary_heap(self) -> BinaryHeap<(S, T)> {
+        BinaryHeap::from(self.into_vec())
+    }
+}
+
+impl<S, T> PriorityQueue<S, T, Min>
+where
+    S: Ord,
+    T: Ord,
+{
+    /// Consumes the priority queue and returns a [`BinaryHeap`] of its
+    /// elements wrapped into [`Reverse`], which pops them in the same order.
+    /// Elements with equal scores are ordered by their items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cmp::Reverse;
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq = PriorityQueue::from([(2, 'b'), (1, 'a'), (3, 'c')]);
+    ///
+    /// let mut heap = pq.into_binary_heap();
+    /// assert_eq!(Some(Reverse((1, 'a'))), heap.pop());
+    /// ```
+    ///
+    /// [`BinaryHeap`]: std::collections::BinaryHeap
+    /// [`Reverse`]: std::cmp::Reverse
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)***
+    pub fn into_binary_heap(self) -> BinaryHeap<Reverse<(S, T)>> {
+        self.into_vec().into_iter().map(Reverse).collect()
+    }
+}
+
+impl<T> From<BinaryHeap<T>> for PriorityQueue<T, T, Max>
+where
+    T: Ord + Clone,
+{
+    /// Create `PriorityQueue` from a [`BinaryHeap`], using each value as its
+    /// own score. The highest value stays on top.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BinaryHeap;
+    /// use priq::{Max, PriorityQueue};
+    ///
+    /// let heap = BinaryHeap::from([3, 1, 4, 1, 5]);
+    /// let mut pq: PriorityQueue<_, _, Max> = PriorityQueue::from(heap);
+    /// assert_eq!(Some((5, 5)), pq.pop());
+    /// ```
+    ///
+    /// The elements are already in heap order, so none of them is moved.
+    ///
+    /// [`BinaryHeap`]: std::collections::BinaryHeap
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)***
+    fn from(heap: BinaryHeap<T>) -> Self {
+        PriorityQueue::from_values(heap.into_vec())
+    }
+}
+
+impl<T> From<BinaryHeap<Reverse<T>>> for PriorityQueue<T, T>
+where
+    T: Ord + Clone,
+{
+    /// Create `PriorityQueue` from a min-heap built with [`BinaryHeap`] and
+    /// [`Reverse`], using each value as its own score. The lowest value stays
+    /// on top.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cmp::Reverse;
+    /// use std::collections::BinaryHeap;
+    /// use priq::PriorityQueue;
+    ///
+    /// let heap: BinaryHeap<_> = [3, 1, 4].into_iter().map(Reverse).collect();
+    /// let mut pq = PriorityQueue::from(heap);
+    /// assert_eq!(Some((1, 1)), pq.pop());
+    /// ```
+    ///
+    /// [`BinaryHeap`]: std::collections::BinaryHeap
+    /// [`Reverse`]: std::cmp::Reverse
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)***
+    fn from(heap: BinaryHeap<Reverse<T>>) -> Self {
+        PriorityQueue::from_values(heap.into_vec().into_iter().map(|Reverse(value)| value))
+    }
+}
+
+impl<S, T> From<BTreeMap<S, T>> for PriorityQueue<S, T>
+where
+    S: Ord,
+{
+    /// Create `PriorityQueue` from a `BTreeMap` keyed by scores.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use priq::PriorityQueue;
+    ///
+    /// let map = BTreeMap::from([(3, "c"), (1, "a"), (2, "b")]);
+    /// let mut pq = PriorityQueue::from(map);
+    /// assert_eq!(Some((1, "a")), pq.pop());
+    /// ```
+    ///
+    /// The map yields its entries sorted by scores, which is already a valid
+    /// heap, so they're only moved into the queue and never re-ordered.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)***
+    fn from(map: BTreeMap<S, T>) -> Self {
+        PriorityQueue::from_sorted_vec(map.into_iter().collect())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S, T, H> From<HashMap<T, S, H>> for PriorityQueue<S, T>
+where
+    S: PartialOrd,
+    H: BuildHasher,
+{
+    /// Create `PriorityQueue` from a `HashMap` of items to their scores.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use priq::PriorityQueue;
+    ///
+    /// let map = HashMap::from([("backup", 2), ("deploy", 1), ("report", 3)]);
+    /// let mut pq = PriorityQueue::from(map);
+    /// assert_eq!(Some((1, "deploy")), pq.pop());
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)***
+    fn from(map: HashMap<T, S, H>) -> Self {
+        PriorityQueue::from(map.into_iter().map(|(item, score)| (score, item)).collect::<Vec<_>>())
+    }
+}
+
+impl<S, T, O> From<PriorityQueue<S, T, O>> for BTreeMap<S, Vec<T>>
+where
+    S: Ord,
+    O: Order,
+{
+    /// Groups the items of a priority queue by their scores, see
+    /// [`PriorityQueue::into_btree_map`].
+    fn from(pq: PriorityQueue<S, T, O>) -> Self {
+        pq.into_btree_map()
+    }
+}
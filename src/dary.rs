@@ -0,0 +1,260 @@
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+use alloc::vec::{self, Vec};
+
+use crate::{Max, Min, Order};
+use crate::sift;
+
+/// A heap where every node has `D` children instead of two. Such heap is
+/// shallower, so `put` does fewer comparisons, and children of a node sit
+/// next to each other in memory, which makes `pop` more cache friendly for
+/// large queues. Arity of 4 or 8 is usually the fastest.
+///
+/// Elements are ordered by their scores the same way as in
+/// [`PriorityQueue`], including the direction given by the [`Order`] type
+/// parameter and incomparable scores sinking to the back.
+///
+/// [`PriorityQueue`]: crate::PriorityQueue
+///
+/// # Examples
+///
+/// ```
+/// use priq::DaryHeap;
+///
+/// let mut pq: DaryHeap<u32, &str, 4> = DaryHeap::new();
+/// pq.put(3, "c");
+/// pq.put(1, "a");
+/// pq.put(2, "b");
+///
+/// assert_eq!(Some((1, "a")), pq.pop());
+/// assert_eq!(Some((2, "b")), pq.pop());
+/// assert_eq!(Some((3, "c")), pq.pop());
+/// ```
+pub struct DaryHeap<S, T, const D: usize, O = Min> {
+    data: Vec<(S, T)>,
+    _order: PhantomData<O>,
+}
+
+impl<S, T, const D: usize> DaryHeap<S, T, D>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `DaryHeap` where the lowest score is on top. Using
+    /// the heap with `D` less than two is a compile error.
+    #[must_use]
+    pub fn new() -> Self {
+        DaryHeap::from_data(Vec::new())
+    }
+
+    /// Create an empty `DaryHeap` with space for at least `cap` elements.
+    #[must_use]
+    pub fn with_capacity(cap: usize) -> Self {
+        DaryHeap::from_data(Vec::with_capacity(cap))
+    }
+}
+
+impl<S, T, const D: usize> DaryHeap<S, T, D, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `DaryHeap` where the highest score is on top.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::DaryHeap;
+    ///
+    /// let mut pq: DaryHeap<u32, char, 8, _> = DaryHeap::new_max();
+    /// pq.extend([(1, 'a'), (3, 'c'), (2, 'b')]);
+    ///
+    /// assert_eq!(Some((3, 'c')), pq.pop());
+    /// ```
+    #[must_use]
+    pub fn new_max() -> Self {
+        DaryHeap::from_data(Vec::new())
+    }
+}
+
+impl<S, T, const D: usize, O> DaryHeap<S, T, D, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    const ARITY: usize = {
+        assert!(D >= 2, "`DaryHeap` needs at least two children per node");
+        D
+    };
+
+    /// Takes over the elements and restores the heap order.
+    fn from_data(data: Vec<(S, T)>) -> Self {
+        let mut pq = DaryHeap { data, _order: PhantomData };
+        pq.rebuild();
+        pq
+    }
+
+    /// Inserts an element in the heap.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log<sub>D</sub>(n))***.
+    pub fn put(&mut self, score: S, item: T) {
+        self.data.push((score, item));
+        self.heapify_up(self.data.len() - 1);
+    }
+
+    /// Get the top priority element from `DaryHeap`.
+    ///
+    /// # Time Complexity
+    ///
+    /// Worst case is ***O(D log<sub>D</sub>(n))***.
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        if self.data.is_empty() {
+            return None
+        }
+        let top = self.data.swap_remove(0);
+        if self.data.len() > 1 { self.heapify_down(0); }
+        Some(top)
+    }
+
+    /// Check what is a top element in `DaryHeap`, by getting the reference.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek(&self) -> Option<&(S, T)> {
+        self.data.first()
+    }
+
+    /// Returns the number of elements in the `DaryHeap`
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` is there are no elements in `DaryHeap`
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the number of elements the heap can hold without
+    /// reallocating.
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Remove all the elements from `DaryHeap`
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Consumes the heap and returns the elements in the heap order (NOT
+    /// sorted).
+    pub fn into_vec(self) -> Vec<(S, T)> {
+        self.data
+    }
+
+    /// Restores the heap order from the bottom up.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)***
+    fn rebuild(&mut self) {
+        sift::heapify(&mut self.data, Self::ARITY, Self::less);
+    }
+
+    /// Checks if element `lhs` should be placed closer to the root than `rhs`.
+    #[inline]
+    fn less(lhs: &(S, T), rhs: &(S, T)) -> bool {
+        sift::precedes::<S, O>(&lhs.0, &rhs.0)
+    }
+
+    fn heapify_up(&mut self, index: usize) {
+        sift::sift_up(&mut self.data, index, Self::ARITY, Self::less);
+    }
+
+    fn heapify_down(&mut self, index: usize) {
+        sift::sift_down(&mut self.data, index, Self::ARITY, Self::less);
+    }
+}
+
+impl<S, T, const D: usize, O> Default for DaryHeap<S, T, D, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn default() -> Self {
+        DaryHeap::from_data(Vec::new())
+    }
+}
+
+impl<S, T, const D: usize, O> Deref for DaryHeap<S, T, D, O> {
+    type Target = [(S, T)];
+
+    fn deref(&self) -> &[(S, T)] {
+        &self.data
+    }
+}
+
+impl<S, T, const D: usize> From<Vec<(S, T)>> for DaryHeap<S, T, D>
+where
+    S: PartialOrd,
+{
+    /// Builds the heap from the elements in ***O(n)***.
+    fn from(vec: Vec<(S, T)>) -> Self {
+        DaryHeap::from_data(vec)
+    }
+}
+
+impl<S, T, const D: usize> FromIterator<(S, T)> for DaryHeap<S, T, D>
+where
+    S: PartialOrd,
+{
+    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
+        DaryHeap::from_data(iter.into_iter().collect())
+    }
+}
+
+impl<S, T, const D: usize, O> Extend<(S, T)> for DaryHeap<S, T, D, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.data.reserve(iter.size_hint().0);
+        iter.for_each(|(s, e)| self.put(s, e));
+    }
+}
+
+impl<S, T, const D: usize, O> IntoIterator for DaryHeap<S, T, D, O> {
+    type Item = (S, T);
+    type IntoIter = vec::IntoIter<(S, T)>;
+
+    /// Consumes the heap returning elements in arbitrary order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<S, T, const D: usize, O> Clone for DaryHeap<S, T, D, O>
+where
+    S: Clone,
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        DaryHeap { data: self.data.clone(), _order: PhantomData }
+    }
+}
+
+impl<S, T, const D: usize, O> fmt::Debug for DaryHeap<S, T, D, O>
+where
+    S: fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DaryHeap")
+         .field("arity", &D)
+         .field("data", &self.data)
+         .finish()
+    }
+}
@@ -0,0 +1,228 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use crate::{Max, Min, Order};
+use crate::sift;
+
+/// A priority queue where the effective score of every element depends on
+/// time. Stored scores are never rewritten, instead the decay function
+/// computes the effective score `decay(&score, &now)` whenever elements are
+/// compared, with the `now` clock value kept in the queue and advanced by the
+/// caller with [`set_now`].
+///
+/// Advancing the clock is ***O(1)***, the heap is not rebuilt. This is
+/// correct as long as the decay doesn't change the relative order of the
+/// elements already in the queue, like exponential decay or linear aging
+/// with the same rate for every element, which compare differently only to
+/// the elements put later. If the order of the stored elements can change,
+/// [`rebuild`] should be called after the clock moves.
+///
+/// [`set_now`]: DecayPriorityQueue::set_now
+/// [`rebuild`]: DecayPriorityQueue::rebuild
+///
+/// # Examples
+///
+/// ```
+/// use priq::DecayPriorityQueue;
+///
+/// // score halves every 10 ticks since the element was put
+/// let half_life = |&(score, at): &(f64, u32), now: &u32| {
+///     score * 0.5f64.powf((now - at) as f64 / 10.0)
+/// };
+/// let mut pq = DecayPriorityQueue::new_max(0, half_life);
+///
+/// pq.put((8.0, 0), "old news");
+/// pq.set_now(20);
+/// pq.put((3.0, 20), "fresh news");
+///
+/// assert_eq!(Some(3.0), pq.peek_score());
+/// assert_eq!("fresh news", pq.pop().unwrap().1);
+/// assert_eq!(Some(2.0), pq.peek_score());
+/// ```
+pub struct DecayPriorityQueue<S, T, C, K, F, O = Min> {
+    data: Vec<(S, T)>,
+    now: C,
+    decay: F,
+    _marker: PhantomData<fn() -> (K, O)>,
+}
+
+impl<S, T, C, K, F> DecayPriorityQueue<S, T, C, K, F>
+where
+    K: PartialOrd,
+    F: Fn(&S, &C) -> K,
+{
+    /// Create an empty `DecayPriorityQueue` where the element with the
+    /// lowest effective score at the given time is on top.
+    #[must_use]
+    pub fn new(now: C, decay: F) -> Self {
+        DecayPriorityQueue::with_clock(now, decay)
+    }
+}
+
+impl<S, T, C, K, F> DecayPriorityQueue<S, T, C, K, F, Max>
+where
+    K: PartialOrd,
+    F: Fn(&S, &C) -> K,
+{
+    /// Create an empty `DecayPriorityQueue` where the element with the
+    /// highest effective score at the given time is on top.
+    #[must_use]
+    pub fn new_max(now: C, decay: F) -> Self {
+        DecayPriorityQueue::with_clock(now, decay)
+    }
+}
+
+impl<S, T, C, K, F, O> DecayPriorityQueue<S, T, C, K, F, O>
+where
+    K: PartialOrd,
+    F: Fn(&S, &C) -> K,
+    O: Order,
+{
+    fn with_clock(now: C, decay: F) -> Self {
+        DecayPriorityQueue {
+            data: Vec::new(),
+            now,
+            decay,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the current value of the clock.
+    pub fn now(&self) -> &C {
+        &self.now
+    }
+
+    /// Advances the clock, changing effective scores of all the elements.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***, elements are not re-scored.
+    pub fn set_now(&mut self, now: C) {
+        self.now = now;
+    }
+
+    /// Inserts an element with the stored score in the heap.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(n))***.
+    pub fn put(&mut self, score: S, item: T) {
+        self.data.push((score, item));
+        self.heapify_up(self.data.len() - 1);
+    }
+
+    /// Get the top priority element from `DecayPriorityQueue`, with its
+    /// stored score.
+    ///
+    /// # Time Complexity
+    ///
+    /// Worst case is ***O(log(n))***.
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        if self.data.is_empty() {
+            return None
+        }
+        let top = self.data.swap_remove(0);
+        if self.data.len() > 1 { self.heapify_down(0); }
+        Some(top)
+    }
+
+    /// Check what is a top element in `DecayPriorityQueue`, by getting the
+    /// reference.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek(&self) -> Option<&(S, T)> {
+        self.data.first()
+    }
+
+    /// Computes the effective score of a top element at the current time.
+    pub fn peek_score(&self) -> Option<K> {
+        self.peek().map(|(s, _)| (self.decay)(s, &self.now))
+    }
+
+    /// Returns the number of elements in the `DecayPriorityQueue`
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` is there are no elements in `DecayPriorityQueue`
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Remove all the elements from `DecayPriorityQueue`
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Restores the heap order with effective scores at the current time.
+    /// Needed only if the decay changes the relative order of the elements.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)***
+    pub fn rebuild(&mut self) {
+        let (decay, now) = (&self.decay, &self.now);
+        sift::heapify(&mut self.data, 2, |a, b| {
+            sift::precedes::<K, O>(&decay(&a.0, now), &decay(&b.0, now))
+        });
+    }
+
+    /// Consumes the priority queue and returns the elements in the heap
+    /// order (NOT sorted).
+    pub fn into_vec(self) -> Vec<(S, T)> {
+        self.data
+    }
+
+    fn heapify_up(&mut self, index: usize) {
+        let (decay, now) = (&self.decay, &self.now);
+        sift::sift_up(&mut self.data, index, 2, |a, b| {
+            sift::precedes::<K, O>(&decay(&a.0, now), &decay(&b.0, now))
+        });
+    }
+
+    fn heapify_down(&mut self, index: usize) {
+        let (decay, now) = (&self.decay, &self.now);
+        sift::sift_down(&mut self.data, index, 2, |a, b| {
+            sift::precedes::<K, O>(&decay(&a.0, now), &decay(&b.0, now))
+        });
+    }
+}
+
+impl<S, T, C, K, F, O> Deref for DecayPriorityQueue<S, T, C, K, F, O> {
+    type Target = [(S, T)];
+
+    fn deref(&self) -> &[(S, T)] {
+        &self.data
+    }
+}
+
+impl<S, T, C, K, F, O> Extend<(S, T)> for DecayPriorityQueue<S, T, C, K, F, O>
+where
+    K: PartialOrd,
+    F: Fn(&S, &C) -> K,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.data.reserve(iter.size_hint().0);
+        iter.for_each(|(s, e)| self.put(s, e));
+    }
+}
+
+impl<S, T, C, K, F, O> fmt::Debug for DecayPriorityQueue<S, T, C, K, F, O>
+where
+    S: fmt::Debug,
+    T: fmt::Debug,
+    C: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecayPriorityQueue")
+         .field("data", &self.data)
+         .field("now", &self.now)
+         .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,282 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::Deref;
+
+/// A double-ended priority queue, which serves both the lowest and the
+/// highest score in ***O(log(n))***, e.g. to evict the worst element when a
+/// bounded queue is full while still popping the best one.
+///
+/// It's a min-max heap, where the levels of the tree alternate between
+/// ordering by the lowest and by the highest score. Scores that can't be
+/// compared even with themselves, like `NAN`, are treated as the highest.
+///
+/// # Examples
+///
+/// ```
+/// use priq::DoubleEndedPriorityQueue;
+///
+/// let mut pq = DoubleEndedPriorityQueue::new();
+/// pq.put(3, "c");
+/// pq.put(1, "a");
+/// pq.put(5, "e");
+/// pq.put(4, "d");
+///
+/// assert_eq!(Some(&(1, "a")), pq.peek_min());
+/// assert_eq!(Some((5, "e")), pq.pop_max());
+/// assert_eq!(Some((1, "a")), pq.pop_min());
+/// assert_eq!(Some((4, "d")), pq.pop_max());
+/// ```
+#[derive(Clone)]
+pub struct DoubleEndedPriorityQueue<S, T> {
+    data: Vec<(S, T)>,
+}
+
+impl<S, T> DoubleEndedPriorityQueue<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `DoubleEndedPriorityQueue`.
+    #[must_use]
+    pub fn new() -> Self {
+        DoubleEndedPriorityQueue { data: Vec::new() }
+    }
+
+    /// Create an empty `DoubleEndedPriorityQueue` with space for at least
+    /// `cap` elements.
+    #[must_use]
+    pub fn with_capacity(cap: usize) -> Self {
+        DoubleEndedPriorityQueue { data: Vec::with_capacity(cap) }
+    }
+
+    /// Inserts an element in the heap.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(n))***.
+    pub fn put(&mut self, score: S, item: T) {
+        self.data.push((score, item));
+        self.bubble_up(self.data.len() - 1);
+    }
+
+    /// Get the element with the lowest score.
+    ///
+    /// # Time Complexity
+    ///
+    /// Worst case is ***O(log(n))***.
+    pub fn pop_min(&mut self) -> Option<(S, T)> {
+        self.remove(0)
+    }
+
+    /// Get the element with the highest score.
+    ///
+    /// # Time Complexity
+    ///
+    /// Worst case is ***O(log(n))***.
+    pub fn pop_max(&mut self) -> Option<(S, T)> {
+        self.remove(self.max_index()?)
+    }
+
+    /// Check what is the element with the lowest score, by getting the
+    /// reference.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek_min(&self) -> Option<&(S, T)> {
+        self.data.first()
+    }
+
+    /// Check what is the element with the highest score, by getting the
+    /// reference.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek_max(&self) -> Option<&(S, T)> {
+        self.data.get(self.max_index()?)
+    }
+
+    /// Returns the number of elements in the `DoubleEndedPriorityQueue`
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` is there are no elements in `DoubleEndedPriorityQueue`
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Remove all the elements from `DoubleEndedPriorityQueue`
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Consumes the priority queue and returns the elements in the heap
+    /// order (NOT sorted).
+    pub fn into_vec(self) -> Vec<(S, T)> {
+        self.data
+    }
+
+    /// Consumes the priority queue and returns elements sorted from the
+    /// lowest score to the highest.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n log(n))***
+    pub fn into_sorted_vec(mut self) -> Vec<(S, T)> {
+        let mut res = Vec::with_capacity(self.len());
+        while let Some(elem) = self.pop_min() {
+            res.push(elem);
+        }
+        res
+    }
+
+    /// Orders the scores totally, placing the ones that can't be compared
+    /// even with themselves after all the others.
+    #[inline]
+    fn order(&self, a: usize, b: usize) -> Ordering {
+        let (lhs, rhs) = (&self.data[a].0, &self.data[b].0);
+        match lhs.partial_cmp(rhs) {
+            Some(ord) => ord,
+            None => {
+                let lhs_nan = lhs.partial_cmp(lhs).is_none();
+                let rhs_nan = rhs.partial_cmp(rhs).is_none();
+                lhs_nan.cmp(&rhs_nan)
+            },
+        }
+    }
+
+    /// Nodes on the even levels of the tree, starting with the root, are
+    /// the lowest of their subtrees, and on the odd levels the highest.
+    #[inline]
+    fn direction(index: usize) -> Ordering {
+        if (index + 1).ilog2().is_multiple_of(2) { Ordering::Less } else { Ordering::Greater }
+    }
+
+    fn max_index(&self) -> Option<usize> {
+        match self.data.len() {
+            0 => None,
+            1 => Some(0),
+            2 => Some(1),
+            _ => Some(if self.order(2, 1) == Ordering::Greater { 2 } else { 1 }),
+        }
+    }
+
+    fn remove(&mut self, index: usize) -> Option<(S, T)> {
+        if index >= self.data.len() {
+            return None
+        }
+        let elem = self.data.swap_remove(index);
+        if index < self.data.len() {
+            self.trickle_down(index);
+        }
+        Some(elem)
+    }
+
+    fn bubble_up(&mut self, index: usize) {
+        if index == 0 {
+            return
+        }
+        let dir = Self::direction(index);
+        let parent_ = (index - 1) / 2;
+        if self.order(parent_, index) == dir {
+            self.data.swap(parent_, index);
+            self.bubble_up_by(parent_, dir.reverse());
+        } else {
+            self.bubble_up_by(index, dir);
+        }
+    }
+
+    /// Moves the node up through its grandparents while it goes before
+    /// them in the given direction.
+    fn bubble_up_by(&mut self, mut index: usize, dir: Ordering) {
+        while index > 2 {
+            let grandparent = ((index - 1) / 2 - 1) / 2;
+            if self.order(index, grandparent) != dir {
+                break
+            }
+            self.data.swap(grandparent, index);
+            index = grandparent;
+        }
+    }
+
+    fn trickle_down(&mut self, mut index: usize) {
+        let dir = Self::direction(index);
+        loop {
+            let first = 2 * index + 1;
+            let mut best = index;
+            let descendants = [first, first + 1]
+                .into_iter()
+                .chain(2 * first + 1..2 * first + 5);
+            for i in descendants.take_while(|&i| i < self.data.len()) {
+                if self.order(i, best) == dir {
+                    best = i;
+                }
+            }
+            if best == index {
+                return
+            }
+            self.data.swap(index, best);
+            if best <= first + 1 {
+                return
+            }
+            let parent_ = (best - 1) / 2;
+            if self.order(parent_, best) == dir {
+                self.data.swap(parent_, best);
+            }
+            index = best;
+        }
+    }
+}
+
+impl<S, T> Default for DoubleEndedPriorityQueue<S, T>
+where
+    S: PartialOrd,
+{
+    fn default() -> Self {
+        DoubleEndedPriorityQueue::new()
+    }
+}
+
+impl<S, T> Deref for DoubleEndedPriorityQueue<S, T> {
+    type Target = [(S, T)];
+
+    fn deref(&self) -> &[(S, T)] {
+        &self.data
+    }
+}
+
+impl<S, T> FromIterator<(S, T)> for DoubleEndedPriorityQueue<S, T>
+where
+    S: PartialOrd,
+{
+    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
+        let mut pq = DoubleEndedPriorityQueue::new();
+        pq.extend(iter);
+        pq
+    }
+}
+
+impl<S, T> Extend<(S, T)> for DoubleEndedPriorityQueue<S, T>
+where
+    S: PartialOrd,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.data.reserve(iter.size_hint().0);
+        iter.for_each(|(s, e)| self.put(s, e));
+    }
+}
+
+impl<S, T> fmt::Debug for DoubleEndedPriorityQueue<S, T>
+where
+    S: fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DoubleEndedPriorityQueue")
+         .field("data", &self.data)
+         .finish()
+    }
+}
@@ -0,0 +1,25 @@
+use core::alloc::Allocator;
+
+use defmt::{Format, Formatter};
+
+use crate::{Order, PriorityQueue};
+
+impl<S, T, O, A> Format for PriorityQueue<S, T, O, A>
+where
+    S: PartialOrd + Format,
+    T: Format,
+    O: Order,
+    A: Allocator,
+{
+    /// Logs the length, the capacity and the top element of the queue, the
+    /// rest of the elements are left out to keep the frames short.
+    fn format(&self, f: Formatter<'_>) {
+        defmt::write!(
+            f,
+            "PriorityQueue {{ len: {=usize}, capacity: {=usize}, top: {} }}",
+            self.len(),
+            self.capacity(),
+            self.peek(),
+        );
+    }
+}
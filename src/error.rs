@@ -0,0 +1,102 @@
+use core::alloc::Layout;
+use core::error::Error;
+use core::fmt;
+
+/// The error type for [`try_reserve`] and [`try_put`] methods.
+///
+/// [`try_reserve`]: crate::PriorityQueue::try_reserve
+/// [`try_put`]: crate::PriorityQueue::try_put
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TryReserveError {
+    /// Requested capacity exceeds the maximum possible size of the array.
+    CapacityOverflow,
+
+    /// Memory allocator returned an error.
+    AllocError {
+        /// The layout of the allocation request that failed.
+        layout: Layout,
+    },
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("memory allocation failed")?;
+        match self {
+            TryReserveError::CapacityOverflow => {
+                f.write_str(" because the computed capacity exceeded the \
+                             priority queue's maximum")
+            }
+            TryReserveError::AllocError { .. } => {
+                f.write_str(" because the memory allocator returned an error")
+            }
+        }
+    }
+}
+
+impl Error for TryReserveError {}
+
+/// The reason why [`try_put`] didn't put an element in the queue.
+///
+/// [`try_put`]: crate::PriorityQueue::try_put
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PutErrorKind {
+    /// There's no space for a new element and memory can't be allocated.
+    Alloc(TryReserveError),
+
+    /// Score of the element can't be compared and the queue rejects such
+    /// scores, see [`NanPolicy::Reject`].
+    ///
+    /// [`NanPolicy::Reject`]: crate::NanPolicy::Reject
+    Incomparable,
+}
+
+/// The error returned from [`try_put`] when a new element can't be put in the
+/// queue. Gives back the element which couldn't be put in the queue.
+///
+/// [`try_put`]: crate::PriorityQueue::try_put
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PutError<E> {
+    pub(crate) element: E,
+    pub(crate) kind: PutErrorKind,
+}
+
+impl<E> PutError<E> {
+    /// Returns the reason why the element was not put in the queue. New
+    /// reasons may be added, so matching on it needs a wildcard arm.
+    pub fn kind(&self) -> &PutErrorKind {
+        &self.kind
+    }
+
+    /// Returns the allocation error if the element was not put in the queue
+    /// because memory couldn't be allocated.
+    pub fn error(&self) -> Option<&TryReserveError> {
+        match &self.kind {
+            PutErrorKind::Alloc(error) => Some(error),
+            PutErrorKind::Incomparable => None,
+        }
+    }
+
+    /// Consumes the error returning the element that was not put in the
+    /// queue.
+    pub fn into_inner(self) -> E {
+        self.element
+    }
+}
+
+impl<E> fmt::Display for PutError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            PutErrorKind::Alloc(error) =>
+                write!(f, "failed to put an element: {}", error),
+            PutErrorKind::Incomparable =>
+                f.write_str("failed to put an element: score is incomparable"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> Error for PutError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error().map(|e| e as &(dyn Error + 'static))
+    }
+}
@@ -0,0 +1,219 @@
+//! C interface to a priority queue of `double` scores and opaque pointers.
+//!
+//! The queue owns only its own memory: items are passed through as they are
+//! and never dereferenced nor freed, so the caller stays responsible for the
+//! memory they point to. Panics never cross the boundary, every function
+//! reports them with its return value instead.
+//!
+//! The declarations are shipped in `include/priq.h`. The crate is built as a
+//! Rust library only, so that the `no_std` and the other feature sets keep
+//! building; a static or a dynamic library for C is built with the crate
+//! type passed explicitly:
+//!
+//! ```sh
+//! cargo rustc --release --features ffi --crate-type staticlib
+//! cc main.c -Iinclude target/release/libpriq.a -lpthread -ldl -lm
+//! ```
+//!
+//! and `--crate-type cdylib` for `libpriq.so` (`.dylib`, `.dll`).
+
+use std::ffi::{c_int, c_void};
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{Max, PriorityQueue};
+
+/// Returned when an element was put, popped or peeked.
+pub const PRIQ_OK: c_int = 0;
+
+/// Returned by [`priq_pop`] and [`priq_peek`] when the queue is empty.
+pub const PRIQ_EMPTY: c_int = 1;
+
+/// Returned when the queue pointer is null or the operation panicked, for
+/// example because the capacity of the queue overflowed.
+pub const PRIQ_ERROR: c_int = -1;
+
+/// An opaque priority queue handed out to C, created with [`priq_new`] or
+/// [`priq_new_max`] and destroyed with [`priq_free`].
+pub struct PriqQueue {
+    inner: Inner,
+}
+
+enum Inner {
+    Min(PriorityQueue<f64, *mut c_void>),
+    Max(PriorityQueue<f64, *mut c_void, Max>),
+}
+
+impl PriqQueue {
+    fn put(&mut self, score: f64, item: *mut c_void) {
+        match &mut self.inner {
+            Inner::Min(pq) => pq.put(score, item),
+            Inner::Max(pq) => pq.put(score, item),
+        }
+    }
+
+    fn pop(&mut self) -> Option<(f64, *mut c_void)> {
+        match &mut self.inner {
+            Inner::Min(pq) => pq.pop(),
+            Inner::Max(pq) => pq.pop(),
+        }
+    }
+
+    fn peek(&self) -> Option<(f64, *mut c_void)> {
+        match &self.inner {
+            Inner::Min(pq) => pq.peek().copied(),
+            Inner::Max(pq) => pq.peek().copied(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match &self.inner {
+            Inner::Min(pq) => pq.len(),
+            Inner::Max(pq) => pq.len(),
+        }
+    }
+
+    fn clear(&mut self) {
+        match &mut self.inner {
+            Inner::Min(pq) => pq.clear(),
+            Inner::Max(pq) => pq.clear(),
+        }
+    }
+}
+
+/// Runs `f` so that a panic is turned into [`PRIQ_ERROR`].
+fn guard<F: FnOnce() -> c_int>(f: F) -> c_int {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(PRIQ_ERROR)
+}
+
+fn new_queue(inner: Inner) -> *mut PriqQueue {
+    Box::into_raw(Box::new(PriqQueue { inner }))
+}
+
+/// Creates an empty queue where the lowest score is on top. It has to be
+/// destroyed with [`priq_free`].
+#[no_mangle]
+pub extern "C" fn priq_new() -> *mut PriqQueue {
+    new_queue(Inner::Min(PriorityQueue::new()))
+}
+
+/// Creates an empty queue where the highest score is on top. It has to be
+/// destroyed with [`priq_free`].
+#[no_mangle]
+pub extern "C" fn priq_new_max() -> *mut PriqQueue {
+    new_queue(Inner::Max(PriorityQueue::new_max()))
+}
+
+/// Inserts an element in the queue, returning [`PRIQ_OK`] or
+/// [`PRIQ_ERROR`]. `NaN` scores are kept after all the other ones.
+///
+/// # Safety
+///
+/// `pq` has to be null or a live queue created by this module, which is not
+/// used by another thread at the same time.
+#[no_mangle]
+pub unsafe extern "C" fn priq_put(pq: *mut PriqQueue, score: f64, item: *mut c_void) -> c_int {
+    match pq.as_mut() {
+        Some(pq) => guard(|| {
+            pq.put(score, item);
+            PRIQ_OK
+        }),
+        None => PRIQ_ERROR,
+    }
+}
+
+/// Removes the top element, writing its score and item through the output
+/// pointers which aren't null. Returns [`PRIQ_OK`], [`PRIQ_EMPTY`] or
+/// [`PRIQ_ERROR`].
+///
+/// # Safety
+///
+/// `pq` has to be null or a live queue created by this module, which is not
+/// used by another thread at the same time. `score` and `item` have to be
+/// null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn priq_pop(
+    pq: *mut PriqQueue,
+    score: *mut f64,
+    item: *mut *mut c_void,
+) -> c_int {
+    match pq.as_mut() {
+        Some(pq) => guard(|| write_out(pq.pop(), score, item)),
+        None => PRIQ_ERROR,
+    }
+}
+
+/// Writes the score and item of the top element through the output pointers
+/// which aren't null, without removing it. Returns [`PRIQ_OK`],
+/// [`PRIQ_EMPTY`] or [`PRIQ_ERROR`].
+///
+/// # Safety
+///
+/// Same as [`priq_pop`].
+#[no_mangle]
+pub unsafe extern "C" fn priq_peek(
+    pq: *const PriqQueue,
+    score: *mut f64,
+    item: *mut *mut c_void,
+) -> c_int {
+    match pq.as_ref() {
+        Some(pq) => write_out(pq.peek(), score, item),
+        None => PRIQ_ERROR,
+    }
+}
+
+/// Returns the number of elements in the queue, or zero if `pq` is null.
+///
+/// # Safety
+///
+/// `pq` has to be null or a live queue created by this module.
+#[no_mangle]
+pub unsafe extern "C" fn priq_len(pq: *const PriqQueue) -> usize {
+    pq.as_ref().map_or(0, PriqQueue::len)
+}
+
+/// Removes all the elements from the queue. The items are not freed.
+///
+/// # Safety
+///
+/// `pq` has to be null or a live queue created by this module, which is not
+/// used by another thread at the same time.
+#[no_mangle]
+pub unsafe extern "C" fn priq_clear(pq: *mut PriqQueue) {
+    if let Some(pq) = pq.as_mut() {
+        pq.clear();
+    }
+}
+
+/// Destroys the queue. The items are not freed. Does nothing if `pq` is
+/// null.
+///
+/// # Safety
+///
+/// `pq` has to be null or a live queue created by this module, which is not
+/// used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn priq_free(pq: *mut PriqQueue) {
+    if !pq.is_null() {
+        drop(Box::from_raw(pq));
+    }
+}
+
+/// Writes the element through the output pointers which aren't null.
+///
+/// # Safety
+///
+/// `score` and `item` have to be null or valid for writes.
+unsafe fn write_out(elem: Option<(f64, *mut c_void)>, score: *mut f64, item: *mut *mut c_void) -> c_int {
+    match elem {
+        Some((s, i)) => {
+            if !score.is_null() {
+                score.write(s);
+            }
+            if !item.is_null() {
+                item.write(i);
+            }
+            PRIQ_OK
+        }
+        None => PRIQ_EMPTY,
+    }
+}
@@ -0,0 +1,431 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+
+use crate::{Handle, Max, Min, Order};
+use crate::sift;
+use crate::slab::{self, Slab, NIL};
+
+#[derive(Clone)]
+struct Node<S, T> {
+    elem: (S, T),
+    parent: usize,
+    child: usize,
+    /// Neighbours in the circular list of siblings or roots.
+    left: usize,
+    right: usize,
+    degree: usize,
+    /// Whether the node lost a child since it became a child itself.
+    mark: bool,
+}
+
+/// A Fibonacci heap, a tree based priority queue with ***O(1)*** put and
+/// merge, amortized ***O(1)*** [`decrease_key`], and amortized
+/// ***O(log(n))*** pop. These are the best known bounds, though constant
+/// factors are higher than of the [`PairingHeap`], which is usually faster
+/// in practice.
+///
+/// Every [`put`] returns a [`Handle`] of the element for [`decrease_key`].
+/// Elements are ordered by their scores the same way as in
+/// [`PriorityQueue`], including the direction given by the [`Order`] type
+/// parameter.
+///
+/// [`put`]: FibonacciHeap::put
+/// [`decrease_key`]: FibonacciHeap::decrease_key
+/// [`PairingHeap`]: crate::PairingHeap
+/// [`PriorityQueue`]: crate::PriorityQueue
+///
+/// # Examples
+///
+/// ```
+/// use priq::FibonacciHeap;
+///
+/// let mut pq = FibonacciHeap::new();
+/// pq.put(5, "a");
+/// let b = pq.put(7, "b");
+/// pq.put(3, "c");
+///
+/// pq.decrease_key(b, 1);
+/// assert_eq!(Some((1, "b")), pq.pop());
+/// assert_eq!(Some((3, "c")), pq.pop());
+/// ```
+#[derive(Clone)]
+pub struct FibonacciHeap<S, T, O = Min> {
+    nodes: Slab<Node<S, T>>,
+    top: usize,
+    len: usize,
+    _order: PhantomData<O>,
+}
+
+impl<S, T> FibonacciHeap<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `FibonacciHeap` where the lowest score is on top.
+    #[must_use]
+    pub fn new() -> Self {
+        FibonacciHeap::default()
+    }
+
+    /// Create an empty `FibonacciHeap` with space for at least `cap`
+    /// elements.
+    #[must_use]
+    pub fn with_capacity(cap: usize) -> Self {
+        let mut pq = FibonacciHeap::default();
+        pq.nodes.reserve_exact(cap);
+        pq
+    }
+}
+
+impl<S, T> FibonacciHeap<S, T, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `FibonacciHeap` where the highest score is on top.
+    #[must_use]
+    pub fn new_max() -> Self {
+        FibonacciHeap::default()
+    }
+}
+
+impl<S, T, O> FibonacciHeap<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Inserts an element in the heap and returns its handle.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn put(&mut self, score: S, item: T) -> Handle {
+        let node = Node {
+            elem: (score, item),
+            parent: NIL,
+            child: NIL,
+            left: NIL,
+            right: NIL,
+            degree: 0,
+            mark: false,
+        };
+        let handle = self.nodes.insert(node);
+        self.add_root(handle.index);
+        self.len += 1;
+        handle
+    }
+
+    /// Get the top priority element from `FibonacciHeap`. Handle of the
+    /// element is no longer valid.
+    ///
+    /// # Time Complexity
+    ///
+    /// Amortized ***O(log(n))***, worst case is ***O(n)***.
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        if self.top == NIL {
+            return None
+        }
+        let top = self.top;
+        for child in self.siblings(self.node(top).child) {
+            self.node_mut(child).parent = NIL;
+            self.unlink(child);
+            self.add_root(child);
+        }
+        let next = self.node(top).right;
+        self.unlink(top);
+        self.top = if next == top { NIL } else { next };
+        self.consolidate();
+        self.len -= 1;
+        Some(self.nodes.remove(top).elem)
+    }
+
+    /// Check what is a top element in `FibonacciHeap`, by getting the
+    /// reference.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek(&self) -> Option<&(S, T)> {
+        if self.top == NIL {
+            return None
+        }
+        Some(&self.node(self.top).elem)
+    }
+
+    /// Returns the element of the handle, or `None` if the element was
+    /// already popped.
+    pub fn get(&self, handle: Handle) -> Option<&(S, T)> {
+        self.nodes.get(handle).map(|node| &node.elem)
+    }
+
+    /// Replaces the score of the element with the one which goes before
+    /// it, e.g. a lower score if the lowest score is on top.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element of the handle was already popped or if the new
+    /// score goes after the old one.
+    ///
+    /// # Time Complexity
+    ///
+    /// Amortized ***O(1)***
+    pub fn decrease_key(&mut self, handle: Handle, score: S) {
+        assert!(self.get(handle).is_some(), "element of the handle was popped");
+        let index = handle.index;
+        assert!(!self.less(index, &score), "new score goes after the old one");
+        self.node_mut(index).elem.0 = score;
+
+        let parent = self.node(index).parent;
+        if parent != NIL && self.precedes(index, parent) {
+            self.cut(index);
+            self.cascading_cut(parent);
+        }
+        if self.precedes(index, self.top) {
+            self.top = index;
+        }
+    }
+
+    /// Moves all the elements of `other` into this heap, leaving `other`
+    /// empty. Handles of the moved elements are translated to the handles
+    /// in this heap by the returned function.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)*** to link the heaps, after their nodes are moved in
+    /// ***O(m)*** for `m` nodes of `other`.
+    pub fn merge(&mut self, other: &mut Self) -> impl Fn(Handle) -> Handle {
+        let offset = self.nodes.append(&mut other.nodes, |node, offset| {
+            node.parent = slab::shift(node.parent, offset);
+            node.child = slab::shift(node.child, offset);
+            node.left = slab::shift(node.left, offset);
+            node.right = slab::shift(node.right, offset);
+        });
+        let top = slab::shift(mem::replace(&mut other.top, NIL), offset);
+        if top != NIL {
+            if self.top == NIL {
+                self.top = top;
+            } else {
+                self.splice(self.top, top);
+                if self.precedes(top, self.top) {
+                    self.top = top;
+                }
+            }
+        }
+        self.len += mem::take(&mut other.len);
+        move |handle| Handle { index: handle.index + offset, ..handle }
+    }
+
+    /// Returns the number of elements in the `FibonacciHeap`
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` is there are no elements in `FibonacciHeap`
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all the elements from `FibonacciHeap`, invalidating all the
+    /// handles.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.top = NIL;
+        self.len = 0;
+    }
+
+    fn node(&self, index: usize) -> &Node<S, T> {
+        self.nodes.node(index)
+    }
+
+    fn node_mut(&mut self, index: usize) -> &mut Node<S, T> {
+        self.nodes.node_mut(index)
+    }
+
+    /// Checks if the score of the node should be placed closer to the root
+    /// than `score`. Scores that can't be compared even with themselves sink
+    /// to the back.
+    #[inline]
+    fn less(&self, index: usize, score: &S) -> bool {
+        let lhs = &self.node(index).elem.0;
+        sift::precedes::<S, O>(lhs, score)
+    }
+
+    /// Checks if the node `a` should be placed closer to the root than `b`.
+    #[inline]
+    fn precedes(&self, a: usize, b: usize) -> bool {
+        self.less(a, &self.node(b).elem.0)
+    }
+
+    /// Collects the nodes of a circular list starting with `first`.
+    fn siblings(&self, first: usize) -> Vec<usize> {
+        let mut res = Vec::new();
+        let mut next = first;
+        while next != NIL {
+            res.push(next);
+            next = self.node(next).right;
+            if next == first { break }
+        }
+        res
+    }
+
+    /// Joins two circular lists into one.
+    fn splice(&mut self, a: usize, b: usize) {
+        let a_right = self.node(a).right;
+        let b_left = self.node(b).left;
+        self.node_mut(a).right = b;
+        self.node_mut(b).left = a;
+        self.node_mut(b_left).right = a_right;
+        self.node_mut(a_right).left = b_left;
+    }
+
+    /// Removes the node from its circular list, leaving it on its own.
+    fn unlink(&mut self, index: usize) {
+        let Node { left, right, .. } = *self.node(index);
+        self.node_mut(left).right = right;
+        self.node_mut(right).left = left;
+        let node = self.node_mut(index);
+        node.left = index;
+        node.right = index;
+    }
+
+    /// Adds a detached node to the list of roots.
+    fn add_root(&mut self, index: usize) {
+        let node = self.node_mut(index);
+        node.left = index;
+        node.right = index;
+        node.mark = false;
+        if self.top == NIL {
+            self.top = index;
+            return
+        }
+        self.splice(self.top, index);
+        if self.precedes(index, self.top) {
+            self.top = index;
+        }
+    }
+
+    /// Makes the root `child` a child of the root `parent`.
+    fn link(&mut self, child: usize, parent: usize) {
+        self.unlink(child);
+        let first = self.node(parent).child;
+        if first != NIL {
+            self.splice(first, child);
+        }
+        let node = self.node_mut(parent);
+        node.child = child;
+        node.degree += 1;
+        let node = self.node_mut(child);
+        node.parent = parent;
+        node.mark = false;
+    }
+
+    /// Links the roots of the same degree until all the roots have
+    /// different degrees, and finds the new top.
+    fn consolidate(&mut self) {
+        let mut by_degree: Vec<usize> = Vec::new();
+        for root in self.siblings(self.top) {
+            let mut root = root;
+            let mut degree = self.node(root).degree;
+            while let Some(&other) = by_degree.get(degree).filter(|&&i| i != NIL) {
+                let (parent, child) = if self.precedes(other, root) {
+                    (other, root)
+                } else {
+                    (root, other)
+                };
+                self.link(child, parent);
+                by_degree[degree] = NIL;
+                root = parent;
+                degree += 1;
+            }
+            if by_degree.len() <= degree {
+                by_degree.resize(degree + 1, NIL);
+            }
+            by_degree[degree] = root;
+        }
+        self.top = NIL;
+        for root in by_degree.into_iter().filter(|&i| i != NIL) {
+            if self.top == NIL || self.precedes(root, self.top) {
+                self.top = root;
+            }
+        }
+    }
+
+    /// Moves the node with its subtree from its parent to the roots.
+    fn cut(&mut self, index: usize) {
+        let parent = self.node(index).parent;
+        let right = self.node(index).right;
+        let node = self.node_mut(parent);
+        node.degree -= 1;
+        if node.child == index {
+            node.child = if right == index { NIL } else { right };
+        }
+        self.unlink(index);
+        self.node_mut(index).parent = NIL;
+        self.add_root(index);
+    }
+
+    /// Cuts the marked ancestors which lost a second child, and marks the
+    /// first unmarked one.
+    fn cascading_cut(&mut self, mut index: usize) {
+        loop {
+            let Node { parent, mark, .. } = *self.node(index);
+            if parent == NIL {
+                return
+            }
+            if !mark {
+                self.node_mut(index).mark = true;
+                return
+            }
+            self.cut(index);
+            index = parent;
+        }
+    }
+}
+
+impl<S, T, O> Default for FibonacciHeap<S, T, O> {
+    fn default() -> Self {
+        FibonacciHeap {
+            nodes: Slab::new(),
+            top: NIL,
+            len: 0,
+            _order: PhantomData,
+        }
+    }
+}
+
+impl<S, T> FromIterator<(S, T)> for FibonacciHeap<S, T>
+where
+    S: PartialOrd,
+{
+    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
+        let mut pq = FibonacciHeap::new();
+        pq.extend(iter);
+        pq
+    }
+}
+
+impl<S, T, O> Extend<(S, T)> for FibonacciHeap<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.nodes.reserve(iter.size_hint().0);
+        iter.for_each(|(s, e)| { self.put(s, e); });
+    }
+}
+
+impl<S, T, O> fmt::Debug for FibonacciHeap<S, T, O>
+where
+    S: fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let elems = self.nodes.iter().map(|node| &node.elem);
+        f.debug_struct("FibonacciHeap")
+         .field("len", &self.len)
+         .field("data", &elems.collect::<Vec<_>>())
+         .finish()
+    }
+}
@@ -0,0 +1,306 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+
+use crate::{Max, Min, Order, PriorityQueue};
+
+/// Floating point types which can be used as scores of [`FloatPriorityQueue`].
+/// Implemented only for `f32` and `f64`.
+pub trait Float: Copy + PartialOrd + Send + Sync + 'static + private::Sealed {
+    /// Total ordering of floats, same as `total_cmp` except that every `NAN`
+    /// is greater than any other number and equal to another `NAN`.
+    fn total_order(&self, other: &Self) -> Ordering;
+
+    /// Checks if two floats differ by no more than `epsilon`. `NAN` is never
+    /// equal to anything.
+    fn approx_eq(&self, other: &Self, epsilon: Self) -> bool;
+}
+
+macro_rules! float_impl {
+    ($($float:ty),*) => {$(
+        impl Float for $float {
+            #[inline]
+            fn total_order(&self, other: &Self) -> Ordering {
+                match (self.is_nan(), other.is_nan()) {
+                    (false, false) => self.total_cmp(other),
+                    (true, true) => Ordering::Equal,
+                    (true, false) => Ordering::Greater,
+                    (false, true) => Ordering::Less,
+                }
+            }
+
+            #[inline]
+            fn approx_eq(&self, other: &Self, epsilon: Self) -> bool {
+                self == other || (self - other).abs() <= epsilon
+            }
+        }
+    )*}
+}
+
+float_impl!(f32, f64);
+
+/// Float score which is always comparable.
+#[derive(Clone, Copy)]
+struct Total<F>(F);
+
+impl<F: Float> PartialEq for Total<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.total_order(&other.0) == Ordering::Equal
+    }
+}
+
+impl<F: Float> Eq for Total<F> {}
+
+impl<F: Float> PartialOrd for Total<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: Float> Ord for Total<F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_order(&other.0)
+    }
+}
+
+/// A priority queue with `f32` or `f64` scores which are totally ordered.
+/// Every `NAN` is treated as the largest score, so it's popped last from the
+/// min-heap and first from the max-heap, and no score is ever quarantined as
+/// incomparable. Negative zero goes before the positive one.
+///
+/// # Examples
+///
+/// ```
+/// use priq::FloatPriorityQueue;
+///
+/// let mut pq = FloatPriorityQueue::new();
+/// pq.put(2.5, "b");
+/// pq.put(f64::NAN, "nan");
+/// pq.put(-1.0, "a");
+///
+/// assert_eq!(Some((-1.0, "a")), pq.pop());
+/// assert_eq!(Some((2.5, "b")), pq.pop());
+/// assert_eq!("nan", pq.pop().unwrap().1);
+/// ```
+pub struct FloatPriorityQueue<F, T, O = Min>
+where
+    F: Float,
+    O: Order,
+{
+    pq: PriorityQueue<Total<F>, T, O>,
+}
+
+impl<F, T> FloatPriorityQueue<F, T>
+where
+    F: Float,
+{
+    /// Create an empty `FloatPriorityQueue` where the lowest score is on top.
+    #[must_use]
+    pub fn new() -> Self {
+        FloatPriorityQueue::default()
+    }
+
+    /// Create an empty `FloatPriorityQueue` with space for at least `cap`
+    /// elements.
+    #[must_use]
+    pub fn with_capacity(cap: usize) -> Self {
+        FloatPriorityQueue { pq: PriorityQueue::with_capacity(cap) }
+    }
+}
+
+impl<F, T> FloatPriorityQueue<F, T, Max>
+where
+    F: Float,
+{
+    /// Create an empty `FloatPriorityQueue` where the highest score is on
+    /// top, `NAN` being the highest of all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::FloatPriorityQueue;
+    ///
+    /// let mut pq = FloatPriorityQueue::new_max();
+    /// pq.extend([(1.5f32, 'a'), (f32::NAN, '?'), (3.0, 'c')]);
+    ///
+    /// assert_eq!('?', pq.pop().unwrap().1);
+    /// assert_eq!(Some((3.0, 'c')), pq.pop());
+    /// ```
+    #[must_use]
+    pub fn new_max() -> Self {
+        FloatPriorityQueue::default()
+    }
+}
+
+impl<F, T, O> FloatPriorityQueue<F, T, O>
+where
+    F: Float,
+    O: Order,
+{
+    /// Inserts an element in the heap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if new capacity overflows `usize`.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(n))***.
+    pub fn put(&mut self, score: F, item: T) {
+        self.pq.put(Total(score), item);
+    }
+
+    /// Get the top priority element from `FloatPriorityQueue`.
+    ///
+    /// # Time Complexity
+    ///
+    /// Worst case is ***O(log(n))***.
+    pub fn pop(&mut self) -> Option<(F, T)> {
+        self.pq.pop().map(|(s, e)| (s.0, e))
+    }
+
+    /// Check what is a top element in `FloatPriorityQueue`, by getting its
+    /// score and a reference to its item.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek(&self) -> Option<(F, &T)> {
+        self.pq.peek().map(|(s, e)| (s.0, e))
+    }
+
+    /// Returns the number of elements in the `FloatPriorityQueue`
+    pub fn len(&self) -> usize {
+        self.pq.len()
+    }
+
+    /// Returns `true` is there are no elements in `FloatPriorityQueue`
+    pub fn is_empty(&self) -> bool {
+        self.pq.is_empty()
+    }
+
+    /// Returns the number of elements the queue can hold without
+    /// reallocating.
+    pub fn capacity(&self) -> usize {
+        self.pq.capacity()
+    }
+
+    /// Remove all the elements from `FloatPriorityQueue`
+    pub fn clear(&mut self) {
+        self.pq.clear();
+    }
+
+    /// Consumes the priority queue and returns elements in the order they
+    /// would be popped.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n log(n))***
+    pub fn into_sorted_vec(self) -> Vec<(F, T)> {
+        self.pq.into_sorted_vec()
+            .into_iter()
+            .map(|(s, e)| (s.0, e))
+            .collect()
+    }
+
+    /// Converts every score into another type, see
+    /// [`PriorityQueue::map_scores_into`].
+    #[cfg(feature = "ordered-float")]
+    pub(crate) fn map_scores_into<S2, G>(self, mut f: G) -> PriorityQueue<S2, T, O>
+    where
+        S2: PartialOrd,
+        G: FnMut(F) -> S2,
+    {
+        self.pq.map_scores_into(|s| f(s.0))
+    }
+}
+
+impl<F, T, O> Default for FloatPriorityQueue<F, T, O>
+where
+    F: Float,
+    O: Order,
+{
+    fn default() -> Self {
+        FloatPriorityQueue { pq: PriorityQueue::default() }
+    }
+}
+
+impl<F, T, O> Extend<(F, T)> for FloatPriorityQueue<F, T, O>
+where
+    F: Float,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = (F, T)>>(&mut self, iter: I) {
+        self.pq.put_many(iter.into_iter().map(|(s, e)| (Total(s), e)));
+    }
+}
+
+impl<F, T> FromIterator<(F, T)> for FloatPriorityQueue<F, T>
+where
+    F: Float,
+{
+    fn from_iter<I: IntoIterator<Item = (F, T)>>(iter: I) -> Self {
+        let mut pq = FloatPriorityQueue::new();
+        pq.extend(iter);
+        pq
+    }
+}
+
+impl<F, T, O> Clone for FloatPriorityQueue<F, T, O>
+where
+    F: Float,
+    T: Clone,
+    O: Order,
+{
+    fn clone(&self) -> Self {
+        FloatPriorityQueue { pq: self.pq.clone() }
+    }
+}
+
+impl<F, T, O> fmt::Debug for FloatPriorityQueue<F, T, O>
+where
+    F: Float + fmt::Debug,
+    T: fmt::Debug,
+    O: Order,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let elems: Vec<_> = self.pq.iter().map(|(s, e)| (s.0, e)).collect();
+        f.debug_struct("FloatPriorityQueue")
+         .field("data", &elems)
+         .finish_non_exhaustive()
+    }
+}
+
+/// Largest integer not greater than `x`, which is available without `std`.
+pub(crate) fn floor(x: f64) -> f64 {
+    // Floats this large have no fractional part.
+    if !x.is_finite() || x.abs() >= (1u64 << 52) as f64 {
+        return x;
+    }
+    let t = x as i64 as f64;
+    if t > x { t - 1.0 } else { t }
+}
+
+/// Smallest integer not less than `x`, which is available without `std`.
+pub(crate) fn ceil(x: f64) -> f64 {
+    -floor(-x)
+}
+
+/// Smallest `n` such that `2^n` is at least `x`, saturating at zero, the same
+/// as `x.log2().ceil() as u32` which is available without `std`.
+pub(crate) fn log2_ceil(x: f64) -> u32 {
+    let mut n = 0;
+    let mut power = 1.0;
+    while power < x && n < f64::MAX_EXP as u32 {
+        power *= 2.0;
+        n += 1;
+    }
+    n
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
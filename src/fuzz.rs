@@ -0,0 +1,43 @@
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{Order, PriorityQueue};
+
+/// Generates a valid heap from arbitrary elements, so fuzz targets can take
+/// priority queues as their input.
+///
+/// # Examples
+///
+/// ```
+/// use arbitrary::{Arbitrary, Unstructured};
+/// use priq::PriorityQueue;
+///
+/// let bytes = [7, 3, 9, 1, 4, 8, 2, 6, 5, 0, 1, 2];
+/// let mut u = Unstructured::new(&bytes);
+/// let pq = PriorityQueue::<u8, u8>::arbitrary(&mut u).unwrap();
+///
+/// let sorted = pq.into_sorted_vec();
+/// assert!(sorted.windows(2).all(|w| w[0].0 <= w[1].0));
+/// ```
+impl<'a, S, T, O> Arbitrary<'a> for PriorityQueue<S, T, O>
+where
+    S: PartialOrd + Arbitrary<'a>,
+    T: Arbitrary<'a>,
+    O: Order,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut pq = PriorityQueue::default();
+        pq.put_many(u.arbitrary_iter()?.collect::<Result<Vec<(S, T)>>>()?);
+        Ok(pq)
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        let mut pq = PriorityQueue::default();
+        pq.put_many(u.arbitrary_take_rest_iter()?.collect::<Result<Vec<(S, T)>>>()?);
+        Ok(pq)
+    }
+
+    #[inline]
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <Vec<(S, T)> as Arbitrary<'a>>::size_hint(depth)
+    }
+}
@@ -0,0 +1,14 @@
+/// Refers to an element put in a heap which supports changing priorities of
+/// its elements, like [`PairingHeap`] or [`FibonacciHeap`].
+///
+/// Handle stays valid until its element is popped from the heap. Handles
+/// of the removed elements are never mistaken for the new ones, even if
+/// their memory is reused.
+///
+/// [`PairingHeap`]: crate::PairingHeap
+/// [`FibonacciHeap`]: crate::FibonacciHeap
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    pub(crate) index: usize,
+    pub(crate) gen: u32,
+}
@@ -0,0 +1,266 @@
+use core::fmt;
+use core::marker::PhantomData;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+
+use crate::{Max, Min, Order};
+use crate::sift;
+
+#[derive(Clone)]
+struct Node<S, T> {
+    elem: (S, T),
+    /// Length of the right spine, which is never longer than the left one.
+    rank: usize,
+    left: Option<Rc<Node<S, T>>>,
+    right: Option<Rc<Node<S, T>>>,
+}
+
+fn rank<S, T>(node: &Option<Rc<Node<S, T>>>) -> usize {
+    node.as_ref().map_or(0, |node| node.rank)
+}
+
+/// A leftist heap, a persistent tree based priority queue which merges two
+/// heaps in ***O(log(n))*** walking only down their short right spines.
+///
+/// Nodes are shared between the versions of the heap, so cloning it is
+/// ***O(1)***, and merging another heap leaves it untouched. Modifying one
+/// version copies only the ***O(log(n))*** nodes on its path, cloning their
+/// elements, so scores and items should be cheap to clone.
+///
+/// Elements are ordered by their scores the same way as in
+/// [`PriorityQueue`], including the direction given by the [`Order`] type
+/// parameter.
+///
+/// [`PriorityQueue`]: crate::PriorityQueue
+///
+/// # Examples
+///
+/// ```
+/// use priq::LeftistHeap;
+///
+/// let mut pq = LeftistHeap::new();
+/// pq.put(2, "b");
+/// pq.put(1, "a");
+///
+/// let snapshot = pq.clone();
+/// pq.put(0, "z");
+/// assert_eq!(Some((0, "z")), pq.pop());
+/// assert_eq!(Some((1, "a")), pq.pop());
+///
+/// assert_eq!(2, snapshot.len());
+/// assert_eq!(Some(&(1, "a")), snapshot.peek());
+/// ```
+pub struct LeftistHeap<S, T, O = Min> {
+    root: Option<Rc<Node<S, T>>>,
+    len: usize,
+    _order: PhantomData<O>,
+}
+
+impl<S, T> LeftistHeap<S, T>
+where
+    S: PartialOrd + Clone,
+    T: Clone,
+{
+    /// Create an empty `LeftistHeap` where the lowest score is on top.
+    #[must_use]
+    pub fn new() -> Self {
+        LeftistHeap::default()
+    }
+}
+
+impl<S, T> LeftistHeap<S, T, Max>
+where
+    S: PartialOrd + Clone,
+    T: Clone,
+{
+    /// Create an empty `LeftistHeap` where the highest score is on top.
+    #[must_use]
+    pub fn new_max() -> Self {
+        LeftistHeap::default()
+    }
+}
+
+impl<S, T, O> LeftistHeap<S, T, O>
+where
+    S: PartialOrd + Clone,
+    T: Clone,
+    O: Order,
+{
+    /// Inserts an element in the heap.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(n))***.
+    pub fn put(&mut self, score: S, item: T) {
+        let node = Node { elem: (score, item), rank: 1, left: None, right: None };
+        let root = self.root.take();
+        self.root = self.merge_nodes(root, Some(Rc::new(node)));
+        self.len += 1;
+    }
+
+    /// Get the top priority element from `LeftistHeap`. The element is
+    /// cloned if the top node is shared with another version of the heap.
+    ///
+    /// # Time Complexity
+    ///
+    /// Worst case is ***O(log(n))***.
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        let root = Rc::unwrap_or_clone(self.root.take()?);
+        self.root = self.merge_nodes(root.left, root.right);
+        self.len -= 1;
+        Some(root.elem)
+    }
+
+    /// Check what is a top element in `LeftistHeap`, by getting the
+    /// reference.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek(&self) -> Option<&(S, T)> {
+        self.root.as_ref().map(|node| &node.elem)
+    }
+
+    /// Adds all the elements of `other` to this heap, sharing its nodes
+    /// instead of copying them, so `other` stays as it is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::{HeapBackend, LeftistHeap};
+    ///
+    /// let mut pq1: LeftistHeap<_, _> = [(2, 'b'), (4, 'd')].into_iter().collect();
+    /// let pq2: LeftistHeap<_, _> = [(1, 'a'), (3, 'c')].into_iter().collect();
+    /// pq1.merge(&pq2);
+    ///
+    /// assert_eq!(2, pq2.len());
+    /// assert_eq!(vec![(1, 'a'), (2, 'b'), (3, 'c'), (4, 'd')], pq1.into_sorted_vec());
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(log(n + m))***
+    pub fn merge(&mut self, other: &Self) {
+        let root = self.root.take();
+        self.root = self.merge_nodes(root, other.root.clone());
+        self.len += other.len;
+    }
+
+    /// Returns the number of elements in the `LeftistHeap`
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` is there are no elements in `LeftistHeap`
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all the elements from `LeftistHeap`
+    pub fn clear(&mut self) {
+        drop_tree(self.root.take());
+        self.len = 0;
+    }
+
+    /// Checks if `lhs` should be placed closer to the root than `rhs`.
+    /// Scores that can't be compared even with themselves sink to the back.
+    #[inline]
+    fn less(&self, lhs: &S, rhs: &S) -> bool {
+        sift::precedes::<S, O>(lhs, rhs)
+    }
+
+    /// Merges the right spines of two trees, swapping the children where
+    /// the right spine becomes longer than the left one.
+    fn merge_nodes(&self, a: Option<Rc<Node<S, T>>>, b: Option<Rc<Node<S, T>>>)
+        -> Option<Rc<Node<S, T>>>
+    {
+        let (a, b) = match (a, b) {
+            (None, node) | (node, None) => return node,
+            (Some(a), Some(b)) => (a, b),
+        };
+        let (top, other) = if self.less(&b.elem.0, &a.elem.0) { (b, a) } else { (a, b) };
+        let mut top = Rc::unwrap_or_clone(top);
+        let right = self.merge_nodes(top.right.take(), Some(other));
+        if rank(&top.left) < rank(&right) {
+            top.right = top.left.take();
+            top.left = right;
+        } else {
+            top.right = right;
+        }
+        top.rank = rank(&top.right) + 1;
+        Some(Rc::new(top))
+    }
+}
+
+/// Drops the nodes one by one, as left spines can be too long for the
+/// recursive drop.
+fn drop_tree<S, T>(root: Option<Rc<Node<S, T>>>) {
+    let mut stack: Vec<_> = root.into_iter().collect();
+    while let Some(node) = stack.pop() {
+        if let Ok(mut node) = Rc::try_unwrap(node) {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+impl<S, T, O> Drop for LeftistHeap<S, T, O> {
+    fn drop(&mut self) {
+        drop_tree(self.root.take());
+    }
+}
+
+impl<S, T, O> Clone for LeftistHeap<S, T, O> {
+    /// Shares all the nodes with the cloned heap in ***O(1)***.
+    fn clone(&self) -> Self {
+        LeftistHeap { root: self.root.clone(), len: self.len, _order: PhantomData }
+    }
+}
+
+impl<S, T, O> Default for LeftistHeap<S, T, O> {
+    fn default() -> Self {
+        LeftistHeap { root: None, len: 0, _order: PhantomData }
+    }
+}
+
+impl<S, T> FromIterator<(S, T)> for LeftistHeap<S, T>
+where
+    S: PartialOrd + Clone,
+    T: Clone,
+{
+    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
+        let mut pq = LeftistHeap::new();
+        pq.extend(iter);
+        pq
+    }
+}
+
+impl<S, T, O> Extend<(S, T)> for LeftistHeap<S, T, O>
+where
+    S: PartialOrd + Clone,
+    T: Clone,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(s, e)| self.put(s, e));
+    }
+}
+
+impl<S, T, O> fmt::Debug for LeftistHeap<S, T, O>
+where
+    S: fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut elems = Vec::with_capacity(self.len);
+        let mut stack: Vec<&Node<S, T>> = self.root.iter().map(|n| &**n).collect();
+        while let Some(node) = stack.pop() {
+            elems.push(&node.elem);
+            stack.extend(node.right.iter().chain(&node.left).map(|n| &**n));
+        }
+        f.debug_struct("LeftistHeap")
+         .field("len", &self.len)
+         .field("data", &elems)
+         .finish()
+    }
+}
@@ -0,0 +1,111 @@
+use core::cmp::Ordering;
+use core::marker::PhantomData;
+
+use crate::{Min, Order};
+
+/// Orders one component of a lexicographic score in the direction of `O`,
+/// so [`Max`] makes greater values go first.
+///
+/// [`Max`]: crate::Max
+#[inline]
+fn directed<O: Order>(ord: Ordering) -> Ordering {
+    if O::TOP == Ordering::Less { ord } else { ord.reverse() }
+}
+
+macro_rules! lex {
+    ($(#[$attr:meta])* $name:ident { $($field:ident: $t:ident / $o:ident),+ }) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+        pub struct $name<$($t),+, $($o = Min),+> {
+            $(
+                #[allow(missing_docs)]
+                pub $field: $t,
+            )+
+            _order: PhantomData<($($o),+)>,
+        }
+
+        impl<$($t),+, $($o),+> $name<$($t),+, $($o),+> {
+            /// Creates a score from its components, the first one being the
+            /// most significant.
+            pub fn new($($field: $t),+) -> Self {
+                $name { $($field),+, _order: PhantomData }
+            }
+        }
+
+        impl<$($t: PartialOrd),+, $($o: Order),+> PartialOrd for $name<$($t),+, $($o),+> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                $(
+                    match self.$field.partial_cmp(&other.$field)? {
+                        Ordering::Equal => {}
+                        ord => return Some(directed::<$o>(ord)),
+                    }
+                )+
+                Some(Ordering::Equal)
+            }
+        }
+
+        impl<$($t: Ord),+, $($o: Order),+> Ord for $name<$($t),+, $($o),+> {
+            fn cmp(&self, other: &Self) -> Ordering {
+                $(
+                    match self.$field.cmp(&other.$field) {
+                        Ordering::Equal => {}
+                        ord => return directed::<$o>(ord),
+                    }
+                )+
+                Ordering::Equal
+            }
+        }
+    }
+}
+
+lex! {
+    /// Score made of two components compared lexicographically, each in its
+    /// own direction given with [`Min`] (ascending, the default) or
+    /// [`Max`] (descending).
+    ///
+    /// Scores are incomparable if the first pair of components which are not
+    /// equal can't be compared.
+    ///
+    /// [`Max`]: crate::Max
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::{Lex2, Max, Min, PriorityQueue};
+    ///
+    /// // earliest deadline first, then the heaviest job
+    /// let mut pq: PriorityQueue<Lex2<u32, f64, Min, Max>, &str> = PriorityQueue::new();
+    /// pq.put(Lex2::new(2, 1.0), "later");
+    /// pq.put(Lex2::new(1, 0.5), "light");
+    /// pq.put(Lex2::new(1, 3.5), "heavy");
+    ///
+    /// assert_eq!("heavy", pq.pop().unwrap().1);
+    /// assert_eq!("light", pq.pop().unwrap().1);
+    /// assert_eq!("later", pq.pop().unwrap().1);
+    /// ```
+    Lex2 { first: A / OA, second: B / OB }
+}
+
+lex! {
+    /// Score made of three components compared lexicographically, each in
+    /// its own direction, see [`Lex2`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::{Lex3, Max, Min, PriorityQueue};
+    ///
+    /// // by deadline, then by weight descending, then by id
+    /// type Score = Lex3<u64, u32, u64, Min, Max, Min>;
+    ///
+    /// let mut pq: PriorityQueue<Score, &str> = PriorityQueue::new();
+    /// pq.put(Lex3::new(10, 5, 2), "b");
+    /// pq.put(Lex3::new(10, 5, 1), "a");
+    /// pq.put(Lex3::new(10, 1, 0), "c");
+    ///
+    /// assert_eq!("a", pq.pop().unwrap().1);
+    /// assert_eq!("b", pq.pop().unwrap().1);
+    /// assert_eq!("c", pq.pop().unwrap().1);
+    /// ```
+    Lex3 { first: A / OA, second: B / OB, third: C / OC }
+}
@@ -0,0 +1,181 @@
+use core::cmp::Ordering;
+
+use crate::{float, Max, NanPolicy, PriorityQueue};
+
+/// Keeps track of the median, or any other quantile, of the scores put in a
+/// stream. Elements are split between two priority queues, a max-heap with
+/// the scores up to the quantile and a min-heap with the rest, so the
+/// element at the quantile is always on top of the first one.
+///
+/// The element at quantile `q` of `n` elements is the one with the rank
+/// `ceil(q * n)` counting from one and from the lowest score, so the median
+/// of an even number of elements is the lower one of the two middle ones.
+/// Scores that can't be compared, like `NAN`, are counted after all the
+/// others.
+///
+/// # Examples
+///
+/// ```
+/// use priq::MedianHeap;
+///
+/// let mut latencies = MedianHeap::new();
+/// latencies.put(12, "a");
+/// latencies.put(3, "b");
+/// assert_eq!(Some(&(3, "b")), latencies.peek());
+///
+/// latencies.put(40, "c");
+/// assert_eq!(Some(&(12, "a")), latencies.peek());
+/// ```
+#[derive(Clone, Debug)]
+pub struct MedianHeap<S, T>
+where
+    S: PartialOrd,
+{
+    low: PriorityQueue<S, T, Max>,
+    high: PriorityQueue<S, T>,
+    quantile: f64,
+}
+
+impl<S, T> MedianHeap<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `MedianHeap` which tracks the median.
+    #[must_use]
+    pub fn new() -> Self {
+        MedianHeap::with_quantile(0.5)
+    }
+
+    /// Create an empty `MedianHeap` which tracks the given quantile, e.g.
+    /// `0.99` for the 99th percentile.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the quantile is not greater than `0.0` and up to `1.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::MedianHeap;
+    ///
+    /// let mut p90 = MedianHeap::with_quantile(0.9);
+    /// (1..=100).for_each(|ms| p90.put(ms, ()));
+    ///
+    /// assert_eq!(Some(&90), p90.peek_score());
+    /// ```
+    #[must_use]
+    pub fn with_quantile(quantile: f64) -> Self {
+        assert!(quantile > 0.0 && quantile <= 1.0, "quantile should be in (0, 1]");
+        // incomparable scores are the highest, so they go first from the
+        // lower heap and last from the upper one
+        let mut low = PriorityQueue::new_max();
+        low.set_nan_policy(NanPolicy::First);
+        MedianHeap { low, high: PriorityQueue::new(), quantile }
+    }
+
+    /// Inserts an element.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(n))***.
+    pub fn put(&mut self, score: S, item: T) {
+        let lower = match self.low.peek_score() {
+            Some(top) => match score.partial_cmp(top) {
+                Some(ord) => ord != Ordering::Greater,
+                // the top can be incomparable only when it's the highest
+                None => score.partial_cmp(&score).is_some(),
+            },
+            None => false,
+        };
+        if lower {
+            self.low.put(score, item);
+        } else {
+            self.high.put(score, item);
+        }
+        self.rebalance();
+    }
+
+    /// Get the element at the quantile, the next element becomes the new
+    /// quantile.
+    ///
+    /// # Time Complexity
+    ///
+    /// Worst case is ***O(log(n))***.
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        let elem = self.low.pop()?;
+        self.rebalance();
+        Some(elem)
+    }
+
+    /// Check what is the element at the quantile, by getting the reference.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek(&self) -> Option<&(S, T)> {
+        self.low.peek()
+    }
+
+    /// Check what is the score at the quantile, by getting the reference.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek_score(&self) -> Option<&S> {
+        self.low.peek_score()
+    }
+
+    /// Returns the tracked quantile.
+    pub fn quantile(&self) -> f64 {
+        self.quantile
+    }
+
+    /// Returns the number of elements in the `MedianHeap`
+    pub fn len(&self) -> usize {
+        self.low.len() + self.high.len()
+    }
+
+    /// Returns `true` is there are no elements in `MedianHeap`
+    pub fn is_empty(&self) -> bool {
+        self.low.is_empty() && self.high.is_empty()
+    }
+
+    /// Remove all the elements from `MedianHeap`
+    pub fn clear(&mut self) {
+        self.low.clear();
+        self.high.clear();
+    }
+
+    /// Moves the elements between the heaps until the lower one holds
+    /// exactly the elements up to the quantile.
+    fn rebalance(&mut self) {
+        let len = self.len();
+        let target = (float::ceil(self.quantile * len as f64) as usize).clamp(len.min(1), len);
+        while self.low.len() > target {
+            let (s, t) = self.low.pop().expect("lower heap is not empty");
+            self.high.put(s, t);
+        }
+        while self.low.len() < target {
+            let (s, t) = self.high.pop().expect("upper heap is not empty");
+            self.low.put(s, t);
+        }
+    }
+}
+
+impl<S, T> Default for MedianHeap<S, T>
+where
+    S: PartialOrd,
+{
+    fn default() -> Self {
+        MedianHeap::new()
+    }
+}
+
+impl<S, T> Extend<(S, T)> for MedianHeap<S, T>
+where
+    S: PartialOrd,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(s, e)| self.put(s, e));
+    }
+}
@@ -0,0 +1,157 @@
+use core::ops::Deref;
+
+use crate::{Max, Min, Order, PriorityQueue};
+
+/// A priority queue for monotone workloads, such as Dijkstra's algorithm or
+/// discrete event simulation, where no element is ever put with a score that
+/// goes before the last popped one.
+///
+/// In debug builds every [`put`] checks this contract and panics if it's
+/// violated, catching time-travel bugs early. In release builds the check is
+/// compiled out and the queue works as a plain [`PriorityQueue`], which it
+/// dereferences to for all the read-only methods.
+///
+/// [`put`]: MonotonePriorityQueue::put
+///
+/// # Examples
+///
+/// ```
+/// use priq::MonotonePriorityQueue;
+///
+/// let mut events = MonotonePriorityQueue::new();
+/// events.put(1.0, "arrive");
+/// events.put(3.0, "leave");
+///
+/// assert_eq!(Some((1.0, "arrive")), events.pop());
+/// events.put(2.0, "serve");
+/// assert_eq!(Some((2.0, "serve")), events.pop());
+/// assert_eq!(1, events.len());
+/// ```
+#[derive(Debug)]
+pub struct MonotonePriorityQueue<S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    pq: PriorityQueue<S, T, O>,
+    #[cfg(debug_assertions)]
+    last: Option<S>,
+}
+
+impl<S, T> MonotonePriorityQueue<S, T>
+where
+    S: PartialOrd + Clone,
+{
+    /// Create an empty `MonotonePriorityQueue` where scores never decrease.
+    #[must_use]
+    pub fn new() -> Self {
+        MonotonePriorityQueue::default()
+    }
+
+    /// Create an empty `MonotonePriorityQueue` with space for at least `cap`
+    /// elements.
+    #[must_use]
+    pub fn with_capacity(cap: usize) -> Self {
+        MonotonePriorityQueue::from_queue(PriorityQueue::with_capacity(cap))
+    }
+}
+
+impl<S, T> MonotonePriorityQueue<S, T, Max>
+where
+    S: PartialOrd + Clone,
+{
+    /// Create an empty `MonotonePriorityQueue` where scores never increase.
+    #[must_use]
+    pub fn new_max() -> Self {
+        MonotonePriorityQueue::default()
+    }
+}
+
+impl<S, T, O> MonotonePriorityQueue<S, T, O>
+where
+    S: PartialOrd + Clone,
+    O: Order,
+{
+    /// Wraps an existing queue. Nothing was popped from it yet, so any score
+    /// can be put until the first `pop`.
+    pub fn from_queue(pq: PriorityQueue<S, T, O>) -> Self {
+        MonotonePriorityQueue {
+            pq,
+            #[cfg(debug_assertions)]
+            last: None,
+        }
+    }
+
+    /// Inserts an element in the heap.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if the score goes before the last popped one.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(n))***.
+    pub fn put(&mut self, score: S, item: T) {
+        #[cfg(debug_assertions)]
+        if let Some(last) = &self.last {
+            assert!(!self.pq.less(&score, last),
+                    "score was put after a greater priority score was popped");
+        }
+        self.pq.put(score, item);
+    }
+
+    /// Get the top priority element from `MonotonePriorityQueue`.
+    ///
+    /// # Time Complexity
+    ///
+    /// Worst case is ***O(log(n))***.
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        let top = self.pq.pop()?;
+        #[cfg(debug_assertions)]
+        { self.last = Some(top.0.clone()); }
+        Some(top)
+    }
+
+    /// Remove all the elements from `MonotonePriorityQueue`. The last popped
+    /// score is still remembered.
+    pub fn clear(&mut self) {
+        self.pq.clear();
+    }
+
+    /// Consumes the wrapper and returns the underlying priority queue.
+    pub fn into_inner(self) -> PriorityQueue<S, T, O> {
+        self.pq
+    }
+}
+
+impl<S, T, O> Default for MonotonePriorityQueue<S, T, O>
+where
+    S: PartialOrd + Clone,
+    O: Order,
+{
+    fn default() -> Self {
+        MonotonePriorityQueue::from_queue(PriorityQueue::default())
+    }
+}
+
+impl<S, T, O> Deref for MonotonePriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    type Target = PriorityQueue<S, T, O>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.pq
+    }
+}
+
+impl<S, T, O> Extend<(S, T)> for MonotonePriorityQueue<S, T, O>
+where
+    S: PartialOrd + Clone,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(s, e)| self.put(s, e));
+    }
+}
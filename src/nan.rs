@@ -0,0 +1,42 @@
+/// Decides what a [`PriorityQueue`] does with scores that can't be compared
+/// even with themselves, like `NAN`. By default they are put in the back of
+/// the queue, which can hide bugs in the code computing the scores.
+///
+/// Policy is checked whenever an element is put in the queue. Scores changed
+/// in-place, e.g. with [`map_scores`], are not checked and incomparable ones
+/// are placed in the back of the queue unless policy is [`First`].
+///
+/// [`PriorityQueue`]: crate::PriorityQueue
+/// [`map_scores`]: crate::PriorityQueue::map_scores
+/// [`First`]: NanPolicy::First
+///
+/// # Examples
+///
+/// ```
+/// use priq::{NanPolicy, PriorityQueue};
+///
+/// let mut pq = PriorityQueue::new();
+/// pq.set_nan_policy(NanPolicy::Reject);
+///
+/// assert!(pq.try_put(1.0, "one").is_ok());
+/// assert!(pq.try_put(f64::NAN, "nan").is_err());
+/// assert_eq!(1, pq.len());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum NanPolicy {
+    /// Incomparable scores are not allowed in the queue. [`try_put`] returns
+    /// an error for them, while methods which can't report an error panic.
+    ///
+    /// [`try_put`]: crate::PriorityQueue::try_put
+    Reject,
+
+    /// Incomparable scores are put after all the comparable ones.
+    #[default]
+    Last,
+
+    /// Incomparable scores are put before all the comparable ones.
+    First,
+
+    /// Putting an incomparable score is a bug and panics.
+    Panic,
+}
@@ -0,0 +1,42 @@
+use core::cmp::Ordering;
+use core::fmt::Debug;
+use core::hash::Hash;
+
+/// Direction in which [`PriorityQueue`] orders its elements. It's chosen with
+/// the marker type parameter, so the comparison is resolved at compile time
+/// and queues with different directions can't be mixed up.
+///
+/// Implemented only for [`Min`] and [`Max`].
+///
+/// [`PriorityQueue`]: crate::PriorityQueue
+pub trait Order: Copy + Debug + Default + Eq + Hash + private::Sealed {
+    /// How the score of an element compares to the scores of elements below
+    /// it in the heap.
+    const TOP: Ordering;
+}
+
+/// Marker for the min-heap order, where the lowest score is on top. This is
+/// the default order of [`PriorityQueue`].
+///
+/// [`PriorityQueue`]: crate::PriorityQueue
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Min;
+
+/// Marker for the max-heap order, where the highest score is on top.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Max;
+
+impl Order for Min {
+    const TOP: Ordering = Ordering::Less;
+}
+
+impl Order for Max {
+    const TOP: Ordering = Ordering::Greater;
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for super::Min {}
+    impl Sealed for super::Max {}
+}
@@ -0,0 +1,132 @@
+use ordered_float::{FloatCore, FloatIsNan, NotNan, OrderedFloat};
+
+use crate::{Float, FloatPriorityQueue, Order, PriorityQueue};
+
+impl<F, T> PriorityQueue<OrderedFloat<F>, T>
+where
+    F: FloatCore,
+{
+    /// Create `PriorityQueue` from raw floats and items, wrapping every score
+    /// into `OrderedFloat`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from_floats([(2.5, 'b'), (f64::NAN, '?'), (0.5, 'a')]);
+    ///
+    /// assert_eq!(Some((0.5, 'a')), pq.pop_float());
+    /// assert_eq!(Some((2.5, 'b')), pq.pop_float());
+    /// ```
+    pub fn from_floats<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (F, T)>,
+    {
+        iter.into_iter().map(|(s, e)| (OrderedFloat(s), e)).collect()
+    }
+}
+
+impl<F, T, O> PriorityQueue<OrderedFloat<F>, T, O>
+where
+    F: FloatCore,
+    O: Order,
+{
+    /// Inserts an element with a raw float score, see [`put`].
+    ///
+    /// [`put`]: PriorityQueue::put
+    pub fn put_float(&mut self, score: F, item: T) {
+        self.put(OrderedFloat(score), item);
+    }
+
+    /// Get the top priority element with its score unwrapped into a raw
+    /// float, see [`pop`].
+    ///
+    /// [`pop`]: PriorityQueue::pop
+    pub fn pop_float(&mut self) -> Option<(F, T)> {
+        self.pop().map(|(s, e)| (s.into_inner(), e))
+    }
+}
+
+impl<F, T> PriorityQueue<NotNan<F>, T>
+where
+    F: FloatCore,
+{
+    /// Create `PriorityQueue` from raw floats and items, checking that none
+    /// of the scores is `NAN`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    /// use ordered_float::NotNan;
+    ///
+    /// let pq = PriorityQueue::<NotNan<f32>, char>::try_from_floats([(1.0, 'a')]);
+    /// assert!(pq.is_ok());
+    ///
+    /// let pq = PriorityQueue::<NotNan<f32>, char>::try_from_floats([(f32::NAN, '?')]);
+    /// assert!(pq.is_err());
+    /// ```
+    pub fn try_from_floats<I>(iter: I) -> Result<Self, FloatIsNan>
+    where
+        I: IntoIterator<Item = (F, T)>,
+    {
+        iter.into_iter().map(|(s, e)| Ok((NotNan::new(s)?, e))).collect()
+    }
+}
+
+impl<F, T, O> PriorityQueue<NotNan<F>, T, O>
+where
+    F: FloatCore,
+    O: Order,
+{
+    /// Inserts an element with a raw float score, which must not be `NAN`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    /// use ordered_float::NotNan;
+    ///
+    /// let mut pq: PriorityQueue<NotNan<f64>, &str> = PriorityQueue::new();
+    /// assert!(pq.try_put_float(1.0, "one").is_ok());
+    /// assert!(pq.try_put_float(f64::NAN, "nan").is_err());
+    /// assert_eq!(Some((1.0, "one")), pq.pop_float());
+    /// ```
+    pub fn try_put_float(&mut self, score: F, item: T) -> Result<(), FloatIsNan> {
+        self.put(NotNan::new(score)?, item);
+        Ok(())
+    }
+
+    /// Get the top priority element with its score unwrapped into a raw
+    /// float, see [`pop`].
+    ///
+    /// [`pop`]: PriorityQueue::pop
+    pub fn pop_float(&mut self) -> Option<(F, T)> {
+        self.pop().map(|(s, e)| (s.into_inner(), e))
+    }
+}
+
+impl<F, T, O> From<FloatPriorityQueue<F, T, O>> for PriorityQueue<OrderedFloat<F>, T, O>
+where
+    F: Float + FloatCore,
+    O: Order,
+{
+    /// Converts the queue keeping all the elements. `OrderedFloat` puts `NAN`
+    /// after all the other scores too, but doesn't tell negative zero apart.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::{FloatPriorityQueue, PriorityQueue};
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let fpq: FloatPriorityQueue<f64, char> = [(2.0, 'b'), (1.0, 'a')].into_iter().collect();
+    /// let mut pq = PriorityQueue::<OrderedFloat<f64>, char>::from(fpq);
+    ///
+    /// assert_eq!(Some((1.0, 'a')), pq.pop_float());
+    /// ```
+    fn from(pq: FloatPriorityQueue<F, T, O>) -> Self {
+        pq.map_scores_into(OrderedFloat)
+    }
+}
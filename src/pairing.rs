@@ -0,0 +1,352 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+
+use crate::{Handle, Max, Min, Order};
+use crate::sift;
+use crate::slab::{self, Slab, NIL};
+
+#[derive(Clone)]
+struct Node<S, T> {
+    elem: (S, T),
+    child: usize,
+    sibling: usize,
+    /// Parent for the leftmost child, previous sibling for the rest.
+    prev: usize,
+}
+
+/// A pairing heap, a tree based priority queue which puts elements and
+/// merges heaps in ***O(1)***, and pops in amortized ***O(log(n))***.
+///
+/// Every [`put`] returns a [`Handle`] of the element, which can be used to
+/// move the element closer to the top with [`decrease_key`] in amortized
+/// ***o(log(n))***, making the heap a good fit for graph algorithms like
+/// Dijkstra's or Prim's.
+///
+/// Nodes are kept in a slab, so there is no allocation per element once
+/// the heap has grown. Elements are ordered by their scores the same way as
+/// in [`PriorityQueue`], including the direction given by the [`Order`] type
+/// parameter.
+///
+/// [`put`]: PairingHeap::put
+/// [`decrease_key`]: PairingHeap::decrease_key
+/// [`PriorityQueue`]: crate::PriorityQueue
+///
+/// # Examples
+///
+/// ```
+/// use priq::PairingHeap;
+///
+/// let mut pq = PairingHeap::new();
+/// pq.put(5, "a");
+/// let b = pq.put(7, "b");
+/// pq.put(3, "c");
+///
+/// pq.decrease_key(b, 1);
+/// assert_eq!(Some((1, "b")), pq.pop());
+/// assert_eq!(Some((3, "c")), pq.pop());
+/// ```
+#[derive(Clone)]
+pub struct PairingHeap<S, T, O = Min> {
+    nodes: Slab<Node<S, T>>,
+    root: usize,
+    len: usize,
+    _order: PhantomData<O>,
+}
+
+impl<S, T> PairingHeap<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `PairingHeap` where the lowest score is on top.
+    #[must_use]
+    pub fn new() -> Self {
+        PairingHeap::default()
+    }
+
+    /// Create an empty `PairingHeap` with space for at least `cap` elements.
+    #[must_use]
+    pub fn with_capacity(cap: usize) -> Self {
+        let mut pq = PairingHeap::default();
+        pq.nodes.reserve_exact(cap);
+        pq
+    }
+}
+
+impl<S, T> PairingHeap<S, T, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `PairingHeap` where the highest score is on top.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PairingHeap;
+    ///
+    /// let mut pq = PairingHeap::new_max();
+    /// pq.extend([(1, 'a'), (3, 'c'), (2, 'b')]);
+    ///
+    /// assert_eq!(Some((3, 'c')), pq.pop());
+    /// ```
+    #[must_use]
+    pub fn new_max() -> Self {
+        PairingHeap::default()
+    }
+}
+
+impl<S, T, O> PairingHeap<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Inserts an element in the heap and returns its handle.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn put(&mut self, score: S, item: T) -> Handle {
+        let node = Node { elem: (score, item), child: NIL, sibling: NIL, prev: NIL };
+        let handle = self.nodes.insert(node);
+        self.root = self.link(self.root, handle.index);
+        self.len += 1;
+        handle
+    }
+
+    /// Get the top priority element from `PairingHeap`. Handle of the
+    /// element is no longer valid.
+    ///
+    /// # Time Complexity
+    ///
+    /// Amortized ***O(log(n))***, worst case is ***O(n)***.
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        if self.root == NIL {
+            return None
+        }
+        let root = self.root;
+        let child = self.node(root).child;
+        self.root = self.merge_pairs(child);
+        self.len -= 1;
+        Some(self.nodes.remove(root).elem)
+    }
+
+    /// Check what is a top element in `PairingHeap`, by getting the
+    /// reference.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek(&self) -> Option<&(S, T)> {
+        if self.root == NIL {
+            return None
+        }
+        Some(&self.node(self.root).elem)
+    }
+
+    /// Returns the element of the handle, or `None` if the element was
+    /// already popped.
+    pub fn get(&self, handle: Handle) -> Option<&(S, T)> {
+        self.nodes.get(handle).map(|node| &node.elem)
+    }
+
+    /// Replaces the score of the element with the one which goes before
+    /// it, e.g. a lower score if the lowest score is on top.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element of the handle was already popped or if the new
+    /// score goes after the old one.
+    ///
+    /// # Time Complexity
+    ///
+    /// Amortized ***o(log(n))***.
+    pub fn decrease_key(&mut self, handle: Handle, score: S) {
+        assert!(self.get(handle).is_some(), "element of the handle was popped");
+        let index = handle.index;
+        assert!(!self.less(&self.node(index).elem.0, &score),
+                "new score goes after the old one");
+        self.node_mut(index).elem.0 = score;
+        if index == self.root {
+            return
+        }
+        self.cut(index);
+        self.root = self.link(self.root, index);
+    }
+
+    /// Moves all the elements of `other` into this heap, leaving `other`
+    /// empty. Handles of the moved elements are translated to the handles
+    /// in this heap by the returned function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PairingHeap;
+    ///
+    /// let mut pq1 = PairingHeap::new();
+    /// pq1.put(2, "b");
+    /// let mut pq2 = PairingHeap::new();
+    /// let c = pq2.put(3, "c");
+    ///
+    /// let moved = pq1.merge(&mut pq2);
+    /// pq1.decrease_key(moved(c), 1);
+    /// assert!(pq2.is_empty());
+    /// assert_eq!(Some((1, "c")), pq1.pop());
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)*** to link the heaps, after their nodes are moved in
+    /// ***O(m)*** for `m` nodes of `other`.
+    pub fn merge(&mut self, other: &mut Self) -> impl Fn(Handle) -> Handle {
+        let offset = self.nodes.append(&mut other.nodes, |node, offset| {
+            node.child = slab::shift(node.child, offset);
+            node.sibling = slab::shift(node.sibling, offset);
+            node.prev = slab::shift(node.prev, offset);
+        });
+        let root = slab::shift(mem::replace(&mut other.root, NIL), offset);
+        self.root = self.link(self.root, root);
+        self.len += mem::take(&mut other.len);
+        move |handle| Handle { index: handle.index + offset, ..handle }
+    }
+
+    /// Returns the number of elements in the `PairingHeap`
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` is there are no elements in `PairingHeap`
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all the elements from `PairingHeap`, invalidating all the
+    /// handles.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.root = NIL;
+        self.len = 0;
+    }
+
+    fn node(&self, index: usize) -> &Node<S, T> {
+        self.nodes.node(index)
+    }
+
+    fn node_mut(&mut self, index: usize) -> &mut Node<S, T> {
+        self.nodes.node_mut(index)
+    }
+
+    /// Checks if `lhs` should be placed closer to the root than `rhs`.
+    /// Scores that can't be compared even with themselves sink to the back.
+    #[inline]
+    fn less(&self, lhs: &S, rhs: &S) -> bool {
+        sift::precedes::<S, O>(lhs, rhs)
+    }
+
+    /// Links two detached trees making the one with the higher priority
+    /// root a parent of the other one, and returns the new root.
+    fn link(&mut self, a: usize, b: usize) -> usize {
+        if a == NIL { return b }
+        if b == NIL { return a }
+        let (parent, child) = if self.less(&self.node(b).elem.0, &self.node(a).elem.0) {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        let first = self.node(parent).child;
+        if first != NIL {
+            self.node_mut(first).prev = child;
+        }
+        let node = self.node_mut(child);
+        node.sibling = first;
+        node.prev = parent;
+        self.node_mut(parent).child = child;
+        parent
+    }
+
+    /// Detaches the node with its subtree from its parent.
+    fn cut(&mut self, index: usize) {
+        let Node { prev, sibling, .. } = *self.node(index);
+        if self.node(prev).child == index {
+            self.node_mut(prev).child = sibling;
+        } else {
+            self.node_mut(prev).sibling = sibling;
+        }
+        if sibling != NIL {
+            self.node_mut(sibling).prev = prev;
+        }
+        let node = self.node_mut(index);
+        node.sibling = NIL;
+        node.prev = NIL;
+    }
+
+    /// Merges a list of siblings into a single tree in two passes, first
+    /// linking them in pairs left to right, then linking the pairs into one
+    /// tree right to left.
+    fn merge_pairs(&mut self, first: usize) -> usize {
+        let mut pairs = Vec::new();
+        let mut next = first;
+        while next != NIL {
+            let a = next;
+            let b = self.node(a).sibling;
+            next = if b == NIL { NIL } else { self.node(b).sibling };
+            for index in [a, b] {
+                if index != NIL {
+                    let node = self.node_mut(index);
+                    node.sibling = NIL;
+                    node.prev = NIL;
+                }
+            }
+            pairs.push(self.link(a, b));
+        }
+        pairs.into_iter().rev().fold(NIL, |root, tree| self.link(tree, root))
+    }
+}
+
+impl<S, T, O> Default for PairingHeap<S, T, O> {
+    fn default() -> Self {
+        PairingHeap {
+            nodes: Slab::new(),
+            root: NIL,
+            len: 0,
+            _order: PhantomData,
+        }
+    }
+}
+
+impl<S, T> FromIterator<(S, T)> for PairingHeap<S, T>
+where
+    S: PartialOrd,
+{
+    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
+        let mut pq = PairingHeap::new();
+        pq.extend(iter);
+        pq
+    }
+}
+
+impl<S, T, O> Extend<(S, T)> for PairingHeap<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.nodes.reserve(iter.size_hint().0);
+        iter.for_each(|(s, e)| { self.put(s, e); });
+    }
+}
+
+impl<S, T, O> fmt::Debug for PairingHeap<S, T, O>
+where
+    S: fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let elems = self.nodes.iter().map(|node| &node.elem);
+        f.debug_struct("PairingHeap")
+         .field("len", &self.len)
+         .field("data", &elems.collect::<Vec<_>>())
+         .finish()
+    }
+}
@@ -0,0 +1,175 @@
+use std::mem;
+
+use rayon::iter::{FromParallelIterator, IntoParallelIterator, ParallelExtend, ParallelIterator};
+use rayon::prelude::IntoParallelRefIterator;
+use rayon::slice::ParallelSliceMut;
+use rayon::{slice, vec};
+
+use crate::{NanPolicy, Order, PriorityQueue};
+
+impl<S, T, O> PriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Returns a parallel iterator over the elements in the order of the
+    /// underlying array (NOT sorted), like iterating over the slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rayon::prelude::*;
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq: PriorityQueue<_, _> = (0..100).map(|i| (i, i * 2)).collect();
+    /// assert_eq!(9900, pq.par_iter().map(|(_, e)| e).sum::<i32>());
+    /// ```
+    pub fn par_iter(&self) -> slice::Iter<'_, (S, T)>
+    where
+        S: Sync,
+        T: Sync,
+    {
+        (**self).par_iter()
+    }
+
+    /// Removes all the elements from the priority queue, returning a
+    /// parallel iterator over them in the order of the underlying array
+    /// (NOT sorted). The queue keeps its settings, while the buffer of the
+    /// elements is moved into the iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rayon::prelude::*;
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut events: PriorityQueue<_, _> = (0..1000).map(|i| (i, i)).collect();
+    /// let handled = events.par_drain().filter(|(_, e)| e % 2 == 0).count();
+    ///
+    /// assert_eq!(500, handled);
+    /// assert!(events.is_empty());
+    /// ```
+    pub fn par_drain(&mut self) -> vec::IntoIter<(S, T)>
+    where
+        S: Send,
+        T: Send,
+    {
+        let empty = self.empty_like(0);
+        mem::replace(self, empty).into_vec().into_par_iter()
+    }
+
+    /// Consumes the priority queue and returns elements sorted by their
+    /// priority, the top one first, same as [`into_sorted_vec`]. The
+    /// underlying array is reused as the returned `Vec` and sorted with the
+    /// parallel merge sort of rayon, so elements with equal priority keep
+    /// the order they had in the array.
+    ///
+    /// [`into_sorted_vec`]: PriorityQueue::into_sorted_vec
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq: PriorityQueue<_, _> = (0..10_000).rev().map(|i| (i, i * 2)).collect();
+    /// let sorted = pq.par_into_sorted_vec();
+    /// assert_eq!((0, 0), sorted[0]);
+    /// assert_eq!((9999, 19998), sorted[9999]);
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n log(n))*** split between the threads of rayon.
+    pub fn par_into_sorted_vec(self) -> Vec<(S, T)>
+    where
+        S: Send + Sync,
+        T: Send + Sync,
+        O: Sync,
+    {
+        let (heap_len, nan) = (self.heap_len(), self.nan);
+        let order = self.empty_like(0);
+        let mut res = self.into_vec();
+        res[..heap_len].par_sort_by(|lhs, rhs| order.order(lhs, rhs));
+
+        // quarantined elements are popped from the back
+        res[heap_len..].reverse();
+        if nan == NanPolicy::First {
+            res.rotate_left(heap_len);
+        }
+        res
+    }
+}
+
+impl<S, T> FromParallelIterator<(S, T)> for PriorityQueue<S, T>
+where
+    S: PartialOrd + Send,
+    T: Send,
+{
+    /// Collects the elements of a parallel iterator into a `PriorityQueue`.
+    /// Every thread builds a heap of its part of the elements, then the heaps
+    /// are merged pairwise, the smaller one into the larger.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rayon::prelude::*;
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq: PriorityQueue<_, _> = (0..1000).into_par_iter()
+    ///                                        .map(|i| (1000 - i, i))
+    ///                                        .collect();
+    /// assert_eq!(1000, pq.len());
+    /// assert_eq!(Some(&(1, 999)), pq.peek());
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)*** to build the heaps, split between the threads of rayon,
+    /// and at most linear time in the size of both heaps for every merge.
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: IntoParallelIterator<Item = (S, T)>,
+    {
+        par_iter.into_par_iter()
+            .fold(Vec::new, |mut elems, elem| {
+                elems.push(elem);
+                elems
+            })
+            .map(PriorityQueue::from)
+            .reduce(PriorityQueue::new, |lhs, rhs| {
+                let (mut pq, rest) = if lhs.len() < rhs.len() { (rhs, lhs) } else { (lhs, rhs) };
+                pq.put_many(rest.into_vec());
+                pq
+            })
+    }
+}
+
+impl<S, T, O> ParallelExtend<(S, T)> for PriorityQueue<S, T, O>
+where
+    S: PartialOrd + Send,
+    T: Send,
+    O: Order,
+{
+    /// Extends the priority queue with the elements of a parallel iterator.
+    /// Elements are gathered in parallel and then put the same way as with
+    /// [`put_many`], rebuilding the heap once if it's cheaper.
+    ///
+    /// [`put_many`]: PriorityQueue::put_many
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rayon::prelude::*;
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::new_max();
+    /// pq.par_extend((0..1000).into_par_iter().map(|i| (i, i * 10)));
+    /// assert_eq!(Some(&(999, 9990)), pq.peek());
+    /// ```
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoParallelIterator<Item = (S, T)>,
+    {
+        self.put_many(par_iter.into_par_iter().collect::<Vec<_>>());
+    }
+}
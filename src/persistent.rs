@@ -0,0 +1,16 @@
+//! Priority queues which keep their elements in files.
+//!
+//! * [`WalPriorityQueue`] keeps the queue in memory and journals every
+//!   operation to a write-ahead log, so no accepted element is lost in a
+//!   crash.
+//! * [`MmapPriorityQueue`] stores its binary heap in a memory-mapped file, so
+//!   the queue survives process restarts and large queues live in the page
+//!   cache instead of the process heap. Requires the `mmap` feature.
+
+#[cfg(feature = "mmap")]
+mod mmap;
+mod wal;
+
+#[cfg(feature = "mmap")]
+pub use mmap::MmapPriorityQueue;
+pub use wal::WalPriorityQueue;
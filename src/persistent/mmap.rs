@@ -0,0 +1,380 @@
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::marker::PhantomData;
+use std::mem;
+use std::path::{Path, PathBuf};
+
+use bytemuck::Pod;
+use memmap2::MmapMut;
+
+use crate::{Max, Min, Order};
+use crate::sift;
+
+/// The bytes every queue file starts with.
+const MAGIC: [u8; 8] = *b"PRIQMMAP";
+
+/// Version of the file layout, bumped whenever it changes.
+const VERSION: u32 = 1;
+
+/// Size of the file header, followed by the slots of the elements.
+///
+/// ```text
+/// magic [u8; 8] | version u32 | order u32 | score size u32 | item size u32 | len u64
+/// ```
+const HEADER: usize = 32;
+const LEN_AT: usize = 24;
+
+/// Number of slots the file is created with.
+const MIN_CAP: usize = 16;
+
+/// A binary heap of plain-old-data elements in a memory-mapped file.
+///
+/// Elements are ordered by their scores the same way as in
+/// [`PriorityQueue`], including the direction given by the [`Order`] type
+/// parameter and incomparable scores sinking to the back. Every `put` and
+/// `pop` works on the mapped file in place and the file grows by doubling
+/// when it runs out of space.
+///
+/// Changes are written back to the file by the operating system at its own
+/// pace; call [`flush`] to make sure they're on disk. The file stores the
+/// order and the sizes of the score and item, and opening it with a queue of
+/// different types is an error. Scores and items are copied in and out of
+/// the file, so they have to be [`Pod`] and the file is only portable between
+/// machines with the same endianness. The file must not be modified or
+/// truncated by anyone else while the queue is open.
+///
+/// [`PriorityQueue`]: crate::PriorityQueue
+/// [`flush`]: MmapPriorityQueue::flush
+///
+/// # Examples
+///
+/// ```
+/// use priq::persistent::MmapPriorityQueue;
+///
+/// let path = std::env::temp_dir().join("priq-doc-mmap.pq");
+/// # let _ = std::fs::remove_file(&path);
+/// let mut pq: MmapPriorityQueue<u64, u32> = MmapPriorityQueue::open(&path).unwrap();
+/// pq.put(3, 30).unwrap();
+/// pq.put(1, 10).unwrap();
+/// pq.flush().unwrap();
+/// drop(pq);
+///
+/// let mut pq: MmapPriorityQueue<u64, u32> = MmapPriorityQueue::open(&path).unwrap();
+/// assert_eq!(Some((1, 10)), pq.pop());
+/// assert_eq!(Some((3, 30)), pq.pop());
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct MmapPriorityQueue<S, T, O = Min> {
+    file: File,
+    map: MmapMut,
+    path: PathBuf,
+    len: usize,
+    _marker: PhantomData<(S, T, O)>,
+}
+
+impl<S, T> MmapPriorityQueue<S, T>
+where
+    S: PartialOrd + Pod,
+    T: Pod,
+{
+    /// Opens the queue stored in the file at `path`, where the lowest score
+    /// is on top. The file is created if it doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be opened or mapped, and an error
+    /// of [`InvalidData`] kind if it's not a queue file or it was written by
+    /// a queue with a different order or element types.
+    ///
+    /// [`InvalidData`]: std::io::ErrorKind::InvalidData
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        MmapPriorityQueue::open_with(path.as_ref())
+    }
+}
+
+impl<S, T> MmapPriorityQueue<S, T, Max>
+where
+    S: PartialOrd + Pod,
+    T: Pod,
+{
+    /// Opens the queue stored in the file at `path`, where the highest score
+    /// is on top. The file is created if it doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`open`](MmapPriorityQueue::open).
+    pub fn open_max<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        MmapPriorityQueue::open_with(path.as_ref())
+    }
+}
+
+impl<S, T, O> MmapPriorityQueue<S, T, O>
+where
+    S: PartialOrd + Pod,
+    T: Pod,
+    O: Order,
+{
+    const SCORE: usize = mem::size_of::<S>();
+    const SLOT: usize = mem::size_of::<S>() + mem::size_of::<T>();
+
+    fn open_with(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).create(true)
+                                     .truncate(false).open(path)?;
+        let fresh = file.metadata()?.len() == 0;
+        if fresh {
+            file.set_len((HEADER + MIN_CAP * Self::SLOT) as u64)?;
+        }
+        // SAFETY: the mapping is only valid while nobody else modifies or
+        // truncates the file, which is a documented requirement of the queue.
+        let mut map = unsafe { MmapMut::map_mut(&file)? };
+        if map.len() < HEADER {
+            return Err(invalid("file is too short to be a priority queue"));
+        }
+
+        let header = Self::header();
+        if fresh {
+            map[..LEN_AT].copy_from_slice(&header[..LEN_AT]);
+        } else if map[..8] != MAGIC {
+            return Err(invalid("not a priority queue file"));
+        } else if map[..LEN_AT] != header[..LEN_AT] {
+            return Err(invalid("priority queue file has a different version, \
+                                order or element types"));
+        }
+
+        let len = u64::from_ne_bytes(map[LEN_AT..HEADER].try_into().unwrap());
+        let pq = MmapPriorityQueue {
+            file, map, path: path.to_owned(), len: len as usize, _marker: PhantomData
+        };
+        if pq.len > pq.capacity() {
+            return Err(invalid("priority queue file is truncated"));
+        }
+        Ok(pq)
+    }
+
+    fn header() -> [u8; HEADER] {
+        let mut header = [0; HEADER];
+        header[..8].copy_from_slice(&MAGIC);
+        header[8..12].copy_from_slice(&VERSION.to_ne_bytes());
+        header[12..16].copy_from_slice(&(O::TOP as i32 as u32).to_ne_bytes());
+        header[16..20].copy_from_slice(&(mem::size_of::<S>() as u32).to_ne_bytes());
+        header[20..24].copy_from_slice(&(mem::size_of::<T>() as u32).to_ne_bytes());
+        header
+    }
+
+    /// Returns the path of the file backing the queue.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the number of elements in the queue.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` is there are no elements in the queue.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements the file can hold before it grows.
+    pub fn capacity(&self) -> usize {
+        (self.map.len() - HEADER).checked_div(Self::SLOT).unwrap_or(usize::MAX)
+    }
+
+    /// Inserts an element in the heap, growing the file if it's full.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be grown or mapped again, in which
+    /// case the queue is left unchanged.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(log(n))***, or ***O(n)*** when the file grows.
+    pub fn put(&mut self, score: S, item: T) -> io::Result<()> {
+        if self.len == self.capacity() {
+            self.grow()?;
+        }
+        self.write(self.len, &score, &item);
+        self.set_len(self.len + 1);
+        self.heapify_up(self.len - 1);
+        Ok(())
+    }
+
+    /// Removes the top element from the heap and returns it, or `None` if
+    /// the queue is empty.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(log(n))***
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        if self.is_empty() {
+            return None;
+        }
+        let top = self.read(0);
+        let last = self.len - 1;
+        self.swap(0, last);
+        self.set_len(last);
+        self.heapify_down(0);
+        Some(top)
+    }
+
+    /// Returns a copy of the top element without removing it.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek(&self) -> Option<(S, T)> {
+        (!self.is_empty()).then(|| self.read(0))
+    }
+
+    /// Returns an iterator over copies of the elements in the order of the
+    /// file (NOT sorted).
+    pub fn iter(&self) -> impl Iterator<Item = (S, T)> + '_ {
+        (0..self.len).map(move |i| self.read(i))
+    }
+
+    /// Removes all the elements from the queue, keeping the size of the file.
+    pub fn clear(&mut self) {
+        self.set_len(0);
+    }
+
+    /// Shrinks the file to fit the elements currently in the queue.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be truncated or mapped again.
+    pub fn shrink_to_fit(&mut self) -> io::Result<()> {
+        self.resize(self.len.max(MIN_CAP))
+    }
+
+    /// Writes all the changes to the file and waits until they're on disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the operating system fails to sync the mapping.
+    pub fn flush(&self) -> io::Result<()> {
+        self.map.flush()
+    }
+
+    /// Starts writing all the changes to the file without waiting for them
+    /// to be on disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the operating system fails to schedule the sync.
+    pub fn flush_async(&self) -> io::Result<()> {
+        self.map.flush_async()
+    }
+
+    fn grow(&mut self) -> io::Result<()> {
+        let cap = self.capacity().checked_mul(2).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::OutOfMemory, "capacity overflow")
+        })?;
+        self.resize(cap.max(MIN_CAP))
+    }
+
+    fn resize(&mut self, cap: usize) -> io::Result<()> {
+        let size = cap.checked_mul(Self::SLOT).and_then(|s| s.checked_add(HEADER))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::OutOfMemory, "capacity overflow"))?;
+        self.map.flush()?;
+        self.file.set_len(size as u64)?;
+        // SAFETY: see `open_with`.
+        self.map = unsafe { MmapMut::map_mut(&self.file)? };
+        Ok(())
+    }
+
+    #[inline]
+    fn set_len(&mut self, len: usize) {
+        self.len = len;
+        self.map[LEN_AT..HEADER].copy_from_slice(&(len as u64).to_ne_bytes());
+    }
+
+    #[inline]
+    fn at(index: usize) -> usize {
+        HEADER + index * Self::SLOT
+    }
+
+    #[inline]
+    fn score(&self, index: usize) -> S {
+        let at = Self::at(index);
+        bytemuck::pod_read_unaligned(&self.map[at..at + Self::SCORE])
+    }
+
+    fn read(&self, index: usize) -> (S, T) {
+        let at = Self::at(index);
+        let (score, item) = self.map[at..at + Self::SLOT].split_at(Self::SCORE);
+        (bytemuck::pod_read_unaligned(score), bytemuck::pod_read_unaligned(item))
+    }
+
+    fn write(&mut self, index: usize, score: &S, item: &T) {
+        let at = Self::at(index);
+        let (s, i) = self.map[at..at + Self::SLOT].split_at_mut(Self::SCORE);
+        s.copy_from_slice(bytemuck::bytes_of(score));
+        i.copy_from_slice(bytemuck::bytes_of(item));
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        if a != b {
+            let (sa, ia) = self.read(a);
+            let (sb, ib) = self.read(b);
+            self.write(a, &sb, &ib);
+            self.write(b, &sa, &ia);
+        }
+    }
+
+    fn heapify_up(&mut self, mut index: usize) {
+        let score = self.score(index);
+        while index > 0 {
+            let parent_ = (index - 1) / 2;
+            if !sift::precedes::<S, O>(&score, &self.score(parent_)) {
+                break
+            }
+            self.swap(parent_, index);
+            index = parent_;
+        }
+    }
+
+    fn heapify_down(&mut self, mut index: usize) {
+        loop {
+            let left = 2 * index + 1;
+            let right = left + 1;
+            let mut min_ = index;
+            let mut min_score = self.score(index);
+            for child in [left, right] {
+                if child < self.len {
+                    let score = self.score(child);
+                    if sift::precedes::<S, O>(&score, &min_score) {
+                        min_ = child;
+                        min_score = score;
+                    }
+                }
+            }
+            if min_ == index {
+                break
+            }
+            self.swap(index, min_);
+            index = min_;
+        }
+    }
+}
+
+impl<S, T, O> fmt::Debug for MmapPriorityQueue<S, T, O>
+where
+    O: Order,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MmapPriorityQueue")
+         .field("path", &self.path)
+         .field("len", &self.len)
+         .field("order", &O::default())
+         .finish()
+    }
+}
+
+fn invalid(error: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
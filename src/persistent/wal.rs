@@ -0,0 +1,430 @@
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::{Max, Min, Order, Persist, PriorityQueue};
+
+const SNAPSHOT: &str = "queue.snapshot";
+const SNAPSHOT_TMP: &str = "queue.snapshot.tmp";
+const LOG: &str = "queue.wal";
+
+/// The bytes the log starts with, followed by its generation.
+const MAGIC: [u8; 4] = *b"PRIW";
+const LOG_HEADER: u64 = 12;
+
+/// Every record starts with the length of its payload and a CRC-32 of the
+/// length and the payload, so a torn or garbage tail isn't taken for one.
+const FRAME: usize = 8;
+
+const PUT: u8 = 1;
+const POP: u8 = 2;
+
+/// A [`PriorityQueue`] which journals every `put` and `pop` to a write-ahead
+/// log before applying it, so the queue can be recovered after a crash.
+///
+/// The queue lives in a directory with a snapshot of the queue and a log of
+/// the operations done since that snapshot. Opening the directory loads the
+/// snapshot and replays the log, dropping the tail of the log which was
+/// only partially written when the process died: a record cut short or
+/// failing its checksum, unless a valid record follows it. [`checkpoint`] writes a new snapshot and
+/// empties the log, so it doesn't grow forever. Both files carry a
+/// generation number, so a crash in the middle of a checkpoint never applies
+/// the same operations twice.
+///
+/// By default every operation waits until the record is on disk, so an
+/// element is never lost once `put` returns. [`set_sync`] trades this for
+/// speed, leaving it to [`sync`] or the operating system.
+///
+/// Scores and items are written with the [`Persist`] trait. Settings like a
+/// custom comparator, [`NanPolicy`] or growth policy are not saved.
+///
+/// [`checkpoint`]: WalPriorityQueue::checkpoint
+/// [`set_sync`]: WalPriorityQueue::set_sync
+/// [`sync`]: WalPriorityQueue::sync
+/// [`NanPolicy`]: crate::NanPolicy
+///
+/// # Examples
+///
+/// ```
+/// use priq::persistent::WalPriorityQueue;
+///
+/// let dir = std::env::temp_dir().join("priq-doc-wal");
+/// # let _ = std::fs::remove_dir_all(&dir);
+/// let mut pq: WalPriorityQueue<u32, String> = WalPriorityQueue::open(&dir).unwrap();
+/// pq.put(2, "backup".to_string()).unwrap();
+/// pq.put(1, "deploy".to_string()).unwrap();
+/// drop(pq);
+///
+/// let mut pq: WalPriorityQueue<u32, String> = WalPriorityQueue::open(&dir).unwrap();
+/// assert_eq!(Some((1, "deploy".to_string())), pq.pop().unwrap());
+/// pq.checkpoint().unwrap();
+/// assert_eq!(0, pq.journal_len());
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub struct WalPriorityQueue<S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    pq: PriorityQueue<S, T, O>,
+    dir: PathBuf,
+    log: File,
+    /// Length of the log up to the end of the last complete record.
+    log_len: u64,
+    generation: u64,
+    records: usize,
+    sync: bool,
+    buf: Vec<u8>,
+}
+
+impl<S, T> WalPriorityQueue<S, T>
+where
+    S: PartialOrd + Persist,
+    T: Persist,
+{
+    /// Opens the queue stored in the directory `dir`, where the lowest score
+    /// is on top, replaying the operations logged since the last checkpoint.
+    /// The directory and its files are created if they don't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the files can't be read or created, and an error
+    /// of [`InvalidData`] kind if they are not a queue snapshot and log, or
+    /// the log is corrupted before its tail.
+    ///
+    /// [`InvalidData`]: std::io::ErrorKind::InvalidData
+    pub fn open<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        WalPriorityQueue::open_with(dir.as_ref())
+    }
+}
+
+impl<S, T> WalPriorityQueue<S, T, Max>
+where
+    S: PartialOrd + Persist,
+    T: Persist,
+{
+    /// Opens the queue stored in the directory `dir`, where the highest
+    /// score is on top.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`open`](WalPriorityQueue::open).
+    pub fn open_max<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        WalPriorityQueue::open_with(dir.as_ref())
+    }
+}
+
+impl<S, T, O> WalPriorityQueue<S, T, O>
+where
+    S: PartialOrd + Persist,
+    T: Persist,
+    O: Order,
+{
+    fn open_with(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let (generation, mut pq) = match File::open(dir.join(SNAPSHOT)) {
+            Ok(file) => {
+                let mut reader = BufReader::new(file);
+                let generation = u64::read_from(&mut reader)?;
+                (generation, PriorityQueue::load_from(reader)?)
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => (0, PriorityQueue::default()),
+            Err(e) => return Err(e),
+        };
+
+        let mut log = OpenOptions::new().read(true).append(true).create(true)
+                                        .open(dir.join(LOG))?;
+        let mut bytes = Vec::new();
+        log.read_to_end(&mut bytes)?;
+
+        let mut records = 0;
+        let mut input = &bytes[..];
+        let logged = read_header(&mut input)?;
+        if logged == Some(generation) {
+            while !input.is_empty() {
+                match split_record(&mut input) {
+                    Some(mut payload) => {
+                        read_record(&mut payload, &mut pq)?;
+                        records += 1;
+                    }
+                    // The process died while writing the last record, unless
+                    // there is a valid one after it.
+                    None if !followed_by_record(input) => break,
+                    None => return Err(invalid("corrupted log record")),
+                }
+            }
+        }
+
+        let log_len = (bytes.len() - input.len()) as u64;
+        let mut wal = WalPriorityQueue {
+            pq, dir: dir.to_owned(), log, log_len, generation, records, sync: true,
+            buf: Vec::new()
+        };
+        if logged == Some(generation) {
+            if !input.is_empty() {
+                wal.log.set_len(log_len)?;
+                wal.log.sync_data()?;
+            }
+        } else {
+            // The log is empty, torn or older than the snapshot, which
+            // happens when a checkpoint was interrupted after the rename.
+            wal.reset_log()?;
+        }
+        Ok(wal)
+    }
+
+    /// Logs an element and then inserts it in the heap.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the record can't be written to the log, in which
+    /// case the element is not put in the queue.
+    pub fn put(&mut self, score: S, item: T) -> io::Result<()> {
+        self.start_record(PUT);
+        score.write_to(&mut self.buf)?;
+        item.write_to(&mut self.buf)?;
+        self.append()?;
+        self.pq.put(score, item);
+        Ok(())
+    }
+
+    /// Logs the removal and then removes the top element from the heap,
+    /// returning it. Nothing is logged if the queue is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the record can't be written to the log, in which
+    /// case the element stays in the queue.
+    pub fn pop(&mut self) -> io::Result<Option<(S, T)>> {
+        if self.pq.is_empty() {
+            return Ok(None);
+        }
+        self.start_record(POP);
+        self.append()?;
+        Ok(self.pq.pop())
+    }
+
+    /// Returns a reference to the top element, without logging anything.
+    pub fn peek(&self) -> Option<&(S, T)> {
+        self.pq.peek()
+    }
+
+    /// Returns the number of elements in the queue.
+    pub fn len(&self) -> usize {
+        self.pq.len()
+    }
+
+    /// Returns `true` is there are no elements in the queue.
+    pub fn is_empty(&self) -> bool {
+        self.pq.is_empty()
+    }
+
+    /// Returns the in-memory queue.
+    pub fn queue(&self) -> &PriorityQueue<S, T, O> {
+        &self.pq
+    }
+
+    /// Returns the directory the queue is stored in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the number of operations logged since the last checkpoint,
+    /// which have to be replayed when the queue is opened.
+    pub fn journal_len(&self) -> usize {
+        self.records
+    }
+
+    /// Sets whether every operation waits until its record is on disk,
+    /// which is the default.
+    pub fn set_sync(&mut self, sync: bool) {
+        self.sync = sync;
+    }
+
+    /// Waits until all the logged operations are on disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the operating system fails to sync the log.
+    pub fn sync(&self) -> io::Result<()> {
+        self.log.sync_data()
+    }
+
+    /// Writes a snapshot of the queue and empties the log.
+    ///
+    /// The snapshot is written to a temporary file and renamed over the old
+    /// one, so a crash at any point leaves either the old snapshot and log or
+    /// the new snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the snapshot or the log can't be written.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)***
+    pub fn checkpoint(&mut self) -> io::Result<()> {
+        let generation = self.generation + 1;
+        let tmp = self.dir.join(SNAPSHOT_TMP);
+        let mut writer = BufWriter::new(File::create(&tmp)?);
+        generation.write_to(&mut writer)?;
+        self.pq.save_to(&mut writer)?;
+        writer.into_inner().map_err(io::IntoInnerError::into_error)?.sync_all()?;
+        fs::rename(&tmp, self.dir.join(SNAPSHOT))?;
+        // Make the rename durable, which isn't supported on every platform.
+        if let Ok(dir) = File::open(&self.dir) {
+            let _ = dir.sync_all();
+        }
+
+        self.generation = generation;
+        self.reset_log()
+    }
+
+    /// Truncates the log and writes the header of the current generation.
+    fn reset_log(&mut self) -> io::Result<()> {
+        self.log.set_len(0)?;
+        self.log.write_all(&MAGIC)?;
+        self.log.write_all(&self.generation.to_le_bytes())?;
+        self.log.sync_data()?;
+        self.log_len = LOG_HEADER;
+        self.records = 0;
+        Ok(())
+    }
+
+    /// Clears the buffer leaving space for the frame of a new record.
+    fn start_record(&mut self, tag: u8) {
+        self.buf.clear();
+        self.buf.extend_from_slice(&[0; FRAME]);
+        self.buf.push(tag);
+    }
+
+    /// Frames the record from the buffer and writes it at the end of the
+    /// log. If it fails, the log is cut back to the end of the previous
+    /// record, so that an operation which returned an error is not applied on
+    /// the replay.
+    fn append(&mut self) -> io::Result<()> {
+        let len = u32::try_from(self.buf.len() - FRAME).map_err(|_| {
+            io::Error::new(ErrorKind::InvalidInput, "log record is too large")
+        })?;
+        self.buf[..4].copy_from_slice(&len.to_le_bytes());
+        let crc = crc32(crc32(0, &self.buf[..4]), &self.buf[FRAME..]);
+        self.buf[4..FRAME].copy_from_slice(&crc.to_le_bytes());
+
+        let written = self.log.write_all(&self.buf).and_then(|()| {
+            if self.sync { self.log.sync_data() } else { Ok(()) }
+        });
+        if let Err(e) = written {
+            // A record which is still torn after a failed truncation is
+            // dropped on the replay anyway.
+            let _ = self.log.set_len(self.log_len);
+            return Err(e);
+        }
+        self.log_len += self.buf.len() as u64;
+        self.records += 1;
+        Ok(())
+    }
+}
+
+impl<S, T, O> fmt::Debug for WalPriorityQueue<S, T, O>
+where
+    S: PartialOrd + fmt::Debug,
+    T: fmt::Debug,
+    O: Order,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WalPriorityQueue")
+         .field("dir", &self.dir)
+         .field("generation", &self.generation)
+         .field("records", &self.records)
+         .field("pq", &self.pq)
+         .finish()
+    }
+}
+
+/// Reads the generation from the header of the log, or `None` if the header
+/// is missing or torn.
+fn read_header(input: &mut &[u8]) -> io::Result<Option<u64>> {
+    if (input.len() as u64) < LOG_HEADER {
+        return Ok(None);
+    }
+    let mut magic = [0; 4];
+    input.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        return Err(invalid("not a priority queue log"));
+    }
+    u64::read_from(input).map(Some)
+}
+
+/// Splits the next record off the log and returns its payload, or `None`
+/// leaving the log as it is if the record is cut short or fails its checksum.
+fn split_record<'a>(input: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let (frame, rest) = input.split_at_checked(FRAME)?;
+    let len = u32::from_le_bytes(frame[..4].try_into().unwrap()) as usize;
+    let crc = u32::from_le_bytes(frame[4..].try_into().unwrap());
+    let (payload, rest) = rest.split_at_checked(len)?;
+    if crc32(crc32(0, &frame[..4]), payload) != crc {
+        return None;
+    }
+    *input = rest;
+    Some(payload)
+}
+
+/// Checks if a valid record follows the bad record the log starts with.
+fn followed_by_record(input: &[u8]) -> bool {
+    let len = match input.get(..4) {
+        Some(len) => u32::from_le_bytes(len.try_into().unwrap()) as usize,
+        None => return false,
+    };
+    let mut rest = match input.get(FRAME.saturating_add(len)..) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    split_record(&mut rest).is_some()
+}
+
+/// Applies the operation from the payload of a record to the queue.
+fn read_record<S, T, O>(input: &mut &[u8], pq: &mut PriorityQueue<S, T, O>) -> io::Result<()>
+where
+    S: PartialOrd + Persist,
+    T: Persist,
+    O: Order,
+{
+    // The checksum matched, so a record which doesn't decode was written in
+    // another format rather than torn.
+    let truncated = |e: io::Error| match e.kind() {
+        ErrorKind::UnexpectedEof => invalid("truncated log record"),
+        _ => e,
+    };
+    match u8::read_from(input).map_err(truncated)? {
+        PUT => {
+            let score = S::read_from(input).map_err(truncated)?;
+            let item = T::read_from(input).map_err(truncated)?;
+            pq.put(score, item);
+        }
+        POP => {
+            pq.pop();
+        }
+        tag => return Err(invalid(format!("unknown log record {}", tag))),
+    }
+    if !input.is_empty() {
+        return Err(invalid("trailing bytes in log record"));
+    }
+    Ok(())
+}
+
+fn invalid<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(ErrorKind::InvalidData, error)
+}
+
+/// Continues the CRC-32 (IEEE) checksum `crc` over `bytes`.
+fn crc32(crc: u32, bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!crc, |crc, &byte| {
+        (0..8).fold(crc ^ byte as u32, |crc, _| {
+            (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
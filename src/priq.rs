@@ -1,4 +1,5 @@
-#![feature(slice_range)]
+#![feature(allocator_api, slice_range)]
+#![cfg_attr(not(feature = "std"), no_std)]
 //! Priority queue (min/max heap) using raw binary heap.
 //!
 //! `PriorityQueue` is built using raw array for efficient performance.
@@ -23,23 +24,166 @@
 //!
 //! 3 - Equal scoring items are stored at first available free space.
 //!     * This gives performance boost for large number of entries.
+//!     * Use [`StablePriorityQueue`] if equal scores must come out in the
+//!     insertion order.
 //!
 //! 4 - Easy to use!
 //!
 //! You can read more about this crate on [my blog](https://www.bexxmodd.com)
-
+//!
+//! # Features
+//!
+//! * `std` (default) - the crate depends only on `core` and `alloc` without
+//!   it, so it can be used in `#![no_std]` code. The [`sync`], [`channel`],
+//!   [`time`] and [`persistent`] modules and all the other features need it.
+//! * `arbitrary` - `Arbitrary` implementation for `PriorityQueue` to use it
+//!   as an input of fuzz targets.
+//! * `bumpalo` - `BumpPriorityQueue` allocated in a
+//!   [`bumpalo`](https://docs.rs/bumpalo) arena.
+//! * `ordered-float` - conveniences for `OrderedFloat` and `NotNan` scores
+//!   from the [`ordered_float`](https://docs.rs/ordered-float) crate.
+//! * `concurrent` - lock-free priority queues in the [`concurrent`] module.
+//! * `defmt` - `defmt::Format` implementation for `PriorityQueue` to log it
+//!   from embedded targets.
+//! * `ffi` - C interface to a priority queue in the [`ffi`] module.
+//! * `futures` - `Stream` implementations for the async queues and channels.
+//! * `mmap` - a priority queue backed by a memory-mapped file in the
+//!   [`persistent`] module.
+//! * `python` - Python bindings built with [`pyo3`](https://docs.rs/pyo3) in
+//!   the `python` module.
+//! * `rayon` - parallel iterators over `PriorityQueue` with the
+//!   [`rayon`](https://docs.rs/rayon) crate.
+//! * `rkyv` - zero-copy archiving of `PriorityQueue` with the
+//!   [`rkyv`](https://docs.rs/rkyv) crate.
+//! * `testing` - property testing helpers in the `testing` module, built on
+//!   the [`proptest`](https://docs.rs/proptest) crate.
+
+extern crate alloc;
+#[cfg(feature = "std")]
 extern crate rand;
 
-use std::mem;
-use std::ptr;
-use std::slice;
-use std::marker::PhantomData;
-use std::convert::From;
-use std::cmp::{self, Ordering};
-use std::ops::{Add, Deref, DerefMut, Range, RangeBounds};
-
+use alloc::alloc::Global;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::mem::{self, MaybeUninit};
+use core::alloc::Allocator;
+use core::fmt;
+use core::ptr;
+use core::slice;
+use core::marker::PhantomData;
+use core::convert::From;
+use core::cmp::{self, Ordering};
+use core::ops::{self, Add, Deref, DerefMut, Range, RangeBounds};
+use core::iter::{FusedIterator, Sum};
+
+#[cfg(feature = "rkyv")]
+mod archive;
+#[cfg(feature = "bumpalo")]
+mod arena;
+mod backend;
+mod banded;
+mod bheap;
+mod binomial;
+mod btree;
+mod bucket;
+mod builder;
+mod by_key;
+mod calendar;
+#[cfg(feature = "std")]
+pub mod channel;
+mod compare;
+#[cfg(feature = "concurrent")]
+pub mod concurrent;
+mod convert;
+mod dary;
+mod decay;
+mod depq;
+#[cfg(feature = "defmt")]
+mod embedded;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod fibonacci;
+mod float;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+mod handle;
+mod leftist;
+mod lex;
+mod median;
+mod monotone;
+mod nan;
+mod order;
+#[cfg(feature = "ordered-float")]
+mod ordered;
+mod pairing;
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "std")]
+pub mod persistent;
+#[cfg(feature = "python")]
+pub mod python;
+mod radix;
 mod rawpq;
+mod segmented;
+mod sift;
+mod slab;
+mod small;
+#[cfg(feature = "std")]
+mod snapshot;
+mod soa;
+mod soft;
+mod stable;
+#[cfg(feature = "std")]
+pub mod sync;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod time;
+mod topk;
+mod weak;
 use rawpq::RawPQ;
+use compare::Comparator;
+
+#[cfg(feature = "rkyv")]
+pub use archive::ArchivedPriorityQueue;
+#[cfg(feature = "bumpalo")]
+pub use arena::BumpPriorityQueue;
+pub use backend::{DrainSorted, HeapBackend, IntoSorted};
+pub use banded::{BandedPriorityQueue, ServicePolicy};
+pub use bheap::BHeap;
+pub use binomial::BinomialHeap;
+pub use btree::BTreePriorityQueue;
+pub use bucket::BucketQueue;
+pub use builder::Builder;
+pub use by_key::PriorityQueueBy;
+pub use calendar::CalendarQueue;
+pub use dary::DaryHeap;
+pub use decay::DecayPriorityQueue;
+pub use depq::DoubleEndedPriorityQueue;
+pub use error::{PutError, PutErrorKind, TryReserveError};
+pub use fibonacci::FibonacciHeap;
+pub use float::{Float, FloatPriorityQueue};
+pub use handle::Handle;
+pub use leftist::LeftistHeap;
+pub use lex::{Lex2, Lex3};
+pub use median::MedianHeap;
+pub use monotone::MonotonePriorityQueue;
+pub use nan::NanPolicy;
+pub use order::{Max, Min, Order};
+pub use pairing::PairingHeap;
+pub use radix::{Radix, RadixHeap};
+pub use rawpq::{GrowthPolicy, ShrinkPolicy};
+pub use segmented::SegmentedPriorityQueue;
+pub use small::SmallPriorityQueue;
+#[cfg(feature = "std")]
+pub use snapshot::Persist;
+pub use soa::SoaPriorityQueue;
+pub use soft::SoftHeap;
+pub use stable::StablePriorityQueue;
+pub use topk::TopK;
+pub use weak::WeakHeap;
 
 /// A Min-Max Heap with designated arguments for `score` and associated `item`!
 ///
@@ -102,8 +246,9 @@ use rawpq::RawPQ;
 /// # Partial Ordering
 ///
 /// Because `priq` allows `score` arguments that only implement `PartialOrd`, 
-/// elements that can't be compared are evaluated and are put in the back of
-/// the queue:
+/// elements that can't be compared even with themselves are quarantined
+/// apart from the heap, in the back of the queue (see [`incomparable`] and
+/// [`NanPolicy`]):
 ///
 /// ```
 /// use priq::PriorityQueue;
@@ -175,6 +320,22 @@ use rawpq::RawPQ;
 /// assert_eq!(pq.pop().unwrap().1, "Z");
 /// ```
 ///
+/// Direction of the heap is also a part of the type, given by the third
+/// type parameter, which is [`Min`] by default. Max-Heap is [`Max`] (or
+/// [`MaxPriorityQueue`] alias) and compares scores in reverse without any
+/// wrappers.
+///
+/// ```
+/// use priq::{Max, PriorityQueue};
+///
+/// let mut pq: PriorityQueue<u8, String, Max> = PriorityQueue::default();
+///
+/// pq.put(26, "Z".to_string());
+/// pq.put(1, "A".to_string());
+///
+/// assert_eq!(pq.pop().unwrap().1, "Z");
+/// ```
+///
 /// # Merging and Combining 
 ///
 /// You can merge another priority queue to this one. Right hand side priority
@@ -217,16 +378,45 @@ use rawpq::RawPQ;
 /// [`put`]: PriorityQueue::put
 /// [`peek`]: PriorityQueue::peek
 /// [`pop`]: PriorityQueue::pop
+/// [`incomparable`]: PriorityQueue::incomparable
 ///
-#[derive(Debug)]
-pub struct PriorityQueue<S, T> 
+pub struct PriorityQueue<S, T, O = Min, A = Global>
 where
-    S: PartialOrd,
+    O: Order,
+    A: Allocator,
 {
-    data: RawPQ<S, T>,
+    data: RawPQ<S, T, A>,
     len: usize,
+    nans: usize,
+    max: usize,
+    cmp: Comparator<S>,
+    tiebreak: Option<Comparator<T>>,
+    nan: NanPolicy,
+    _order: PhantomData<O>,
 }
 
+/// `PriorityQueue` with the highest score on top. See [`Max`].
+///
+/// Constructors like [`PriorityQueue::new`] or `From` conversions create a
+/// min-heap, so the type can be inferred without annotations. Max-heap is
+/// created with [`new_max`], [`Default`] or a [`Builder`] and filled with
+/// [`Extend`].
+///
+/// # Examples
+///
+/// ```
+/// use priq::MaxPriorityQueue;
+///
+/// let mut pq = MaxPriorityQueue::new_max();
+/// pq.extend([(5, 55), (1, 11), (4, 44)]);
+///
+/// assert_eq!(55, pq.pop().unwrap().1);
+/// assert_eq!(44, pq.pop().unwrap().1);
+/// ```
+///
+/// [`new_max`]: PriorityQueue::new_max
+pub type MaxPriorityQueue<S, T> = PriorityQueue<S, T, Max>;
+
 
 impl<S, T> PriorityQueue<S, T>
 where
@@ -244,10 +434,7 @@ where
     #[inline]
     #[must_use]
     pub fn new() -> Self {
-        PriorityQueue {
-            data: RawPQ::new(),
-            len: 0,
-        }
+        PriorityQueue::from_raw(RawPQ::new(), 0, Comparator::default())
     }
 
     /// If you expect that you’ll be putting at least `n` number of items in 
@@ -255,21 +442,364 @@ where
     /// to `cap`. This can boost the performance for a large number of sets 
     /// because it'll eliminate the need to grow the underlying array often.
     ///
+    /// Space for exactly `cap` elements is allocated, capacity is never
+    /// rounded up. If `cap` is zero nothing is allocated, same as [`new`].
+    /// Zero-sized elements don't need any memory, so capacity for them is
+    /// always the maximum possible.
+    ///
+    /// [`new`]: PriorityQueue::new
+    ///
     /// # Examples
     ///
     /// ```
     /// use priq::PriorityQueue;
     ///
     /// let pq: PriorityQueue<usize, usize> = PriorityQueue::with_capacity(100);
+    /// assert_eq!(100, pq.capacity());
+    ///
+    /// let pq: PriorityQueue<usize, usize> = PriorityQueue::with_capacity(0);
+    /// assert_eq!(0, pq.capacity());
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
     #[inline]
     #[must_use]
     pub fn with_capacity(cap: usize) -> Self {
-        PriorityQueue {
-            data: RawPQ::with_capacity(cap),
-            len: 0,
-        }
+        PriorityQueue::from_raw(RawPQ::with_capacity(cap), 0, Comparator::default())
+    }
+
+    /// Returns a [`Builder`] to configure a new `PriorityQueue` step by step,
+    /// instead of picking one of the `with_*` constructors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::{GrowthPolicy, PriorityQueue};
+    ///
+    /// let pq: PriorityQueue<f64, &str> = PriorityQueue::builder()
+    ///     .capacity(64)
+    ///     .growth_policy(GrowthPolicy::new(8, 4.0))
+    ///     .build();
+    ///
+    /// assert!(pq.is_empty());
+    /// assert_eq!(64, pq.capacity());
+    /// ```
+    #[inline]
+    pub fn builder() -> Builder<S, T> {
+        Builder::new()
+    }
+
+    /// Create an empty `PriorityQueue` which grows its underlying array
+    /// according to the given [`GrowthPolicy`]. E.g. for large elements
+    /// smaller growth factor can be used to waste less memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::{GrowthPolicy, PriorityQueue};
+    ///
+    /// let policy = GrowthPolicy::new(100, 1.25);
+    /// let pq: PriorityQueue<usize, [u8; 1024]> =
+    ///     PriorityQueue::with_growth_policy(policy);
+    /// assert_eq!(policy, pq.growth_policy());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_growth_policy(policy: GrowthPolicy) -> Self {
+        let mut pq = PriorityQueue::new();
+        pq.set_growth_policy(policy);
+        pq
+    }
+
+    /// Create an empty `PriorityQueue` which resolves ties between elements
+    /// with equal scores by comparing their items with the given function.
+    /// Item which compares as `Less` is popped first, regardless of the
+    /// order of the queue. Without a tiebreak the order of equal elements
+    /// depends on their positions in the heap and is unspecified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// // tasks with the same priority are done by their id
+    /// let mut pq = PriorityQueue::with_tiebreak(|a: &u32, b: &u32| a.cmp(b));
+    /// pq.put(1, 7);
+    /// pq.put(0, 9);
+    /// pq.put(1, 3);
+    /// pq.put(1, 5);
+    ///
+    /// assert_eq!(Some((0, 9)), pq.pop());
+    /// assert_eq!(Some((1, 3)), pq.pop());
+    /// assert_eq!(Some((1, 5)), pq.pop());
+    /// assert_eq!(Some((1, 7)), pq.pop());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_tiebreak<F>(tiebreak: F) -> Self
+    where
+        F: Fn(&T, &T) -> Ordering + Send + Sync + 'static,
+    {
+        let mut pq = PriorityQueue::new();
+        pq.tiebreak = Some(Comparator::new(move |a, b| Some(tiebreak(a, b))));
+        pq
+    }
+
+    /// Create an empty `PriorityQueue` with float scores which are treated
+    /// as equal if they differ by no more than `epsilon`. Such scores are
+    /// ties for [`pop_ties`], [`dedup`] and the tiebreak of the queue, instead
+    /// of being ordered by meaningless rounding errors.
+    ///
+    /// Note that closeness is not transitive, scores `0.0` and `2.0` are not
+    /// equal with `epsilon` of `1.0`, even though both are equal to `1.0`.
+    /// Negative or `NAN` epsilon makes only the exactly equal scores equal.
+    ///
+    /// [`pop_ties`]: PriorityQueue::pop_ties
+    /// [`dedup`]: PriorityQueue::dedup
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::with_epsilon(1e-9);
+    /// pq.put(0.3, "a");
+    /// pq.put(0.1 + 0.2, "b");
+    /// pq.put(0.5, "c");
+    ///
+    /// assert_eq!(2, pq.pop_ties().len());
+    /// assert_eq!("c", pq.pop().unwrap().1);
+    /// ```
+    #[must_use]
+    pub fn with_epsilon(epsilon: S) -> Self
+    where
+        S: Float,
+    {
+        PriorityQueue::builder().epsilon(epsilon).build()
+    }
+
+    /// Create `PriorityQueue` from a `Vec` which is already sorted by scores
+    /// in an ascending order. Sorted array is already a valid heap, so no
+    /// re-ordering is done and the `Vec`'s memory is reused as is.
+    ///
+    /// It's up to the caller to make sure that the `Vec` is sorted. This is
+    /// checked only in debug builds and if it's not the case, priority queue
+    /// will return elements in an unspecified order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from_sorted_vec(vec![(1, 11), (2, 22), (5, 55)]);
+    /// assert_eq!(3, pq.len());
+    /// assert_eq!(11, pq.pop().unwrap().1);
+    /// assert_eq!(22, pq.pop().unwrap().1);
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// Done in a constant time ***O(1)*** (excluding debug check).
+    pub fn from_sorted_vec(vec: Vec<(S, T)>) -> Self {
+        let len = vec.len();
+        let mut vec = mem::ManuallyDrop::new(vec);
+        // SAFETY: same as when building from `Vec`, we take over its buffer.
+        let data = unsafe {
+            RawPQ::from_raw_parts(vec.as_mut_ptr(), vec.capacity())
+        };
+
+        let mut pq = PriorityQueue::from_raw(data, len, Comparator::default());
+        pq.nans = pq.iter().rev().take_while(|e| !pq.comparable(&e.0, &e.0)).count();
+        pq.max = pq.heap_len().saturating_sub(1);
+        debug_assert!(
+            pq.windows(2).all(|w| !pq.less(&w[1].0, &w[0].0)),
+            "`from_sorted_vec` requires elements sorted by scores"
+        );
+        pq
+    }
+
+    /// Create `PriorityQueue` from an iterator which yields elements already
+    /// sorted by scores in an ascending order. Elements are collected without
+    /// being sifted as described in [`from_sorted_vec`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from_sorted_iter((0..5).map(|i| (i, i * 2)));
+    /// assert_eq!(5, pq.len());
+    /// assert_eq!(0, pq.pop().unwrap().1);
+    /// assert_eq!(2, pq.pop().unwrap().1);
+    /// ```
+    ///
+    /// [`from_sorted_vec`]: PriorityQueue::from_sorted_vec
+    pub fn from_sorted_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (S, T)>,
+    {
+        PriorityQueue::from_sorted_vec(iter.into_iter().collect())
+    }
+}
+
+impl<S, T> PriorityQueue<S, T> {
+    /// Create an empty `PriorityQueue` which compares scores with the given
+    /// function instead of their `PartialOrd` implementation. Comparator is
+    /// stored in the queue and used for every operation that orders elements,
+    /// so prioritization logic can live in a closure without newtypes.
+    ///
+    /// Same as with `partial_cmp`, scores for which comparator returns `None`
+    /// even when compared with themselves are put in the back of the queue.
+    /// Scores don't need to implement `PartialOrd` at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// // case-insensitive ordering of names
+    /// let mut pq = PriorityQueue::with_comparator(|a: &String, b: &String| {
+    ///     a.to_lowercase().partial_cmp(&b.to_lowercase())
+    /// });
+    ///
+    /// pq.put("bazel".to_string(), 2);
+    /// pq.put("Odo".to_string(), 3);
+    /// pq.put("Aria".to_string(), 1);
+    ///
+    /// assert_eq!(1, pq.pop().unwrap().1);
+    /// assert_eq!(2, pq.pop().unwrap().1);
+    /// assert_eq!(3, pq.pop().unwrap().1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_comparator<F>(cmp: F) -> Self
+    where
+        F: Fn(&S, &S) -> Option<Ordering> + Send + Sync + 'static,
+    {
+        PriorityQueue::from_raw(RawPQ::new(), 0, Comparator::new(cmp))
+    }
+}
+
+impl<S, T> PriorityQueue<S, T, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `PriorityQueue` where the top element is the one with
+    /// the highest score, so [`pop`] yields the largest scores first. Scores
+    /// are compared in reverse internally and don't need to be wrapped into
+    /// [`Reverse`].
+    ///
+    /// Scores that can't be compared still end up in the back of the queue.
+    ///
+    /// [`pop`]: PriorityQueue::pop
+    /// [`Reverse`]: std::cmp::Reverse
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::new_max();
+    /// pq.put(1.0, "A");
+    /// pq.put(f64::NAN, "?");
+    /// pq.put(26.0, "Z");
+    ///
+    /// assert!(pq.is_max());
+    /// assert_eq!("Z", pq.pop().unwrap().1);
+    /// assert_eq!("A", pq.pop().unwrap().1);
+    /// assert_eq!("?", pq.pop().unwrap().1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new_max() -> Self {
+        PriorityQueue::from_raw(RawPQ::new(), 0, Comparator::default())
+    }
+}
+
+impl<S, T, A> PriorityQueue<S, T, Min, A>
+where
+    S: PartialOrd,
+    A: Allocator,
+{
+    /// Create an empty `PriorityQueue` which allocates its memory with
+    /// `alloc`, so it can live in an arena, a pool or be tracked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use std::alloc::System;
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::new_in(System);
+    /// pq.put(2, "b");
+    /// pq.put(1, "a");
+    ///
+    /// assert_eq!(Some((1, "a")), pq.pop());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new_in(alloc: A) -> Self {
+        PriorityQueue::from_raw(RawPQ::new_in(alloc), 0, Comparator::default())
+    }
+
+    /// Create an empty `PriorityQueue` with space for exactly `cap` elements
+    /// allocated with `alloc`. See [`with_capacity`].
+    ///
+    /// [`with_capacity`]: PriorityQueue::with_capacity
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    #[inline]
+    #[must_use]
+    pub fn with_capacity_in(cap: usize, alloc: A) -> Self {
+        PriorityQueue::from_raw(RawPQ::with_capacity_in(cap, alloc), 0, Comparator::default())
+    }
+}
+
+impl<S, T, A> PriorityQueue<S, T, Max, A>
+where
+    S: PartialOrd,
+    A: Allocator,
+{
+    /// Create an empty `PriorityQueue` where the highest score is on top,
+    /// which allocates its memory with `alloc`.
+    #[inline]
+    #[must_use]
+    pub fn new_max_in(alloc: A) -> Self {
+        PriorityQueue::from_raw(RawPQ::new_in(alloc), 0, Comparator::default())
     }
+}
+
+impl<S, T, O, A> PriorityQueue<S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
+{
+    /// Returns a reference to the allocator of the `PriorityQueue`.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        &self.data.alloc
+    }
+
+    /// Returns `true` if the top element of `PriorityQueue` is the one with
+    /// the highest score, i.e. its order is [`Max`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq: PriorityQueue<usize, usize> = PriorityQueue::new();
+    /// assert!(!pq.is_max());
+    /// ```
+    pub fn is_max(&self) -> bool {
+        O::TOP == Ordering::Greater
+    }
+
 
     /// Inserts an element in the heap.
     ///
@@ -310,17 +840,122 @@ where
     ///
     /// For worst case scenario ***O(log(n))***.
     ///
+    /// # Panics
+    ///
+    /// Panics if the score is incomparable and the [`NanPolicy`] of the queue
+    /// is `Reject` or `Panic`.
+    ///
     pub fn put(&mut self, score: S, item: T) {
+        self.check_score(&score);
         if self.cap() == self.len { self.data.grow(); }
-        self.len += 1;
+        self.push_up((score, item));
+    }
 
-        // SAFETY: We're writing new element in the back of the array. We ensure
-        //      that this is safe by first checking if we have enough capacity 
-        //      allocated, and if not we grow it to accommodate new entries.
-        unsafe {
-            ptr::write(self.ptr().add(self.len - 1), (score, item))
-        };
-        self.heapify_up(self.len - 1);
+    /// Tries to insert an element in the `PriorityQueue`, same as [`put`].
+    /// If the underlying array needs to grow and the memory can't be
+    /// allocated, the error is returned together with the element instead of
+    /// aborting the program. Incomparable scores are returned the same way
+    /// when the queue uses [`NanPolicy::Reject`].
+    ///
+    /// [`put`]: PriorityQueue::put
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq: PriorityQueue<u8, String> = PriorityQueue::new();
+    ///
+    /// assert!(pq.try_put(1, String::from("Ruiner")).is_ok());
+    /// assert_eq!(1, pq.len());
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(n))***.
+    pub fn try_put(&mut self, score: S, item: T) -> Result<(), PutError<(S, T)>> {
+        if !self.admits(&score) {
+            let kind = PutErrorKind::Incomparable;
+            return Err(PutError { element: (score, item), kind })
+        }
+        if self.cap() == self.len {
+            if let Err(error) = self.data.try_grow() {
+                let kind = PutErrorKind::Alloc(error);
+                return Err(PutError { element: (score, item), kind })
+            }
+        }
+        self.push_up((score, item));
+        Ok(())
+    }
+
+    /// Inserts all the elements from an iterator in the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from([(5, 55), (4, 44)]);
+    /// pq.put_many((1..4).map(|i| (i, i * 11)));
+    ///
+    /// assert_eq!(5, pq.len());
+    /// assert_eq!(11, pq.pop().unwrap().1);
+    /// ```
+    ///
+    /// All the new elements are first written in the back of the array and
+    /// only then put in their correct places. Depending on how many elements
+    /// were added compared to the existing ones, each of them is percolated
+    /// up separately or the whole heap is rebuilt once, whichever is cheaper.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(min(k log(n + k), n + k))*** for `k` new elements.
+    pub fn put_many<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (S, T)>,
+    {
+        let iter = iter.into_iter();
+        let start = self.heap_len();
+        self.data.reserve(self.len + iter.size_hint().0);
+        iter.for_each(|elem| {
+            self.check_score(&elem.0);
+            if self.cap() == self.len { self.data.grow(); }
+            self.push(elem);
+        });
+        self.rebuild_tail(start);
+    }
+
+    /// Clones and inserts all the elements of a slice in the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from([(5, 55), (4, 44)]);
+    /// pq.extend_from_slice(&[(3, 33), (1, 11), (2, 22)]);
+    ///
+    /// assert_eq!(5, pq.len());
+    /// assert_eq!(11, pq.pop().unwrap().1);
+    /// ```
+    ///
+    /// Memory for all the elements is reserved once and they're cloned
+    /// straight into the back of the array, after which heap order is
+    /// restored the same way as in [`put_many`].
+    ///
+    /// [`put_many`]: PriorityQueue::put_many
+    pub fn extend_from_slice(&mut self, other: &[(S, T)])
+    where
+        S: Clone,
+        T: Clone,
+    {
+        let start = self.heap_len();
+        self.data.reserve(self.len + other.len());
+        other.iter().for_each(|elem| {
+            self.check_score(&elem.0);
+            self.push(elem.clone());
+        });
+        self.rebuild_tail(start);
     }
 
     /// Get the top priority element from `PriorityQueue`.
@@ -368,30 +1003,35 @@ where
     ///
     /// Worst case is ***O(log(n))***.
     pub fn pop(&mut self) -> Option<(S, T)> {
+        if self.nans_first() {
+            self.len -= 1;
+            self.nans -= 1;
+            // SAFETY: the last element is cut off from the array by reducing
+            //      `len`, so it's read out exactly once.
+            return Some(unsafe { ptr::read(self.ptr().add(self.len)) })
+        }
         if self.len > 0 {
-            // TODO fix the algo
-            let last_ = self.len - 1;
-            // If any of the scores is uncomparable move it to the back
-            let mut i = last_;
-            while i > 0 && !self.comparable(&self[i].0, &self[i].0) {
-                i -= 1;
-            }
-            if i != last_ {
-                self.swap(0, i + 1);
-            }
-
             unsafe {
+                let last = self.heap_len() - 1;
                 let _top = ptr::read(self.ptr());
-                let _tmp = ptr::read(self.ptr().add(self.len - 1));
+                let _tmp = ptr::read(self.ptr().add(last));
                 
                 // SAFETY: this is safe because the last element will written
                 //      in-place of the first element in an allocated space.
+                //      Freed slot is taken by the last quarantined element.
                 ptr::write(self.ptr(), _tmp);
+                if self.nans > 0 {
+                    ptr::copy_nonoverlapping(
+                        self.ptr().add(self.len - 1), self.ptr().add(last), 1);
+                }
 
                 self.len -= 1;
-                
-                if self.len > 1 { self.heapify_down(0); }
-                if self.cap() > 1_000 && self.cap() / 4 >= self.len {
+
+                // the last element was moved to the top, or the top was the
+                // maximum itself, so every remaining element is equal to it
+                if self.max == last || self.max == 0 { self.max = 0; }
+                if last > 1 { self.heapify_down(0); }
+                if self.data.shrink.should_shrink(self.cap(), self.len) {
                     self.data.shrink();
                 }
                 Some(_top)
@@ -452,9 +1092,164 @@ where
     ///
     /// `peek`-ing is done in a constant time ***O(1)***
     pub fn peek(&self) -> Option<&(S, T)> {
-        if !self.is_empty() {
-            Some(&self[0])
-        } else { None }
+        if self.nans_first() {
+            self.last()
+        } else {
+            self.first()
+        }
+    }
+
+    /// Get the reference to the score of a top element in `PriorityQueue`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq = PriorityQueue::from([(5, "Ruiner"), (2, "Bazel")]);
+    /// assert_eq!(Some(&2), pq.peek_score());
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek_score(&self) -> Option<&S> {
+        self.peek().map(|(s, _)| s)
+    }
+
+    /// Get the reference to the item of a top element in `PriorityQueue`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq = PriorityQueue::from([(5, "Ruiner"), (2, "Bazel")]);
+    /// assert_eq!(Some(&"Bazel"), pq.peek_item());
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek_item(&self) -> Option<&T> {
+        self.peek().map(|(_, e)| e)
+    }
+
+    /// Remove the top element from `PriorityQueue` and return only its item,
+    /// dropping the score.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from([(5, "Ruiner"), (2, "Bazel")]);
+    /// assert_eq!(Some("Bazel"), pq.pop_item());
+    /// assert_eq!(Some("Ruiner"), pq.pop_item());
+    /// assert_eq!(None, pq.pop_item());
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// Worst case is ***O(log(n))***.
+    pub fn pop_item(&mut self) -> Option<T> {
+        self.pop().map(|(_, e)| e)
+    }
+
+    /// Remove the top element together with all the elements which have the
+    /// score equal to the top one. Elements are returned in the order they
+    /// were popped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from([(2, 'c'), (1, 'a'), (3, 'd'), (1, 'b')]);
+    /// let mut ties = pq.pop_ties();
+    /// ties.sort();
+    ///
+    /// assert_eq!(vec![(1, 'a'), (1, 'b')], ties);
+    /// assert_eq!(2, pq.len());
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(k log(n))*** for `k` popped elements.
+    pub fn pop_ties(&mut self) -> Vec<(S, T)> {
+        let mut ties = Vec::new();
+        if let Some(top) = self.pop() {
+            while let Some((s, _)) = self.peek() {
+                if self.compare(&top.0, s) != Some(Ordering::Equal) {
+                    break
+                }
+                ties.extend(self.pop());
+            }
+            ties.insert(0, top);
+        }
+        ties
+    }
+
+    /// Check what is a bottom element in `PriorityQueue`, the one with the
+    /// lowest priority, by getting the reference. It's the element with the
+    /// highest score, or with the lowest one for the queue created with
+    /// [`new_max`]. Elements with scores that can't be compared are
+    /// considered the lowest priority.
+    ///
+    /// [`new_max`]: PriorityQueue::new_max
+    ///
+    /// Position of this element is tracked on every change of the queue, so
+    /// no scan is needed to find it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from([(5, "Ruiner"), (2, "Bazel"), (7, "Jho")]);
+    /// assert_eq!(Some(&(7, "Jho")), pq.peek_bottom());
+    ///
+    /// pq.put(9, "Odo");
+    /// assert_eq!(Some(&(9, "Odo")), pq.peek_bottom());
+    ///
+    /// let mut pq = PriorityQueue::new_max();
+    /// pq.extend([(5, "Ruiner"), (2, "Bazel"), (7, "Jho")]);
+    /// assert_eq!(Some(&(2, "Bazel")), pq.peek_bottom());
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek_bottom(&self) -> Option<&(S, T)> {
+        if self.nans > 0 && (self.nan != NanPolicy::First || self.nans == self.len) {
+            self.last()
+        } else if !self.is_empty() {
+            Some(&self[self.max])
+        } else { None }
+    }
+
+    /// Get the reference to the score of a bottom element in `PriorityQueue`
+    /// as described in [`peek_bottom`], e.g. to monitor the spread of priorities
+    /// together with [`peek_score`].
+    ///
+    /// [`peek_bottom`]: PriorityQueue::peek_bottom
+    ///
+    /// [`peek_score`]: PriorityQueue::peek_score
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq = PriorityQueue::from([(5, "Ruiner"), (2, "Bazel"), (7, "Jho")]);
+    /// assert_eq!(Some(&7), pq.bottom_score());
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn bottom_score(&self) -> Option<&S> {
+        self.peek_bottom().map(|(s, _)| s)
     }
 
     /// Returns the number of elements in the `PriorityQueue`
@@ -481,7 +1276,7 @@ where
     ///
     /// ```
     /// use priq::PriorityQueue;
-
+    ///
     /// let mut pq: PriorityQueue<usize, usize> = PriorityQueue::new();
     /// assert!(pq.is_empty());
     ///
@@ -492,208 +1287,863 @@ where
         self.len == 0
     }
 
-    /// Remove all the elements from `PriorityQueue`
+    /// Returns the number of elements `PriorityQueue` can hold without
+    /// growing the underlying array.
     ///
-    /// # Example
+    /// # Examples
     ///
     /// ```
     /// use priq::PriorityQueue;
     ///
-    /// let mut pq = PriorityQueue::from([(5, 55), (1, 11), (4, 44)]);
-    /// assert!(!pq.is_empty());
-    ///
-    /// pq.clear();
-    /// assert!(pq.is_empty());
+    /// let pq: PriorityQueue<usize, usize> = PriorityQueue::with_capacity(10);
+    /// assert!(pq.capacity() >= 10);
     /// ```
-    pub fn clear(&mut self) {
-        self.drain(..);
+    pub fn capacity(&self) -> usize {
+        self.cap()
     }
 
-    /// Clears the priority queue, returning iterator over the removed elements
-    /// returned items will NOT be in a sorted order. Method takes range as an 
-    /// argument.
+    /// Returns the [`GrowthPolicy`] used when the underlying array is full.
     ///
-    /// # Example
+    /// # Examples
     ///
     /// ```
-    /// use priq::PriorityQueue;
+    /// use priq::{GrowthPolicy, PriorityQueue};
     ///
-    /// let mut pq = PriorityQueue::from([(5, 55), (1, 11), (4, 44), (7, 77)]);
-    /// assert!(!pq.is_empty());
+    /// let pq: PriorityQueue<usize, usize> = PriorityQueue::new();
+    /// assert_eq!(GrowthPolicy::default(), pq.growth_policy());
+    /// assert_eq!(2.0, pq.growth_policy().factor());
+    /// ```
+    pub fn growth_policy(&self) -> GrowthPolicy {
+        self.data.growth
+    }
+
+    /// Changes the [`GrowthPolicy`] for the subsequent growths of the
+    /// underlying array. Already allocated memory is left as is.
     ///
-    /// // drain everything starting from index 2 till the end.
-    /// let mut res: PriorityQueue<usize, usize> = pq.drain(2..).collect();
-    /// assert!(pq.is_empty());
-    /// assert_eq!(2, res.len());
+    /// # Examples
     ///
-    /// // drain the remaining priority queue by giving it full range (..) arg.
-    /// res.drain(..);
-    /// assert!(res.is_empty());
     /// ```
-    pub fn drain<R>(&mut self, range: R) -> Drain<'_, S, T>
-    where 
-        R: RangeBounds<usize>,
-    {
-        let len = self.len();
-        let Range { start, end } = slice::range(range, ..len);
-
-        // SAFETY: we are reading from row memory within a range from start to 
-        //      the `len` where `len` we know is within a memory space of this 
-        //      priority queue.
-        unsafe {
-            let range_slice = slice::from_raw_parts_mut(
-                self.as_mut_ptr().add(start), end - start);
-
-            let iter = RawPQIter::new(range_slice);
-
-            // SAFETY: we set up `len` to zero so even if method panics, memory
-            //      leak will never happen.
-            self.len = 0;
+    /// use priq::{GrowthPolicy, PriorityQueue};
+    ///
+    /// let mut pq = PriorityQueue::from([(1, 11), (2, 22)]);
+    /// pq.set_growth_policy(GrowthPolicy::new(4, 3.0));
+    ///
+    /// pq.put(3, 33);
+    /// assert_eq!(6, pq.capacity());
+    /// ```
+    pub fn set_growth_policy(&mut self, policy: GrowthPolicy) {
+        self.data.growth = policy;
+    }
 
-            Drain {
-                pq: PhantomData,
-                iter,
-            }
-        }
+    /// Returns the [`ShrinkPolicy`] applied when elements are popped.
+    pub fn shrink_policy(&self) -> ShrinkPolicy {
+        self.data.shrink
     }
 
-    /// Clears the priority queue and returns `Vec` with elements in a 
-    /// sorted order.
+    /// Changes the [`ShrinkPolicy`] applied when elements are popped.
     ///
-    /// # Example
+    /// # Examples
     ///
     /// ```
-    /// use priq::PriorityQueue;
+    /// use priq::{PriorityQueue, ShrinkPolicy};
     ///
-    /// let pq = PriorityQueue::from([(5, 55), (1, 11), (4, 44)]);
-    ///
-    /// let mut res = pq.into_sorted_vec(); 
-    /// assert_eq!(3, res.len());
+    /// let mut pq: PriorityQueue<usize, usize> = PriorityQueue::with_capacity(4096);
+    /// pq.set_shrink_policy(ShrinkPolicy::never());
+    /// pq.put(1, 11);
+    /// pq.put(2, 22);
     ///
-    /// // we'll be `pop`-ing values from the back of the vector.
-    /// // this means highest scoring will be all the way back into the `Vec`
-    /// assert_eq!(55, res.pop().unwrap().1);
-    /// assert_eq!(44, res.pop().unwrap().1);
-    /// assert_eq!(11, res.pop().unwrap().1);
+    /// pq.pop();
+    /// assert_eq!(4096, pq.capacity());
     /// ```
+    pub fn set_shrink_policy(&mut self, policy: ShrinkPolicy) {
+        self.data.shrink = policy;
+    }
+
+    /// Returns the [`NanPolicy`] applied to incomparable scores.
+    pub fn nan_policy(&self) -> NanPolicy {
+        self.nan
+    }
+
+    /// Changes the [`NanPolicy`] applied to incomparable scores. Elements
+    /// which are already in the queue are kept, but come out before or after
+    /// the comparable ones as the new policy says.
     ///
-    /// If priority queue has NAN values they will be sorted after all 
-    /// comparable scores without any particular order
+    /// # Examples
     ///
     /// ```
-    /// use priq::PriorityQueue;
+    /// use priq::{NanPolicy, PriorityQueue};
     ///
-    /// let mut pq: PriorityQueue<f32, isize> = PriorityQueue::new();
-    /// pq.put(1.1, 10);
-    /// pq.put(f32::NAN, -1);
-    /// pq.put(2.2, 20);
-    /// pq.put(3.3, 30);
-    /// pq.put(f32::NAN, -3);
-    /// pq.put(4.4, 40);
-    /// let res = pq.into_sorted_vec();
+    /// let mut pq = PriorityQueue::from([(2.0, 'b'), (f64::NAN, '?'), (1.0, 'a')]);
+    /// assert_eq!('a', pq.peek().unwrap().1);
     ///
-    /// assert_eq!(10, res[0].1);
-    /// assert_eq!(20, res[1].1);
-    /// assert_eq!(30, res[2].1);
-    /// assert_eq!(40, res[3].1);
-    /// assert!(res[4].1 < 0 && res[4].1 > -4);
-    /// assert!(res[5].1 < 0 && res[5].1 > -4);
+    /// pq.set_nan_policy(NanPolicy::First);
+    /// assert_eq!('?', pq.pop().unwrap().1);
+    /// assert_eq!('a', pq.pop().unwrap().1);
     /// ```
     ///
-    /// # Time
-    /// 
-    /// This method drains priority queue into vector and sorts in 
-    /// ***O(n log(n))*** time.
-    pub fn into_sorted_vec(mut self) -> Vec<(S, T)> {
-        let mut res: Vec<(S, T)> = self.drain(..)
-                                       .collect();
-
-        res.sort_by(|a, b| {
-            match a.0.partial_cmp(&b.0) {
-                Some(r) => r,
-                None => Ordering::Less,
-            }
-        });
-        res
+    pub fn set_nan_policy(&mut self, policy: NanPolicy) {
+        self.nan = policy;
     }
 
-    /// Reduce the length of a priority queue by keeping the first `len` 
-    /// elements and dropping the rest.
-    ///
-    /// If you pass `len` greater than the length of a priority queue this 
-    /// will have no effect.
+    /// Returns the elements with incomparable scores, which are kept apart
+    /// from the heap. They come out after all the other elements, or before
+    /// them with [`NanPolicy::First`], starting from the end of the slice.
     ///
-    /// # Example
-    /// 
-    /// truncate to keep the first three elements of priority queue.
+    /// # Examples
     ///
     /// ```
     /// use priq::PriorityQueue;
     ///
-    /// let mut pq = PriorityQueue::from(
-    ///     [(5, 55), (1, 11), (4, 44), (2, 22), (7, 77), (8, 88)]
-    /// );
+    /// let pq = PriorityQueue::from([(2.0, 'b'), (f64::NAN, '?'), (1.0, 'a')]);
     ///
-    /// pq.truncate(3);
-    /// assert_eq!(3, pq.len());
-    /// assert_eq!(11, pq.peek().unwrap().1);
+    /// assert_eq!(1, pq.nan_count());
+    /// assert_eq!('?', pq.incomparable()[0].1);
     /// ```
+    pub fn incomparable(&self) -> &[(S, T)] {
+        &self[self.heap_len()..]
+    }
+
+    /// Returns the number of elements with incomparable scores, see
+    /// [`incomparable`].
     ///
-    /// If we try to truncate with `len` > the length of a priority queue:
+    /// [`incomparable`]: PriorityQueue::incomparable
+    pub fn nan_count(&self) -> usize {
+        self.nans
+    }
+
+    /// Renders the heap as a binary tree in the Graphviz DOT language, with
+    /// scores as the labels of the nodes. Elements with incomparable scores
+    /// are not a part of the tree, they are drawn as dashed nodes on the
+    /// side.
+    ///
+    /// # Examples
     ///
     /// ```
     /// use priq::PriorityQueue;
     ///
-    /// let mut pq = PriorityQueue::from([(4, 44), (2, 22), (7, 77), (8, 88)]);
-    /// pq.truncate(5);
-    ///
-    /// assert_eq!(4, pq.len());
+    /// let pq = PriorityQueue::from([(1, 'a'), (2, 'b'), (3, 'c')]);
+    /// assert_eq!(pq.to_dot(), "\
+    /// digraph PriorityQueue {
+    ///     node [shape=circle];
+    ///     n0 [label=\"1\"];
+    ///     n1 [label=\"2\"];
+    ///     n0 -> n1;
+    ///     n2 [label=\"3\"];
+    ///     n0 -> n2;
+    /// }
+    /// ");
     /// ```
-    pub fn truncate(&mut self, len: usize) {
-        if len > self.len {
-            return
+    ///
+    /// The output can be turned into an image with `dot -Tsvg heap.dot`.
+    pub fn to_dot(&self) -> String
+    where
+        S: fmt::Display,
+    {
+        use core::fmt::Write;
+
+        let mut dot = String::from("digraph PriorityQueue {\n    node [shape=circle];\n");
+        let escape = |score: &S| alloc::format!("{}", score).replace('\\', "\\\\").replace('"', "\\\"");
+        for (index, (score, _)) in self.iter().enumerate() {
+            let style = if index < self.heap_len() { "" } else { ", style=dashed" };
+            // Writing to a `String` never fails.
+            let _ = writeln!(dot, "    n{} [label=\"{}\"{}];", index, escape(score), style);
+            if index > 0 && index < self.heap_len() {
+                let _ = writeln!(dot, "    n{} -> n{};", self.parent(index), index);
+            }
         }
+        dot.push_str("}\n");
+        dot
+    }
 
-        // SAFETY: concerns are none for this because:
-        //    * `len` passed to the method is less than self.len so no invalid 
-        //       slice can be created.
-        //    * self.len is reduced to a new size before `drop_in_place` is called.
-        //       no double free error in case `drop_in_place` panics.
-        unsafe {
-            let remaining = self.len - len;
-            let s_ = ptr::slice_from_raw_parts_mut(
-                self.as_mut_ptr().add(len), remaining);
-            self.len = len;
-            ptr::drop_in_place(s_);
+    /// Reserves capacity for at least `additional` more elements to be put
+    /// in the `PriorityQueue`. May reserve more space than requested to
+    /// avoid frequent reallocations. Does nothing if capacity is already
+    /// sufficient.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from([(1, 11)]);
+    /// pq.reserve(10);
+    /// assert!(pq.capacity() >= 11);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self.len.checked_add(additional)
+                                .expect("Capacity Overflow");
+        if required > self.cap() {
+            self.data.reserve(cmp::max(required, self.grown_cap()));
         }
     }
 
-    /// Merge second priority queue into this one. Values from the right hand 
-    /// side queue will be drained into the left hand side queue, leaving 
-    /// right hand side queue empty.
+    /// Reserves the minimum capacity for exactly `additional` more elements
+    /// to be put in the `PriorityQueue`. Prefer [`reserve`] if more puts are
+    /// expected afterwards. Does nothing if capacity is already sufficient.
+    ///
+    /// [`reserve`]: PriorityQueue::reserve
     ///
     /// # Examples
     ///
     /// ```
     /// use priq::PriorityQueue;
     ///
-    /// let mut pq1 = PriorityQueue::from([(5, 55), (1, 11), (3, 33), (2, 22)]);
-    /// let mut pq2 = PriorityQueue::from([(4, 44), (6, 66)]);
-    /// pq1.merge(&mut pq2);
+    /// let mut pq = PriorityQueue::from([(1, 11)]);
+    /// pq.reserve_exact(10);
+    /// assert_eq!(11, pq.capacity());
+    /// ```
     ///
-    /// assert_eq!(6, pq1.len());
-    /// assert!(pq2.is_empty()); // rhs queue's elements are moved
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        let required = self.len.checked_add(additional)
+                                .expect("Capacity Overflow");
+        self.data.reserve(required);
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to
+    /// be put in the `PriorityQueue`, same as [`reserve`]. Instead of
+    /// panicking or aborting, returns an error if the capacity overflows or
+    /// the allocator reports a failure.
+    ///
+    /// [`reserve`]: PriorityQueue::reserve
+    ///
+    /// # Examples
     ///
-    /// // assert that added elements are properly placed
-    /// (1..=6).for_each(|i| {
-    ///     assert_eq!(i * 11, pq1.pop().unwrap().1); 
-    ///     });
     /// ```
-    pub fn merge(&mut self, pq: &mut PriorityQueue<S ,T>) {
-        while !pq.is_empty() {
-            let elem = pq.pop().unwrap();
-            self.put(elem.0, elem.1);
+    /// use priq::{PriorityQueue, TryReserveError};
+    ///
+    /// let mut pq = PriorityQueue::from([(1, 11)]);
+    /// assert!(pq.try_reserve(10).is_ok());
+    /// assert!(pq.capacity() >= 11);
+    ///
+    /// assert_eq!(Err(TryReserveError::CapacityOverflow),
+    ///            pq.try_reserve(usize::MAX));
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize)
+        -> Result<(), TryReserveError>
+    {
+        let required = self.len.checked_add(additional)
+                                .ok_or(TryReserveError::CapacityOverflow)?;
+        if required > self.cap() {
+            self.data.try_reserve(cmp::max(required, self.grown_cap()))
+                     .or_else(|_| self.data.try_reserve(required))?;
         }
+        Ok(())
+    }
+
+    /// Shrinks the capacity of the `PriorityQueue` as much as possible,
+    /// so it holds exactly as much memory as needed for its elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq: PriorityQueue<usize, usize> = PriorityQueue::with_capacity(100);
+    /// pq.put(1, 11);
+    /// pq.shrink_to_fit();
+    /// assert_eq!(1, pq.capacity());
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_to(0);
+    }
+
+    /// Shrinks the capacity of the `PriorityQueue` with a lower bound. The
+    /// capacity will remain at least as large as both the length and the
+    /// supplied `min_cap`. Does nothing if the current capacity is already
+    /// smaller than that.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq: PriorityQueue<usize, usize> = PriorityQueue::with_capacity(100);
+    /// pq.put(1, 11);
+    ///
+    /// pq.shrink_to(10);
+    /// assert_eq!(10, pq.capacity());
+    /// pq.shrink_to(0);
+    /// assert_eq!(1, pq.capacity());
+    /// ```
+    pub fn shrink_to(&mut self, min_cap: usize) {
+        self.data.shrink_to(cmp::max(self.len, min_cap));
+    }
+
+    /// Returns the remaining spare capacity of the `PriorityQueue` as a slice
+    /// of `MaybeUninit<(S, T)>`. It can be used to write elements directly
+    /// in the underlying array, e.g. while decoding them from a file, before
+    /// marking them as initialized with [`set_len`] and restoring the heap
+    /// order with [`rebuild`].
+    ///
+    /// [`set_len`]: PriorityQueue::set_len
+    /// [`rebuild`]: PriorityQueue::rebuild
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from([(2, 22)]);
+    /// pq.reserve(3);
+    ///
+    /// let spare = pq.spare_capacity_mut();
+    /// spare[0].write((5, 55));
+    /// spare[1].write((1, 11));
+    /// spare[2].write((3, 33));
+    ///
+    /// unsafe { pq.set_len(4) };
+    /// pq.rebuild();
+    ///
+    /// assert_eq!(11, pq.pop().unwrap().1);
+    /// assert_eq!(22, pq.pop().unwrap().1);
+    /// ```
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<(S, T)>] {
+        // SAFETY: memory after `len` up to the capacity is allocated and is
+        //      not used by any of the elements. For ZST the slice is empty
+        //      memory-wise, any pointer is valid.
+        unsafe {
+            slice::from_raw_parts_mut(
+                self.ptr().add(self.len) as *mut MaybeUninit<(S, T)>,
+                self.cap() - self.len)
+        }
+    }
+
+    /// Forces the length of the `PriorityQueue` to `new_len`, without
+    /// dropping or initializing any elements. Heap order of the new elements
+    /// is not restored, [`rebuild`] should be called afterwards.
+    ///
+    /// [`rebuild`]: PriorityQueue::rebuild
+    ///
+    /// # Safety
+    ///
+    /// `new_len` must be less than or equal to [`capacity`], and all the
+    /// elements up to `new_len` must be initialized. If the length is
+    /// reduced, elements after `new_len` are leaked.
+    ///
+    /// [`capacity`]: PriorityQueue::capacity
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(new_len <= self.cap());
+        self.cut_len(new_len.min(self.len));
+        self.len = new_len;
+    }
+
+    /// Restores the heap order of the whole priority queue from the bottom
+    /// up. Needed only after the elements were written or modified directly,
+    /// e.g. through [`spare_capacity_mut`] or a mutable slice.
+    ///
+    /// [`spare_capacity_mut`]: PriorityQueue::spare_capacity_mut
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from([(1, 11), (2, 22), (3, 33)]);
+    /// pq[0].0 = 5;
+    /// pq.rebuild();
+    ///
+    /// assert_eq!(22, pq.pop().unwrap().1);
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)***
+    pub fn rebuild(&mut self) {
+        self.quarantine();
+        sift::heapify_nodes(self, 2);
+        self.find_max();
+    }
+
+    /// Remove all the elements from `PriorityQueue`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from([(5, 55), (1, 11), (4, 44)]);
+    /// assert!(!pq.is_empty());
+    ///
+    /// pq.clear();
+    /// assert!(pq.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.drain(..);
+    }
+
+    /// Removes the elements in the given range of the underlying array from
+    /// the priority queue, returning iterator over the removed elements.
+    /// Returned items will NOT be in a sorted order. Method takes range as an
+    /// argument.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from([(5, 55), (1, 11), (4, 44), (7, 77)]);
+    /// assert!(!pq.is_empty());
+    ///
+    /// // drain everything starting from index 2 till the end.
+    /// let mut res: PriorityQueue<usize, usize> = pq.drain(2..).collect();
+    /// assert_eq!(2, pq.len());
+    /// assert_eq!(2, res.len());
+    ///
+    /// // drain the remaining priority queue by giving it full range (..) arg.
+    /// res.drain(..);
+    /// assert!(res.is_empty());
+    /// ```
+    ///
+    /// Elements outside of the range stay in the priority queue. Once the
+    /// returned iterator is dropped they are moved to close the gap and the
+    /// heap order is restored.
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from([(5, 55), (1, 11), (4, 44), (7, 77)]);
+    ///
+    /// assert_eq!(2, pq.drain(1..3).count());
+    /// assert_eq!(2, pq.len());
+    /// assert_eq!(11, pq.pop().unwrap().1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end or if the end
+    /// is greater than the length of a priority queue.
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, S, T, O, A>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len();
+        let Range { start, end } = slice::range(range, ..len);
+
+        // SAFETY: we are reading from row memory within a range from start to
+        //      the `len` where `len` we know is within a memory space of this
+        //      priority queue.
+        unsafe {
+            let range_slice = slice::from_raw_parts_mut(
+                self.as_mut_ptr().add(start), end - start);
+
+            let iter = RawPQIter::new(range_slice);
+
+            // SAFETY: we cut `len` down to the start of the range so even if
+            //      `Drain` is leaked, drained elements are never dropped twice.
+            //      The tail is moved back when `Drain` is dropped.
+            self.cut_len(start);
+
+            Drain {
+                pq: self,
+                tail_start: end,
+                tail_len: len - end,
+                iter,
+            }
+        }
+    }
+
+    /// Returns an iterator over references to the elements in the order of
+    /// their priority, without modifying the priority queue.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq = PriorityQueue::from([(5, 55), (1, 11), (4, 44), (2, 22)]);
+    ///
+    /// let items: Vec<_> = pq.iter_sorted().map(|(_, e)| *e).collect();
+    /// assert_eq!(vec![11, 22, 44, 55], items);
+    /// assert_eq!(4, pq.len());
+    /// ```
+    ///
+    /// Iterator keeps its own small heap of indices of the elements that can
+    /// be yielded next. As the top of a sub-tree is yielded, its children
+    /// become new candidates. This way elements are not cloned or moved and
+    /// only as much work is done as many elements are taken.
+    ///
+    /// # Time
+    ///
+    /// Each step of iteration takes ***O(log(k))*** time, where `k` is a
+    /// number of elements yielded so far.
+    pub fn iter_sorted(&self) -> IterSorted<'_, S, T, O, A> {
+        IterSorted {
+            pq: self,
+            candidates: if self.heap_len() == 0 { Vec::new() } else { vec![0] },
+            nans: self.nans,
+            remaining: self.len,
+        }
+    }
+
+    /// Consumes the priority queue and returns an iterator which yields
+    /// elements in the order of their priority. Elements are `pop`-ed one by
+    /// one as the iterator advances.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq = PriorityQueue::from([(5, 55), (1, 11), (4, 44), (2, 22)]);
+    ///
+    /// let top: Vec<_> = pq.into_iter_sorted().take(2).collect();
+    /// assert_eq!(vec![(1, 11), (2, 22)], top);
+    /// ```
+    ///
+    /// # Time
+    ///
+    /// Taking the first `k` elements takes ***O(k log(n))*** time.
+    pub fn into_iter_sorted(self) -> IntoIterSorted<S, T, O, A> {
+        IntoIterSorted { pq: self }
+    }
+
+    /// Clears the priority queue and returns `Vec` with elements in a
+    /// sorted order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq = PriorityQueue::from([(5, 55), (1, 11), (4, 44)]);
+    ///
+    /// let mut res = pq.into_sorted_vec(); 
+    /// assert_eq!(3, res.len());
+    ///
+    /// // we'll be `pop`-ing values from the back of the vector.
+    /// // this means highest scoring will be all the way back into the `Vec`
+    /// assert_eq!(55, res.pop().unwrap().1);
+    /// assert_eq!(44, res.pop().unwrap().1);
+    /// assert_eq!(11, res.pop().unwrap().1);
+    /// ```
+    ///
+    /// If priority queue has NAN values they will be sorted after all 
+    /// comparable scores without any particular order
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq: PriorityQueue<f32, isize> = PriorityQueue::new();
+    /// pq.put(1.1, 10);
+    /// pq.put(f32::NAN, -1);
+    /// pq.put(2.2, 20);
+    /// pq.put(3.3, 30);
+    /// pq.put(f32::NAN, -3);
+    /// pq.put(4.4, 40);
+    /// let res = pq.into_sorted_vec();
+    ///
+    /// assert_eq!(10, res[0].1);
+    /// assert_eq!(20, res[1].1);
+    /// assert_eq!(30, res[2].1);
+    /// assert_eq!(40, res[3].1);
+    /// assert!(res[4].1 < 0 && res[4].1 > -4);
+    /// assert!(res[5].1 < 0 && res[5].1 > -4);
+    /// ```
+    ///
+    /// # Time
+    /// 
+    /// This method sorts elements in-place with a heapsort, reusing the
+    /// underlying array of the priority queue as the returned `Vec`, in
+    /// ***O(n log(n))*** time.
+    pub fn into_sorted_vec(mut self) -> Vec<(S, T), A> {
+        let (len, heap_len) = (self.len, self.heap_len());
+
+        // The top element is moved to the back of the shrinking heap, so we
+        // end up with the array sorted in the descending order.
+        self.nans = 0;
+        for end in (1..heap_len).rev() {
+            self.swap(0, end);
+            self.len = end;
+            self.heapify_down(0);
+        }
+        self.len = len;
+        self[..heap_len].reverse();
+
+        // quarantined elements are popped from the back
+        self[heap_len..].reverse();
+        if self.nan == NanPolicy::First {
+            self.rotate_left(heap_len);
+        }
+        self.into_vec()
+    }
+
+    /// Consumes the priority queue and returns the underlying array as a `Vec`
+    /// with elements in the heap order (NOT sorted). No elements are copied
+    /// and no memory is allocated, the `Vec` takes over the existing buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq = PriorityQueue::from([(5, 55), (1, 11), (4, 44)]);
+    ///
+    /// let mut res = pq.into_vec();
+    /// assert_eq!(3, res.len());
+    ///
+    /// // top element is always in the front
+    /// assert_eq!(11, res[0].1);
+    ///
+    /// res.sort();
+    /// assert_eq!(vec![(1, 11), (4, 44), (5, 55)], res);
+    /// ```
+    ///
+    /// # Time
+    ///
+    /// Done in a constant time ***O(1)***.
+    pub fn into_vec(self) -> Vec<(S, T), A> {
+        let (len, cap) = (self.len, self.cap());
+        let data = mem::ManuallyDrop::new(self.into_raw());
+
+        // SAFETY: buffer was allocated by the queue's allocator with the
+        //      layout of an array of `cap` elements, same as `Vec` does, and
+        //      first `len` of them are initialized. The allocator is moved
+        //      out of the buffer which is never dropped.
+        unsafe {
+            let alloc = ptr::read(&data.alloc);
+            Vec::from_raw_parts_in(data.ptr.as_ptr(), len, cap, alloc)
+        }
+    }
+
+    /// Reduce the length of a priority queue by keeping the first `len` 
+    /// elements and dropping the rest.
+    ///
+    /// If you pass `len` greater than the length of a priority queue this 
+    /// will have no effect.
+    ///
+    /// # Example
+    /// 
+    /// truncate to keep the first three elements of priority queue.
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from(
+    ///     [(5, 55), (1, 11), (4, 44), (2, 22), (7, 77), (8, 88)]
+    /// );
+    ///
+    /// pq.truncate(3);
+    /// assert_eq!(3, pq.len());
+    /// assert_eq!(11, pq.peek().unwrap().1);
+    /// ```
+    ///
+    /// If we try to truncate with `len` > the length of a priority queue:
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from([(4, 44), (2, 22), (7, 77), (8, 88)]);
+    /// pq.truncate(5);
+    ///
+    /// assert_eq!(4, pq.len());
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len > self.len {
+            return
+        }
+
+        // SAFETY: concerns are none for this because:
+        //    * `len` passed to the method is less than self.len so no invalid 
+        //       slice can be created.
+        //    * self.len is reduced to a new size before `drop_in_place` is called.
+        //       no double free error in case `drop_in_place` panics.
+        unsafe {
+            let remaining = self.len - len;
+            let s_ = ptr::slice_from_raw_parts_mut(
+                self.as_mut_ptr().add(len), remaining);
+            self.cut_len(len);
+            ptr::drop_in_place(s_);
+        }
+    }
+
+    /// Reduce the length of a priority queue by keeping the `len` elements
+    /// with the highest priority and dropping the rest.
+    ///
+    /// Unlike [`truncate`], which keeps the first `len` elements of the
+    /// underlying array, this method guarantees that none of the dropped
+    /// elements has a higher priority than the kept ones. If you pass `len`
+    /// greater than the length of a priority queue this will have no effect.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from(
+    ///     [(5, 55), (1, 11), (4, 44), (2, 22), (7, 77), (8, 88)]
+    /// );
+    ///
+    /// pq.truncate_best(3);
+    /// assert_eq!(3, pq.len());
+    /// assert_eq!(11, pq.pop().unwrap().1);
+    /// assert_eq!(22, pq.pop().unwrap().1);
+    /// assert_eq!(44, pq.pop().unwrap().1);
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)*** on average, for partial selection and re-heapify.
+    ///
+    /// [`truncate`]: PriorityQueue::truncate
+    pub fn truncate_best(&mut self, len: usize) {
+        if len >= self.len {
+            return
+        }
+
+        if len > 0 {
+            // SAFETY: slice covers only initialized elements of this queue and
+            //      comparison below reads nothing but the elements it is given.
+            let elems = unsafe { slice::from_raw_parts_mut(self.ptr(), self.len) };
+            elems.select_nth_unstable_by(len, |a, b| self.order(a, b));
+        }
+        self.truncate(len);
+        self.rebuild();
+    }
+
+    /// Merge second priority queue into this one. Values from the right hand 
+    /// side queue will be drained into the left hand side queue, leaving 
+    /// right hand side queue empty.
+    ///
+    /// Moved elements are ordered by the comparator, tiebreak and
+    /// [`NanPolicy`] of this queue, configuration of `pq` is not checked. Both
+    /// queues are expected to be created with the same comparator, otherwise
+    /// elements come out in the order of this queue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq1 = PriorityQueue::from([(5, 55), (1, 11), (3, 33), (2, 22)]);
+    /// let mut pq2 = PriorityQueue::from([(4, 44), (6, 66)]);
+    /// pq1.merge(&mut pq2);
+    ///
+    /// assert_eq!(6, pq1.len());
+    /// assert!(pq2.is_empty()); // rhs queue's elements are moved
+    ///
+    /// // assert that added elements are properly placed
+    /// (1..=6).for_each(|i| {
+    ///     assert_eq!(i * 11, pq1.pop().unwrap().1); 
+    ///     });
+    /// ```
+    pub fn merge(&mut self, pq: &mut PriorityQueue<S ,T, O>) {
+        while !pq.is_empty() {
+            let elem = pq.pop().unwrap();
+            self.put(elem.0, elem.1);
+        }
+    }
+
+    /// Move elements selected by a range or a predicate from this priority
+    /// queue into the `other` one. Destination's memory is reserved once for
+    /// all the moved elements and both queues are re-heapified once after the
+    /// transfer, instead of `pop`-ing and `put`-ing element by element.
+    ///
+    /// Range selects elements by their position in the underlying array (same
+    /// as [`drain`]), while predicate selects every element it returns `true`
+    /// for.
+    ///
+    /// Moved elements are re-heapified with the comparator of `other`, as in
+    /// [`merge`] the two queues are expected to compare scores the same way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::{selector, PriorityQueue};
+    ///
+    /// let mut pq1 = PriorityQueue::from([(5, 55), (1, 11), (4, 44), (2, 22)]);
+    /// let mut pq2 = PriorityQueue::from([(3, 33)]);
+    ///
+    /// // move every element with an even score
+    /// pq1.drain_to(&mut pq2, selector(|(s, _)| s % 2 == 0));
+    ///
+    /// assert_eq!(2, pq1.len());
+    /// assert_eq!(3, pq2.len());
+    /// assert_eq!(22, pq2.pop().unwrap().1);
+    /// assert_eq!(33, pq2.pop().unwrap().1);
+    /// ```
+    ///
+    /// Moving elements by a range:
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq1 = PriorityQueue::from([(5, 55), (1, 11), (4, 44), (2, 22)]);
+    /// let mut pq2 = PriorityQueue::new();
+    ///
+    /// pq1.drain_to(&mut pq2, 2..);
+    /// assert_eq!(2, pq1.len());
+    /// assert_eq!(2, pq2.len());
+    ///
+    /// pq1.drain_to(&mut pq2, ..);
+    /// assert!(pq1.is_empty());
+    /// assert_eq!(11, pq2.peek().unwrap().1);
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n + m)*** where `n` and `m` are lengths of the queues.
+    ///
+    /// [`drain`]: PriorityQueue::drain
+    /// [`merge`]: PriorityQueue::merge
+    pub fn drain_to<D>(&mut self, other: &mut Self, selector: D)
+    where
+        D: DrainSelector<S, T>,
+    {
+        let moved = selector.select(&mut self[..]);
+        if moved == 0 {
+            return
+        }
+        let keep = self.len - moved;
+        other.data.reserve(other.len + moved);
+
+        // SAFETY: selected elements are located in the tail of this queue's
+        //      array and we reserved enough space in the destination for all
+        //      of them. Lengths are updated right after copy so every element
+        //      is owned by exactly one queue.
+        unsafe {
+            ptr::copy_nonoverlapping(
+                self.ptr().add(keep), other.ptr().add(other.len), moved);
+        }
+        self.cut_len(keep);
+        other.len += moved;
+
+        self.rebuild();
+        other.rebuild();
+    }
+
+    /// Re-scores every element in the priority queue with the given function,
+    /// e.g. to normalize priorities once in a while. Heap is restored only
+    /// once, after all the scores were updated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from([(3, "c"), (1, "a"), (2, "b")]);
+    /// pq.map_scores(|s| 10 - s);
+    ///
+    /// assert_eq!((7, "c"), pq.pop().unwrap());
+    /// assert_eq!((8, "b"), pq.pop().unwrap());
+    /// assert_eq!((9, "a"), pq.pop().unwrap());
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)*** function calls followed by a single ***O(n)*** re-heapify.
+    pub fn map_scores<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&S) -> S,
+    {
+        self.iter_mut().for_each(|(s, _)| *s = f(s));
+        self.rebuild();
     }
 
     /// Provides the raw pointer to the contiguous block of memory of data
@@ -708,263 +2158,999 @@ where
         self.data.cap
     }
 
+    /// Capacity the underlying array would have after growing once
+    fn grown_cap(&self) -> usize {
+        self.data.growth.next_capacity(self.cap()).unwrap_or(0)
+    }
+
+    /// Compares two scores with the comparator of the queue if it was given
+    /// one, or with their `PartialOrd` implementation otherwise.
+    #[inline]
+    fn compare(&self, lhs: &S, rhs: &S) -> Option<Ordering> {
+        self.cmp.compare(lhs, rhs)
+    }
+
     /// Check if two values are comparable
     fn comparable(&self, lhs: &S, rhs: &S) -> bool {
-        lhs.partial_cmp(rhs).is_some()
+        self.compare(lhs, rhs).is_some()
+    }
+
+    /// Checks the score of a new element against the [`NanPolicy`] of the
+    /// queue. Returns `false` if the element must be rejected.
+    fn admits(&self, score: &S) -> bool {
+        match self.nan {
+            NanPolicy::Last | NanPolicy::First => true,
+            _ if self.comparable(score, score) => true,
+            NanPolicy::Reject => false,
+            NanPolicy::Panic => panic!("incomparable score put in the priority queue"),
+        }
+    }
+
+    /// Same as [`admits`] for the methods which can't return an error.
+    ///
+    /// [`admits`]: PriorityQueue::admits
+    #[inline]
+    fn check_score(&self, score: &S) {
+        assert!(self.admits(score), "incomparable score rejected by the priority queue");
+    }
+
+    /// Takes the buffer out of the queue without dropping the elements in
+    /// it. The rest of the queue, like its comparators, is dropped.
+    fn into_raw(self) -> RawPQ<S, T, A> {
+        let pq = mem::ManuallyDrop::new(self);
+        // SAFETY: every owned field is read out exactly once and the queue
+        //      itself is never dropped, so the elements stay in the buffer.
+        unsafe {
+            drop(ptr::read(&pq.cmp));
+            drop(ptr::read(&pq.tiebreak));
+            ptr::read(&pq.data)
+        }
+    }
+
+    /// Creates a priority queue which owns the buffer with first `len`
+    /// elements initialized. Heap order is not restored.
+    fn from_raw(data: RawPQ<S, T, A>, len: usize, cmp: Comparator<S>) -> Self {
+        PriorityQueue {
+            data,
+            len,
+            nans: 0,
+            max: 0,
+            cmp,
+            tiebreak: None,
+            nan: NanPolicy::default(),
+            _order: PhantomData,
+        }
+    }
+
+    /// Checks if `lhs` should be placed closer to the root than `rhs`.
+    /// Scores that can't be compared even with themselves (like `NAN`) are
+    /// treated as greater than any comparable score, so they sink to the back,
+    /// unless [`NanPolicy::First`] moves them to the front.
+    #[inline]
+    fn less(&self, lhs: &S, rhs: &S) -> bool {
+        let nans_first = self.nan == NanPolicy::First;
+        sift::precedes_by::<S, O, _>(lhs, rhs, |l, r| self.compare(l, r), nans_first)
+    }
+
+    /// Checks if element `lhs` should be placed closer to the root than `rhs`.
+    /// Same as [`less`] for their scores, but equal scores are resolved with
+    /// the tiebreak of the queue if it was given one.
+    ///
+    /// [`less`]: PriorityQueue::less
+    #[inline]
+    fn precedes(&self, lhs: &(S, T), rhs: &(S, T)) -> bool {
+        match (&self.tiebreak, self.compare(&lhs.0, &rhs.0)) {
+            (Some(tiebreak), Some(Ordering::Equal)) =>
+                tiebreak.compare(&lhs.1, &rhs.1) == Some(Ordering::Less),
+            _ => self.less(&lhs.0, &rhs.0),
+        }
+    }
+
+    /// Total ordering of elements consistent with [`precedes`], which can be
+    /// used for sorting and selection.
+    ///
+    /// [`precedes`]: PriorityQueue::precedes
+    fn order(&self, lhs: &(S, T), rhs: &(S, T)) -> Ordering {
+        if self.precedes(lhs, rhs) {
+            Ordering::Less
+        } else if self.precedes(rhs, lhs) {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    /// Number of elements in the heap, without the quarantined elements with
+    /// incomparable scores stored after them.
+    #[inline]
+    fn heap_len(&self) -> usize {
+        self.len - self.nans
+    }
+
+    /// Checks if the top element of the queue is a quarantined one.
+    #[inline]
+    fn nans_first(&self) -> bool {
+        self.nans > 0 && (self.nan == NanPolicy::First || self.nans == self.len)
+    }
+
+    /// Moves all the elements with incomparable scores to the back of the
+    /// array, so the heap is made only of the comparable ones.
+    fn quarantine(&mut self) {
+        let mut end = self.len;
+        let mut i = 0;
+        while i < end {
+            if self.comparable(&self[i].0, &self[i].0) {
+                i += 1;
+            } else {
+                end -= 1;
+                self.swap(i, end);
+            }
+        }
+        self.nans = self.len - end;
+    }
+
+    /// Reduces the length of the array to `len`, cutting off quarantined
+    /// elements first. Cut off elements are not dropped.
+    #[inline]
+    fn cut_len(&mut self, len: usize) {
+        debug_assert!(len <= self.len);
+        let heap_len = self.heap_len().min(len);
+        self.len = len;
+        self.nans = len - heap_len;
+        self.fix_max();
+    }
+
+    /// Generates the index of a left child (if any) of a item on a given index
+    #[inline]
+    fn left_child(&self, index: usize) -> usize {
+        2 * index + 1
+    }
+
+    /// Generates the index of a right child (if any) of a item on a given index
+    #[inline]
+    fn right_child(&self, index: usize) -> usize {
+        2 * index + 2
+    }
+
+    /// Generates the index of a parent item (if any) of a item on a given index
+    #[inline]
+    fn parent(&self, index: usize) -> usize {
+        (index - 1) / 2
+    }
+
+    /// Checks if given item on provided index has a left child
+    #[inline]
+    fn has_left(&self, index: usize) -> bool {
+        self.left_child(index) < self.heap_len()
+    }
+
+    /// Checks if given item on provided index has a right child
+    #[inline]
+    fn has_right(&self, index: usize) -> bool {
+        self.right_child(index) < self.heap_len()
+    }
+
+    /// Writes element in the back of the heap without restoring heap order.
+    /// Elements with incomparable scores are quarantined in the back of the
+    /// array instead. Caller must ensure that there is enough capacity
+    /// allocated.
+    #[inline]
+    fn push(&mut self, elem: (S, T)) {
+        debug_assert!(self.len < self.cap());
+        let comparable = self.comparable(&elem.0, &elem.0);
+        // SAFETY: there's space for one more element after `len`. First
+        //      quarantined element is moved there to free the slot right
+        //      after the heap.
+        unsafe {
+            let end = self.ptr().add(self.len);
+            if comparable {
+                let slot = self.ptr().add(self.heap_len());
+                if self.nans > 0 {
+                    ptr::copy_nonoverlapping(slot, end, 1);
+                }
+                ptr::write(slot, elem);
+            } else {
+                ptr::write(end, elem);
+            }
+        }
+        self.len += 1;
+        if comparable {
+            self.track_max(self.heap_len() - 1);
+        } else {
+            self.nans += 1;
+        }
+    }
+
+    /// Writes element in the back of the heap and moves it up to its place.
+    #[inline]
+    fn push_up(&mut self, elem: (S, T)) {
+        let heap_len = self.heap_len();
+        self.push(elem);
+        if self.heap_len() > heap_len {
+            self.heapify_up(heap_len);
+        }
+    }
+
+    /// Remembers the element at `index` as the maximum if it's not less than
+    /// the current one. Ties are resolved in favor of the new element, as it
+    /// is more likely to stay at the bottom of the heap.
+    #[inline]
+    fn track_max(&mut self, index: usize) {
+        if index == 0 || !self.precedes(&self[index], &self[self.max]) {
+            self.max = index;
+        }
+    }
+
+    /// Finds the maximum element from scratch. It is always present among
+    /// the leaves, which are the second half of the array.
+    fn find_max(&mut self) {
+        let heap_len = self.heap_len();
+        self.max = heap_len / 2;
+        (heap_len / 2..heap_len).for_each(|i| self.track_max(i));
+    }
+
+    /// Finds the maximum element again if it was cut off from the array.
+    #[inline]
+    fn fix_max(&mut self) {
+        if self.max >= self.heap_len() {
+            self.find_max();
+        }
+    }
+
+    /// Swaps two elements in the array, keeping track of the maximum.
+    #[inline]
+    fn swap_nodes(&mut self, a: usize, b: usize) {
+        self.swap(a, b);
+        if self.max == a {
+            self.max = b;
+        } else if self.max == b {
+            self.max = a;
+        }
+    }
+
+    /// Restore the heap order after elements were written in the back of the
+    /// array starting from the index `start`. Rebuilds the whole heap if it's
+    /// cheaper than percolating up every new element.
+    fn rebuild_tail(&mut self, start: usize) {
+        let heap_len = self.heap_len();
+        let tail = heap_len - start;
+        if tail == 0 {
+            return
+        }
+
+        // rebuild takes at most 2 * len comparisons, while percolating up
+        // takes up to log(len) comparisons for each of the new elements
+        let log_len = (usize::BITS - heap_len.leading_zeros()) as usize;
+        if start == 0 || 2 * heap_len < tail * log_len {
+            self.rebuild();
+        } else {
+            (start..heap_len).for_each(|i| self.heapify_up(i));
+        }
+    }
+
+    /// Checks if every element of the heap is in order with its parent, so
+    /// the array can be used as it is.
+    #[cfg(feature = "std")]
+    fn is_heap(&self) -> bool {
+        (1..self.heap_len()).all(|i| !self.precedes(&self[i], &self[self.parent(i)]))
+    }
+
+    /// After item is `pop`-ed this methods helps to balance remaining values
+    /// so the prioritized item remains as a root.
+    #[inline]
+    fn heapify_up(&mut self, index: usize) {
+        sift::sift_up_nodes(self, index, 2);
     }
 
-    /// Generates the index of a left child (if any) of a item on a given index
+    /// Store inserted value into a proper position to maintain the balanced
+    /// order of parent child relationships and prioritized item as a root.
     #[inline]
-    fn left_child(&self, index: usize) -> usize {
-        2 * index + 1
+    fn heapify_down(&mut self, index: usize) {
+        sift::sift_down_nodes(self, index, 2);
     }
+}
 
-    /// Generates the index of a right child (if any) of a item on a given index
+/// The heap made of the comparable elements in the front of the array,
+/// sifted with the routines shared by the array based heaps. The maximum is
+/// tracked as the elements are swapped.
+impl<S, T, O, A> sift::Nodes for PriorityQueue<S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
+{
     #[inline]
-    fn right_child(&self, index: usize) -> usize {
-        2 * index + 2
+    fn count(&self) -> usize {
+        self.heap_len()
     }
 
-    /// Generates the index of a parent item (if any) of a item on a given index
     #[inline]
-    fn parent(&self, index: usize) -> usize {
-        (index - 1) / 2
+    fn before(&self, a: usize, b: usize) -> bool {
+        self.precedes(&self[a], &self[b])
     }
 
-    /// Checks if given item on provided index has a left child
     #[inline]
-    fn has_left(&self, index: usize) -> bool {
-        self.left_child(index) < self.len
+    fn exchange(&mut self, a: usize, b: usize) {
+        self.swap_nodes(a, b);
     }
+}
 
-    /// Checks if given item on provided index has a right child
+impl<S, T, O> Default for PriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
     #[inline]
-    fn has_right(&self, index: usize) -> bool {
-        self.right_child(index) < self.len
+    fn default() -> Self {
+        PriorityQueue::from_raw(RawPQ::new(), 0, Comparator::default())
     }
+}
 
-    #[inline]
-    #[allow(dead_code)]
-    fn push(&mut self, elem: (S, T)) {
+impl<S, T, O, A> Drop for PriorityQueue<S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
+{
+    fn drop(&mut self) {
+        // SAFETY: first `len` elements are initialized and are never touched
+        //      again, buffer itself is deallocated by `RawPQ`.
         unsafe {
-            ptr::write(self.ptr().add(self.len), elem);
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                self.ptr(), self.len));
         }
     }
+}
 
-    /// After item is `pop`-ed this methods helps to balance remaining values
-    /// so the prioritized item remains as a root.
-    #[inline]
-    fn heapify_up(&mut self, index: usize) {
-        if index > 0 {
-            let parent_ = self.parent(index);
-            if self[parent_].0 > self[index].0 {
-                self.swap(parent_, index);
-                self.heapify_up(parent_);
+impl<S, T, O, A> Deref for PriorityQueue<S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
+{
+    type Target = [(S, T)];
+    fn deref(&self) -> &[(S, T)] {
+        unsafe { slice::from_raw_parts(self.ptr(), self.len) }
+    }
+}
+
+impl<S, T, O, A> DerefMut for PriorityQueue<S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
+{
+    fn deref_mut(&mut self) -> &mut [(S, T)] {
+        unsafe { slice::from_raw_parts_mut(self.ptr(), self.len) }
+    }
+}
+
+/// Number of elements printed by the `Debug` implementation of
+/// `PriorityQueue`, the rest is replaced with `..`.
+const DEBUG_LIMIT: usize = 32;
+
+impl<S, T, O, A> fmt::Debug for PriorityQueue<S, T, O, A>
+where
+    S: fmt::Debug,
+    T: fmt::Debug,
+    O: Order,
+    A: Allocator,
+{
+    /// Prints the length and the elements in the order they are stored,
+    /// with the top element first. Only the first 32 elements are shown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq = PriorityQueue::from([(2, 'b'), (1, 'a')]);
+    /// assert_eq!("PriorityQueue { len: 2, data: [(1, 'a'), (2, 'b')] }",
+    ///            format!("{:?}", pq));
+    ///
+    /// let pq: PriorityQueue<_, _> = (0..100).map(|i| (i, ())).collect();
+    /// assert!(format!("{:?}", pq).ends_with("(31, ()), ..] }"));
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PriorityQueue")
+         .field("len", &self.len)
+         .field("data", &Truncated(self))
+         .finish()
+    }
+}
+
+impl<S, T, O, A> fmt::Display for PriorityQueue<S, T, O, A>
+where
+    S: fmt::Display,
+    T: fmt::Display,
+    O: Order,
+    A: Allocator,
+{
+    /// Prints the elements as `score: item` pairs in the order they would be
+    /// popped, without changing the queue. Formatting options like the
+    /// precision are applied to the scores only.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq = PriorityQueue::from([(2.5, "backup"), (0.25, "deploy"), (1.0, "test")]);
+    /// assert_eq!("[0.25: deploy, 1: test, 2.5: backup]", pq.to_string());
+    /// assert_eq!("[0.2: deploy, 1.0: test, 2.5: backup]", format!("{:.1}", pq));
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n log(n))***
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[")?;
+        for (i, (score, item)) in self.iter_sorted().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
             }
+            score.fmt(f)?;
+            write!(f, ": {}", item)?;
         }
+        f.write_str("]")
     }
+}
 
-    /// Store inserted value into a proper position to maintain the balanced
-    /// order of parent child relationships and prioritized item as a root.
-    #[inline]
-    fn heapify_down(&mut self, index: usize) {
-        let _left = self.left_child(index);
-        let _right = self.right_child(index);
-        let mut min_ = index;
-        if self.has_left(index) && self[_left].0 < self[min_].0 {
-            min_ = _left;
-        }
-        if self.has_right(index) && self[_right].0 < self[min_].0 {
-            min_ = _right;
+/// Elements of a slice printed up to [`DEBUG_LIMIT`].
+struct Truncated<'a, E>(&'a [E]);
+
+impl<E: fmt::Debug> fmt::Debug for Truncated<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        list.entries(self.0.iter().take(DEBUG_LIMIT));
+        if self.0.len() > DEBUG_LIMIT {
+            list.entry(&format_args!(".."));
         }
-        if min_ != index {
-            self.swap(index, min_);
-            self.heapify_down(min_);
+        list.finish()
+    }
+}
+
+impl<S, T> From<Vec<(S, T)>> for PriorityQueue<S, T>
+where 
+    S: PartialOrd,
+{
+    /// Create `PriorityQueue` from a `Vec` 
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let vec = vec![(5, 55), (4, 44), (2, 22), (3, 33)];
+    /// let mut pq = PriorityQueue::from(vec);
+    /// assert_eq!(4, pq.len());
+    /// assert_eq!(22, pq.pop().unwrap().1);
+    /// ```
+    ///
+    /// `PriorityQueue` takes over the memory allocated by the `Vec`, so no
+    /// elements are copied, and builds the heap in-place from the bottom up.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)***
+    fn from(other: Vec<(S, T)>) -> Self {
+        let len = other.len();
+        if mem::size_of::<(S, T)>() == 0 {
+            assert!(len < rawpq::MAX_ZST_CAPACITY, "Capacity Overflow");
         }
+
+        let mut vec = mem::ManuallyDrop::new(other);
+        // SAFETY: `Vec` allocates its buffer with the global allocator and
+        //      the layout of an array of `capacity` elements, the same way
+        //      `RawPQ` does, and we own it now as `Vec` will never be dropped.
+        let data = unsafe {
+            RawPQ::from_raw_parts(vec.as_mut_ptr(), vec.capacity())
+        };
+
+        let mut pq = PriorityQueue::from_raw(data, len, Comparator::default());
+        pq.rebuild();
+        pq
     }
 }
 
-impl<S, T> Default for PriorityQueue<S, T>
-where
+impl<S, T, const N: usize> From<[(S, T); N]> for PriorityQueue<S, T>
+where 
     S: PartialOrd,
 {
-    #[inline]
-    fn default() -> Self {
-        PriorityQueue::new()
+    /// Create `PriorityQueue` from a slice
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq = PriorityQueue::from([(5, 55), (1, 11), (4, 44)]);
+    /// assert_eq!(3, pq.len());
+    /// assert_eq!(11, pq.peek().unwrap().1);
+    /// ```
+    fn from(arr: [(S, T); N]) -> Self {
+        PriorityQueue::from(Vec::from(arr))
     }
 }
 
-impl<S, T> Drop for PriorityQueue<S, T>
-where
+impl<S, T> FromIterator<(S, T)> for PriorityQueue<S, T>
+where 
     S: PartialOrd,
 {
-    fn drop(&mut self) {
-        while self.pop().is_some() {}
+    /// Or you can create `PriorityQueue` from any iterable collection. This 
+    /// also allows to use `collect` method to collect iterable elements into 
+    /// a `PriorityQueue`. This allows to build a PriorityQueuety collection 
+    /// through `into_iter`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// // build an iterator
+    /// let iter = (0..5).into_iter()
+    ///                  .map(|i| (i, i * 2));
+    /// 
+    /// // create a priority queue from it
+    /// let pq = PriorityQueue::from_iter(iter);
+    ///
+    /// assert_eq!(5, pq.len());
+    /// assert_eq!(0, pq.peek().unwrap().1);
+    /// ```
+    ///
+    /// Building priority queue while iterating over values with `collect`
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq: PriorityQueue<_, _> = (1..6).into_iter()
+    ///                                     .map(|i| (i, i + i))
+    ///                                     .collect();
+    /// assert_eq!(5, pq.len());
+    /// assert_eq!(1, pq.peek().unwrap().0);
+    /// ```
+    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
+        let mut pq_ = PriorityQueue::new();
+        pq_.extend(iter);
+        pq_
     }
 }
 
-impl<S, T> Deref for PriorityQueue<S, T>
+impl<S, T, O, A> Extend<(S, T)> for PriorityQueue<S, T, O, A>
 where
-    S: PartialOrd,
+    O: Order,
+    A: Allocator,
 {
-    type Target = [(S, T)];
-    fn deref(&self) -> &[(S, T)] {
-        unsafe { std::slice::from_raw_parts(self.ptr(), self.len) }
+    /// Extend priority queue with the elements from an iterator. Memory is
+    /// reserved up front based on the iterator's size hint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from([(5, 55), (4, 44)]);
+    /// pq.extend((1..4).map(|i| (i, i * 11)));
+    ///
+    /// assert_eq!(5, pq.len());
+    /// assert_eq!(11, pq.peek().unwrap().1);
+    /// ```
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        self.put_many(iter);
     }
 }
 
-impl<S, T> DerefMut for PriorityQueue<S, T>
+impl<'a, S, T, O, A> Extend<&'a (S, T)> for PriorityQueue<S, T, O, A>
 where
-    S: PartialOrd,
+    S: Clone + 'a,
+    T: Clone + 'a,
+    O: Order,
+    A: Allocator,
 {
-    fn deref_mut(&mut self) -> &mut [(S, T)] {
-        unsafe { std::slice::from_raw_parts_mut(self.ptr(), self.len) }
+    /// Extend priority queue with the clones of elements from an iterator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let elems = vec![(3, "c"), (1, "a"), (2, "b")];
+    /// let mut pq = PriorityQueue::new();
+    /// pq.extend(&elems);
+    ///
+    /// assert_eq!(3, pq.len());
+    /// assert_eq!("a", pq.peek().unwrap().1);
+    /// ```
+    fn extend<I: IntoIterator<Item = &'a (S, T)>>(&mut self, iter: I) {
+        self.put_many(iter.into_iter().cloned());
+    }
+}
+
+impl<S, T, O, A> Clone for PriorityQueue<S, T, O, A>
+where
+    S: Clone,
+    T: Clone,
+    O: Order,
+    A: Allocator + Clone,
+{
+    fn clone(&self) -> Self {
+        let mut dst = self.empty_like(self.len);
+
+        // elements are cloned in the same order, so heap stays valid
+        self.iter().for_each(|elem| dst.push(elem.clone()));
+        dst
+    }
+}
+
+impl<S, T, O> Add for &PriorityQueue<S, T, O>
+where
+    S: Clone,
+    T: Clone,
+    O: Order,
+{
+    type Output = PriorityQueue<S, T, O>;
+
+    /// Combine two priority queues without consuming them. Result is a new
+    /// priority queue with clones of elements from both operands, configured
+    /// as the left hand side one (see [`merge`]).
+    ///
+    /// [`merge`]: PriorityQueue::merge
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq1 = PriorityQueue::from([(5, 55), (1, 11), (4, 44), (2, 22)]);
+    /// let pq2 = PriorityQueue::from([(8, 44), (1, 22)]);
+    ///
+    /// let res = &pq1 + &pq2;
+    ///
+    /// assert_eq!(6, res.len());
+    /// assert_eq!(4, pq1.len());
+    /// assert_eq!(2, pq2.len());
+    /// ```
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut res = self.empty_like(self.len + rhs.len);
+        self.iter().for_each(|elem| res.push(elem.clone()));
+        res.extend_from_slice(rhs);
+        res
+    }
+}
+
+impl<S, T, O> Add for PriorityQueue<S, T, O>
+where 
+    O: Order,
+{
+    type Output = Self;
+
+    /// Combine two priority queues, elements of `rhs` are merged into `self`
+    /// with its comparator. See [`merge`].
+    ///
+    /// [`merge`]: PriorityQueue::merge
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut res: PriorityQueue<S, T, O> = self;
+        let mut rhs_ = rhs;
+        res.merge(&mut rhs_);
+        res
+    }
+}
+
+impl<S, T, O, A> PriorityQueue<S, T, O, A>
+where
+    O: Order,
+    A: Allocator + Clone,
+{
+    /// Removes elements which have the same score as another element, so
+    /// only the first of them to be popped is kept. Elements with
+    /// incomparable scores are all kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let mut pq = PriorityQueue::from([(2, 'b'), (1, 'a'), (2, 'c'), (1, 'd')]);
+    /// pq.dedup();
+    ///
+    /// assert_eq!(2, pq.len());
+    /// assert_eq!(1, pq.pop().unwrap().0);
+    /// assert_eq!(2, pq.pop().unwrap().0);
+    /// ```
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n log(n))*** as the elements are sorted first.
+    pub fn dedup(&mut self) {
+        let mut kept = self.empty_like(self.len);
+        while !self.is_empty() {
+            let mut ties = self.pop_ties().into_iter();
+            kept.extend(ties.next());
+        }
+        mem::swap(self, &mut kept);
+    }
+
+    /// Returns a sorted copy of all the elements in the priority queue
+    /// leaving the queue itself intact. Same as [`into_sorted_vec`] but it
+    /// can be used to take a snapshot of a live queue.
+    ///
+    /// [`into_sorted_vec`]: PriorityQueue::into_sorted_vec
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq = PriorityQueue::from([(5, 55), (1, 11), (4, 44)]);
+    ///
+    /// assert_eq!(vec![(1, 11), (4, 44), (5, 55)], pq.to_sorted_vec());
+    /// assert_eq!(3, pq.len());
+    /// ```
+    ///
+    /// # Time
+    ///
+    /// Elements are cloned in ***O(n)*** and sorted in ***O(n log(n))*** time.
+    pub fn to_sorted_vec(&self) -> Vec<(S, T), A>
+    where
+        S: Clone,
+        T: Clone,
+    {
+        self.clone().into_sorted_vec()
     }
-}
 
-impl<S, T> From<Vec<(S, T)>> for PriorityQueue<S, T>
-where 
-    S: PartialOrd,
-{
-    /// Create `PriorityQueue` from a `Vec` 
+    /// Splits the priority queue into two at the given index of the underlying
+    /// array. Returns a newly allocated priority queue with elements in the
+    /// range `[at, len)`, while this one keeps elements in the range `[0, at)`.
+    ///
+    /// Elements that stay are the top part of the heap, so they still are in
+    /// a valid order. Only the returned priority queue is re-heapified.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
     ///
     /// # Examples
     ///
     /// ```
     /// use priq::PriorityQueue;
     ///
-    /// let vec = vec![(5, 55), (4, 44), (2, 22), (3, 33)];
-    /// let mut pq = PriorityQueue::from(vec);
-    /// assert_eq!(4, pq.len());
-    /// assert_eq!(22, pq.pop().unwrap().1);
+    /// let mut pq1 = PriorityQueue::from([(5, 55), (1, 11), (4, 44), (2, 22)]);
+    /// let mut pq2 = pq1.split_off(1);
+    ///
+    /// assert_eq!(1, pq1.len());
+    /// assert_eq!(3, pq2.len());
+    /// assert_eq!(11, pq1.pop().unwrap().1);
+    /// assert_eq!(22, pq2.pop().unwrap().1);
     /// ```
-    fn from(other: Vec<(S, T)>) -> Self {
-        let len = other.len();
-        let _cap = rawpq::MIN_CAPACITY;
-        match mem::size_of::<(S, T)>() {
-            0 => assert!(len < rawpq::MAX_ZST_CAPACITY, "Capacity Overflow"),
-            _ => {
-                let min_cap = cmp::max(rawpq::MIN_CAPACITY, len) + 1;
-                let _cap = cmp::max(min_cap, other.capacity())
-                    .next_power_of_two();
-            }
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n - at)*** to move and re-heapify split off elements.
+    #[must_use = "use `.truncate()` if you don't need the other half"]
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len, "`at` split index (is {}) should be <= len (is {})",
+                at, self.len);
+        let moved = self.len - at;
+        let mut other = self.empty_like(moved);
+
+        // SAFETY: new priority queue has enough capacity allocated for all
+        //      the moved elements and `len` of this one is reduced, so
+        //      elements are owned by exactly one of the queues.
+        unsafe {
+            ptr::copy_nonoverlapping(self.ptr().add(at), other.ptr(), moved);
         }
-
-        let mut pq: PriorityQueue<S, T> = PriorityQueue::with_capacity(_cap);
-        other.into_iter()
-             .for_each(|(s, e)| pq.put(s, e));
-        pq
+        self.cut_len(at);
+        other.len = moved;
+        other.rebuild();
+        other
     }
-}
 
-impl<S, T, const N: usize> From<[(S, T); N]> for PriorityQueue<S, T>
-where 
-    S: PartialOrd,
-{
-    /// Create `PriorityQueue` from a slice
+    /// Consumes the priority queue and splits it into two: first one with all
+    /// the elements which scores are less than or equal to the `threshold`
+    /// and the second one with the rest of the elements.
+    ///
+    /// Elements with scores that can't be compared to the `threshold` end up
+    /// in the second priority queue.
     ///
     /// # Examples
     ///
     /// ```
     /// use priq::PriorityQueue;
     ///
-    /// let pq = PriorityQueue::from([(5, 55), (1, 11), (4, 44)]);
-    /// assert_eq!(3, pq.len());
-    /// assert_eq!(11, pq.peek().unwrap().1);
+    /// let pq = PriorityQueue::from([(5, 55), (1, 11), (4, 44), (2, 22)]);
+    /// let (mut due, rest) = pq.partition_by_score(&2);
+    ///
+    /// assert_eq!(2, due.len());
+    /// assert_eq!(2, rest.len());
+    /// assert_eq!(11, due.pop().unwrap().1);
+    /// assert_eq!(44, rest.peek().unwrap().1);
     /// ```
-    fn from(arr: [(S, T); N]) -> Self {
-        let mut pq: PriorityQueue<S, T> = PriorityQueue::with_capacity(N);
-        if mem::size_of::<(S, T)>() != 0 {
-            arr.into_iter()
-               .for_each(|(s, e)| pq.put(s, e));
-        }
-        pq
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)*** as both queues are re-heapified once.
+    pub fn partition_by_score(mut self, threshold: &S) -> (Self, Self) {
+        let mut rest = self.empty_like(0);
+        let cmp = self.cmp.clone();
+        self.drain_to(&mut rest, |(s, _): &(S, T)| {
+            !matches!(cmp.compare(s, threshold), Some(Ordering::Less | Ordering::Equal))
+        });
+        (self, rest)
     }
-}
 
-impl<S, T> FromIterator<(S, T)> for PriorityQueue<S, T>
-where 
-    S: PartialOrd
-{
-    /// Or you can create `PriorityQueue` from any iterable collection. This 
-    /// also allows to use `collect` method to collect iterable elements into 
-    /// a `PriorityQueue`. This allows to build a PriorityQueuety collection 
-    /// through `into_iter`.
+    /// Consumes the priority queue and converts every score into another type
+    /// with the given function, e.g. when floating point scores need to be
+    /// turned into a fixed-point integers.
     ///
-    /// # Example
+    /// If the elements with new scores have the same memory layout as old
+    /// ones, the underlying array is reused and scores are converted in-place.
+    /// Otherwise new array is allocated once. Either way, the priority queue
+    /// is re-heapified only once after all the scores have been converted.
+    ///
+    /// # Examples
     ///
     /// ```
     /// use priq::PriorityQueue;
     ///
-    /// // build an iterator
-    /// let iter = (0..5).into_iter()
-    ///                  .map(|i| (i, i * 2));
-    /// 
-    /// // create a priority queue from it
-    /// let pq = PriorityQueue::from_iter(iter);
+    /// let pq = PriorityQueue::from([(0.25, "b"), (0.125, "a"), (0.5, "c")]);
+    /// let mut pq: PriorityQueue<u64, &str> = pq.map_scores_into(|s: f64| {
+    ///     (s * 1000.0) as u64
+    /// });
     ///
-    /// assert_eq!(5, pq.len());
-    /// assert_eq!(0, pq.peek().unwrap().1);
+    /// assert_eq!((125, "a"), pq.pop().unwrap());
+    /// assert_eq!((250, "b"), pq.pop().unwrap());
+    /// assert_eq!((500, "c"), pq.pop().unwrap());
     /// ```
     ///
-    /// Building priority queue while iterating over values with `collect`
+    /// # Time Complexity
+    ///
+    /// ***O(n)*** conversions followed by a single ***O(n)*** re-heapify.
+    pub fn map_scores_into<S2, F>(self, mut f: F) -> PriorityQueue<S2, T, O, A>
+    where
+        S2: PartialOrd,
+        F: FnMut(S) -> S2,
+    {
+        let (tiebreak, nan) = (self.tiebreak.clone(), self.nan);
+        let mut pq = self.map_elems(Comparator::default(), |(s, e)| (f(s), e));
+        pq.tiebreak = tiebreak;
+        pq.nan = nan;
+        pq.rebuild();
+        pq
+    }
+
+    /// Consumes the priority queue and converts every item into another type
+    /// with the given function. Scores are left untouched, so the heap keeps
+    /// its structure and doesn't need to be re-heapified.
+    ///
+    /// If the elements with new items have the same memory layout as old
+    /// ones, the underlying array is reused and items are converted in-place.
+    ///
+    /// # Examples
     ///
     /// ```
     /// use priq::PriorityQueue;
     ///
-    /// let pq: PriorityQueue<_, _> = (1..6).into_iter()
-    ///                                     .map(|i| (i, i + i))
-    ///                                     .collect();
-    /// assert_eq!(5, pq.len());
-    /// assert_eq!(1, pq.peek().unwrap().0);
+    /// let pq = PriorityQueue::from([(2, "bb"), (1, "a"), (3, "ccc")]);
+    /// let mut pq = pq.map_items(|s| s.len());
+    ///
+    /// assert_eq!((1, 1), pq.pop().unwrap());
+    /// assert_eq!((2, 2), pq.pop().unwrap());
+    /// assert_eq!((3, 3), pq.pop().unwrap());
     /// ```
-    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
-        let mut pq_ = PriorityQueue::new();
-        iter.into_iter()
-            .for_each(|(s, e)| pq_.put(s, e));
-        pq_
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)*** conversions.
+    pub fn map_items<U, F>(self, mut f: F) -> PriorityQueue<S, U, O, A>
+    where
+        F: FnMut(T) -> U,
+    {
+        let (cmp, nan) = (self.cmp.clone(), self.nan);
+        let custom = cmp.is_custom();
+        let mut pq = self.map_elems(cmp, |(s, e)| (s, f(e)));
+        if custom || nan != NanPolicy::default() {
+            pq.nan = nan;
+            pq.find_max();
+        }
+        pq
     }
-}
 
-impl<S, T> Clone for PriorityQueue<S, T>
-where 
-    S: PartialOrd
-{
-    fn clone(&self) -> Self {
-        let mut dst = PriorityQueue::<S, T>::with_capacity(self.len + 1);
+    /// Converts every element with the given function keeping their positions
+    /// in the underlying array. Doesn't restore the heap order.
+    fn map_elems<S2, U, F>(self, cmp: Comparator<S2>, mut f: F) -> PriorityQueue<S2, U, O, A>
+    where
+        F: FnMut((S, T)) -> (S2, U),
+    {
+        if core::alloc::Layout::new::<(S, T)>() != core::alloc::Layout::new::<(S2, U)>() {
+            let data = RawPQ::with_capacity_in(self.len, self.data.alloc.clone());
+            let mut pq: PriorityQueue<S2, U, O, A> = PriorityQueue::from_raw(data, 0, cmp);
+            pq.set_growth_policy(self.growth_policy());
+            pq.set_shrink_policy(self.shrink_policy());
+            self.into_iter().for_each(|elem| pq.push(f(elem)));
+            return pq
+        }
 
-        // SAFETY: precondition ensures the source is aligned and valid,
-        //      and creating `with_capacity` ensures there is enough memory
-        //      allocated for a copy priority queue.
-        unsafe {
-            ptr::copy(self.ptr(), dst.as_mut_ptr(), self.len);
+        let (len, nans, max) = (self.len, self.nans, self.max);
+        let data = self.into_raw();
+
+        let mut guard = MapGuard::<(S, T), (S2, U)> {
+            ptr: data.ptr.as_ptr(),
+            done: 0,
+            len,
+            _marker: PhantomData,
+        };
+        while guard.done < len {
+            // SAFETY: layouts of `(S, T)` and `(S2, U)` are the same, so each
+            //      slot can be read as an old element and written back as a
+            //      new one. Guard keeps track of already converted elements
+            //      in case `f` panics.
+            unsafe {
+                let slot = guard.ptr.add(guard.done);
+                let elem = ptr::read(slot);
+                ptr::write(slot as *mut (S2, U), f(elem));
+            }
+            guard.done += 1;
         }
+        mem::forget(guard);
 
-        // SAFETY: we cloned queue with this capacity so we update its `len` too.
-        dst.len = self.len;
-        dst
+        let mut pq = PriorityQueue::from_raw(unsafe { data.cast::<S2, U>() }, len, cmp);
+        pq.nans = nans;
+        pq.max = max;
+        pq
+    }
+
+    /// Creates an empty priority queue with the same configuration as this
+    /// one and the capacity for exactly `cap` elements.
+    fn empty_like(&self, cap: usize) -> Self {
+        let data = RawPQ::with_capacity_in(cap, self.data.alloc.clone());
+        let mut pq = PriorityQueue::from_raw(data, 0, self.cmp.clone());
+        pq.set_growth_policy(self.growth_policy());
+        pq.set_shrink_policy(self.shrink_policy());
+        pq.tiebreak = self.tiebreak.clone();
+        pq.nan = self.nan;
+        pq
     }
 }
 
-impl<S, T> Add for PriorityQueue<S, T>
-where 
-    S: PartialOrd
+impl<S, T, O> Sum for PriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
 {
-    type Output = Self;
-    fn add(self, rhs: Self) -> Self::Output {
-        let mut res: PriorityQueue<S, T> = self;
-        let mut rhs_ = rhs;
-        res.merge(&mut rhs_);
-        res
+    /// Combine all the priority queues from an iterator into one. Memory of
+    /// the first priority queue is reused and elements of the rest of them
+    /// are moved into it in bulk, the same way as with [`put_many`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let queues = vec![
+    ///     PriorityQueue::from([(5, 55), (2, 22)]),
+    ///     PriorityQueue::from([(4, 44), (1, 11)]),
+    ///     PriorityQueue::from([(3, 33)]),
+    /// ];
+    ///
+    /// let mut pq: PriorityQueue<_, _> = queues.into_iter().sum();
+    /// assert_eq!(5, pq.len());
+    /// (1..=5).for_each(|i| assert_eq!(i * 11, pq.pop().unwrap().1));
+    /// ```
+    ///
+    /// [`put_many`]: PriorityQueue::put_many
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(PriorityQueue::default(), |mut acc, mut pq| {
+            if acc.is_empty() {
+                return pq
+            }
+            acc.put_many(pq.drain(..));
+            acc
+        })
     }
 }
 
-pub struct IntoIter<S, T> {
-    _buf: RawPQ<S, T>,
+pub struct IntoIter<S, T, A: Allocator = Global> {
+    _buf: RawPQ<S, T, A>,
     iter: RawPQIter<S, T>,
 }
 
-impl<S, T> Iterator for IntoIter<S, T> {
+impl<S, T, A: Allocator> Iterator for IntoIter<S, T, A> {
     type Item = (S, T);
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -976,24 +3162,34 @@ impl<S, T> Iterator for IntoIter<S, T> {
     }
 }
 
-impl<S, T> Drop for IntoIter<S, T> {
+impl<S, T, A: Allocator> DoubleEndedIterator for IntoIter<S, T, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<S, T, A: Allocator> ExactSizeIterator for IntoIter<S, T, A> {}
+
+impl<S, T, A: Allocator> FusedIterator for IntoIter<S, T, A> {}
+
+impl<S, T, A: Allocator> Drop for IntoIter<S, T, A> {
     fn drop(&mut self) {
         for _ in &mut *self {}
     }
 }
 
-impl<S, T> IntoIterator for PriorityQueue<S, T>
-where 
-    S: PartialOrd + Clone
+impl<S, T, O, A> IntoIterator for PriorityQueue<S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
 {
     type Item = (S, T);
-    type IntoIter = IntoIter<S, T>;
+    type IntoIter = IntoIter<S, T, A>;
 
     fn into_iter(self) -> Self::IntoIter {
         unsafe {
             let iter = RawPQIter::new(&self);
-            let _buf = ptr::read(&self.data);
-            mem::forget(self);
+            let _buf = self.into_raw();
 
             IntoIter { iter, _buf, }
         }
@@ -1046,17 +3242,173 @@ impl<S, T> Iterator for RawPQIter<S, T> {
     }
 }
 
-pub struct Drain<'a, S: 'a, T: 'a>
-where 
-    S: PartialOrd,
+impl<S, T> DoubleEndedIterator for RawPQIter<S, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            None
+        } else {
+            unsafe {
+                self.end = match mem::size_of::<(S, T)>() {
+                    0 => (self.end as usize - 1) as *const _,
+                    _ => self.end.offset(-1),
+                };
+                Some(ptr::read(self.end))
+            }
+        }
+    }
+}
+
+/// Iterator over the references to the elements of a priority queue in the
+/// order of their priority, created by [`PriorityQueue::iter_sorted`].
+pub struct IterSorted<'a, S: 'a, T: 'a, O = Min, A = Global>
+where
+    O: Order,
+    A: Allocator,
+{
+    pq: &'a PriorityQueue<S, T, O, A>,
+    candidates: Vec<usize>,
+    nans: usize,
+    remaining: usize,
+}
+
+impl<'a, S, T, O, A> IterSorted<'a, S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
+{
+    /// Checks if element at index `lhs` goes before the one at index `rhs`.
+    #[inline]
+    fn less(&self, lhs: usize, rhs: usize) -> bool {
+        self.pq.precedes(&self.pq[self.candidates[lhs]],
+                         &self.pq[self.candidates[rhs]])
+    }
+
+    fn push(&mut self, index: usize) {
+        self.candidates.push(index);
+        let mut i = self.candidates.len() - 1;
+        while i > 0 && self.less(i, (i - 1) / 2) {
+            self.candidates.swap(i, (i - 1) / 2);
+            i = (i - 1) / 2;
+        }
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        let top = self.candidates.swap_remove(0);
+        let mut i = 0;
+        loop {
+            let (left, right) = (2 * i + 1, 2 * i + 2);
+            let mut min_ = i;
+            if left < self.candidates.len() && self.less(left, min_) {
+                min_ = left;
+            }
+            if right < self.candidates.len() && self.less(right, min_) {
+                min_ = right;
+            }
+            if min_ == i {
+                return Some(top)
+            }
+            self.candidates.swap(i, min_);
+            i = min_;
+        }
+    }
+}
+
+impl<'a, S, T, O, A> Iterator for IterSorted<'a, S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
+{
+    type Item = &'a (S, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let nans_first = self.pq.nan == NanPolicy::First || self.candidates.is_empty();
+        if self.nans > 0 && nans_first {
+            self.nans -= 1;
+            self.remaining -= 1;
+            return Some(&self.pq[self.pq.heap_len() + self.nans])
+        }
+        if self.candidates.is_empty() {
+            return None
+        }
+
+        let index = self.pop()?;
+        let (left, right) = (self.pq.left_child(index), self.pq.right_child(index));
+        if self.pq.has_left(index) { self.push(left); }
+        if self.pq.has_right(index) { self.push(right); }
+        self.remaining -= 1;
+        Some(&self.pq[index])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<S, T, O, A> ExactSizeIterator for IterSorted<'_, S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
+{}
+
+impl<S, T, O, A> FusedIterator for IterSorted<'_, S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
+{}
+
+/// Consuming iterator over the elements of a priority queue in the order of
+/// their priority, created by [`PriorityQueue::into_iter_sorted`].
+pub struct IntoIterSorted<S, T, O = Min, A = Global>
+where
+    O: Order,
+    A: Allocator,
+{
+    pq: PriorityQueue<S, T, O, A>,
+}
+
+impl<S, T, O, A> Iterator for IntoIterSorted<S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
+{
+    type Item = (S, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pq.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.pq.len, Some(self.pq.len))
+    }
+}
+
+impl<S, T, O, A> ExactSizeIterator for IntoIterSorted<S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
+{}
+
+impl<S, T, O, A> FusedIterator for IntoIterSorted<S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
+{}
+
+pub struct Drain<'a, S: 'a, T: 'a, O = Min, A = Global>
+where
+    O: Order,
+    A: Allocator,
 {
-    pq: PhantomData<&'a mut PriorityQueue<S, T>>,
+    pq: &'a mut PriorityQueue<S, T, O, A>,
+    tail_start: usize,
+    tail_len: usize,
     iter: RawPQIter<S, T>,
 }
 
-impl<'a, S, T> Iterator for Drain<'a, S, T>
-where 
-    S: PartialOrd,
+impl<'a, S, T, O, A> Iterator for Drain<'a, S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
 {
     type Item = (S, T);
 
@@ -1069,11 +3421,141 @@ where
     }
 }
 
-impl<'a, S, T> Drop for Drain<'a, S, T>
-where 
-    S: PartialOrd,
+impl<S, T, O, A> DoubleEndedIterator for Drain<'_, S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<S, T, O, A> ExactSizeIterator for Drain<'_, S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
+{}
+
+impl<S, T, O, A> FusedIterator for Drain<'_, S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
+{}
+
+impl<'a, S, T, O, A> Drop for Drain<'a, S, T, O, A>
+where
+    O: Order,
+    A: Allocator,
 {
     fn drop(&mut self) {
         for _ in &mut *self {}
+
+        if self.tail_len > 0 {
+            let start = self.pq.len;
+            // SAFETY: all the drained elements are moved out, so the tail
+            //      is moved right after the remaining head of the array.
+            unsafe {
+                let ptr = self.pq.ptr();
+                ptr::copy(ptr.add(self.tail_start), ptr.add(start), self.tail_len);
+            }
+            self.pq.len = start + self.tail_len;
+            self.pq.rebuild();
+        }
+    }
+}
+
+/// Selects which elements [`PriorityQueue::drain_to`] moves into another
+/// priority queue. It's implemented for ranges, which select elements by 
+/// their position in the underlying array, and for predicates over elements.
+pub trait DrainSelector<S, T> {
+    /// Moves all the selected elements to the back of `elems` and returns 
+    /// how many of them were selected.
+    fn select(self, elems: &mut [(S, T)]) -> usize;
+}
+
+macro_rules! range_selector {
+    ($($range:ty),*) => {$(
+        impl<S, T> DrainSelector<S, T> for $range {
+            fn select(self, elems: &mut [(S, T)]) -> usize {
+                let Range { start, end } = slice::range(self, ..elems.len());
+                elems[start..].rotate_left(end - start);
+                end - start
+            }
+        }
+    )*}
+}
+
+range_selector!(
+    ops::Range<usize>,
+    ops::RangeFrom<usize>,
+    ops::RangeFull,
+    ops::RangeInclusive<usize>,
+    ops::RangeTo<usize>,
+    ops::RangeToInclusive<usize>
+);
+
+impl<S, T, F> DrainSelector<S, T> for F
+where
+    F: FnMut(&(S, T)) -> bool,
+{
+    fn select(mut self, elems: &mut [(S, T)]) -> usize {
+        // keep the unselected elements in the front, pushing others back
+        let mut keep = 0;
+        for i in 0..elems.len() {
+            if !self(&elems[i]) {
+                elems.swap(keep, i);
+                keep += 1;
+            }
+        }
+        elems.len() - keep
+    }
+}
+
+/// Turns a predicate over elements into a [`DrainSelector`]. A closure
+/// passed to [`PriorityQueue::drain_to`] directly needs the type of its
+/// argument annotated, while wrapped in `selector` it's inferred from the
+/// queue.
+///
+/// # Examples
+///
+/// ```
+/// use priq::{selector, PriorityQueue};
+///
+/// let mut pq1 = PriorityQueue::from([(3, "c"), (1, "a"), (2, "b")]);
+/// let mut pq2 = PriorityQueue::new();
+///
+/// pq1.drain_to(&mut pq2, selector(|(s, _)| *s > 1));
+/// assert_eq!(Some(&(1, "a")), pq1.peek());
+/// assert_eq!(Some(&(2, "b")), pq2.peek());
+/// ```
+pub fn selector<S, T, F>(predicate: F) -> F
+where
+    F: FnMut(&(S, T)) -> bool,
+{
+    predicate
+}
+
+/// Keeps track of the elements converted by [`PriorityQueue::map_scores_into`]
+/// or [`PriorityQueue::map_items`] so they can be dropped properly if the
+/// conversion function panics.
+struct MapGuard<E, E2> {
+    ptr: *mut E,
+    done: usize,
+    len: usize,
+    _marker: PhantomData<E2>,
+}
+
+impl<E, E2> Drop for MapGuard<E, E2> {
+    fn drop(&mut self) {
+        // SAFETY: elements before `done` are already converted, the one at 
+        //      `done` was moved out into the panicking function and the rest
+        //      are still the old ones.
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                self.ptr as *mut E2, self.done));
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                self.ptr.add(self.done + 1), self.len - self.done - 1));
+        }
     }
 }
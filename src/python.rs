@@ -0,0 +1,89 @@
+//! Python bindings, a priority queue of `float` scores and arbitrary
+//! objects.
+//!
+//! The `priq` Python module is defined by [`priq`]. The crate is built as a
+//! Rust library only, so the extension module is built with the crate type
+//! passed explicitly, and the library renamed to the name of the module:
+//!
+//! ```sh
+//! cargo rustc --release --features python,pyo3/extension-module --crate-type cdylib
+//! cp target/release/libpriq.so priq.so
+//! ```
+//!
+//! The library is `libpriq.dylib` on macOS, and `priq.dll` which has to be
+//! renamed to `priq.pyd` on Windows.
+//!
+//! ```python
+//! from priq import PriorityQueue
+//!
+//! pq = PriorityQueue()
+//! pq.put(2.5, "backup")
+//! pq.put(0.5, "deploy")
+//! assert pq.pop() == (0.5, "deploy")
+//! assert len(pq) == 1
+//! ```
+
+use pyo3::exceptions::PyIndexError;
+use pyo3::prelude::*;
+
+use crate::PriorityQueue;
+
+/// A min-heap of `float` scores and any Python objects as items, where the
+/// lowest score is on top. Unlike `heapq`, items are never compared, so they
+/// don't have to be orderable.
+#[pyclass(name = "PriorityQueue", module = "priq")]
+#[derive(Default)]
+pub struct PyPriorityQueue {
+    pq: PriorityQueue<f64, PyObject>,
+}
+
+#[pymethods]
+impl PyPriorityQueue {
+    /// Create an empty queue.
+    #[new]
+    pub fn new() -> Self {
+        PyPriorityQueue::default()
+    }
+
+    /// Inserts an item with its score in the queue. `nan` scores are kept
+    /// after all the other ones.
+    pub fn put(&mut self, score: f64, item: PyObject) {
+        self.pq.put(score, item);
+    }
+
+    /// Removes the top element and returns it as a `(score, item)` tuple.
+    ///
+    /// Raises `IndexError` if the queue is empty.
+    pub fn pop(&mut self) -> PyResult<(f64, PyObject)> {
+        self.pq.pop().ok_or_else(|| PyIndexError::new_err("pop from an empty priority queue"))
+    }
+
+    /// Returns the top element as a `(score, item)` tuple without removing
+    /// it, or `None` if the queue is empty.
+    pub fn peek(&self, py: Python<'_>) -> Option<(f64, PyObject)> {
+        self.pq.peek().map(|(score, item)| (*score, item.clone_ref(py)))
+    }
+
+    /// Removes all the elements from the queue.
+    pub fn clear(&mut self) {
+        self.pq.clear();
+    }
+
+    fn __len__(&self) -> usize {
+        self.pq.len()
+    }
+
+    fn __bool__(&self) -> bool {
+        !self.pq.is_empty()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("PriorityQueue(len={})", self.pq.len())
+    }
+}
+
+/// The `priq` Python module.
+#[pymodule]
+pub fn priq(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyPriorityQueue>()
+}
@@ -0,0 +1,201 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Unsigned integer types which can be used as scores of [`RadixHeap`].
+/// Implemented for `u8`, `u16`, `u32`, `u64`, `u128` and `usize`.
+pub trait Radix: Copy + Ord + Default + private::Sealed {
+    /// Number of bits in the type.
+    const BITS: u32;
+
+    /// Number of the highest bit in which two integers differ, counting
+    /// from one, or zero if they are equal.
+    fn radix_distance(&self, other: &Self) -> usize;
+}
+
+macro_rules! radix_impl {
+    ($($int:ty),*) => {$(
+        impl Radix for $int {
+            const BITS: u32 = <$int>::BITS;
+
+            #[inline]
+            fn radix_distance(&self, other: &Self) -> usize {
+                (Self::BITS - (self ^ other).leading_zeros()) as usize
+            }
+        }
+    )*}
+}
+
+radix_impl!(u8, u16, u32, u64, u128, usize);
+
+/// A min-priority queue with unsigned integer scores for monotone
+/// workloads, where no element is ever put with a score lower than the last
+/// popped one, like Dijkstra's algorithm with integer weights.
+///
+/// Elements are kept in buckets by the highest bit in which their score
+/// differs from the last popped one, so putting an element is ***O(1)***,
+/// and every element is moved between buckets at most once per bit of the
+/// score, making pop amortized ***O(bits)*** regardless of the queue size.
+///
+/// # Examples
+///
+/// ```
+/// use priq::RadixHeap;
+///
+/// let mut pq = RadixHeap::new();
+/// pq.put(5u32, "e");
+/// pq.put(2, "b");
+///
+/// assert_eq!(Some((2, "b")), pq.pop());
+/// pq.put(3, "c");
+/// assert_eq!(Some((3, "c")), pq.pop());
+/// assert_eq!(Some((5, "e")), pq.pop());
+/// ```
+#[derive(Clone)]
+pub struct RadixHeap<S, T> {
+    buckets: Vec<Vec<(S, T)>>,
+    last: S,
+    len: usize,
+}
+
+impl<S, T> RadixHeap<S, T>
+where
+    S: Radix,
+{
+    /// Create an empty `RadixHeap` which accepts any score.
+    #[must_use]
+    pub fn new() -> Self {
+        RadixHeap::starting_at(S::default())
+    }
+
+    /// Create an empty `RadixHeap` which accepts only scores not lower than
+    /// `last`, as if it was popped already.
+    #[must_use]
+    pub fn starting_at(last: S) -> Self {
+        RadixHeap {
+            buckets: (0..=S::BITS).map(|_| Vec::new()).collect(),
+            last,
+            len: 0,
+        }
+    }
+
+    /// Inserts an element in the heap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the score is lower than the last popped one.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn put(&mut self, score: S, item: T) {
+        assert!(score >= self.last, "score is lower than the last popped one");
+        self.buckets[score.radix_distance(&self.last)].push((score, item));
+        self.len += 1;
+    }
+
+    /// Get the element with the lowest score from `RadixHeap`.
+    ///
+    /// # Time Complexity
+    ///
+    /// Amortized ***O(bits)***, where `bits` is the size of the score type.
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        if self.buckets[0].is_empty() {
+            let bucket = self.buckets.iter().position(|b| !b.is_empty())?;
+            let elems = core::mem::take(&mut self.buckets[bucket]);
+            self.last = elems.iter().map(|(s, _)| *s).min().expect("bucket is not empty");
+            for (score, item) in elems {
+                self.buckets[score.radix_distance(&self.last)].push((score, item));
+            }
+        }
+        self.len -= 1;
+        self.buckets[0].pop()
+    }
+
+    /// Check what is the element with the lowest score in `RadixHeap`, by
+    /// getting the reference.
+    ///
+    /// # Time Complexity
+    ///
+    /// Linear in the number of elements in the bucket with the lowest
+    /// scores, ***O(bits)*** if the lowest score was already popped.
+    pub fn peek(&self) -> Option<&(S, T)> {
+        let bucket = self.buckets.iter().find(|b| !b.is_empty())?;
+        bucket.iter().min_by_key(|(s, _)| *s)
+    }
+
+    /// Returns the score of the last popped element, the lowest score the
+    /// heap accepts.
+    pub fn last(&self) -> S {
+        self.last
+    }
+
+    /// Returns the number of elements in the `RadixHeap`
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` is there are no elements in `RadixHeap`
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all the elements from `RadixHeap`. The last popped score is
+    /// still remembered.
+    pub fn clear(&mut self) {
+        self.buckets.iter_mut().for_each(Vec::clear);
+        self.len = 0;
+    }
+}
+
+impl<S, T> Default for RadixHeap<S, T>
+where
+    S: Radix,
+{
+    fn default() -> Self {
+        RadixHeap::new()
+    }
+}
+
+impl<S, T> FromIterator<(S, T)> for RadixHeap<S, T>
+where
+    S: Radix,
+{
+    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
+        let mut pq = RadixHeap::new();
+        pq.extend(iter);
+        pq
+    }
+}
+
+impl<S, T> Extend<(S, T)> for RadixHeap<S, T>
+where
+    S: Radix,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(s, e)| self.put(s, e));
+    }
+}
+
+impl<S, T> fmt::Debug for RadixHeap<S, T>
+where
+    S: fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RadixHeap")
+         .field("last", &self.last)
+         .field("data", &self.buckets.iter().flatten().collect::<Vec<_>>())
+         .finish()
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for u8 {}
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
+    impl Sealed for u64 {}
+    impl Sealed for u128 {}
+    impl Sealed for usize {}
+}
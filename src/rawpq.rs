@@ -1,24 +1,199 @@
-use std::mem;
-use std::ptr;
-use std::marker;
-use std::alloc;
+use core::cmp;
+use core::mem;
+use core::ptr;
+use core::marker;
+use alloc::alloc;
+use core::alloc::{Allocator, Layout};
+
+use crate::error::TryReserveError;
 
 const INITIAL_CAPACITY: usize = 7;
+const GROWTH_FACTOR: f64 = 2.0;
+const SHRINK_MIN_CAPACITY: usize = 1_000;
+const SHRINK_LOAD: f64 = 0.25;
 pub const MAX_ZST_CAPACITY: usize = 1 << (usize::BITS - 1);
-pub const MIN_CAPACITY: usize = 4;
+
+/// Describes how the underlying array of a priority queue grows when it runs
+/// out of space: how many elements are allocated for the first time and
+/// how many times the capacity is multiplied afterwards.
+///
+/// By default, space for 7 elements is allocated and capacity is doubled
+/// every time the array is full.
+///
+/// # Examples
+///
+/// ```
+/// use priq::{GrowthPolicy, PriorityQueue};
+///
+/// let policy = GrowthPolicy::new(16, 1.5);
+/// let mut pq = PriorityQueue::with_growth_policy(policy);
+///
+/// pq.put(1, 11);
+/// assert_eq!(16, pq.capacity());
+/// (0..16).for_each(|i| pq.put(i, i));
+/// assert_eq!(24, pq.capacity());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GrowthPolicy {
+    initial_capacity: usize,
+    factor: f64,
+}
+
+impl GrowthPolicy {
+    /// Creates a new policy which allocates `initial_capacity` elements
+    /// first and then multiplies capacity by `factor` every time it grows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `initial_capacity` is zero or `factor` is not a finite
+    /// number greater than one.
+    pub fn new(initial_capacity: usize, factor: f64) -> Self {
+        assert_ne!(initial_capacity, 0, "Initial capacity can't be zero");
+        assert!(factor.is_finite() && factor > 1.0,
+                "Growth factor must be greater than one");
+        GrowthPolicy { initial_capacity, factor }
+    }
+
+    /// Number of elements allocated when the empty array grows.
+    pub fn initial_capacity(&self) -> usize {
+        self.initial_capacity
+    }
+
+    /// How many times capacity is multiplied when the array grows.
+    pub fn factor(&self) -> f64 {
+        self.factor
+    }
+
+    /// Capacity the array with `cap` elements grows to, or `None` if it
+    /// doesn't fit in `usize`. New capacity is always larger than `cap`.
+    pub(crate) fn next_capacity(&self, cap: usize) -> Option<usize> {
+        if cap == 0 {
+            return Some(self.initial_capacity)
+        }
+
+        let grown = cap as f64 * self.factor;
+        if grown >= usize::MAX as f64 {
+            return None
+        }
+        Some(cmp::max(grown as usize, cap + 1))
+    }
+}
+
+impl Default for GrowthPolicy {
+    fn default() -> Self {
+        GrowthPolicy::new(INITIAL_CAPACITY, GROWTH_FACTOR)
+    }
+}
+
+/// Describes when the underlying array of a priority queue gives memory
+/// back as elements are popped: once its capacity is over `min_capacity`
+/// and at most `load` part of it is used, the capacity is halved.
+///
+/// By default arrays with space for more than 1000 elements are shrunk
+/// when they are at most a quarter full.
+///
+/// # Examples
+///
+/// ```
+/// use priq::{PriorityQueue, ShrinkPolicy};
+///
+/// let mut pq: PriorityQueue<usize, usize> = PriorityQueue::builder()
+///     .capacity(64)
+///     .shrink_policy(ShrinkPolicy::new(16, 0.5))
+///     .build();
+/// (0..64).for_each(|i| pq.put(i, i));
+///
+/// (0..32).for_each(|_| { pq.pop(); });
+/// assert_eq!(32, pq.capacity());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShrinkPolicy {
+    min_capacity: usize,
+    load: f64,
+}
+
+impl ShrinkPolicy {
+    /// Creates a new policy which halves the capacity larger than
+    /// `min_capacity` once at most `load` part of it is used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `load` is not greater than zero and at most one half, so
+    /// the elements always fit in the shrunk array.
+    pub fn new(min_capacity: usize, load: f64) -> Self {
+        assert!(load > 0.0 && load <= 0.5,
+                "Shrink load must be greater than zero and at most one half");
+        ShrinkPolicy { min_capacity, load }
+    }
+
+    /// A policy which never shrinks the array, the memory is given back
+    /// only with [`shrink_to_fit`] or [`shrink_to`].
+    ///
+    /// [`shrink_to_fit`]: crate::PriorityQueue::shrink_to_fit
+    /// [`shrink_to`]: crate::PriorityQueue::shrink_to
+    pub fn never() -> Self {
+        ShrinkPolicy { min_capacity: usize::MAX, load: SHRINK_LOAD }
+    }
+
+    /// Capacity the array must exceed before it's shrunk.
+    pub fn min_capacity(&self) -> usize {
+        self.min_capacity
+    }
+
+    /// Part of the capacity which is used when the array is shrunk.
+    pub fn load(&self) -> f64 {
+        self.load
+    }
+
+    /// Checks if the array with `cap` elements of which `len` are used
+    /// should be shrunk.
+    pub(crate) fn should_shrink(&self, cap: usize, len: usize) -> bool {
+        cap > self.min_capacity && len as f64 <= cap as f64 * self.load
+    }
+}
+
+impl Default for ShrinkPolicy {
+    fn default() -> Self {
+        ShrinkPolicy::new(SHRINK_MIN_CAPACITY, SHRINK_LOAD)
+    }
+}
 
 #[derive(Debug)]
-pub struct RawPQ<S, T> {
+pub struct RawPQ<S, T, A: Allocator = alloc::Global> {
     pub ptr: ptr::NonNull<(S, T)>,
     pub cap: usize,
+    pub growth: GrowthPolicy,
+    pub shrink: ShrinkPolicy,
+    pub alloc: A,
     _marker: marker::PhantomData<(S, T)>,
 }
 
-unsafe impl<T: Send, S: Send> Send for RawPQ<S, T> {}
-unsafe impl<T: Sync, S: Sync> Sync for RawPQ<S, T> {}
+unsafe impl<T: Send, S: Send, A: Allocator + Send> Send for RawPQ<S, T, A> {}
+unsafe impl<T: Sync, S: Sync, A: Allocator + Sync> Sync for RawPQ<S, T, A> {}
 
-impl<S, T> RawPQ<S,T> {
+impl<S, T> RawPQ<S, T> {
     pub fn new() -> Self {
+        RawPQ::new_in(alloc::Global)
+    }
+
+    pub fn with_capacity(cap: usize) -> Self {
+        RawPQ::with_capacity_in(cap, alloc::Global)
+    }
+
+    /// Creates a buffer from the memory allocated somewhere else.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be allocated by the global allocator with the layout of an
+    /// array of `cap` elements of `(S, T)` (or be dangling if nothing was
+    /// allocated), same as `Vec` does.
+    pub unsafe fn from_raw_parts(ptr: *mut (S, T), cap: usize) -> Self {
+        RawPQ::from_raw_parts_in(ptr, cap, alloc::Global)
+    }
+}
+
+impl<S, T, A: Allocator> RawPQ<S, T, A> {
+    pub fn new_in(alloc: A) -> Self {
         let cap = match mem::size_of::<(S, T)>() {
             0 => MAX_ZST_CAPACITY,
             _ => 0,
@@ -27,89 +202,163 @@ impl<S, T> RawPQ<S,T> {
         RawPQ {
             ptr: ptr::NonNull::dangling(),
             cap,
+            growth: GrowthPolicy::default(),
+            shrink: ShrinkPolicy::default(),
+            alloc,
             _marker: marker::PhantomData,
         }
     }
 
-    pub fn with_capacity(cap: usize) -> Self {
-        assert_ne!(cap, 0, "Capacity Overflow");
-        let layout = alloc::Layout::array::<(S, T)>(cap).unwrap();
+    pub fn with_capacity_in(cap: usize, alloc: A) -> Self {
+        let mut raw = RawPQ::new_in(alloc);
+        raw.reserve(cap);
+        raw
+    }
 
-        assert!(layout.size() <= MAX_ZST_CAPACITY, "Allocation is too large");
-        let new_ptr = unsafe { alloc::alloc(layout) };
+    /// Creates a buffer from the memory allocated by `alloc` somewhere else.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`RawPQ::from_raw_parts`], but the memory must be allocated
+    /// by `alloc`.
+    pub unsafe fn from_raw_parts_in(ptr: *mut (S, T), cap: usize, alloc: A) -> Self {
+        if mem::size_of::<(S, T)>() == 0 || cap == 0 {
+            return RawPQ::new_in(alloc)
+        }
 
         RawPQ {
-            ptr: match ptr::NonNull::new(new_ptr as *mut (S, T)) {
-                Some(p) => p,
-                None => alloc::handle_alloc_error(layout),
-            },
+            ptr: ptr::NonNull::new_unchecked(ptr),
             cap,
+            growth: GrowthPolicy::default(),
+            shrink: ShrinkPolicy::default(),
+            alloc,
             _marker: marker::PhantomData,
         }
     }
 
     pub fn grow(&mut self) {
-        assert_ne!(mem::size_of::<(S, T)>(), 0, "Capacity Overflow");
+        handle_reserve(self.try_grow());
+    }
 
-        let (new_cap, new_layout) = match self.cap {
-            0 => (INITIAL_CAPACITY,
-                alloc::Layout::array::<(S, T)>(INITIAL_CAPACITY).unwrap()),
-            _ => {
-                let new_cap = 2 * self.cap;
-                let new_layout = alloc::Layout::array::<(S, T)>(new_cap)
-                                    .unwrap();
-                (new_cap, new_layout)
-            }
-        };
+    pub fn try_grow(&mut self) -> Result<(), TryReserveError> {
+        if mem::size_of::<(S, T)>() == 0 {
+            return Err(TryReserveError::CapacityOverflow)
+        }
+
+        let new_cap = self.growth.next_capacity(self.cap)
+                                 .ok_or(TryReserveError::CapacityOverflow)?;
+        self.realloc(new_cap)
+    }
+
+    pub fn reserve(&mut self, cap: usize) {
+        handle_reserve(self.try_reserve(cap));
+    }
+
+    pub fn try_reserve(&mut self, cap: usize) -> Result<(), TryReserveError> {
+        if cap <= self.cap || mem::size_of::<(S, T)>() == 0 {
+            return Ok(())
+        }
+        self.realloc(cap)
+    }
+
+    /// Moves elements into the newly allocated memory that can hold `cap`
+    /// elements, which must be more than the current capacity.
+    fn realloc(&mut self, cap: usize) -> Result<(), TryReserveError> {
+        let new_layout = Layout::array::<(S, T)>(cap)
+                            .map_err(|_| TryReserveError::CapacityOverflow)?;
+        if new_layout.size() > MAX_ZST_CAPACITY {
+            return Err(TryReserveError::CapacityOverflow)
+        }
 
-        assert!(
-            new_layout.size() <= MAX_ZST_CAPACITY, "Allocation is too large"
-        );
         let new_ptr = match self.cap {
-            0 => unsafe { alloc::alloc(new_layout) },
+            0 => self.alloc.allocate(new_layout),
             _ => {
-                let old_layout = alloc::Layout::array::<(S, T)>(self.cap)
-                                    .unwrap();
-                let old_ptr = self.ptr.as_ptr() as *mut u8;
+                let old_layout = Layout::array::<(S, T)>(self.cap).unwrap();
                 unsafe {
-                    alloc::realloc(old_ptr, old_layout, new_layout.size())
+                    self.alloc.grow(self.ptr.cast(), old_layout, new_layout)
                 }
             }
         };
 
-        self.ptr = match ptr::NonNull::new(new_ptr as *mut (S, T)) {
-            Some(p) => p,
-            None => alloc::handle_alloc_error(new_layout),
+        self.ptr = match new_ptr {
+            Ok(p) => p.cast(),
+            Err(_) => {
+                return Err(TryReserveError::AllocError { layout: new_layout })
+            }
         };
-        self.cap = new_cap;
+        self.cap = cap;
+        Ok(())
+    }
+
+    /// Reinterprets the buffer as a storage of elements with other types.
+    ///
+    /// # Safety
+    ///
+    /// `(S, T)` and `(S2, T2)` must have the same memory layout.
+    pub unsafe fn cast<S2, T2>(self) -> RawPQ<S2, T2, A> {
+        let raw = mem::ManuallyDrop::new(self);
+        RawPQ {
+            ptr: raw.ptr.cast(),
+            cap: raw.cap,
+            growth: raw.growth,
+            shrink: raw.shrink,
+            alloc: ptr::read(&raw.alloc),
+            _marker: marker::PhantomData,
+        }
     }
 
     pub fn shrink(&mut self) {
-        let old_layout = alloc::Layout::array::<(S, T)>(self.cap).unwrap();
-        let old_ptr = self.ptr.as_ptr() as *mut u8;
-        let new_cap = self.cap / 2;
-        let new_layout = alloc::Layout::array::<(S, T)>(new_cap).unwrap();
+        self.shrink_to(self.cap / 2);
+    }
+
+    /// Shrinks the buffer to hold exactly `cap` elements, releasing it
+    /// completely if `cap` is zero.
+    pub fn shrink_to(&mut self, cap: usize) {
+        if cap >= self.cap || mem::size_of::<(S, T)>() == 0 {
+            return
+        }
+
+        let old_layout = Layout::array::<(S, T)>(self.cap).unwrap();
+        if cap == 0 {
+            unsafe { self.alloc.deallocate(self.ptr.cast(), old_layout) };
+            self.ptr = ptr::NonNull::dangling();
+            self.cap = 0;
+            return
+        }
 
+        let new_layout = Layout::array::<(S, T)>(cap).unwrap();
         let new_ptr = unsafe {
-            alloc::realloc(old_ptr, old_layout, new_layout.size())
+            self.alloc.shrink(self.ptr.cast(), old_layout, new_layout)
         };
 
-        self.ptr = match ptr::NonNull::new(new_ptr as *mut (S, T)) {
-            Some(p) => p,
-            None => alloc::handle_alloc_error(new_layout),
+        self.ptr = match new_ptr {
+            Ok(p) => p.cast(),
+            Err(_) => alloc::handle_alloc_error(new_layout),
         };
-        self.cap = new_cap;
+        self.cap = cap;
+    }
+}
+
+/// Turns the allocation error into a panic or an abort, same as infallible
+/// collections of the standard library do.
+fn handle_reserve(res: Result<(), TryReserveError>) {
+    match res {
+        Ok(()) => (),
+        Err(TryReserveError::CapacityOverflow) => panic!("Capacity Overflow"),
+        Err(TryReserveError::AllocError { layout }) => {
+            alloc::handle_alloc_error(layout)
+        }
     }
 }
 
-impl<S, T> Drop for RawPQ<S, T> {
+impl<S, T, A: Allocator> Drop for RawPQ<S, T, A> {
     fn drop(&mut self) {
         let elem_size = mem::size_of::<(S, T)>();
         if self.cap != 0 && elem_size != 0 {
             unsafe {
-                alloc::dealloc(
-                    self.ptr.as_ptr() as *mut u8,
-                    alloc::Layout::array::<(S, T)>(self.cap).unwrap(),
+                self.alloc.deallocate(
+                    self.ptr.cast(),
+                    Layout::array::<(S, T)>(self.cap).unwrap(),
                 )
             }
         }
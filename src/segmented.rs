@@ -0,0 +1,293 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+
+use crate::{Max, Min, Order};
+use crate::sift;
+
+/// Number of elements in a segment by default.
+const SEGMENT_LEN: usize = 1024;
+
+/// A binary heap stored in fixed-size segments instead of a single array.
+/// When the queue runs out of space a new segment is allocated, and the
+/// elements are never copied to a bigger buffer, so the worst case latency
+/// of [`put`] stays bounded no matter how large the queue is. The price is a
+/// little more work to find an element by its position.
+///
+/// A single empty segment is kept around after the elements are popped, so
+/// a queue which keeps growing and shrinking over the segment boundary
+/// doesn't allocate over and over again.
+///
+/// Elements are ordered by their scores the same way as in
+/// [`PriorityQueue`], including the direction given by the [`Order`] type
+/// parameter.
+///
+/// [`PriorityQueue`]: crate::PriorityQueue
+/// [`put`]: SegmentedPriorityQueue::put
+///
+/// # Examples
+///
+/// ```
+/// use priq::SegmentedPriorityQueue;
+///
+/// let mut pq = SegmentedPriorityQueue::with_segment_len(4);
+/// (0..10).rev().for_each(|i| pq.put(i, i * 10));
+///
+/// assert_eq!(12, pq.capacity());
+/// assert_eq!(Some((0, 0)), pq.pop());
+/// assert_eq!(Some((1, 10)), pq.pop());
+/// ```
+pub struct SegmentedPriorityQueue<S, T, O = Min> {
+    /// Segments with `1 << shift` capacity each, all full except the last
+    /// ones.
+    segments: Vec<Vec<(S, T)>>,
+    shift: u32,
+    len: usize,
+    _order: PhantomData<O>,
+}
+
+impl<S, T> SegmentedPriorityQueue<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `SegmentedPriorityQueue` where the lowest score is on
+    /// top.
+    #[must_use]
+    pub fn new() -> Self {
+        SegmentedPriorityQueue::default()
+    }
+
+    /// Create an empty `SegmentedPriorityQueue` where the lowest score is on
+    /// top, with segments which hold `len` elements, rounded up to a power
+    /// of two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is zero.
+    #[must_use]
+    pub fn with_segment_len(len: usize) -> Self {
+        SegmentedPriorityQueue::empty(len)
+    }
+}
+
+impl<S, T> SegmentedPriorityQueue<S, T, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `SegmentedPriorityQueue` where the highest score is
+    /// on top.
+    #[must_use]
+    pub fn new_max() -> Self {
+        SegmentedPriorityQueue::default()
+    }
+
+    /// Create an empty `SegmentedPriorityQueue` where the highest score is
+    /// on top, with segments which hold `len` elements, rounded up to a
+    /// power of two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is zero.
+    #[must_use]
+    pub fn with_segment_len_max(len: usize) -> Self {
+        SegmentedPriorityQueue::empty(len)
+    }
+}
+
+impl<S, T, O> SegmentedPriorityQueue<S, T, O> {
+    fn empty(segment_len: usize) -> Self {
+        assert_ne!(segment_len, 0, "Segment length can't be zero");
+        SegmentedPriorityQueue {
+            segments: Vec::new(),
+            shift: segment_len.next_power_of_two().ilog2(),
+            len: 0,
+            _order: PhantomData,
+        }
+    }
+
+    /// Finds the segment and the position in it of an element.
+    #[inline]
+    fn locate(&self, index: usize) -> (usize, usize) {
+        (index >> self.shift, index & ((1 << self.shift) - 1))
+    }
+
+    #[inline]
+    fn get(&self, index: usize) -> &(S, T) {
+        let (segment, offset) = self.locate(index);
+        &self.segments[segment][offset]
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        let ((seg_a, off_a), (seg_b, off_b)) = (self.locate(a), self.locate(b));
+        if seg_a == seg_b {
+            self.segments[seg_a].swap(off_a, off_b);
+            return
+        }
+        let (lower, upper) = self.segments.split_at_mut(seg_a.max(seg_b));
+        let (low, high) = if seg_a < seg_b { (off_a, off_b) } else { (off_b, off_a) };
+        mem::swap(&mut lower[seg_a.min(seg_b)][low], &mut upper[0][high]);
+    }
+}
+
+impl<S, T, O> SegmentedPriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Inserts an element in the heap. When all the segments are full, a
+    /// new one is allocated, while the elements stay where they are.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(n))***.
+    pub fn put(&mut self, score: S, item: T) {
+        let (segment, _) = self.locate(self.len);
+        if segment == self.segments.len() {
+            self.segments.push(Vec::with_capacity(self.segment_len()));
+        }
+        self.segments[segment].push((score, item));
+        self.len += 1;
+        sift::sift_up_nodes(self, self.len - 1, 2);
+    }
+
+    /// Get the top priority element from `SegmentedPriorityQueue`.
+    ///
+    /// # Time Complexity
+    ///
+    /// Worst case is ***O(log(n))***.
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        if self.len == 0 {
+            return None
+        }
+        self.len -= 1;
+        self.swap(0, self.len);
+        let (segment, _) = self.locate(self.len);
+        let top = self.segments[segment].pop();
+        // keep a spare segment after the ones in use
+        let used = (self.len + self.segment_len() - 1) >> self.shift;
+        self.segments.truncate(used + 1);
+        if self.len > 1 { sift::sift_down_nodes(self, 0, 2); }
+        top
+    }
+
+    /// Check what is a top element in `SegmentedPriorityQueue`, by getting
+    /// the reference.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek(&self) -> Option<&(S, T)> {
+        self.segments.first()?.first()
+    }
+
+    /// Returns the number of elements the segments can hold.
+    pub fn capacity(&self) -> usize {
+        self.segments.len() << self.shift
+    }
+
+    /// Returns the number of elements a segment holds.
+    pub fn segment_len(&self) -> usize {
+        1 << self.shift
+    }
+
+    /// Returns the number of elements in the `SegmentedPriorityQueue`
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` is there are no elements in `SegmentedPriorityQueue`
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all the elements from `SegmentedPriorityQueue`, the segments
+    /// are freed too.
+    pub fn clear(&mut self) {
+        self.segments.clear();
+        self.len = 0;
+    }
+}
+
+/// Elements are addressed by their index in the heap spanning all the
+/// segments.
+impl<S, T, O> sift::Nodes for SegmentedPriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    #[inline]
+    fn count(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn before(&self, a: usize, b: usize) -> bool {
+        sift::precedes::<S, O>(&self.get(a).0, &self.get(b).0)
+    }
+
+    #[inline]
+    fn exchange(&mut self, a: usize, b: usize) {
+        self.swap(a, b);
+    }
+}
+
+impl<S, T, O> Default for SegmentedPriorityQueue<S, T, O> {
+    fn default() -> Self {
+        SegmentedPriorityQueue::empty(SEGMENT_LEN)
+    }
+}
+
+impl<S, T, O> Clone for SegmentedPriorityQueue<S, T, O>
+where
+    S: Clone,
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        let segments = self.segments.iter().map(|segment| {
+            let mut copy = Vec::with_capacity(segment.capacity());
+            copy.extend_from_slice(segment);
+            copy
+        });
+        SegmentedPriorityQueue {
+            segments: segments.collect(),
+            shift: self.shift,
+            len: self.len,
+            _order: PhantomData,
+        }
+    }
+}
+
+impl<S, T> FromIterator<(S, T)> for SegmentedPriorityQueue<S, T>
+where
+    S: PartialOrd,
+{
+    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
+        let mut pq = SegmentedPriorityQueue::new();
+        pq.extend(iter);
+        pq
+    }
+}
+
+impl<S, T, O> Extend<(S, T)> for SegmentedPriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(s, e)| self.put(s, e));
+    }
+}
+
+impl<S, T, O> fmt::Debug for SegmentedPriorityQueue<S, T, O>
+where
+    S: fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SegmentedPriorityQueue")
+         .field("segment_len", &(1_usize << self.shift))
+         .field("data", &self.segments.iter().flatten().collect::<Vec<_>>())
+         .finish()
+    }
+}
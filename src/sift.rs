@@ -0,0 +1,172 @@
+use core::cmp::Ordering;
+
+use crate::Order;
+
+/// Checks if score `lhs` should be placed closer to the root than `rhs` in
+/// a heap with the `O` order. Scores that can't be compared even with
+/// themselves (like `NAN`) are treated as greater than any comparable score,
+/// so they sink to the back.
+#[inline]
+pub(crate) fn precedes<S, O>(lhs: &S, rhs: &S) -> bool
+where
+    S: PartialOrd + ?Sized,
+    O: Order,
+{
+    precedes_by::<S, O, _>(lhs, rhs, S::partial_cmp, false)
+}
+
+/// Same as [`precedes`], but scores are compared with `cmp`, and the ones
+/// which can't be compared even with themselves go to the front instead if
+/// `nans_first` is set.
+#[inline]
+pub(crate) fn precedes_by<S, O, F>(lhs: &S, rhs: &S, cmp: F, nans_first: bool) -> bool
+where
+    S: ?Sized,
+    O: Order,
+    F: Fn(&S, &S) -> Option<Ordering>,
+{
+    match cmp(lhs, rhs) {
+        Some(ord) => ord == O::TOP,
+        None if nans_first => cmp(lhs, lhs).is_none() && cmp(rhs, rhs).is_some(),
+        None => cmp(lhs, lhs).is_some() && cmp(rhs, rhs).is_none(),
+    }
+}
+
+/// Total order of scores agreeing with [`precedes`]: the top score is
+/// `Less` than the others, and scores which can't be compared even with
+/// themselves go after all the others. Scores incomparable with each other
+/// are equal.
+pub(crate) fn order<S, O>(lhs: &S, rhs: &S) -> Ordering
+where
+    S: PartialOrd + ?Sized,
+    O: Order,
+{
+    match lhs.partial_cmp(rhs) {
+        Some(ord) if O::TOP == Ordering::Less => ord,
+        Some(ord) => ord.reverse(),
+        None => {
+            let lhs_nan = lhs.partial_cmp(lhs).is_none();
+            let rhs_nan = rhs.partial_cmp(rhs).is_none();
+            lhs_nan.cmp(&rhs_nan)
+        },
+    }
+}
+
+/// An implicit heap addressed by the indexes of its elements, for heaps
+/// which keep track of their elements while they are moved around.
+pub(crate) trait Nodes {
+    /// Returns the number of elements in the heap.
+    fn count(&self) -> usize;
+
+    /// Checks if the element at `a` should be placed closer to the root than
+    /// the one at `b`.
+    fn before(&self, a: usize, b: usize) -> bool;
+
+    /// Swaps the elements at `a` and `b`.
+    fn exchange(&mut self, a: usize, b: usize);
+}
+
+/// Elements of a slice ordered by a predicate.
+struct Slice<'a, E, F> {
+    data: &'a mut [E],
+    less: F,
+}
+
+impl<E, F> Nodes for Slice<'_, E, F>
+where
+    F: Fn(&E, &E) -> bool,
+{
+    #[inline]
+    fn count(&self) -> usize {
+        self.data.len()
+    }
+
+    #[inline]
+    fn before(&self, a: usize, b: usize) -> bool {
+        (self.less)(&self.data[a], &self.data[b])
+    }
+
+    #[inline]
+    fn exchange(&mut self, a: usize, b: usize) {
+        self.data.swap(a, b);
+    }
+}
+
+/// Moves the element at `index` of an implicit heap with `arity` children
+/// per node up, while it precedes its parent.
+pub(crate) fn sift_up<E, F>(data: &mut [E], index: usize, arity: usize, less: F)
+where
+    F: Fn(&E, &E) -> bool,
+{
+    sift_up_nodes(&mut Slice { data, less }, index, arity);
+}
+
+/// Moves the element at `index` of an implicit heap with `arity` children
+/// per node down, while any of its children precedes it.
+pub(crate) fn sift_down<E, F>(data: &mut [E], index: usize, arity: usize, less: F)
+where
+    F: Fn(&E, &E) -> bool,
+{
+    sift_down_nodes(&mut Slice { data, less }, index, arity);
+}
+
+/// Restores the heap order of the whole array from the bottom up.
+///
+/// # Time Complexity
+///
+/// ***O(n)***
+pub(crate) fn heapify<E, F>(data: &mut [E], arity: usize, less: F)
+where
+    F: Fn(&E, &E) -> bool,
+{
+    heapify_nodes(&mut Slice { data, less }, arity);
+}
+
+/// Same as [`sift_up`] for a heap addressed by indexes.
+pub(crate) fn sift_up_nodes<H>(heap: &mut H, mut index: usize, arity: usize)
+where
+    H: Nodes + ?Sized,
+{
+    while index > 0 {
+        let parent_ = (index - 1) / arity;
+        if !heap.before(index, parent_) {
+            break
+        }
+        heap.exchange(parent_, index);
+        index = parent_;
+    }
+}
+
+/// Same as [`sift_down`] for a heap addressed by indexes.
+pub(crate) fn sift_down_nodes<H>(heap: &mut H, mut index: usize, arity: usize)
+where
+    H: Nodes + ?Sized,
+{
+    let len = heap.count();
+    loop {
+        let first = arity.saturating_mul(index).saturating_add(1);
+        let last = first.saturating_add(arity).min(len);
+        let mut min_ = index;
+        for child in first..last {
+            if heap.before(child, min_) {
+                min_ = child;
+            }
+        }
+        if min_ == index {
+            break
+        }
+        heap.exchange(index, min_);
+        index = min_;
+    }
+}
+
+/// Same as [`heapify`] for a heap addressed by indexes.
+pub(crate) fn heapify_nodes<H>(heap: &mut H, arity: usize)
+where
+    H: Nodes + ?Sized,
+{
+    let len = heap.count();
+    if len > 1 {
+        (0..=(len - 2) / arity).rev().for_each(|i| sift_down_nodes(heap, i, arity));
+    }
+}
@@ -0,0 +1,113 @@
+use alloc::vec::Vec;
+
+use crate::Handle;
+
+/// Marks an absent link between the nodes of a slab.
+pub(crate) const NIL: usize = usize::MAX;
+
+#[derive(Clone)]
+struct Slot<N> {
+    gen: u32,
+    node: Option<N>,
+}
+
+/// Storage for the nodes of tree based heaps. Nodes link each other by
+/// their indices in the slab and slots of the removed nodes are reused, so
+/// there is no allocation per node once the slab has grown.
+#[derive(Clone)]
+pub(crate) struct Slab<N> {
+    slots: Vec<Slot<N>>,
+    free: Vec<usize>,
+}
+
+impl<N> Slab<N> {
+    pub(crate) fn new() -> Self {
+        Slab { slots: Vec::new(), free: Vec::new() }
+    }
+
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        self.slots.reserve(additional.saturating_sub(self.free.len()));
+    }
+
+    pub(crate) fn reserve_exact(&mut self, additional: usize) {
+        self.slots.reserve_exact(additional.saturating_sub(self.free.len()));
+    }
+
+    /// Stores the node and returns its handle.
+    pub(crate) fn insert(&mut self, node: N) -> Handle {
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.slots[index].node = Some(node);
+                index
+            },
+            None => {
+                self.slots.push(Slot { gen: 0, node: Some(node) });
+                self.slots.len() - 1
+            },
+        };
+        Handle { index, gen: self.slots[index].gen }
+    }
+
+    /// Returns the node of the handle if it was not removed yet.
+    pub(crate) fn get(&self, handle: Handle) -> Option<&N> {
+        self.slots.get(handle.index)
+            .filter(|slot| slot.gen == handle.gen)
+            .and_then(|slot| slot.node.as_ref())
+    }
+
+    pub(crate) fn node(&self, index: usize) -> &N {
+        self.slots[index].node.as_ref().expect("node is linked")
+    }
+
+    pub(crate) fn node_mut(&mut self, index: usize) -> &mut N {
+        self.slots[index].node.as_mut().expect("node is linked")
+    }
+
+    /// Takes the node out, invalidating its handle.
+    pub(crate) fn remove(&mut self, index: usize) -> N {
+        let slot = &mut self.slots[index];
+        slot.gen = slot.gen.wrapping_add(1);
+        self.free.push(index);
+        slot.node.take().expect("node is linked")
+    }
+
+    /// Removes all the nodes, invalidating all the handles.
+    pub(crate) fn clear(&mut self) {
+        self.free.clear();
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if slot.node.take().is_some() {
+                slot.gen = slot.gen.wrapping_add(1);
+            }
+            self.free.push(index);
+        }
+    }
+
+    /// Moves all the nodes of `other` to the end of this slab, leaving
+    /// `other` empty. Indices of the moved nodes grow by the returned
+    /// offset, and `relink` should shift the links of every moved node.
+    pub(crate) fn append<F>(&mut self, other: &mut Self, mut relink: F) -> usize
+    where
+        F: FnMut(&mut N, usize),
+    {
+        let offset = self.slots.len();
+        self.slots.extend(other.slots.drain(..).map(|mut slot| {
+            if let Some(node) = &mut slot.node {
+                relink(node, offset);
+            }
+            slot
+        }));
+        self.free.extend(other.free.drain(..).map(|index| index + offset));
+        offset
+    }
+
+    /// Iterates over all the stored nodes in the slab order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &N> {
+        self.slots.iter().filter_map(|slot| slot.node.as_ref())
+    }
+}
+
+/// Shifts a link of the node moved by [`Slab::append`].
+#[inline]
+pub(crate) fn shift(link: usize, offset: usize) -> usize {
+    if link == NIL { NIL } else { link + offset }
+}
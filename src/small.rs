@@ -0,0 +1,367 @@
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::{self, MaybeUninit};
+use core::ops::Deref;
+use core::ptr;
+use core::slice;
+use alloc::vec::Vec;
+
+use crate::{Max, Min, Order};
+use crate::sift;
+
+/// A binary heap which keeps up to `N` elements inline, in the struct
+/// itself, and allocates only when it grows beyond that. Queues which
+/// usually hold a handful of elements never touch the allocator, at the
+/// cost of a larger struct.
+///
+/// Once spilled to the heap the queue stays there, even if it shrinks, so
+/// it doesn't move the elements back and forth around `N`;
+/// [`shrink_to_fit`] brings it back inline when the elements fit.
+///
+/// Elements are ordered by their scores the same way as in
+/// [`PriorityQueue`], including the direction given by the [`Order`] type
+/// parameter and incomparable scores sinking to the back.
+///
+/// [`shrink_to_fit`]: SmallPriorityQueue::shrink_to_fit
+/// [`PriorityQueue`]: crate::PriorityQueue
+///
+/// # Examples
+///
+/// ```
+/// use priq::SmallPriorityQueue;
+///
+/// let mut pq: SmallPriorityQueue<u32, &str, 4> = SmallPriorityQueue::new();
+/// pq.put(3, "c");
+/// pq.put(1, "a");
+/// pq.put(2, "b");
+/// assert!(!pq.spilled());
+///
+/// assert_eq!(Some((1, "a")), pq.pop());
+/// assert_eq!(Some((2, "b")), pq.pop());
+/// assert_eq!(Some((3, "c")), pq.pop());
+/// ```
+pub struct SmallPriorityQueue<S, T, const N: usize, O = Min> {
+    data: Data<S, T, N>,
+    _order: PhantomData<O>,
+}
+
+enum Data<S, T, const N: usize> {
+    /// First `len` elements of `buf` are initialized.
+    Inline { buf: [MaybeUninit<(S, T)>; N], len: usize },
+    Spilled(Vec<(S, T)>),
+}
+
+impl<S, T, const N: usize> Data<S, T, N> {
+    const EMPTY: Self = Data::Inline { buf: [const { MaybeUninit::uninit() }; N], len: 0 };
+}
+
+impl<S, T, const N: usize> SmallPriorityQueue<S, T, N>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `SmallPriorityQueue` where the lowest score is on
+    /// top.
+    #[must_use]
+    pub fn new() -> Self {
+        SmallPriorityQueue::default()
+    }
+}
+
+impl<S, T, const N: usize> SmallPriorityQueue<S, T, N, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `SmallPriorityQueue` where the highest score is on
+    /// top.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::SmallPriorityQueue;
+    ///
+    /// let mut pq: SmallPriorityQueue<u32, char, 8, _> = SmallPriorityQueue::new_max();
+    /// pq.extend([(1, 'a'), (3, 'c'), (2, 'b')]);
+    ///
+    /// assert_eq!(Some((3, 'c')), pq.pop());
+    /// ```
+    #[must_use]
+    pub fn new_max() -> Self {
+        SmallPriorityQueue::default()
+    }
+}
+
+impl<S, T, const N: usize, O> SmallPriorityQueue<S, T, N, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Inserts an element in the heap, moving all the elements to the heap
+    /// memory if there are already `N` of them inline.
+    ///
+    /// # Panics
+    ///
+    /// Panics if new capacity overflows `usize`.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(n))***, or ***O(N)*** when the
+    /// elements are moved.
+    pub fn put(&mut self, score: S, item: T) {
+        self.push((score, item));
+        self.heapify_up(self.len() - 1);
+    }
+
+    /// Get the top priority element from `SmallPriorityQueue`.
+    ///
+    /// # Time Complexity
+    ///
+    /// Worst case is ***O(log(n))***.
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        let len = self.len();
+        if len == 0 {
+            return None
+        }
+        self.as_mut_slice().swap(0, len - 1);
+        let top = self.pop_last();
+        if self.len() > 1 { self.heapify_down(0); }
+        top
+    }
+
+    /// Check what is a top element in `SmallPriorityQueue`, by getting the
+    /// reference.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek(&self) -> Option<&(S, T)> {
+        self.as_slice().first()
+    }
+
+    /// Returns the number of elements in the `SmallPriorityQueue`
+    pub fn len(&self) -> usize {
+        match &self.data {
+            Data::Inline { len, .. } => *len,
+            Data::Spilled(vec) => vec.len(),
+        }
+    }
+
+    /// Returns `true` is there are no elements in `SmallPriorityQueue`
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the elements were moved out of the struct to the
+    /// heap memory.
+    pub fn spilled(&self) -> bool {
+        matches!(self.data, Data::Spilled(_))
+    }
+
+    /// Returns the number of elements the queue can hold without
+    /// reallocating, which is `N` until it spills.
+    pub fn capacity(&self) -> usize {
+        match &self.data {
+            Data::Inline { .. } => N,
+            Data::Spilled(vec) => vec.capacity(),
+        }
+    }
+
+    /// Remove all the elements from `SmallPriorityQueue`, keeping the
+    /// allocated memory if it was spilled.
+    pub fn clear(&mut self) {
+        match &mut self.data {
+            Data::Inline { buf, len } => {
+                let elems = ptr::slice_from_raw_parts_mut(buf.as_mut_ptr().cast::<(S, T)>(), *len);
+                // Set first, so a panicking destructor can't cause a double drop.
+                *len = 0;
+                // SAFETY: the first `len` elements were initialized and are
+                // no longer reachable.
+                unsafe { ptr::drop_in_place(elems) }
+            }
+            Data::Spilled(vec) => vec.clear(),
+        }
+    }
+
+    /// Moves the elements back inline if there are at most `N` of them,
+    /// otherwise shrinks the allocated memory to fit them.
+    pub fn shrink_to_fit(&mut self) {
+        if let Data::Spilled(vec) = &mut self.data {
+            if vec.len() > N {
+                vec.shrink_to_fit();
+                return
+            }
+            let vec = mem::take(vec);
+            self.data = Data::EMPTY;
+            vec.into_iter().for_each(|elem| self.push(elem));
+        }
+    }
+
+    /// Consumes the heap and returns the elements in the heap order (NOT
+    /// sorted).
+    pub fn into_vec(mut self) -> Vec<(S, T)> {
+        match mem::replace(&mut self.data, Data::EMPTY) {
+            Data::Inline { buf, len } => {
+                buf.into_iter()
+                   .take(len)
+                   // SAFETY: the first `len` elements were initialized.
+                   .map(|elem| unsafe { elem.assume_init() })
+                   .collect()
+            }
+            Data::Spilled(vec) => vec,
+        }
+    }
+
+    /// Appends the element after the last one, spilling if the inline
+    /// buffer is full.
+    fn push(&mut self, elem: (S, T)) {
+        match &mut self.data {
+            Data::Inline { buf, len } if *len < N => {
+                buf[*len].write(elem);
+                *len += 1;
+            }
+            Data::Inline { buf, len } => {
+                let mut vec = Vec::with_capacity((2 * N).max(4));
+                let moved = mem::replace(buf, [const { MaybeUninit::uninit() }; N]);
+                // SAFETY: the buffer is full and its elements were moved out.
+                vec.extend(moved.into_iter().map(|elem| unsafe { elem.assume_init() }));
+                *len = 0;
+                vec.push(elem);
+                self.data = Data::Spilled(vec);
+            }
+            Data::Spilled(vec) => vec.push(elem),
+        }
+    }
+
+    /// Removes the last element.
+    fn pop_last(&mut self) -> Option<(S, T)> {
+        match &mut self.data {
+            Data::Inline { len: 0, .. } => None,
+            Data::Inline { buf, len } => {
+                *len -= 1;
+                // SAFETY: the element was initialized and is now past `len`.
+                Some(unsafe { buf[*len].assume_init_read() })
+            }
+            Data::Spilled(vec) => vec.pop(),
+        }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [(S, T)] {
+        match &mut self.data {
+            // SAFETY: the first `len` elements are initialized.
+            Data::Inline { buf, len } => unsafe {
+                slice::from_raw_parts_mut(buf.as_mut_ptr().cast(), *len)
+            },
+            Data::Spilled(vec) => vec,
+        }
+    }
+
+    /// Checks if element `lhs` should be placed closer to the root than `rhs`.
+    #[inline]
+    fn less(lhs: &(S, T), rhs: &(S, T)) -> bool {
+        sift::precedes::<S, O>(&lhs.0, &rhs.0)
+    }
+
+    fn heapify_up(&mut self, index: usize) {
+        sift::sift_up(self.as_mut_slice(), index, 2, Self::less);
+    }
+
+    fn heapify_down(&mut self, index: usize) {
+        sift::sift_down(self.as_mut_slice(), index, 2, Self::less);
+    }
+}
+
+impl<S, T, const N: usize, O> SmallPriorityQueue<S, T, N, O> {
+    fn as_slice(&self) -> &[(S, T)] {
+        match &self.data {
+            // SAFETY: the first `len` elements are initialized.
+            Data::Inline { buf, len } => unsafe {
+                slice::from_raw_parts(buf.as_ptr().cast(), *len)
+            },
+            Data::Spilled(vec) => vec,
+        }
+    }
+}
+
+impl<S, T, const N: usize, O> Drop for SmallPriorityQueue<S, T, N, O> {
+    fn drop(&mut self) {
+        if let Data::Inline { buf, len } = &mut self.data {
+            // SAFETY: the first `len` elements are initialized and dropped
+            // only here.
+            unsafe {
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                    buf.as_mut_ptr().cast::<(S, T)>(), *len));
+            }
+        }
+    }
+}
+
+impl<S, T, const N: usize, O> Default for SmallPriorityQueue<S, T, N, O> {
+    fn default() -> Self {
+        SmallPriorityQueue { data: Data::EMPTY, _order: PhantomData }
+    }
+}
+
+impl<S, T, const N: usize, O> Deref for SmallPriorityQueue<S, T, N, O> {
+    type Target = [(S, T)];
+
+    fn deref(&self) -> &[(S, T)] {
+        self.as_slice()
+    }
+}
+
+impl<S, T, const N: usize> FromIterator<(S, T)> for SmallPriorityQueue<S, T, N>
+where
+    S: PartialOrd,
+{
+    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
+        let mut pq = SmallPriorityQueue::new();
+        pq.extend(iter);
+        pq
+    }
+}
+
+impl<S, T, const N: usize, O> Extend<(S, T)> for SmallPriorityQueue<S, T, N, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(s, e)| self.put(s, e));
+    }
+}
+
+impl<S, T, const N: usize, O> Clone for SmallPriorityQueue<S, T, N, O>
+where
+    S: Clone,
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        let data = match &self.data {
+            Data::Inline { len, .. } => {
+                let mut buf = [const { MaybeUninit::uninit() }; N];
+                let mut cloned = 0;
+                for (slot, elem) in buf.iter_mut().zip(self.as_slice()) {
+                    slot.write(elem.clone());
+                    cloned += 1;
+                }
+                debug_assert_eq!(*len, cloned);
+                Data::Inline { buf, len: cloned }
+            }
+            Data::Spilled(vec) => Data::Spilled(vec.clone()),
+        };
+        SmallPriorityQueue { data, _order: PhantomData }
+    }
+}
+
+impl<S, T, const N: usize, O> fmt::Debug for SmallPriorityQueue<S, T, N, O>
+where
+    S: fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SmallPriorityQueue")
+         .field("inline", &N)
+         .field("spilled", &matches!(self.data, Data::Spilled(_)))
+         .field("data", &self.as_slice())
+         .finish()
+    }
+}
@@ -0,0 +1,257 @@
+use std::io::{self, Read, Write};
+
+use crate::{Order, PriorityQueue};
+
+/// The bytes every snapshot starts with.
+const MAGIC: [u8; 4] = *b"PRIQ";
+
+/// Version of the snapshot format, bumped whenever the layout changes.
+const VERSION: u8 = 1;
+
+/// How many elements are reserved up-front while loading, so a corrupted
+/// length doesn't make the queue allocate a huge buffer.
+const MAX_PREALLOC: usize = 1 << 16;
+
+/// Types which can be written to and read from a queue snapshot, see
+/// [`PriorityQueue::save_to`] and [`PriorityQueue::load_from`].
+///
+/// Integers and floats are stored as little-endian bytes, `bool` as a single
+/// byte, while `char` and `String` are stored as UTF-8.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{self, Read, Write};
+/// use priq::{Persist, PriorityQueue};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Job(u16);
+///
+/// impl Persist for Job {
+///     fn write_to<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+///         self.0.write_to(writer)
+///     }
+///
+///     fn read_from<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+///         u16::read_from(reader).map(Job)
+///     }
+/// }
+///
+/// let mut pq = PriorityQueue::new();
+/// pq.put(2, Job(7));
+///
+/// let mut buf = Vec::new();
+/// pq.save_to(&mut buf).unwrap();
+/// let mut pq: PriorityQueue<i32, Job> = PriorityQueue::load_from(&buf[..]).unwrap();
+/// assert_eq!(Some((2, Job(7))), pq.pop());
+/// ```
+pub trait Persist: Sized {
+    /// Writes the value to the `writer`.
+    fn write_to<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()>;
+
+    /// Reads a value previously written with [`write_to`] from the `reader`.
+    ///
+    /// [`write_to`]: Persist::write_to
+    fn read_from<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self>;
+}
+
+macro_rules! impl_persist_num {
+    ($($t:ty),*) => {$(
+        impl Persist for $t {
+            #[inline]
+            fn write_to<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+                writer.write_all(&self.to_le_bytes())
+            }
+
+            #[inline]
+            fn read_from<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+                let mut buf = [0; std::mem::size_of::<$t>()];
+                reader.read_exact(&mut buf)?;
+                Ok(<$t>::from_le_bytes(buf))
+            }
+        }
+    )*};
+}
+
+impl_persist_num!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl Persist for usize {
+    fn write_to<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as u64).write_to(writer)
+    }
+
+    fn read_from<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        usize::try_from(u64::read_from(reader)?).map_err(invalid)
+    }
+}
+
+impl Persist for isize {
+    fn write_to<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as i64).write_to(writer)
+    }
+
+    fn read_from<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        isize::try_from(i64::read_from(reader)?).map_err(invalid)
+    }
+}
+
+impl Persist for bool {
+    fn write_to<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as u8).write_to(writer)
+    }
+
+    fn read_from<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        match u8::read_from(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid("invalid bool")),
+        }
+    }
+}
+
+impl Persist for char {
+    fn write_to<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as u32).write_to(writer)
+    }
+
+    fn read_from<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        char::from_u32(u32::read_from(reader)?).ok_or_else(|| invalid("invalid char"))
+    }
+}
+
+impl Persist for () {
+    fn write_to<W: Write + ?Sized>(&self, _writer: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn read_from<R: Read + ?Sized>(_reader: &mut R) -> io::Result<Self> {
+        Ok(())
+    }
+}
+
+impl Persist for String {
+    fn write_to<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        self.len().write_to(writer)?;
+        writer.write_all(self.as_bytes())
+    }
+
+    fn read_from<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        let len = usize::read_from(reader)?;
+        let mut buf = Vec::with_capacity(len.min(MAX_PREALLOC));
+        reader.take(len as u64).read_to_end(&mut buf)?;
+        if buf.len() != len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        String::from_utf8(buf).map_err(invalid)
+    }
+}
+
+impl<S, T, O> PriorityQueue<S, T, O>
+where
+    S: PartialOrd + Persist,
+    T: Persist,
+    O: Order,
+{
+    /// Writes a snapshot of the priority queue to the `writer`, which can be
+    /// loaded back with [`load_from`].
+    ///
+    /// The snapshot is a small header with the format version, followed by
+    /// the number of elements and then the score and item of each element in
+    /// the order of the underlying array. Settings like a custom comparator,
+    /// [`NanPolicy`] or growth policy are not saved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::PriorityQueue;
+    ///
+    /// let pq: PriorityQueue<u32, String> =
+    ///     (0..10).map(|i| (i, i.to_string())).collect();
+    ///
+    /// let mut buf = Vec::new();
+    /// pq.save_to(&mut buf).unwrap();
+    ///
+    /// let mut pq: PriorityQueue<u32, String> =
+    ///     PriorityQueue::load_from(&buf[..]).unwrap();
+    /// assert_eq!(10, pq.len());
+    /// assert_eq!(Some((0, "0".to_string())), pq.pop());
+    /// ```
+    ///
+    /// The writer is not buffered nor flushed, so wrap files in a
+    /// [`BufWriter`] and sync them as needed.
+    ///
+    /// [`load_from`]: PriorityQueue::load_from
+    /// [`NanPolicy`]: crate::NanPolicy
+    /// [`BufWriter`]: std::io::BufWriter
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)***
+    pub fn save_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&MAGIC)?;
+        VERSION.write_to(&mut writer)?;
+        self.len().write_to(&mut writer)?;
+        for (score, item) in self.iter() {
+            score.write_to(&mut writer)?;
+            item.write_to(&mut writer)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a priority queue from a snapshot written by [`save_to`].
+    ///
+    /// Elements are put back in the saved layout of the array, so they are
+    /// popped in the same order as from the saved queue, even the ones with
+    /// equal or incomparable scores. The heap is rebuilt only if the snapshot
+    /// doesn't hold a valid one, e.g. when it was saved with another order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of [`InvalidData`] kind if the snapshot has an
+    /// unknown format or version or any element can't be decoded, and
+    /// [`UnexpectedEof`] if the snapshot is truncated.
+    ///
+    /// [`save_to`]: PriorityQueue::save_to
+    /// [`InvalidData`]: std::io::ErrorKind::InvalidData
+    /// [`UnexpectedEof`]: std::io::ErrorKind::UnexpectedEof
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)***
+    pub fn load_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(invalid("not a priority queue snapshot"));
+        }
+        let version = u8::read_from(&mut reader)?;
+        if version != VERSION {
+            return Err(invalid(format!("unsupported snapshot version {}", version)));
+        }
+
+        let len = usize::read_from(&mut reader)?;
+        let mut data = Vec::with_capacity(len.min(MAX_PREALLOC));
+        for _ in 0..len {
+            let score = S::read_from(&mut reader)?;
+            let item = T::read_from(&mut reader)?;
+            data.push((score, item));
+        }
+
+        let mut pq = PriorityQueue::default();
+        pq.reserve(data.len());
+        // comparable scores are saved before the quarantined incomparable
+        // ones, so pushing them in order restores both parts as they were
+        data.into_iter().for_each(|elem| pq.push(elem));
+        if !pq.is_heap() {
+            pq.rebuild();
+        }
+        Ok(pq)
+    }
+}
+
+fn invalid<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
@@ -0,0 +1,274 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::{Max, Min, Order};
+use crate::sift;
+
+/// A priority queue which keeps scores apart from items, for large items.
+///
+/// The heap is made only of scores and the indices of their items, kept
+/// in two parallel arrays. Items are stored once in a separate buffer and
+/// never moved until they are popped. Sifting compares and moves only the
+/// small, cache-dense scores, so put and pop cost the same for any size of
+/// items.
+///
+/// Elements are ordered by their scores the same way as in
+/// [`PriorityQueue`], including the direction given by the [`Order`] type
+/// parameter.
+///
+/// [`PriorityQueue`]: crate::PriorityQueue
+///
+/// # Examples
+///
+/// ```
+/// use priq::SoaPriorityQueue;
+///
+/// let mut pq = SoaPriorityQueue::new();
+/// pq.put(2, [2u8; 4096]);
+/// pq.put(1, [1u8; 4096]);
+///
+/// assert_eq!(Some(&1), pq.peek_score());
+/// let (score, page) = pq.pop().unwrap();
+/// assert_eq!((1, 1), (score, page[0]));
+/// ```
+pub struct SoaPriorityQueue<S, T, O = Min> {
+    scores: Vec<S>,
+    /// Index in `items` of the item of every score.
+    slots: Vec<usize>,
+    items: Vec<Option<T>>,
+    free: Vec<usize>,
+    _order: PhantomData<O>,
+}
+
+impl<S, T> SoaPriorityQueue<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `SoaPriorityQueue` where the lowest score is on top.
+    #[must_use]
+    pub fn new() -> Self {
+        SoaPriorityQueue::default()
+    }
+
+    /// Create an empty `SoaPriorityQueue` with space for at least `cap`
+    /// elements.
+    #[must_use]
+    pub fn with_capacity(cap: usize) -> Self {
+        SoaPriorityQueue {
+            scores: Vec::with_capacity(cap),
+            slots: Vec::with_capacity(cap),
+            items: Vec::with_capacity(cap),
+            free: Vec::new(),
+            _order: PhantomData,
+        }
+    }
+}
+
+impl<S, T> SoaPriorityQueue<S, T, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `SoaPriorityQueue` where the highest score is on
+    /// top.
+    #[must_use]
+    pub fn new_max() -> Self {
+        SoaPriorityQueue::default()
+    }
+}
+
+impl<S, T, O> SoaPriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Inserts an element in the heap. Only the score is moved while the
+    /// heap order is restored.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(n))***.
+    pub fn put(&mut self, score: S, item: T) {
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.items[slot] = Some(item);
+                slot
+            },
+            None => {
+                self.items.push(Some(item));
+                self.items.len() - 1
+            },
+        };
+        self.scores.push(score);
+        self.slots.push(slot);
+        sift::sift_up_nodes(self, self.scores.len() - 1, 2);
+    }
+
+    /// Get the top priority element from `SoaPriorityQueue`.
+    ///
+    /// # Time Complexity
+    ///
+    /// Worst case is ***O(log(n))***.
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        if self.scores.is_empty() {
+            return None
+        }
+        let score = self.scores.swap_remove(0);
+        let slot = self.slots.swap_remove(0);
+        if self.scores.len() > 1 { sift::sift_down_nodes(self, 0, 2); }
+        let item = self.items[slot].take().expect("item of a score is stored");
+        if self.scores.is_empty() {
+            self.items.clear();
+            self.free.clear();
+        } else {
+            self.free.push(slot);
+        }
+        Some((score, item))
+    }
+
+    /// Check what is a top element in `SoaPriorityQueue`, by getting the
+    /// references to its score and item.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek(&self) -> Option<(&S, &T)> {
+        let score = self.scores.first()?;
+        Some((score, self.item(0)))
+    }
+
+    /// Get the reference to the score of a top element.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek_score(&self) -> Option<&S> {
+        self.scores.first()
+    }
+
+    /// Returns the scores in the heap order (NOT sorted).
+    pub fn scores(&self) -> &[S] {
+        &self.scores
+    }
+
+    /// Returns the number of elements in the `SoaPriorityQueue`
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    /// Returns `true` is there are no elements in `SoaPriorityQueue`
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    /// Remove all the elements from `SoaPriorityQueue`
+    pub fn clear(&mut self) {
+        self.scores.clear();
+        self.slots.clear();
+        self.items.clear();
+        self.free.clear();
+    }
+
+    #[inline]
+    fn swap(&mut self, a: usize, b: usize) {
+        self.scores.swap(a, b);
+        self.slots.swap(a, b);
+    }
+}
+
+/// Elements are addressed by the index of their score, and the slots of their
+/// items are moved along.
+impl<S, T, O> sift::Nodes for SoaPriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    #[inline]
+    fn count(&self) -> usize {
+        self.scores.len()
+    }
+
+    #[inline]
+    fn before(&self, a: usize, b: usize) -> bool {
+        sift::precedes::<S, O>(&self.scores[a], &self.scores[b])
+    }
+
+    #[inline]
+    fn exchange(&mut self, a: usize, b: usize) {
+        self.swap(a, b);
+    }
+}
+
+impl<S, T, O> SoaPriorityQueue<S, T, O> {
+    /// Returns the item of the score at the given position in the heap.
+    #[inline]
+    fn item(&self, index: usize) -> &T {
+        self.items[self.slots[index]].as_ref().expect("item of a score is stored")
+    }
+}
+
+impl<S, T, O> Default for SoaPriorityQueue<S, T, O> {
+    fn default() -> Self {
+        SoaPriorityQueue {
+            scores: Vec::new(),
+            slots: Vec::new(),
+            items: Vec::new(),
+            free: Vec::new(),
+            _order: PhantomData,
+        }
+    }
+}
+
+impl<S, T, O> Clone for SoaPriorityQueue<S, T, O>
+where
+    S: Clone,
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        SoaPriorityQueue {
+            scores: self.scores.clone(),
+            slots: self.slots.clone(),
+            items: self.items.clone(),
+            free: self.free.clone(),
+            _order: PhantomData,
+        }
+    }
+}
+
+impl<S, T> FromIterator<(S, T)> for SoaPriorityQueue<S, T>
+where
+    S: PartialOrd,
+{
+    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
+        let mut pq = SoaPriorityQueue::new();
+        pq.extend(iter);
+        pq
+    }
+}
+
+impl<S, T, O> Extend<(S, T)> for SoaPriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        self.scores.reserve(lower);
+        self.slots.reserve(lower);
+        iter.for_each(|(s, e)| self.put(s, e));
+    }
+}
+
+impl<S, T, O> fmt::Debug for SoaPriorityQueue<S, T, O>
+where
+    S: fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = self.scores.iter().enumerate().map(|(i, s)| (s, self.item(i)));
+        f.debug_struct("SoaPriorityQueue")
+         .field("data", &data.collect::<Vec<_>>())
+         .finish()
+    }
+}
@@ -0,0 +1,316 @@
+use alloc::boxed::Box;
+use alloc::collections::LinkedList;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+
+use crate::{float, Max, Min, Order};
+use crate::sift;
+
+/// Node of a soft heap tree, holding a list of elements which all go
+/// before its common key.
+#[derive(Clone)]
+struct Node<S, T> {
+    /// Common key of the elements, not before any of their scores.
+    ckey: S,
+    rank: u32,
+    /// Number of elements the list is refilled to from the children.
+    size: usize,
+    list: LinkedList<(S, T)>,
+    left: Option<Box<Node<S, T>>>,
+    right: Option<Box<Node<S, T>>>,
+}
+
+impl<S, T> Node<S, T> {
+    fn is_leaf(&self) -> bool {
+        self.left.is_none() && self.right.is_none()
+    }
+}
+
+/// A soft heap, which trades exactness for speed: some elements get
+/// *corrupted*, which means their score is raised to the common key of
+/// other elements they are stored with, so they can be popped later than
+/// they should. In exchange, putting takes amortized ***O(1)*** and popping
+/// amortized ***O(log(1/ε))***, no matter how many elements there are.
+///
+/// The corruption rate `ε` bounds how many elements can be corrupted: at
+/// most `ε * n` of the elements in the heap at any time, where `n` is the
+/// number of elements ever put. That makes it the tool for approximate
+/// selection, e.g. finding an element which is close to the median in
+/// linear time, and for minimum spanning tree algorithms. A small enough
+/// `ε` makes it an exact heap.
+///
+/// Elements are ordered by their scores the same way as in
+/// [`PriorityQueue`], including the direction given by the [`Order`] type
+/// parameter. Scores have to be [`Clone`], as a common key is a copy of a
+/// score.
+///
+/// [`PriorityQueue`]: crate::PriorityQueue
+///
+/// # Examples
+///
+/// ```
+/// use priq::SoftHeap;
+///
+/// let mut pq = SoftHeap::new(0.25);
+/// (0..100).rev().for_each(|i| pq.put(i, i));
+///
+/// // popped elements are roughly in order
+/// let first: Vec<_> = (0..10).filter_map(|_| pq.pop()).map(|(s, _)| s).collect();
+/// assert!(first.iter().all(|&s| s < 50));
+/// ```
+#[derive(Clone)]
+pub struct SoftHeap<S, T, O = Min> {
+    /// Trees in the order of decreasing ranks, no two of the same rank.
+    roots: Vec<Box<Node<S, T>>>,
+    /// Position of the root with the top common key among the roots up to
+    /// the same position.
+    best: Vec<usize>,
+    len: usize,
+    epsilon: f64,
+    /// Rank up to which nodes hold a single element, so aren't corrupted.
+    threshold: u32,
+    _order: PhantomData<O>,
+}
+
+impl<S, T> SoftHeap<S, T>
+where
+    S: PartialOrd + Clone,
+{
+    /// Create an empty `SoftHeap` where the lowest score is on top, with
+    /// the given corruption rate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the corruption rate is not between `0.0` and `1.0`.
+    #[must_use]
+    pub fn new(epsilon: f64) -> Self {
+        SoftHeap::with_epsilon(epsilon)
+    }
+}
+
+impl<S, T> SoftHeap<S, T, Max>
+where
+    S: PartialOrd + Clone,
+{
+    /// Create an empty `SoftHeap` where the highest score is on top, with
+    /// the given corruption rate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the corruption rate is not between `0.0` and `1.0`.
+    #[must_use]
+    pub fn new_max(epsilon: f64) -> Self {
+        SoftHeap::with_epsilon(epsilon)
+    }
+}
+
+impl<S, T, O> SoftHeap<S, T, O>
+where
+    S: PartialOrd + Clone,
+    O: Order,
+{
+    fn with_epsilon(epsilon: f64) -> Self {
+        assert!(epsilon > 0.0 && epsilon < 1.0, "corruption rate should be in (0, 1)");
+        SoftHeap {
+            roots: Vec::new(),
+            best: Vec::new(),
+            len: 0,
+            epsilon,
+            threshold: float::log2_ceil(3.0 / epsilon),
+            _order: PhantomData,
+        }
+    }
+
+    /// Inserts an element in the heap.
+    ///
+    /// # Time Complexity
+    ///
+    /// Amortized ***O(1)***, worst case is ***O(log(n))***.
+    pub fn put(&mut self, score: S, item: T) {
+        let mut list = LinkedList::new();
+        let ckey = score.clone();
+        list.push_back((score, item));
+        self.roots.push(Box::new(Node { ckey, rank: 0, size: 1, list, left: None, right: None }));
+        while let [.., a, b] = &self.roots[..] {
+            if a.rank != b.rank {
+                break
+            }
+            let b = self.roots.pop().expect("there are two roots");
+            let a = self.roots.pop().expect("there are two roots");
+            let tree = self.combine(a, b);
+            self.roots.push(tree);
+        }
+        self.len += 1;
+        self.update_best(self.roots.len() - 1);
+    }
+
+    /// Get an element from the top of `SoftHeap`, the one with the top
+    /// common key. The element is returned with its original score.
+    ///
+    /// # Time Complexity
+    ///
+    /// Amortized ***O(log(1/ε))***.
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        let top = *self.best.last()?;
+        let root = &mut self.roots[top];
+        let elem = root.list.pop_back().expect("root holds elements");
+        if root.list.len() * 2 <= root.size {
+            if !root.is_leaf() {
+                Self::sift(root);
+                self.update_best(top);
+            } else if root.list.is_empty() {
+                self.roots.remove(top);
+                self.update_best(top);
+            }
+        }
+        self.len -= 1;
+        Some(elem)
+    }
+
+    /// Check what is an element on the top of `SoftHeap`, the next one to
+    /// be popped, by getting the reference.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek(&self) -> Option<&(S, T)> {
+        self.roots[*self.best.last()?].list.back()
+    }
+
+    /// Meld two heaps with the same corruption rate into one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the corruption rates of the heaps differ.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(log(n + m))***
+    #[must_use]
+    pub fn meld(mut self, other: Self) -> Self {
+        assert_eq!(self.threshold, other.threshold, "heaps should have the same corruption rate");
+        let mut ranks: Vec<Option<Box<Node<S, T>>>> = Vec::new();
+        for mut tree in mem::take(&mut self.roots).into_iter().chain(other.roots) {
+            loop {
+                let rank = tree.rank as usize;
+                if ranks.len() <= rank {
+                    ranks.resize_with(rank + 1, || None);
+                }
+                match ranks[rank].take() {
+                    Some(same) => tree = self.combine(same, tree),
+                    None => {
+                        ranks[rank] = Some(tree);
+                        break
+                    },
+                }
+            }
+        }
+        self.roots = ranks.into_iter().rev().flatten().collect();
+        self.len += other.len;
+        self.update_best(0);
+        self
+    }
+
+    /// Returns the corruption rate of the `SoftHeap`
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    /// Returns the number of elements in the `SoftHeap`
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` is there are no elements in `SoftHeap`
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all the elements from `SoftHeap`
+    pub fn clear(&mut self) {
+        self.roots.clear();
+        self.best.clear();
+        self.len = 0;
+    }
+
+    /// Checks if `lhs` should be placed closer to the root than `rhs`.
+    /// Scores that can't be compared even with themselves sink to the back.
+    #[inline]
+    fn less(lhs: &S, rhs: &S) -> bool {
+        sift::precedes::<S, O>(lhs, rhs)
+    }
+
+    /// Links two trees of the same rank under a new root.
+    fn combine(&self, a: Box<Node<S, T>>, b: Box<Node<S, T>>) -> Box<Node<S, T>> {
+        let rank = a.rank + 1;
+        let size = if rank <= self.threshold { 1 } else { (3 * a.size).div_ceil(2) };
+        let ckey = if Self::less(&b.ckey, &a.ckey) { b.ckey.clone() } else { a.ckey.clone() };
+        let mut root = Box::new(Node {
+            ckey,
+            rank,
+            size,
+            list: LinkedList::new(),
+            left: Some(a),
+            right: Some(b),
+        });
+        Self::sift(&mut root);
+        root
+    }
+
+    /// Refills the list of a node with the elements of its children, taking
+    /// over their common keys, until it holds `size` elements or there are
+    /// no children left.
+    fn sift(node: &mut Node<S, T>) {
+        while node.list.len() < node.size && !node.is_leaf() {
+            let swap = match (&node.left, &node.right) {
+                (Some(left), Some(right)) => Self::less(&right.ckey, &left.ckey),
+                (left, _) => left.is_none(),
+            };
+            if swap {
+                mem::swap(&mut node.left, &mut node.right);
+            }
+            let left = node.left.as_deref_mut().expect("node is not a leaf");
+            node.list.append(&mut left.list);
+            // the common key of the child is replaced when it's refilled
+            mem::swap(&mut node.ckey, &mut left.ckey);
+            if left.is_leaf() {
+                node.left = None;
+            } else {
+                Self::sift(left);
+            }
+        }
+    }
+
+    /// Recomputes the top roots from the given position.
+    fn update_best(&mut self, from: usize) {
+        self.best.truncate(from);
+        for index in from..self.roots.len() {
+            let best = match self.best.last() {
+                Some(&best) if !Self::less(&self.roots[index].ckey, &self.roots[best].ckey) => best,
+                _ => index,
+            };
+            self.best.push(best);
+        }
+    }
+}
+
+impl<S, T, O> Extend<(S, T)> for SoftHeap<S, T, O>
+where
+    S: PartialOrd + Clone,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(s, e)| self.put(s, e));
+    }
+}
+
+impl<S, T, O> fmt::Debug for SoftHeap<S, T, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SoftHeap")
+         .field("len", &self.len)
+         .field("epsilon", &self.epsilon)
+         .finish()
+    }
+}
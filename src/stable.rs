@@ -0,0 +1,276 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::{Max, Min, Order, PriorityQueue};
+
+/// Score together with the insertion number of an element. Equal scores are
+/// ordered by the insertion number, so that the element put first is always
+/// closer to the top, whatever the direction of the queue is.
+struct Stamped<S, O> {
+    score: S,
+    seq: u64,
+    _order: PhantomData<O>,
+}
+
+impl<S: PartialEq, O> PartialEq for Stamped<S, O> {
+    fn eq(&self, other: &Self) -> bool {
+        self.score == other.score && self.seq == other.seq
+    }
+}
+
+impl<S, O> PartialOrd for Stamped<S, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.score.partial_cmp(&other.score)? {
+            Ordering::Equal if O::TOP == Ordering::Less =>
+                Some(self.seq.cmp(&other.seq)),
+            Ordering::Equal => Some(other.seq.cmp(&self.seq)),
+            ord => Some(ord),
+        }
+    }
+}
+
+impl<S: Clone, O> Clone for Stamped<S, O> {
+    fn clone(&self) -> Self {
+        Stamped {
+            score: self.score.clone(),
+            seq: self.seq,
+            _order: PhantomData,
+        }
+    }
+}
+
+/// A priority queue which pops elements with equal scores in the same order
+/// as they were put, i.e. first-in-first-out among ties. This makes it
+/// suitable for fair schedulers, where [`PriorityQueue`] gives no guarantees
+/// which of the equal elements comes first.
+///
+/// Every element is stamped with an increasing insertion number, which is
+/// used only when scores compare equal. Numbering starts over every time the
+/// queue becomes empty.
+///
+/// # Examples
+///
+/// ```
+/// use priq::StablePriorityQueue;
+///
+/// let mut pq = StablePriorityQueue::new();
+/// pq.put(2, "second");
+/// pq.put(1, "first");
+/// pq.put(2, "third");
+/// pq.put(2, "fourth");
+///
+/// assert_eq!(Some((1, "first")), pq.pop());
+/// assert_eq!(Some((2, "second")), pq.pop());
+/// assert_eq!(Some((2, "third")), pq.pop());
+/// assert_eq!(Some((2, "fourth")), pq.pop());
+/// ```
+pub struct StablePriorityQueue<S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    pq: PriorityQueue<Stamped<S, O>, T, O>,
+    seq: u64,
+}
+
+impl<S, T> StablePriorityQueue<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `StablePriorityQueue` where the lowest score is on
+    /// top.
+    #[must_use]
+    pub fn new() -> Self {
+        StablePriorityQueue::default()
+    }
+
+    /// Create an empty `StablePriorityQueue` with space for at least `cap`
+    /// elements.
+    #[must_use]
+    pub fn with_capacity(cap: usize) -> Self {
+        StablePriorityQueue {
+            pq: PriorityQueue::with_capacity(cap),
+            seq: 0,
+        }
+    }
+}
+
+impl<S, T> StablePriorityQueue<S, T, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `StablePriorityQueue` where the highest score is on
+    /// top. Equal scores still come out in the insertion order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::StablePriorityQueue;
+    ///
+    /// let mut pq = StablePriorityQueue::new_max();
+    /// pq.extend([(1, 'a'), (5, 'b'), (5, 'c'), (3, 'd')]);
+    ///
+    /// assert_eq!(Some((5, 'b')), pq.pop());
+    /// assert_eq!(Some((5, 'c')), pq.pop());
+    /// assert_eq!(Some((3, 'd')), pq.pop());
+    /// ```
+    #[must_use]
+    pub fn new_max() -> Self {
+        StablePriorityQueue::default()
+    }
+}
+
+impl<S, T, O> StablePriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Inserts an element in the heap after all the elements with the equal
+    /// score.
+    ///
+    /// # Panics
+    ///
+    /// Panics if new capacity overflows `usize`.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(n))***.
+    pub fn put(&mut self, score: S, item: T) {
+        let seq = self.next_seq();
+        self.pq.put(Stamped { score, seq, _order: PhantomData }, item);
+    }
+
+    /// Get the top priority element, the oldest one among equal scores.
+    ///
+    /// # Time Complexity
+    ///
+    /// Worst case is ***O(log(n))***.
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        let (stamped, item) = self.pq.pop()?;
+        if self.pq.is_empty() {
+            self.seq = 0;
+        }
+        Some((stamped.score, item))
+    }
+
+    /// Check what is a top element in `StablePriorityQueue`, by getting
+    /// references to its score and item.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek(&self) -> Option<(&S, &T)> {
+        self.pq.peek().map(|(s, e)| (&s.score, e))
+    }
+
+    /// Returns the number of elements in the `StablePriorityQueue`
+    pub fn len(&self) -> usize {
+        self.pq.len()
+    }
+
+    /// Returns `true` is there are no elements in `StablePriorityQueue`
+    pub fn is_empty(&self) -> bool {
+        self.pq.is_empty()
+    }
+
+    /// Returns the number of elements the queue can hold without
+    /// reallocating.
+    pub fn capacity(&self) -> usize {
+        self.pq.capacity()
+    }
+
+    /// Remove all the elements from `StablePriorityQueue`
+    pub fn clear(&mut self) {
+        self.pq.clear();
+        self.seq = 0;
+    }
+
+    /// Consumes the priority queue and returns elements in the order they
+    /// would be popped.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n log(n))***
+    pub fn into_sorted_vec(self) -> Vec<(S, T)> {
+        self.pq.into_sorted_vec()
+            .into_iter()
+            .map(|(s, e)| (s.score, e))
+            .collect()
+    }
+
+    fn next_seq(&mut self) -> u64 {
+        let seq = self.seq;
+        self.seq += 1;
+        seq
+    }
+}
+
+impl<S, T, O> Default for StablePriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn default() -> Self {
+        StablePriorityQueue {
+            pq: PriorityQueue::default(),
+            seq: 0,
+        }
+    }
+}
+
+impl<S, T, O> Extend<(S, T)> for StablePriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.pq.reserve(iter.size_hint().0);
+        iter.for_each(|(s, e)| self.put(s, e));
+    }
+}
+
+impl<S, T> FromIterator<(S, T)> for StablePriorityQueue<S, T>
+where
+    S: PartialOrd,
+{
+    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
+        let mut pq = StablePriorityQueue::new();
+        pq.extend(iter);
+        pq
+    }
+}
+
+impl<S, T, O> Clone for StablePriorityQueue<S, T, O>
+where
+    S: PartialOrd + Clone,
+    T: Clone,
+    O: Order,
+{
+    fn clone(&self) -> Self {
+        StablePriorityQueue {
+            pq: self.pq.clone(),
+            seq: self.seq,
+        }
+    }
+}
+
+impl<S, T, O> fmt::Debug for StablePriorityQueue<S, T, O>
+where
+    S: PartialOrd + fmt::Debug,
+    T: fmt::Debug,
+    O: Order,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let elems: Vec<_> = self.pq.iter().map(|(s, e)| (&s.score, e)).collect();
+        f.debug_struct("StablePriorityQueue")
+         .field("data", &elems)
+         .finish_non_exhaustive()
+    }
+}
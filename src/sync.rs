@@ -0,0 +1,1894 @@
+//! Priority queues shared between threads.
+
+use std::any::Any;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::future::{self, Future};
+use std::num::NonZeroUsize;
+use std::hint;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError, TryLockError};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::Duration;
+
+use crate::{Max, Min, Order, PriorityQueue};
+use crate::sift;
+
+/// A [`PriorityQueue`] behind a mutex, which consumers can wait on until an
+/// element is put. Share it between threads with an [`Arc`].
+///
+/// A panic in another thread while it holds the lock, e.g. in a comparison
+/// of scores, doesn't make the queue unusable, the elements are still
+/// served.
+///
+/// [`Arc`]: std::sync::Arc
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use priq::sync::BlockingPriorityQueue;
+///
+/// let jobs = Arc::new(BlockingPriorityQueue::new());
+/// let worker = {
+///     let jobs = Arc::clone(&jobs);
+///     thread::spawn(move || jobs.pop_wait())
+/// };
+///
+/// jobs.put(1, "urgent");
+/// assert_eq!((1, "urgent"), worker.join().unwrap());
+/// ```
+pub struct BlockingPriorityQueue<S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    pq: Mutex<PriorityQueue<S, T, O>>,
+    nonempty: Condvar,
+    top: Arc<TopSignal>,
+}
+
+impl<S, T> BlockingPriorityQueue<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `BlockingPriorityQueue` where the lowest score is on
+    /// top.
+    #[must_use]
+    pub fn new() -> Self {
+        BlockingPriorityQueue::from_queue(PriorityQueue::new())
+    }
+}
+
+impl<S, T> BlockingPriorityQueue<S, T, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `BlockingPriorityQueue` where the highest score is on
+    /// top.
+    #[must_use]
+    pub fn new_max() -> Self {
+        BlockingPriorityQueue::from_queue(PriorityQueue::new_max())
+    }
+}
+
+impl<S, T, O> BlockingPriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Wraps an existing priority queue, keeping its elements and settings.
+    pub fn from_queue(pq: PriorityQueue<S, T, O>) -> Self {
+        BlockingPriorityQueue {
+            pq: Mutex::new(pq),
+            nonempty: Condvar::new(),
+            top: Arc::new(TopSignal::default()),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PriorityQueue<S, T, O>> {
+        self.pq.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Inserts an element and wakes up one of the threads waiting for it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the score is incomparable and the [`NanPolicy`] of the
+    /// queue is `Reject` or `Panic`.
+    ///
+    /// [`NanPolicy`]: crate::NanPolicy
+    pub fn put(&self, score: S, item: T) {
+        let elem = (score, item);
+        let mut pq = self.lock();
+        let new_top = becomes_top(&pq, &elem);
+        pq.put(elem.0, elem.1);
+        drop(pq);
+        self.nonempty.notify_one();
+        if new_top {
+            self.top.fire();
+        }
+    }
+
+    /// Inserts clones of all the elements of a batch under a single lock,
+    /// and wakes up the threads waiting for them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use priq::sync::BlockingPriorityQueue;
+    ///
+    /// let pq = BlockingPriorityQueue::new();
+    /// pq.put_batch(&[(3, 'c'), (1, 'a'), (2, 'b')]);
+    /// assert_eq!(3, pq.len());
+    /// assert_eq!((1, 'a'), pq.pop_wait());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if a score is incomparable and the [`NanPolicy`] of the
+    /// queue is `Reject` or `Panic`.
+    ///
+    /// [`NanPolicy`]: crate::NanPolicy
+    pub fn put_batch(&self, batch: &[(S, T)])
+    where
+        S: Clone,
+        T: Clone,
+    {
+        let mut pq = self.lock();
+        let new_top = batch.iter().any(|elem| becomes_top(&pq, elem));
+        pq.extend_from_slice(batch);
+        drop(pq);
+        if new_top {
+            self.top.fire();
+        }
+        match batch.len() {
+            0 => {},
+            1 => self.nonempty.notify_one(),
+            _ => self.nonempty.notify_all(),
+        }
+    }
+
+    /// Get the top priority element if there is one, without waiting.
+    pub fn pop(&self) -> Option<(S, T)> {
+        self.lock().pop()
+    }
+
+    /// Get the top priority element, waiting until one is put if the queue
+    /// is empty.
+    pub fn pop_wait(&self) -> (S, T) {
+        let mut pq = self.wait_nonempty(self.lock());
+        pq.pop().expect("queue is not empty")
+    }
+
+    /// Get the top priority element, waiting at most `timeout` until one is
+    /// put if the queue is empty. Returns `None` if the time is up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use priq::sync::BlockingPriorityQueue;
+    ///
+    /// let pq: BlockingPriorityQueue<u32, ()> = BlockingPriorityQueue::new();
+    /// assert_eq!(None, pq.pop_timeout(Duration::from_millis(10)));
+    /// ```
+    pub fn pop_timeout(&self, timeout: Duration) -> Option<(S, T)> {
+        let (mut pq, _) = self.nonempty
+            .wait_timeout_while(self.lock(), timeout, |pq| pq.is_empty())
+            .unwrap_or_else(PoisonError::into_inner);
+        pq.pop()
+    }
+
+    /// Get a clone of the score of the top element, e.g. the deadline to
+    /// sleep until. Other threads can change the top at any moment after.
+    pub fn peek_score(&self) -> Option<S>
+    where
+        S: Clone,
+    {
+        self.lock().peek_score().cloned()
+    }
+
+    /// Returns a receiver of the notifications about new elements which
+    /// become the top of the queue, e.g. to wake up a consumer sleeping
+    /// until the deadline of the current top.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use priq::sync::BlockingPriorityQueue;
+    ///
+    /// let pq = BlockingPriorityQueue::new();
+    /// let mut top = pq.watch_top();
+    /// pq.put(5, "later");
+    /// pq.put(9, "much later");
+    /// assert!(top.wait_timeout(Duration::ZERO));
+    ///
+    /// // doesn't change the top
+    /// pq.put(7, "between");
+    /// assert!(!top.has_changed());
+    /// ```
+    pub fn watch_top(&self) -> TopWatch {
+        TopWatch::new(&self.top)
+    }
+
+    /// Blocks the current thread until there is at least one element in the
+    /// queue. Another consumer can still pop it first.
+    pub fn wait_until_nonempty(&self) {
+        drop(self.wait_nonempty(self.lock()));
+    }
+
+    fn wait_nonempty<'a>(&self, pq: MutexGuard<'a, PriorityQueue<S, T, O>>)
+        -> MutexGuard<'a, PriorityQueue<S, T, O>>
+    {
+        self.nonempty
+            .wait_while(pq, |pq| pq.is_empty())
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the number of elements in the `BlockingPriorityQueue`
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` is there are no elements in `BlockingPriorityQueue`
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Remove all the elements from `BlockingPriorityQueue`
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Consumes the wrapper and returns the priority queue.
+    pub fn into_inner(self) -> PriorityQueue<S, T, O> {
+        self.pq.into_inner().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<S, T, O> Default for BlockingPriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn default() -> Self {
+        BlockingPriorityQueue::from_queue(PriorityQueue::default())
+    }
+}
+
+impl<S, T, O> fmt::Debug for BlockingPriorityQueue<S, T, O>
+where
+    S: PartialOrd + fmt::Debug,
+    T: fmt::Debug,
+    O: Order,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockingPriorityQueue")
+         .field("pq", &*self.lock())
+         .finish()
+    }
+}
+
+/// A relaxed concurrent priority queue made of shards, one per worker
+/// thread, for schedulers which care more about throughput than about
+/// strict ordering.
+///
+/// Each [`Worker`] puts to and pops from its own shard, so workers rarely
+/// contend for a lock. When its shard is empty, a worker steals the top
+/// element of another shard. The order is therefore only kept within a
+/// shard: `pop` returns the top element of one shard, which isn't always
+/// the top element of the whole queue.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use priq::sync::RelaxedPriorityQueue;
+///
+/// let pq = Arc::new(RelaxedPriorityQueue::new(2));
+/// let worker = {
+///     let pq = Arc::clone(&pq);
+///     thread::spawn(move || {
+///         let local = pq.worker();
+///         local.put(2, "b");
+///         local.put(1, "a");
+///         local.pop()
+///     })
+/// };
+/// assert_eq!(Some((1, "a")), worker.join().unwrap());
+///
+/// // the other worker has an empty shard and steals from the first one
+/// assert_eq!(Some((2, "b")), pq.worker().pop());
+/// ```
+pub struct RelaxedPriorityQueue<S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    shards: Box<[Mutex<PriorityQueue<S, T, O>>]>,
+    next_worker: AtomicUsize,
+}
+
+impl<S, T> RelaxedPriorityQueue<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `RelaxedPriorityQueue` with the given number of
+    /// shards, where the lowest score is on top.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is zero.
+    #[must_use]
+    pub fn new(shards: usize) -> Self {
+        RelaxedPriorityQueue::with_shards(shards)
+    }
+}
+
+impl<S, T> RelaxedPriorityQueue<S, T, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `RelaxedPriorityQueue` with the given number of
+    /// shards, where the highest score is on top.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is zero.
+    #[must_use]
+    pub fn new_max(shards: usize) -> Self {
+        RelaxedPriorityQueue::with_shards(shards)
+    }
+}
+
+impl<S, T, O> RelaxedPriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn with_shards(shards: usize) -> Self {
+        assert_ne!(shards, 0, "Number of shards can't be zero");
+        RelaxedPriorityQueue {
+            shards: (0..shards).map(|_| Mutex::new(PriorityQueue::default())).collect(),
+            next_worker: AtomicUsize::new(0),
+        }
+    }
+
+    fn lock(&self, shard: usize) -> MutexGuard<'_, PriorityQueue<S, T, O>> {
+        self.shards[shard].lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns a handle for the current thread. Handles are given the
+    /// shards in turns, so with as many shards as worker threads each of
+    /// them gets its own.
+    pub fn worker(&self) -> Worker<'_, S, T, O> {
+        let shard = self.next_worker.fetch_add(1, Ordering::Relaxed) % self.shards.len();
+        Worker { pq: self, shard }
+    }
+
+    /// Returns the number of shards.
+    pub fn shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns the number of elements in all the shards. Other threads can
+    /// change it while the shards are counted, so it's only an estimate.
+    pub fn len(&self) -> usize {
+        (0..self.shards.len()).map(|shard| self.lock(shard).len()).sum()
+    }
+
+    /// Returns `true` is there are no elements in `RelaxedPriorityQueue`
+    pub fn is_empty(&self) -> bool {
+        (0..self.shards.len()).all(|shard| self.lock(shard).is_empty())
+    }
+
+    /// Remove all the elements from `RelaxedPriorityQueue`
+    pub fn clear(&self) {
+        (0..self.shards.len()).for_each(|shard| self.lock(shard).clear());
+    }
+
+    /// Consumes the queue and returns the elements of all the shards sorted
+    /// by their priority, the top one first.
+    pub fn into_sorted_vec(self) -> Vec<(S, T)> {
+        let mut pq = PriorityQueue::<S, T, O>::default();
+        for shard in self.shards.into_vec() {
+            let mut shard = shard.into_inner().unwrap_or_else(PoisonError::into_inner);
+            while let Some((s, e)) = shard.pop() {
+                pq.put(s, e);
+            }
+        }
+        pq.into_sorted_vec()
+    }
+}
+
+impl<S, T, O> Default for RelaxedPriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Creates a queue with a shard for every thread which can run in
+    /// parallel.
+    fn default() -> Self {
+        RelaxedPriorityQueue::with_shards(
+            thread::available_parallelism().map_or(1, NonZeroUsize::get)
+        )
+    }
+}
+
+impl<S, T, O> fmt::Debug for RelaxedPriorityQueue<S, T, O>
+where
+    S: PartialOrd + fmt::Debug,
+    T: fmt::Debug,
+    O: Order,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shards: Vec<_> = (0..self.shards.len()).map(|shard| self.lock(shard)).collect();
+        f.debug_struct("RelaxedPriorityQueue")
+         .field("shards", &shards.iter().map(|pq| &**pq).collect::<Vec<_>>())
+         .finish()
+    }
+}
+
+/// Handle of a thread to its shard of a [`RelaxedPriorityQueue`], created by
+/// [`RelaxedPriorityQueue::worker`].
+pub struct Worker<'a, S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    pq: &'a RelaxedPriorityQueue<S, T, O>,
+    shard: usize,
+}
+
+impl<S, T, O> Worker<'_, S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Inserts an element in the shard of the worker.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the score is incomparable and the [`NanPolicy`] of the
+    /// queue is `Reject` or `Panic`.
+    ///
+    /// [`NanPolicy`]: crate::NanPolicy
+    pub fn put(&self, score: S, item: T) {
+        self.pq.lock(self.shard).put(score, item);
+    }
+
+    /// Inserts clones of all the elements of a batch in the shard of the
+    /// worker under a single lock.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a score is incomparable and the [`NanPolicy`] of the
+    /// queue is `Reject` or `Panic`.
+    ///
+    /// [`NanPolicy`]: crate::NanPolicy
+    pub fn put_batch(&self, batch: &[(S, T)])
+    where
+        S: Clone,
+        T: Clone,
+    {
+        self.pq.lock(self.shard).extend_from_slice(batch);
+    }
+
+    /// Get the top priority element of the shard of the worker, or steal
+    /// the top element of the next non-empty shard if it's empty. Returns
+    /// `None` only if all the shards are empty.
+    pub fn pop(&self) -> Option<(S, T)> {
+        let shards = self.pq.shards.len();
+        (0..shards).find_map(|i| self.pq.lock((self.shard + i) % shards).pop())
+    }
+
+    /// Get the top priority element of the shard of the worker, without
+    /// stealing from the others.
+    pub fn pop_local(&self) -> Option<(S, T)> {
+        self.pq.lock(self.shard).pop()
+    }
+
+    /// Returns the index of the shard of the worker.
+    pub fn shard(&self) -> usize {
+        self.shard
+    }
+}
+
+impl<S, T, O> fmt::Debug for Worker<'_, S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Worker")
+         .field("shard", &self.shard)
+         .finish()
+    }
+}
+
+/// A [`PriorityQueue`] which async tasks can wait on until an element is
+/// put, with any executor. Share it between tasks with an [`Arc`].
+///
+/// Consumers waiting in [`pop`] are served in the order they started to
+/// wait, so none of them starves while the others keep getting elements.
+/// With the `futures` feature the queue can also be consumed as a
+/// [`Stream`] returned by [`stream`].
+///
+/// [`Arc`]: std::sync::Arc
+/// [`pop`]: AsyncPriorityQueue::pop
+/// [`stream`]: AsyncPriorityQueue::stream
+/// [`Stream`]: https://docs.rs/futures/latest/futures/stream/trait.Stream.html
+///
+/// # Examples
+///
+/// ```
+/// # use std::future::Future;
+/// # use std::pin::pin;
+/// # use std::sync::Arc;
+/// # use std::task::{Context, Poll, Wake};
+/// # struct Noop;
+/// # impl Wake for Noop { fn wake(self: Arc<Self>) {} }
+/// use priq::sync::AsyncPriorityQueue;
+///
+/// let pq = AsyncPriorityQueue::new();
+/// let waker = Arc::new(Noop).into();
+/// let mut cx = Context::from_waker(&waker);
+///
+/// let mut pop = pin!(pq.pop());
+/// assert!(pop.as_mut().poll(&mut cx).is_pending());
+/// pq.put(1, "job");
+/// assert_eq!(Poll::Ready((1, "job")), pop.poll(&mut cx));
+/// ```
+pub struct AsyncPriorityQueue<S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    state: Mutex<AsyncState<S, T, O>>,
+    top: Arc<TopSignal>,
+}
+
+struct AsyncState<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    pq: PriorityQueue<S, T, O>,
+    /// Consumers waiting for an element, the first one is served next.
+    waiters: VecDeque<(u64, Waker)>,
+    next_waiter: u64,
+}
+
+impl<S, T, O> AsyncState<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Wakes up the first waiting consumer if there is an element for it.
+    fn wake_first(&self) {
+        if !self.pq.is_empty() {
+            if let Some((_, waker)) = self.waiters.front() {
+                waker.wake_by_ref();
+            }
+        }
+    }
+}
+
+impl<S, T> AsyncPriorityQueue<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `AsyncPriorityQueue` where the lowest score is on
+    /// top.
+    #[must_use]
+    pub fn new() -> Self {
+        AsyncPriorityQueue::from_queue(PriorityQueue::new())
+    }
+}
+
+impl<S, T> AsyncPriorityQueue<S, T, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `AsyncPriorityQueue` where the highest score is on
+    /// top.
+    #[must_use]
+    pub fn new_max() -> Self {
+        AsyncPriorityQueue::from_queue(PriorityQueue::new_max())
+    }
+}
+
+impl<S, T, O> AsyncPriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Wraps an existing priority queue, keeping its elements and settings.
+    pub fn from_queue(pq: PriorityQueue<S, T, O>) -> Self {
+        AsyncPriorityQueue {
+            state: Mutex::new(AsyncState { pq, waiters: VecDeque::new(), next_waiter: 0 }),
+            top: Arc::new(TopSignal::default()),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, AsyncState<S, T, O>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Inserts an element and wakes up the consumer which has waited for the
+    /// longest time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the score is incomparable and the [`NanPolicy`] of the
+    /// queue is `Reject` or `Panic`.
+    ///
+    /// [`NanPolicy`]: crate::NanPolicy
+    pub fn put(&self, score: S, item: T) {
+        let elem = (score, item);
+        let mut state = self.lock();
+        let new_top = becomes_top(&state.pq, &elem);
+        state.pq.put(elem.0, elem.1);
+        state.wake_first();
+        drop(state);
+        if new_top {
+            self.top.fire();
+        }
+    }
+
+    /// Inserts clones of all the elements of a batch under a single lock.
+    /// The waiting consumers are woken up one after another, as each of
+    /// them takes its element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a score is incomparable and the [`NanPolicy`] of the
+    /// queue is `Reject` or `Panic`.
+    ///
+    /// [`NanPolicy`]: crate::NanPolicy
+    pub fn put_batch(&self, batch: &[(S, T)])
+    where
+        S: Clone,
+        T: Clone,
+    {
+        let mut state = self.lock();
+        let new_top = batch.iter().any(|elem| becomes_top(&state.pq, elem));
+        state.pq.extend_from_slice(batch);
+        state.wake_first();
+        drop(state);
+        if new_top {
+            self.top.fire();
+        }
+    }
+
+    /// Returns a receiver of the notifications about new elements which
+    /// become the top of the queue, e.g. to wake up a task sleeping until
+    /// the deadline of the current top.
+    pub fn watch_top(&self) -> TopWatch {
+        TopWatch::new(&self.top)
+    }
+
+    /// Get the top priority element, waiting until one is put if the queue
+    /// is empty, or other consumers which waited before are served.
+    pub fn pop(&self) -> Pop<'_, S, T, O> {
+        Pop { pq: self, waiter: None }
+    }
+
+    /// Get the top priority element if there is one, without waiting.
+    /// Doesn't wait for the turn after the waiting consumers either.
+    pub fn try_pop(&self) -> Option<(S, T)> {
+        self.lock().pq.pop()
+    }
+
+    /// Attempts to get the top priority element for the consumer with the
+    /// given place in the line, or takes a place for it.
+    fn poll_pop(&self, waiter: &mut Option<u64>, cx: &mut Context<'_>) -> Poll<(S, T)> {
+        let mut state = self.lock();
+        let first = match *waiter {
+            None => state.waiters.is_empty(),
+            Some(id) => state.waiters.front().is_some_and(|&(first, _)| first == id),
+        };
+        if first {
+            if let Some(elem) = state.pq.pop() {
+                if waiter.take().is_some() {
+                    state.waiters.pop_front();
+                }
+                state.wake_first();
+                return Poll::Ready(elem)
+            }
+        }
+        match *waiter {
+            Some(id) => {
+                let (_, waker) = state.waiters.iter_mut()
+                    .find(|(other, _)| *other == id)
+                    .expect("waiting consumer is in the line");
+                waker.clone_from(cx.waker());
+            },
+            None => {
+                let id = state.next_waiter;
+                state.next_waiter += 1;
+                state.waiters.push_back((id, cx.waker().clone()));
+                *waiter = Some(id);
+            },
+        }
+        Poll::Pending
+    }
+
+    /// Leaves the line of waiting consumers, letting the next one take the
+    /// element which might have been meant for the leaving one.
+    fn cancel(&self, waiter: u64) {
+        let mut state = self.lock();
+        if let Some(pos) = state.waiters.iter().position(|&(id, _)| id == waiter) {
+            state.waiters.remove(pos);
+            if pos == 0 {
+                state.wake_first();
+            }
+        }
+    }
+
+    /// Returns a stream of the elements in the order of their priority,
+    /// which waits for new ones when the queue is empty and never ends.
+    /// Implements [`Stream`] with the `futures` feature.
+    ///
+    /// [`Stream`]: https://docs.rs/futures/latest/futures/stream/trait.Stream.html
+    pub fn stream(&self) -> PopStream<'_, S, T, O> {
+        PopStream { pq: self, waiter: None }
+    }
+
+    /// Returns the number of elements in the `AsyncPriorityQueue`
+    pub fn len(&self) -> usize {
+        self.lock().pq.len()
+    }
+
+    /// Returns `true` is there are no elements in `AsyncPriorityQueue`
+    pub fn is_empty(&self) -> bool {
+        self.lock().pq.is_empty()
+    }
+
+    /// Returns the number of consumers waiting for an element.
+    pub fn waiting(&self) -> usize {
+        self.lock().waiters.len()
+    }
+
+    /// Remove all the elements from `AsyncPriorityQueue`
+    pub fn clear(&self) {
+        self.lock().pq.clear();
+    }
+
+    /// Consumes the wrapper and returns the priority queue.
+    pub fn into_inner(self) -> PriorityQueue<S, T, O> {
+        self.state.into_inner().unwrap_or_else(PoisonError::into_inner).pq
+    }
+}
+
+impl<S, T, O> Default for AsyncPriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn default() -> Self {
+        AsyncPriorityQueue::from_queue(PriorityQueue::default())
+    }
+}
+
+impl<S, T, O> fmt::Debug for AsyncPriorityQueue<S, T, O>
+where
+    S: PartialOrd + fmt::Debug,
+    T: fmt::Debug,
+    O: Order,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+        f.debug_struct("AsyncPriorityQueue")
+         .field("pq", &state.pq)
+         .field("waiting", &state.waiters.len())
+         .finish()
+    }
+}
+
+/// Future of the top priority element of an [`AsyncPriorityQueue`], created
+/// by [`AsyncPriorityQueue::pop`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Pop<'a, S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    pq: &'a AsyncPriorityQueue<S, T, O>,
+    waiter: Option<u64>,
+}
+
+impl<S, T, O> Future for Pop<'_, S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    type Output = (S, T);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.pq.poll_pop(&mut this.waiter, cx)
+    }
+}
+
+impl<S, T, O> Drop for Pop<'_, S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn drop(&mut self) {
+        if let Some(waiter) = self.waiter {
+            self.pq.cancel(waiter);
+        }
+    }
+}
+
+impl<S, T, O> fmt::Debug for Pop<'_, S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pop")
+         .field("waiting", &self.waiter.is_some())
+         .finish()
+    }
+}
+
+/// Endless stream of the elements of an [`AsyncPriorityQueue`] in the order
+/// of their priority, created by [`AsyncPriorityQueue::stream`].
+pub struct PopStream<'a, S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    pq: &'a AsyncPriorityQueue<S, T, O>,
+    waiter: Option<u64>,
+}
+
+impl<S, T, O> PopStream<'_, S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Attempts to get the next element of the stream, with the same
+    /// fairness among the consumers as [`AsyncPriorityQueue::pop`].
+    pub fn poll_next_elem(&mut self, cx: &mut Context<'_>) -> Poll<(S, T)> {
+        self.pq.poll_pop(&mut self.waiter, cx)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<S, T, O> futures_core::Stream for PopStream<'_, S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    type Item = (S, T);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_next_elem(cx).map(Some)
+    }
+}
+
+impl<S, T, O> Drop for PopStream<'_, S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn drop(&mut self) {
+        if let Some(waiter) = self.waiter {
+            self.pq.cancel(waiter);
+        }
+    }
+}
+
+impl<S, T, O> fmt::Debug for PopStream<'_, S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PopStream")
+         .field("waiting", &self.waiter.is_some())
+         .finish()
+    }
+}
+
+/// Operation published by a thread for the combiner, along with its result.
+enum Request<S, T> {
+    Empty,
+    Put(S, T),
+    PutBatch(Vec<(S, T)>),
+    Pop,
+    Done(Option<(S, T)>),
+    /// Operation panicked, the panic is resumed by the publishing thread.
+    Panicked(Box<dyn Any + Send>),
+}
+
+/// Index of the current thread, which picks its publication slot.
+fn thread_index() -> usize {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    thread_local! {
+        static INDEX: usize = NEXT.fetch_add(1, Ordering::Relaxed);
+    }
+    INDEX.with(|index| *index)
+}
+
+/// A [`PriorityQueue`] shared between threads with flat combining, which
+/// holds up better than a plain mutex when many threads use the queue at
+/// once.
+///
+/// Instead of taking turns on the lock, threads publish their operations in
+/// slots, and whichever thread gets the lock becomes the combiner: it
+/// applies the operations of all the slots to the heap in a single pass and
+/// hands back the results. The heap stays hot in the cache of one thread,
+/// and the lock changes hands once per batch instead of once per operation.
+///
+/// Threads pick their slots by their index, so with at least as many slots
+/// as threads each of them has its own. Threads which share a slot take
+/// turns on it.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use priq::sync::CombiningPriorityQueue;
+///
+/// let pq = Arc::new(CombiningPriorityQueue::new());
+/// let producers: Vec<_> = (0..4).map(|t| {
+///     let pq = Arc::clone(&pq);
+///     thread::spawn(move || (0..100).for_each(|i| pq.put(i * 4 + t, t)))
+/// }).collect();
+/// producers.into_iter().for_each(|p| p.join().unwrap());
+///
+/// assert_eq!(400, pq.len());
+/// assert_eq!(Some((0, 0)), pq.pop());
+/// ```
+pub struct CombiningPriorityQueue<S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    pq: Mutex<PriorityQueue<S, T, O>>,
+    slots: Box<[Mutex<Request<S, T>>]>,
+}
+
+impl<S, T> CombiningPriorityQueue<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `CombiningPriorityQueue` where the lowest score is
+    /// on top, with a slot for every thread which can run in parallel.
+    #[must_use]
+    pub fn new() -> Self {
+        CombiningPriorityQueue::default()
+    }
+
+    /// Create an empty `CombiningPriorityQueue` where the lowest score is
+    /// on top, with the given number of slots.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slots` is zero.
+    #[must_use]
+    pub fn with_slots(slots: usize) -> Self {
+        CombiningPriorityQueue::from_queue(PriorityQueue::new(), slots)
+    }
+}
+
+impl<S, T> CombiningPriorityQueue<S, T, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `CombiningPriorityQueue` where the highest score is
+    /// on top, with a slot for every thread which can run in parallel.
+    #[must_use]
+    pub fn new_max() -> Self {
+        CombiningPriorityQueue::default()
+    }
+}
+
+impl<S, T, O> CombiningPriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Wraps an existing priority queue, keeping its elements and settings,
+    /// with the given number of slots.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slots` is zero.
+    pub fn from_queue(pq: PriorityQueue<S, T, O>, slots: usize) -> Self {
+        assert_ne!(slots, 0, "Number of slots can't be zero");
+        CombiningPriorityQueue {
+            pq: Mutex::new(pq),
+            slots: (0..slots).map(|_| Mutex::new(Request::Empty)).collect(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PriorityQueue<S, T, O>> {
+        self.pq.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn lock_slot(&self, slot: usize) -> MutexGuard<'_, Request<S, T>> {
+        self.slots[slot].lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Inserts an element.
+    ///
+    /// # Panics
+    ///
+    /// If the score is incomparable and the [`NanPolicy`] of the queue is
+    /// `Reject` or `Panic`. The panic is raised on the calling thread, even
+    /// if another thread applied the operation as the combiner, and both
+    /// the combiner and the queue stay usable.
+    ///
+    /// [`NanPolicy`]: crate::NanPolicy
+    pub fn put(&self, score: S, item: T) {
+        self.apply(Request::Put(score, item));
+    }
+
+    /// Inserts clones of all the elements of a batch, which the combiner
+    /// puts in the heap at once.
+    ///
+    /// # Panics
+    ///
+    /// Same as [`put`](CombiningPriorityQueue::put).
+    pub fn put_batch(&self, batch: &[(S, T)])
+    where
+        S: Clone,
+        T: Clone,
+    {
+        if !batch.is_empty() {
+            self.apply(Request::PutBatch(batch.to_vec()));
+        }
+    }
+
+    /// Get the top priority element if there is one.
+    pub fn pop(&self) -> Option<(S, T)> {
+        self.apply(Request::Pop)
+    }
+
+    /// Publishes the request in the slot of the current thread and waits
+    /// until it's applied, either by another thread or by this one.
+    fn apply(&self, request: Request<S, T>) -> Option<(S, T)> {
+        let slot = thread_index() % self.slots.len();
+        let mut request = Some(request);
+        while let Some(req) = request.take() {
+            let mut published = self.lock_slot(slot);
+            if matches!(*published, Request::Empty) {
+                *published = req;
+            } else {
+                // another thread with the same slot is waiting for its result
+                request = Some(req);
+                drop(published);
+                thread::yield_now();
+            }
+        }
+
+        loop {
+            match self.pq.try_lock() {
+                Ok(pq) => self.combine(pq),
+                Err(TryLockError::Poisoned(pq)) => self.combine(pq.into_inner()),
+                Err(TryLockError::WouldBlock) => hint::spin_loop(),
+            }
+            let mut published = self.lock_slot(slot);
+            if let Request::Done(_) | Request::Panicked(_) = *published {
+                match mem::replace(&mut *published, Request::Empty) {
+                    Request::Done(res) => return res,
+                    Request::Panicked(payload) => panic::resume_unwind(payload),
+                    _ => unreachable!("request was done"),
+                }
+            }
+            drop(published);
+            thread::yield_now();
+        }
+    }
+
+    /// Applies all the published requests to the heap. A panic of an
+    /// operation is handed over to the thread which published it, instead
+    /// of unwinding the combiner and leaving that thread waiting forever.
+    fn combine(&self, mut pq: MutexGuard<'_, PriorityQueue<S, T, O>>) {
+        for slot in 0..self.slots.len() {
+            let mut published = self.lock_slot(slot);
+            let request = mem::replace(&mut *published, Request::Empty);
+            *published = match request {
+                Request::Put(..) | Request::PutBatch(_) | Request::Pop => {
+                    let apply = AssertUnwindSafe(|| match request {
+                        Request::Put(score, item) => {
+                            pq.put(score, item);
+                            None
+                        },
+                        Request::PutBatch(batch) => {
+                            pq.put_many(batch);
+                            None
+                        },
+                        _ => pq.pop(),
+                    });
+                    panic::catch_unwind(apply).map_or_else(Request::Panicked, Request::Done)
+                },
+                other => other,
+            };
+        }
+    }
+
+    /// Returns the number of slots.
+    pub fn slots(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the number of elements in the `CombiningPriorityQueue`
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` is there are no elements in `CombiningPriorityQueue`
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Remove all the elements from `CombiningPriorityQueue`
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Consumes the wrapper and returns the priority queue.
+    pub fn into_inner(self) -> PriorityQueue<S, T, O> {
+        self.pq.into_inner().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<S, T, O> Default for CombiningPriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn default() -> Self {
+        CombiningPriorityQueue::from_queue(
+            PriorityQueue::default(),
+            thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        )
+    }
+}
+
+impl<S, T, O> fmt::Debug for CombiningPriorityQueue<S, T, O>
+where
+    S: PartialOrd + fmt::Debug,
+    T: fmt::Debug,
+    O: Order,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CombiningPriorityQueue")
+         .field("pq", &*self.lock())
+         .field("slots", &self.slots.len())
+         .finish()
+    }
+}
+
+/// How a [`QueuePool`] picks the queue for a new element.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Routing {
+    /// Every thread puts in the queue picked by hashing its index, which
+    /// keeps the elements of a thread together and off the other queues.
+    #[default]
+    Thread,
+
+    /// Elements are put in the queue with the fewest elements at the
+    /// moment, which keeps the queues evenly loaded.
+    LeastLoaded,
+}
+
+/// Statistics of one of the queues of a [`QueuePool`], for tuning the
+/// number of queues and the routing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueueStats {
+    /// Number of elements in the queue.
+    pub len: usize,
+    /// Number of elements put in the queue so far.
+    pub puts: u64,
+    /// Number of elements popped from the queue so far.
+    pub pops: u64,
+    /// Number of times the lock of the queue was already taken by another
+    /// thread.
+    pub contended: u64,
+}
+
+/// One of the queues of a pool, with its counters.
+struct Lane<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    pq: Mutex<PriorityQueue<S, T, O>>,
+    len: AtomicUsize,
+    puts: AtomicU64,
+    pops: AtomicU64,
+    contended: AtomicU64,
+}
+
+impl<S, T, O> Lane<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn lock(&self) -> MutexGuard<'_, PriorityQueue<S, T, O>> {
+        match self.pq.try_lock() {
+            Ok(pq) => pq,
+            Err(TryLockError::Poisoned(pq)) => pq.into_inner(),
+            Err(TryLockError::WouldBlock) => {
+                self.contended.fetch_add(1, Ordering::Relaxed);
+                self.pq.lock().unwrap_or_else(PoisonError::into_inner)
+            },
+        }
+    }
+
+    fn pop(&self, pq: &mut PriorityQueue<S, T, O>) -> Option<(S, T)> {
+        let elem = pq.pop()?;
+        self.len.store(pq.len(), Ordering::Relaxed);
+        self.pops.fetch_add(1, Ordering::Relaxed);
+        Some(elem)
+    }
+}
+
+/// A relaxed concurrent priority queue spread over a pool of queues, which
+/// scales to many cores by letting threads work on different queues.
+///
+/// New elements are routed to one of the queues as set by [`Routing`].
+/// `pop` compares the tops of two queues chosen at random and takes the
+/// better one, and if both are empty it scans the rest of them. The popped
+/// element is therefore close to the top of the whole pool, but not always
+/// the top. Use [`stats`] to see how the load is spread over the queues.
+///
+/// [`stats`]: QueuePool::stats
+///
+/// # Examples
+///
+/// ```
+/// use priq::sync::{QueuePool, Routing};
+///
+/// let pool = QueuePool::new(4, Routing::LeastLoaded);
+/// (0..8).for_each(|i| pool.put(i, i * 10));
+///
+/// assert!(pool.stats().iter().all(|stats| stats.len == 2));
+/// assert!(pool.pop().is_some());
+/// assert_eq!(7, pool.len());
+/// ```
+pub struct QueuePool<S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    lanes: Box<[Lane<S, T, O>]>,
+    routing: Routing,
+}
+
+impl<S, T> QueuePool<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create a pool of `queues` empty queues where the lowest score is on
+    /// top.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `queues` is zero.
+    #[must_use]
+    pub fn new(queues: usize, routing: Routing) -> Self {
+        QueuePool::with_queues(queues, routing)
+    }
+}
+
+impl<S, T> QueuePool<S, T, Max>
+where
+    S: PartialOrd,
+{
+    /// Create a pool of `queues` empty queues where the highest score is on
+    /// top.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `queues` is zero.
+    #[must_use]
+    pub fn new_max(queues: usize, routing: Routing) -> Self {
+        QueuePool::with_queues(queues, routing)
+    }
+}
+
+impl<S, T, O> QueuePool<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn with_queues(queues: usize, routing: Routing) -> Self {
+        assert_ne!(queues, 0, "Number of queues can't be zero");
+        let lanes = (0..queues).map(|_| Lane {
+            pq: Mutex::new(PriorityQueue::default()),
+            len: AtomicUsize::new(0),
+            puts: AtomicU64::new(0),
+            pops: AtomicU64::new(0),
+            contended: AtomicU64::new(0),
+        });
+        QueuePool { lanes: lanes.collect(), routing }
+    }
+
+    /// Picks the queue for a new element.
+    fn route(&self) -> usize {
+        match self.routing {
+            Routing::Thread => thread_index() % self.lanes.len(),
+            Routing::LeastLoaded => (0..self.lanes.len())
+                .min_by_key(|&i| self.lanes[i].len.load(Ordering::Relaxed))
+                .expect("pool has queues"),
+        }
+    }
+
+    /// Inserts an element in the queue picked by the routing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the score is incomparable and the [`NanPolicy`] of the
+    /// queue is `Reject` or `Panic`.
+    ///
+    /// [`NanPolicy`]: crate::NanPolicy
+    pub fn put(&self, score: S, item: T) {
+        let lane = &self.lanes[self.route()];
+        let mut pq = lane.lock();
+        pq.put(score, item);
+        lane.len.store(pq.len(), Ordering::Relaxed);
+        lane.puts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Inserts clones of all the elements of a batch in the queue picked by
+    /// the routing, under a single lock.
+    ///
+    /// # Panics
+    ///
+    /// Same as [`put`](QueuePool::put).
+    pub fn put_batch(&self, batch: &[(S, T)])
+    where
+        S: Clone,
+        T: Clone,
+    {
+        let lane = &self.lanes[self.route()];
+        let mut pq = lane.lock();
+        pq.extend_from_slice(batch);
+        lane.len.store(pq.len(), Ordering::Relaxed);
+        lane.puts.fetch_add(batch.len() as u64, Ordering::Relaxed);
+    }
+
+    /// Get the better of the top elements of two queues chosen at random,
+    /// or of any other queue if both of them are empty. Returns `None` only
+    /// if all the queues were empty when they were checked.
+    pub fn pop(&self) -> Option<(S, T)> {
+        let queues = self.lanes.len();
+        let first = rand::random::<usize>() % queues;
+        if queues > 1 {
+            let second = (first + 1 + rand::random::<usize>() % (queues - 1)) % queues;
+            // locked in the order of their indexes, so threads don't deadlock
+            let (a, b) = (first.min(second), first.max(second));
+            let (mut pq_a, mut pq_b) = (self.lanes[a].lock(), self.lanes[b].lock());
+            let take_b = match (pq_a.peek(), pq_b.peek()) {
+                (Some((lhs, _)), Some((rhs, _))) => sift::precedes::<S, O>(rhs, lhs),
+                (None, Some(_)) => true,
+                _ => false,
+            };
+            let elem = if take_b {
+                self.lanes[b].pop(&mut pq_b)
+            } else {
+                self.lanes[a].pop(&mut pq_a)
+            };
+            if elem.is_some() {
+                return elem
+            }
+        }
+        (0..queues).find_map(|i| {
+            let lane = &self.lanes[(first + i) % queues];
+            lane.pop(&mut lane.lock())
+        })
+    }
+
+    /// Returns the number of queues in the pool.
+    pub fn queues(&self) -> usize {
+        self.lanes.len()
+    }
+
+    /// Returns the routing of new elements.
+    pub fn routing(&self) -> Routing {
+        self.routing
+    }
+
+    /// Returns the statistics of every queue of the pool.
+    pub fn stats(&self) -> Vec<QueueStats> {
+        self.lanes.iter().map(|lane| QueueStats {
+            len: lane.len.load(Ordering::Relaxed),
+            puts: lane.puts.load(Ordering::Relaxed),
+            pops: lane.pops.load(Ordering::Relaxed),
+            contended: lane.contended.load(Ordering::Relaxed),
+        }).collect()
+    }
+
+    /// Returns the number of elements in all the queues. Other threads can
+    /// change it while the queues are counted, so it's only an estimate.
+    pub fn len(&self) -> usize {
+        self.lanes.iter().map(|lane| lane.len.load(Ordering::Relaxed)).sum()
+    }
+
+    /// Returns `true` is there are no elements in `QueuePool`
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all the elements from `QueuePool`
+    pub fn clear(&self) {
+        for lane in self.lanes.iter() {
+            lane.lock().clear();
+            lane.len.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Consumes the pool and returns the elements of all the queues sorted
+    /// by their priority, the top one first.
+    pub fn into_sorted_vec(self) -> Vec<(S, T)> {
+        let mut pq = PriorityQueue::<S, T, O>::default();
+        for lane in self.lanes.into_vec() {
+            let lane = lane.pq.into_inner().unwrap_or_else(PoisonError::into_inner);
+            pq.put_many(lane.into_vec());
+        }
+        pq.into_sorted_vec()
+    }
+}
+
+impl<S, T, O> fmt::Debug for QueuePool<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueuePool")
+         .field("routing", &self.routing)
+         .field("stats", &self.stats())
+         .finish()
+    }
+}
+
+/// The error returned when an element can't be put in a full
+/// [`BoundedPriorityQueue`]. Gives back the element which couldn't be put.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Full<E>(pub E);
+
+impl<E> Full<E> {
+    /// Consumes the error returning the element that was not put in the
+    /// queue.
+    pub fn into_inner(self) -> E {
+        self.0
+    }
+}
+
+impl<E> fmt::Debug for Full<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Full").finish_non_exhaustive()
+    }
+}
+
+impl<E> fmt::Display for Full<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("failed to put an element: queue is full")
+    }
+}
+
+impl<E> Error for Full<E> {}
+
+struct BoundedState<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    pq: PriorityQueue<S, T, O>,
+    /// Tasks waiting for an element to pop.
+    poppers: Vec<Waker>,
+    /// Tasks waiting for space to put an element.
+    putters: Vec<Waker>,
+}
+
+/// Registers the task of the context to be woken up, unless it's already
+/// registered.
+fn register(wakers: &mut Vec<Waker>, cx: &Context<'_>) {
+    if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+        wakers.push(cx.waker().clone());
+    }
+}
+
+/// A [`PriorityQueue`] shared between threads which holds at most a fixed
+/// number of elements, so fast producers have to wait for the consumers
+/// instead of growing the queue without a limit.
+///
+/// Producers can block the thread with [`put`] until there is space, give
+/// up right away with [`try_put`], or wait in async code with
+/// [`put_async`]. Consumers have the same choices when the queue is empty.
+///
+/// [`put`]: BoundedPriorityQueue::put
+/// [`try_put`]: BoundedPriorityQueue::try_put
+/// [`put_async`]: BoundedPriorityQueue::put_async
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use priq::sync::BoundedPriorityQueue;
+///
+/// let pq = Arc::new(BoundedPriorityQueue::new(2));
+/// pq.put(2, "b");
+/// pq.put(1, "a");
+/// assert!(pq.try_put(3, "c").is_err());
+///
+/// let producer = {
+///     let pq = Arc::clone(&pq);
+///     thread::spawn(move || pq.put(3, "c"))
+/// };
+/// assert_eq!((1, "a"), pq.pop_wait());
+/// producer.join().unwrap();
+/// assert_eq!(2, pq.len());
+/// ```
+pub struct BoundedPriorityQueue<S, T, O = Min>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    state: Mutex<BoundedState<S, T, O>>,
+    capacity: usize,
+    nonempty: Condvar,
+    nonfull: Condvar,
+}
+
+impl<S, T> BoundedPriorityQueue<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `BoundedPriorityQueue` where the lowest score is on
+    /// top, which holds at most `capacity` elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        BoundedPriorityQueue::from_queue(PriorityQueue::new(), capacity)
+    }
+}
+
+impl<S, T> BoundedPriorityQueue<S, T, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `BoundedPriorityQueue` where the highest score is on
+    /// top, which holds at most `capacity` elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    #[must_use]
+    pub fn new_max(capacity: usize) -> Self {
+        BoundedPriorityQueue::from_queue(PriorityQueue::new_max(), capacity)
+    }
+}
+
+impl<S, T, O> BoundedPriorityQueue<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Wraps an existing priority queue, keeping its elements and settings,
+    /// limiting it to `capacity` elements. Elements it already holds over
+    /// the capacity are kept, and new ones have to wait until they are
+    /// popped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn from_queue(pq: PriorityQueue<S, T, O>, capacity: usize) -> Self {
+        assert_ne!(capacity, 0, "Capacity can't be zero");
+        BoundedPriorityQueue {
+            state: Mutex::new(BoundedState { pq, poppers: Vec::new(), putters: Vec::new() }),
+            capacity,
+            nonempty: Condvar::new(),
+            nonfull: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BoundedState<S, T, O>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Puts the element if there is space and wakes up a consumer.
+    fn put_locked(&self, state: &mut BoundedState<S, T, O>, elem: (S, T)) -> Result<(), Full<(S, T)>> {
+        if state.pq.len() >= self.capacity {
+            return Err(Full(elem))
+        }
+        state.pq.put(elem.0, elem.1);
+        self.nonempty.notify_one();
+        state.poppers.drain(..).for_each(Waker::wake);
+        Ok(())
+    }
+
+    /// Pops the top element if there is one and wakes up a producer.
+    fn pop_locked(&self, state: &mut BoundedState<S, T, O>) -> Option<(S, T)> {
+        let elem = state.pq.pop()?;
+        self.nonfull.notify_one();
+        state.putters.drain(..).for_each(Waker::wake);
+        Some(elem)
+    }
+
+    /// Inserts an element, waiting until there is space for it if the
+    /// queue is full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the score is incomparable and the [`NanPolicy`] of the
+    /// queue is `Reject` or `Panic`.
+    ///
+    /// [`NanPolicy`]: crate::NanPolicy
+    pub fn put(&self, score: S, item: T) {
+        let mut state = self.nonfull
+            .wait_while(self.lock(), |state| state.pq.len() >= self.capacity)
+            .unwrap_or_else(PoisonError::into_inner);
+        let res = self.put_locked(&mut state, (score, item));
+        debug_assert!(res.is_ok());
+    }
+
+    /// Inserts an element if there is space for it, or gives it back
+    /// without waiting.
+    ///
+    /// # Panics
+    ///
+    /// Same as [`put`](BoundedPriorityQueue::put).
+    pub fn try_put(&self, score: S, item: T) -> Result<(), Full<(S, T)>> {
+        self.put_locked(&mut self.lock(), (score, item))
+    }
+
+    /// Inserts an element, waiting at most `timeout` until there is space
+    /// for it. Gives the element back if the time is up.
+    ///
+    /// # Panics
+    ///
+    /// Same as [`put`](BoundedPriorityQueue::put).
+    pub fn put_timeout(&self, score: S, item: T, timeout: Duration) -> Result<(), Full<(S, T)>> {
+        let (mut state, _) = self.nonfull
+            .wait_timeout_while(self.lock(), timeout, |state| state.pq.len() >= self.capacity)
+            .unwrap_or_else(PoisonError::into_inner);
+        self.put_locked(&mut state, (score, item))
+    }
+
+    /// Inserts an element, waiting without blocking the thread until there
+    /// is space for it if the queue is full.
+    ///
+    /// # Panics
+    ///
+    /// Same as [`put`](BoundedPriorityQueue::put).
+    pub async fn put_async(&self, score: S, item: T) {
+        let mut elem = Some((score, item));
+        future::poll_fn(|cx| {
+            let mut state = self.lock();
+            let pending = elem.take().expect("element is put once");
+            match self.put_locked(&mut state, pending) {
+                Ok(()) => Poll::Ready(()),
+                Err(Full(pending)) => {
+                    elem = Some(pending);
+                    register(&mut state.putters, cx);
+                    Poll::Pending
+                },
+            }
+        }).await
+    }
+
+    /// Get the top priority element if there is one, without waiting.
+    pub fn pop(&self) -> Option<(S, T)> {
+        self.pop_locked(&mut self.lock())
+    }
+
+    /// Get the top priority element, waiting until one is put if the queue
+    /// is empty.
+    pub fn pop_wait(&self) -> (S, T) {
+        let mut state = self.nonempty
+            .wait_while(self.lock(), |state| state.pq.is_empty())
+            .unwrap_or_else(PoisonError::into_inner);
+        self.pop_locked(&mut state).expect("queue is not empty")
+    }
+
+    /// Get the top priority element, waiting at most `timeout` until one is
+    /// put if the queue is empty. Returns `None` if the time is up.
+    pub fn pop_timeout(&self, timeout: Duration) -> Option<(S, T)> {
+        let (mut state, _) = self.nonempty
+            .wait_timeout_while(self.lock(), timeout, |state| state.pq.is_empty())
+            .unwrap_or_else(PoisonError::into_inner);
+        self.pop_locked(&mut state)
+    }
+
+    /// Get the top priority element, waiting without blocking the thread
+    /// until one is put if the queue is empty.
+    pub async fn pop_async(&self) -> (S, T) {
+        future::poll_fn(|cx| {
+            let mut state = self.lock();
+            match self.pop_locked(&mut state) {
+                Some(elem) => Poll::Ready(elem),
+                None => {
+                    register(&mut state.poppers, cx);
+                    Poll::Pending
+                },
+            }
+        }).await
+    }
+
+    /// Returns the maximum number of elements in the queue.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of elements in the `BoundedPriorityQueue`
+    pub fn len(&self) -> usize {
+        self.lock().pq.len()
+    }
+
+    /// Returns `true` is there are no elements in `BoundedPriorityQueue`
+    pub fn is_empty(&self) -> bool {
+        self.lock().pq.is_empty()
+    }
+
+    /// Returns `true` if no more elements can be put until some are popped.
+    pub fn is_full(&self) -> bool {
+        self.lock().pq.len() >= self.capacity
+    }
+
+    /// Remove all the elements from `BoundedPriorityQueue`, waking up all
+    /// the waiting producers.
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.pq.clear();
+        self.nonfull.notify_all();
+        state.putters.drain(..).for_each(Waker::wake);
+    }
+
+    /// Consumes the wrapper and returns the priority queue.
+    pub fn into_inner(self) -> PriorityQueue<S, T, O> {
+        self.state.into_inner().unwrap_or_else(PoisonError::into_inner).pq
+    }
+}
+
+impl<S, T, O> fmt::Debug for BoundedPriorityQueue<S, T, O>
+where
+    S: PartialOrd + fmt::Debug,
+    T: fmt::Debug,
+    O: Order,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundedPriorityQueue")
+         .field("pq", &self.lock().pq)
+         .field("capacity", &self.capacity)
+         .finish()
+    }
+}
+
+/// Checks if the element would become the new top of the queue.
+fn becomes_top<S, T, O>(pq: &PriorityQueue<S, T, O>, elem: &(S, T)) -> bool
+where
+    S: PartialOrd,
+    O: Order,
+{
+    pq.peek().is_none_or(|top| pq.precedes(elem, top))
+}
+
+/// Number of times the top of a queue was replaced by a new element, with
+/// the tasks and threads waiting for it to change.
+#[derive(Default)]
+struct TopSignal {
+    version: Mutex<(u64, Vec<Waker>)>,
+    changed: Condvar,
+}
+
+impl TopSignal {
+    fn lock(&self) -> MutexGuard<'_, (u64, Vec<Waker>)> {
+        self.version.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn fire(&self) {
+        let mut version = self.lock();
+        version.0 += 1;
+        let wakers = mem::take(&mut version.1);
+        drop(version);
+        self.changed.notify_all();
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+/// Receiver of the notifications about new elements which become the top
+/// of a queue, created by [`BlockingPriorityQueue::watch_top`] or
+/// [`AsyncPriorityQueue::watch_top`].
+///
+/// Notifications are not queued: any number of changes since the last
+/// one was seen counts as a single one. Popping elements doesn't count as
+/// a change, as a consumer which pops knows about it already.
+pub struct TopWatch {
+    signal: Arc<TopSignal>,
+    seen: u64,
+}
+
+impl TopWatch {
+    fn new(signal: &Arc<TopSignal>) -> Self {
+        TopWatch { signal: Arc::clone(signal), seen: signal.lock().0 }
+    }
+
+    /// Checks if the top changed since it was last seen, without marking
+    /// the change as seen.
+    pub fn has_changed(&self) -> bool {
+        self.signal.lock().0 != self.seen
+    }
+
+    /// Marks the changes so far as seen.
+    pub fn mark_seen(&mut self) {
+        self.seen = self.signal.lock().0;
+    }
+
+    /// Blocks the current thread until the top changes, unless it already
+    /// changed since it was last seen.
+    pub fn wait(&mut self) {
+        let version = self.signal.changed
+            .wait_while(self.signal.lock(), |version| version.0 == self.seen)
+            .unwrap_or_else(PoisonError::into_inner);
+        self.seen = version.0;
+    }
+
+    /// Blocks the current thread until the top changes, for at most
+    /// `timeout`. Returns `true` if the top changed.
+    pub fn wait_timeout(&mut self, timeout: Duration) -> bool {
+        let (version, _) = self.signal.changed
+            .wait_timeout_while(self.signal.lock(), timeout, |version| version.0 == self.seen)
+            .unwrap_or_else(PoisonError::into_inner);
+        let changed = version.0 != self.seen;
+        self.seen = version.0;
+        changed
+    }
+
+    /// Attempts to see a change of the top. If there is none, the task of
+    /// the context is woken up on the next one.
+    pub fn poll_changed(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let mut version = self.signal.lock();
+        if version.0 != self.seen {
+            self.seen = version.0;
+            return Poll::Ready(())
+        }
+        register(&mut version.1, cx);
+        Poll::Pending
+    }
+
+    /// Waits without blocking the thread until the top changes, unless it
+    /// already changed since it was last seen.
+    pub async fn changed(&mut self) {
+        future::poll_fn(|cx| self.poll_changed(cx)).await
+    }
+}
+
+impl Clone for TopWatch {
+    fn clone(&self) -> Self {
+        TopWatch { signal: Arc::clone(&self.signal), seen: self.seen }
+    }
+}
+
+impl fmt::Debug for TopWatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TopWatch")
+         .field("seen", &self.seen)
+         .finish()
+    }
+}
@@ -0,0 +1,188 @@
+//! Helpers for property testing code built on priority queues.
+//!
+//! * [`queue`] and [`queue_max`] are [`proptest`](https://docs.rs/proptest)
+//!   strategies generating priority queues.
+//! * [`assert_heap_invariant`] checks that every element of a queue is in a
+//!   valid place.
+//! * [`ops`] generates sequences of operations, which [`check_model`] runs
+//!   against any [`HeapBackend`] and a sorted `Vec` as the reference model.
+//!
+//! # Examples
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use priq::{Min, PairingHeap};
+//! use priq::testing::{assert_heap_invariant, check_model, ops, queue};
+//!
+//! proptest! {
+//!     fn heap_is_valid(pq in queue(any::<u16>(), any::<u8>(), 0..100)) {
+//!         assert_heap_invariant(&pq);
+//!     }
+//!
+//!     fn pairing_heap_matches_model(ops in ops(any::<u16>(), any::<u8>(), 0..100)) {
+//!         check_model(&mut PairingHeap::new(), Min, &ops);
+//!     }
+//! }
+//! # heap_is_valid();
+//! # pairing_heap_matches_model();
+//! ```
+
+use std::fmt::Debug;
+
+use proptest::collection::{vec, SizeRange};
+use proptest::prelude::*;
+
+use crate::{HeapBackend, Max, Order, PriorityQueue};
+
+/// An operation on a priority queue, generated by [`ops`] and run by
+/// [`check_model`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Op<S, T> {
+    /// Put an element in the queue.
+    Put(S, T),
+    /// Pop the top element.
+    Pop,
+    /// Look at the top element.
+    Peek,
+    /// Remove all the elements.
+    Clear,
+}
+
+/// A strategy generating a [`PriorityQueue`] where the lowest score is on
+/// top, from elements with scores and items given by the `score` and `item`
+/// strategies.
+pub fn queue<S, T>(
+    score: S,
+    item: T,
+    size: impl Into<SizeRange>,
+) -> impl Strategy<Value = PriorityQueue<S::Value, T::Value>>
+where
+    S: Strategy,
+    S::Value: PartialOrd,
+    T: Strategy,
+{
+    vec((score, item), size).prop_map(PriorityQueue::from)
+}
+
+/// A strategy generating a [`PriorityQueue`] where the highest score is on
+/// top, see [`queue`].
+pub fn queue_max<S, T>(
+    score: S,
+    item: T,
+    size: impl Into<SizeRange>,
+) -> impl Strategy<Value = PriorityQueue<S::Value, T::Value, Max>>
+where
+    S: Strategy,
+    S::Value: PartialOrd,
+    T: Strategy,
+{
+    vec((score, item), size).prop_map(|elements| {
+        let mut pq = PriorityQueue::new_max();
+        pq.put_many(elements);
+        pq
+    })
+}
+
+/// A strategy generating sequences of operations on a priority queue, with
+/// elements given by the `score` and `item` strategies. Puts are generated
+/// the most often, so the queue tends to grow.
+pub fn ops<S, T>(
+    score: S,
+    item: T,
+    size: impl Into<SizeRange>,
+) -> impl Strategy<Value = Vec<Op<S::Value, T::Value>>>
+where
+    S: Strategy + 'static,
+    T: Strategy + 'static,
+{
+    let op = prop_oneof![
+        8 => (score, item).prop_map(|(score, item)| Op::Put(score, item)),
+        6 => Just(()).prop_map(|_| Op::Pop),
+        2 => Just(()).prop_map(|_| Op::Peek),
+        1 => Just(()).prop_map(|_| Op::Clear),
+    ];
+    vec(op, size)
+}
+
+/// Checks that every element of the queue precedes neither of its parents
+/// and that elements with incomparable scores are stored after the heap.
+///
+/// # Panics
+///
+/// Panics if an element is out of place, naming its index.
+pub fn assert_heap_invariant<S, T, O>(pq: &PriorityQueue<S, T, O>)
+where
+    S: PartialOrd + Debug,
+    T: Debug,
+    O: Order,
+{
+    let heap = pq.heap_len();
+    for index in 1..heap {
+        let parent = (index - 1) / 2;
+        assert!(
+            !pq.precedes(&pq[index], &pq[parent]),
+            "heap invariant violated: element {:?} at {} precedes its parent {:?} at {}",
+            pq[index], index, pq[parent], parent,
+        );
+    }
+    for (index, (score, _)) in pq.iter().enumerate().skip(heap) {
+        assert!(
+            !pq.comparable(score, score),
+            "comparable score {:?} at {} is stored with incomparable ones",
+            score, index,
+        );
+    }
+}
+
+/// Runs the operations on the `backend` and on a sorted `Vec` with the same
+/// elements, checking after every step that both agree on the length and the
+/// score of the top element, and that popped elements were put before.
+///
+/// The `order` is the direction of the backend, [`Min`] or [`Max`]. Items
+/// of elements with equal scores may be popped in any order.
+///
+/// [`Min`]: crate::Min
+///
+/// # Panics
+///
+/// Panics with the index of the operation when the backend and the model
+/// disagree.
+pub fn check_model<B, S, T, O>(backend: &mut B, _order: O, ops: &[Op<S, T>])
+where
+    B: HeapBackend<S, T>,
+    S: Ord + Clone + Debug,
+    T: PartialEq + Clone + Debug,
+    O: Order,
+{
+    // Sorted so the top element is in the back.
+    let mut model: Vec<(S, T)> = Vec::new();
+    for (step, op) in ops.iter().enumerate() {
+        match op {
+            Op::Put(score, item) => {
+                backend.put(score.clone(), item.clone());
+                let at = model.partition_point(|(s, _)| s.cmp(score) != O::TOP);
+                model.insert(at, (score.clone(), item.clone()));
+            }
+            Op::Pop => match backend.pop() {
+                Some((score, item)) => {
+                    let top = model.last().map(|(s, _)| s);
+                    assert_eq!(Some(&score), top, "step {}: popped a wrong score", step);
+                    let at = model.iter().rposition(|e| e.0 == score && e.1 == item)
+                        .unwrap_or_else(|| panic!("step {}: popped {:?} which wasn't put",
+                                                  step, (&score, &item)));
+                    model.remove(at);
+                }
+                None => assert!(model.is_empty(), "step {}: nothing popped", step),
+            },
+            Op::Peek => {
+                let top = model.last().map(|(s, _)| s);
+                assert_eq!(top, backend.peek_score(), "step {}: peeked a wrong score", step);
+            }
+            Op::Clear => {
+                backend.clear();
+                model.clear();
+            }
+        }
+        assert_eq!(model.len(), backend.len(), "step {}: wrong length", step);
+    }
+}
@@ -0,0 +1,491 @@
+//! Queues of elements which become available at a point in time.
+
+use std::fmt;
+use std::future;
+use std::iter::FusedIterator;
+use std::sync::{Arc, Condvar, Mutex, OnceLock, PoisonError};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{PriorityQueue, StablePriorityQueue};
+
+/// Waker of a task registered in the [`Timer`]. It's shared with the queue
+/// which registered it, so the waker can be updated or cancelled in place
+/// instead of adding a new entry to the timer.
+type Slot = Arc<Mutex<Option<Waker>>>;
+
+/// Background thread which wakes up tasks at their deadlines, so the queues
+/// can be awaited with any executor.
+struct Timer {
+    wakers: Mutex<PriorityQueue<Instant, Slot>>,
+    changed: Condvar,
+}
+
+impl Timer {
+    fn get() -> &'static Timer {
+        static TIMER: OnceLock<Timer> = OnceLock::new();
+        static SPAWNED: OnceLock<()> = OnceLock::new();
+        let timer = TIMER.get_or_init(|| Timer {
+            wakers: Mutex::new(PriorityQueue::new()),
+            changed: Condvar::new(),
+        });
+        SPAWNED.get_or_init(|| {
+            thread::Builder::new()
+                .name("priq-timer".into())
+                .spawn(move || timer.run())
+                .expect("failed to spawn the timer thread");
+        });
+        timer
+    }
+
+    /// Wakes up the task in the slot at the given deadline, unless the slot
+    /// is emptied by then.
+    fn wake_at(&self, deadline: Instant, slot: Slot) {
+        let mut wakers = self.wakers.lock().unwrap_or_else(PoisonError::into_inner);
+        let earlier = wakers.peek().is_none_or(|(next, _)| deadline < *next);
+        wakers.put(deadline, slot);
+        if earlier {
+            self.changed.notify_one();
+        }
+    }
+
+    fn run(&self) {
+        let mut wakers = self.wakers.lock().unwrap_or_else(PoisonError::into_inner);
+        loop {
+            let now = Instant::now();
+            while wakers.peek().is_some_and(|(deadline, _)| *deadline <= now) {
+                let (_, slot) = wakers.pop().expect("timer has a waker");
+                let waker = slot.lock().unwrap_or_else(PoisonError::into_inner).take();
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            }
+            wakers = match wakers.peek() {
+                Some((deadline, _)) => {
+                    let timeout = deadline.saturating_duration_since(now);
+                    self.changed.wait_timeout(wakers, timeout)
+                        .unwrap_or_else(PoisonError::into_inner).0
+                },
+                None => self.changed.wait(wakers).unwrap_or_else(PoisonError::into_inner),
+            };
+        }
+    }
+}
+
+/// A queue of items which expire at their deadlines, to be awaited in
+/// async code with any executor, like a foundation for timers and
+/// retries.
+///
+/// Items are yielded in the order of their deadlines once they've passed,
+/// and items with the same deadline in the order they were inserted. Items
+/// whose deadline hasn't passed yet stay in the queue. A single background
+/// thread, shared by all the queues, wakes up the waiting tasks.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use priq::time::DelayQueue;
+///
+/// let mut timeouts = DelayQueue::new();
+/// timeouts.insert("later", Duration::from_secs(60));
+/// timeouts.insert_at("now", Instant::now());
+///
+/// assert_eq!(Some("now"), timeouts.pop_expired());
+/// assert_eq!(None, timeouts.pop_expired());
+/// assert_eq!(1, timeouts.len());
+/// ```
+pub struct DelayQueue<T> {
+    pq: StablePriorityQueue<Instant, T>,
+    /// Deadline at which the timer wakes up the task polling the queue.
+    wakeup: Option<(Instant, Slot)>,
+}
+
+impl<T> DelayQueue<T> {
+    /// Create an empty `DelayQueue`.
+    #[must_use]
+    pub fn new() -> Self {
+        DelayQueue { pq: StablePriorityQueue::new(), wakeup: None }
+    }
+
+    /// Create an empty `DelayQueue` with the capacity for `cap` items.
+    #[must_use]
+    pub fn with_capacity(cap: usize) -> Self {
+        DelayQueue { pq: StablePriorityQueue::with_capacity(cap), wakeup: None }
+    }
+
+    /// Inserts an item which expires at `deadline`.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(n))***.
+    pub fn insert_at(&mut self, item: T, deadline: Instant) {
+        self.pq.put(deadline, item);
+    }
+
+    /// Inserts an item which expires after `delay` from now.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the deadline doesn't fit in an `Instant`.
+    pub fn insert(&mut self, item: T, delay: Duration) {
+        self.insert_at(item, Instant::now() + delay);
+    }
+
+    /// Get the item with the earliest deadline if it has passed, without
+    /// waiting.
+    pub fn pop_expired(&mut self) -> Option<T> {
+        let now = Instant::now();
+        match self.pq.peek() {
+            Some((deadline, _)) if *deadline <= now => self.pq.pop().map(|(_, e)| e),
+            _ => None,
+        }
+    }
+
+    /// Attempts to get the item with the earliest deadline if it has passed.
+    /// Otherwise the task of the context is woken up at the deadline.
+    /// Returns `Ready(None)` if the queue is empty.
+    pub fn poll_expired(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let deadline = match self.pq.peek() {
+            Some((deadline, _)) => *deadline,
+            None => return Poll::Ready(None),
+        };
+        if deadline <= Instant::now() {
+            return Poll::Ready(self.pq.pop().map(|(_, e)| e))
+        }
+        self.wake_at(deadline, cx.waker());
+        Poll::Pending
+    }
+
+    /// Makes the timer wake up the task at `deadline`. Polling again with
+    /// the same deadline only updates the waker of the registration, while
+    /// a new deadline cancels the old one.
+    fn wake_at(&mut self, deadline: Instant, waker: &Waker) {
+        if let Some((registered_at, slot)) = &self.wakeup {
+            let mut registered = slot.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Some(registered) = registered.as_mut().filter(|_| *registered_at == deadline) {
+                if !registered.will_wake(waker) {
+                    registered.clone_from(waker);
+                }
+                return
+            }
+            *registered = None;
+        }
+        let slot = Arc::new(Mutex::new(Some(waker.clone())));
+        Timer::get().wake_at(deadline, Arc::clone(&slot));
+        self.wakeup = Some((deadline, slot));
+    }
+
+    /// Waits until the earliest deadline passes and returns its item, or
+    /// returns `None` right away if the queue is empty.
+    pub async fn next_expired(&mut self) -> Option<T> {
+        future::poll_fn(|cx| self.poll_expired(cx)).await
+    }
+
+    /// Returns the earliest deadline in the queue.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pq.peek().map(|(deadline, _)| *deadline)
+    }
+
+    /// Returns the number of items in the `DelayQueue`, expired or not.
+    pub fn len(&self) -> usize {
+        self.pq.len()
+    }
+
+    /// Returns `true` is there are no items in `DelayQueue`
+    pub fn is_empty(&self) -> bool {
+        self.pq.is_empty()
+    }
+
+    /// Remove all the items from `DelayQueue`
+    pub fn clear(&mut self) {
+        self.pq.clear();
+    }
+}
+
+// items are never pinned
+impl<T> Unpin for DelayQueue<T> {}
+
+impl<T> Drop for DelayQueue<T> {
+    fn drop(&mut self) {
+        if let Some((_, slot)) = self.wakeup.take() {
+            *slot.lock().unwrap_or_else(PoisonError::into_inner) = None;
+        }
+    }
+}
+
+impl<T> Default for DelayQueue<T> {
+    fn default() -> Self {
+        DelayQueue::new()
+    }
+}
+
+impl<T> Extend<(T, Instant)> for DelayQueue<T> {
+    fn extend<I: IntoIterator<Item = (T, Instant)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(e, deadline)| self.insert_at(e, deadline));
+    }
+}
+
+impl<T> FromIterator<(T, Instant)> for DelayQueue<T> {
+    fn from_iter<I: IntoIterator<Item = (T, Instant)>>(iter: I) -> Self {
+        let mut queue = DelayQueue::new();
+        queue.extend(iter);
+        queue
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T> futures_core::Stream for DelayQueue<T> {
+    type Item = T;
+
+    /// Yields the items as they expire, and ends when the queue is empty.
+    fn poll_next(self: std::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.get_mut().poll_expired(cx)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for DelayQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DelayQueue")
+         .field("pq", &self.pq)
+         .finish()
+    }
+}
+
+/// Source of the current time for a [`TimerQueue`], which can be replaced
+/// with a [`ManualClock`] in tests.
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&self) -> Instant;
+}
+
+/// The clock of the system, which is used by default.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock which only moves when it's told to, for deterministic tests of
+/// the code using a [`TimerQueue`]. Share it with the queue by reference
+/// or with an [`Arc`].
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use priq::time::{ManualClock, TimerQueue};
+///
+/// let clock = ManualClock::new();
+/// let mut timers = TimerQueue::with_clock(&clock);
+/// timers.schedule_in(Duration::from_secs(5), "retry");
+///
+/// assert_eq!(None, timers.expired().next());
+/// clock.advance(Duration::from_secs(5));
+/// assert_eq!(Some("retry"), timers.expired().next());
+/// ```
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Mutex<Instant>,
+}
+
+impl ManualClock {
+    /// Create a clock which stands at the current time of the system.
+    #[must_use]
+    pub fn new() -> Self {
+        ManualClock::starting_at(Instant::now())
+    }
+
+    /// Create a clock which stands at the given time.
+    #[must_use]
+    pub fn starting_at(now: Instant) -> Self {
+        ManualClock { now: Mutex::new(now) }
+    }
+
+    /// Moves the clock forward.
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap_or_else(PoisonError::into_inner) += by;
+    }
+
+    /// Sets the time of the clock, which may also move it backwards.
+    pub fn set(&self, now: Instant) {
+        *self.now.lock().unwrap_or_else(PoisonError::into_inner) = now;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        ManualClock::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+}
+
+/// A queue of timers for synchronous code, like event loops which check
+/// for expired timers on every turn. Timers with the same deadline expire
+/// in the order they were scheduled.
+///
+/// The current time comes from a [`Clock`], the system one by default.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use priq::time::TimerQueue;
+///
+/// let mut timers = TimerQueue::new();
+/// let now = Instant::now();
+/// timers.schedule_at(now + Duration::from_secs(1), "heartbeat");
+/// timers.schedule_at(now, "flush");
+///
+/// let expired: Vec<_> = timers.pop_expired(now).collect();
+/// assert_eq!(vec!["flush"], expired);
+/// assert_eq!(Some(now + Duration::from_secs(1)), timers.next_deadline());
+/// ```
+pub struct TimerQueue<T, C = SystemClock> {
+    pq: StablePriorityQueue<Instant, T>,
+    clock: C,
+}
+
+impl<T> TimerQueue<T> {
+    /// Create an empty `TimerQueue` using the clock of the system.
+    #[must_use]
+    pub fn new() -> Self {
+        TimerQueue::with_clock(SystemClock)
+    }
+}
+
+impl<T, C: Clock> TimerQueue<T, C> {
+    /// Create an empty `TimerQueue` using the given clock.
+    pub fn with_clock(clock: C) -> Self {
+        TimerQueue { pq: StablePriorityQueue::new(), clock }
+    }
+
+    /// Schedules an item to expire at `deadline`.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(n))***.
+    pub fn schedule_at(&mut self, deadline: Instant, item: T) {
+        self.pq.put(deadline, item);
+    }
+
+    /// Schedules an item to expire after `delay` from the current time of
+    /// the clock.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the deadline doesn't fit in an `Instant`.
+    pub fn schedule_in(&mut self, delay: Duration, item: T) {
+        let deadline = self.clock.now() + delay;
+        self.schedule_at(deadline, item);
+    }
+
+    /// Returns the earliest deadline in the queue.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pq.peek().map(|(deadline, _)| *deadline)
+    }
+
+    /// Returns how long it is until the earliest deadline by the clock, zero
+    /// if it has already passed.
+    pub fn time_until_next(&self) -> Option<Duration> {
+        self.next_deadline()
+            .map(|deadline| deadline.saturating_duration_since(self.clock.now()))
+    }
+
+    /// Returns an iterator which removes the items with deadlines up to and
+    /// including `now`, the earliest first. The items which are not
+    /// iterated over stay in the queue.
+    pub fn pop_expired(&mut self, now: Instant) -> PopExpired<'_, T> {
+        PopExpired { pq: &mut self.pq, now }
+    }
+
+    /// Returns an iterator which removes the items expired by the current
+    /// time of the clock, see [`pop_expired`].
+    ///
+    /// [`pop_expired`]: TimerQueue::pop_expired
+    pub fn expired(&mut self) -> PopExpired<'_, T> {
+        let now = self.clock.now();
+        self.pop_expired(now)
+    }
+
+    /// Returns the clock of the queue.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Returns the number of items in the `TimerQueue`, expired or not.
+    pub fn len(&self) -> usize {
+        self.pq.len()
+    }
+
+    /// Returns `true` is there are no items in `TimerQueue`
+    pub fn is_empty(&self) -> bool {
+        self.pq.is_empty()
+    }
+
+    /// Remove all the items from `TimerQueue`
+    pub fn clear(&mut self) {
+        self.pq.clear();
+    }
+}
+
+impl<T, C: Clock + Default> Default for TimerQueue<T, C> {
+    fn default() -> Self {
+        TimerQueue::with_clock(C::default())
+    }
+}
+
+impl<T, C> fmt::Debug for TimerQueue<T, C>
+where
+    T: fmt::Debug,
+    C: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TimerQueue")
+         .field("pq", &self.pq)
+         .field("clock", &self.clock)
+         .finish()
+    }
+}
+
+/// Iterator over the expired items of a [`TimerQueue`], created by
+/// [`TimerQueue::pop_expired`].
+#[derive(Debug)]
+pub struct PopExpired<'a, T> {
+    pq: &'a mut StablePriorityQueue<Instant, T>,
+    now: Instant,
+}
+
+impl<T> Iterator for PopExpired<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match self.pq.peek() {
+            Some((deadline, _)) if *deadline <= self.now => self.pq.pop().map(|(_, e)| e),
+            _ => None,
+        }
+    }
+}
+
+impl<T> FusedIterator for PopExpired<'_, T> {}
@@ -0,0 +1,214 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::{Max, Min, Order};
+
+/// Collects the `k` best elements of an unbounded stream, the ones which
+/// would be popped first from a [`PriorityQueue`] with the same [`Order`].
+/// Memory stays bounded by `k`, as the elements are kept in a heap with the
+/// worst of them on top, which is evicted whenever a better one is offered.
+///
+/// Scores that can't be compared even with themselves, like `NAN`, are
+/// worse than any other score.
+///
+/// [`PriorityQueue`]: crate::PriorityQueue
+///
+/// # Examples
+///
+/// ```
+/// use priq::TopK;
+///
+/// let mut best = TopK::new_max(2);
+/// for (score, name) in [(7, "b"), (9, "a"), (3, "d"), (5, "c")] {
+///     best.offer(score, name);
+/// }
+///
+/// assert_eq!(Some(&(7, "b")), best.peek_worst());
+/// assert_eq!(vec![(9, "a"), (7, "b")], best.into_sorted_vec());
+/// ```
+#[derive(Clone)]
+pub struct TopK<S, T, O = Min> {
+    data: Vec<(S, T)>,
+    k: usize,
+    _order: PhantomData<O>,
+}
+
+impl<S, T> TopK<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `TopK` which keeps `k` elements with the lowest
+    /// scores.
+    #[must_use]
+    pub fn new(k: usize) -> Self {
+        TopK::with_k(k)
+    }
+}
+
+impl<S, T> TopK<S, T, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `TopK` which keeps `k` elements with the highest
+    /// scores.
+    #[must_use]
+    pub fn new_max(k: usize) -> Self {
+        TopK::with_k(k)
+    }
+}
+
+impl<S, T, O> TopK<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn with_k(k: usize) -> Self {
+        TopK { data: Vec::with_capacity(k), k, _order: PhantomData }
+    }
+
+    /// Offers an element to the collection. If it's full, either the worst
+    /// kept element is evicted to make room for the offered one, or the
+    /// offered one itself is rejected, and the element that didn't make it
+    /// is returned.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(k))***.
+    pub fn offer(&mut self, score: S, item: T) -> Option<(S, T)> {
+        if self.data.len() < self.k {
+            self.data.push((score, item));
+            self.heapify_up(self.data.len() - 1);
+            return None
+        }
+        match self.data.first() {
+            Some((worst, _)) if self.worse(worst, &score) => {
+                let evicted = core::mem::replace(&mut self.data[0], (score, item));
+                self.heapify_down(0);
+                Some(evicted)
+            },
+            _ => Some((score, item)),
+        }
+    }
+
+    /// Check what is the worst of the kept elements, the one an offered
+    /// element has to beat once the collection is full.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek_worst(&self) -> Option<&(S, T)> {
+        self.data.first()
+    }
+
+    /// Returns how many elements are kept at most.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Returns the number of kept elements.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if no element is kept.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns `true` if `k` elements are kept, so an offered element has
+    /// to beat the worst one.
+    pub fn is_full(&self) -> bool {
+        self.data.len() == self.k
+    }
+
+    /// Remove all the kept elements.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Consumes the collection and returns the kept elements in arbitrary
+    /// order.
+    pub fn into_vec(self) -> Vec<(S, T)> {
+        self.data
+    }
+
+    /// Consumes the collection and returns the kept elements sorted from
+    /// the best to the worst.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(k log(k))***
+    pub fn into_sorted_vec(mut self) -> Vec<(S, T)> {
+        let mut res = Vec::with_capacity(self.data.len());
+        while !self.data.is_empty() {
+            res.push(self.data.swap_remove(0));
+            if self.data.len() > 1 { self.heapify_down(0); }
+        }
+        res.reverse();
+        res
+    }
+
+    /// Checks if `lhs` is worse than `rhs`, so it's placed closer to the
+    /// root of the heap.
+    #[inline]
+    fn worse(&self, lhs: &S, rhs: &S) -> bool {
+        match lhs.partial_cmp(rhs) {
+            Some(ord) => ord == O::TOP.reverse(),
+            None => lhs.partial_cmp(lhs).is_none()
+                    && rhs.partial_cmp(rhs).is_some(),
+        }
+    }
+
+    fn heapify_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent_ = (index - 1) / 2;
+            if !self.worse(&self.data[index].0, &self.data[parent_].0) {
+                break
+            }
+            self.data.swap(parent_, index);
+            index = parent_;
+        }
+    }
+
+    fn heapify_down(&mut self, mut index: usize) {
+        loop {
+            let (_left, _right) = (2 * index + 1, 2 * index + 2);
+            let mut max_ = index;
+            if _left < self.len() && self.worse(&self.data[_left].0, &self.data[max_].0) {
+                max_ = _left;
+            }
+            if _right < self.len() && self.worse(&self.data[_right].0, &self.data[max_].0) {
+                max_ = _right;
+            }
+            if max_ == index {
+                break
+            }
+            self.data.swap(index, max_);
+            index = max_;
+        }
+    }
+}
+
+impl<S, T, O> Extend<(S, T)> for TopK<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(s, e)| { self.offer(s, e); });
+    }
+}
+
+impl<S, T, O> fmt::Debug for TopK<S, T, O>
+where
+    S: fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TopK")
+         .field("k", &self.k)
+         .field("data", &self.data)
+         .finish()
+    }
+}
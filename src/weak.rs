@@ -0,0 +1,259 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::{Max, Min, Order};
+use crate::sift;
+
+/// A weak heap, which does fewer score comparisons than a binary heap. It
+/// suits scores with an expensive `PartialOrd`, when the number of
+/// comparisons matters more than the number of moves.
+///
+/// Every node of a weak heap is only ordered against its right subtree, and
+/// the roles of its two children can be swapped by flipping a single bit.
+/// Popping takes at most ***log(n)*** comparisons, against up to
+/// ***2log(n)*** of a binary heap, putting takes a constant number of them
+/// on average, and building the heap from `n` elements takes exactly
+/// ***n - 1*** of them.
+///
+/// Elements are ordered by their scores the same way as in
+/// [`PriorityQueue`], including the direction given by the [`Order`] type
+/// parameter.
+///
+/// [`PriorityQueue`]: crate::PriorityQueue
+///
+/// # Examples
+///
+/// ```
+/// use priq::WeakHeap;
+///
+/// let mut pq: WeakHeap<_, _> = [(3, "c"), (1, "a"), (2, "b")].into_iter().collect();
+///
+/// assert_eq!(Some(&(1, "a")), pq.peek());
+/// assert_eq!(Some((1, "a")), pq.pop());
+/// assert_eq!(Some((2, "b")), pq.pop());
+/// ```
+#[derive(Clone)]
+pub struct WeakHeap<S, T, O = Min> {
+    data: Vec<(S, T)>,
+    /// Reverse bits of the nodes, a set one swaps the children of a node.
+    reverse: Vec<bool>,
+    _order: PhantomData<O>,
+}
+
+impl<S, T> WeakHeap<S, T>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `WeakHeap` where the lowest score is on top.
+    #[must_use]
+    pub fn new() -> Self {
+        WeakHeap::default()
+    }
+
+    /// Create an empty `WeakHeap` with space for at least `cap` elements.
+    #[must_use]
+    pub fn with_capacity(cap: usize) -> Self {
+        WeakHeap {
+            data: Vec::with_capacity(cap),
+            reverse: Vec::with_capacity(cap),
+            _order: PhantomData,
+        }
+    }
+}
+
+impl<S, T> WeakHeap<S, T, Max>
+where
+    S: PartialOrd,
+{
+    /// Create an empty `WeakHeap` where the highest score is on top.
+    #[must_use]
+    pub fn new_max() -> Self {
+        WeakHeap::default()
+    }
+}
+
+impl<S, T, O> WeakHeap<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    /// Builds the heap from the elements in any order.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(n)***, with exactly `n - 1` score comparisons.
+    pub fn from_vec(data: Vec<(S, T)>) -> Self {
+        let reverse = vec![false; data.len()];
+        let mut pq = WeakHeap { data, reverse, _order: PhantomData };
+        for index in (1..pq.data.len()).rev() {
+            pq.join(Self::ancestor(&pq.reverse, index), index);
+        }
+        pq
+    }
+
+    /// Inserts an element in the heap.
+    ///
+    /// # Time Complexity
+    ///
+    /// For worst case scenario ***O(log(n))***, ***O(1)*** on average.
+    pub fn put(&mut self, score: S, item: T) {
+        let mut index = self.data.len();
+        self.data.push((score, item));
+        self.reverse.push(false);
+        if index.is_multiple_of(2) {
+            // the new node is the only child of its parent, keep it on the
+            // left
+            self.reverse[index / 2] = false;
+        }
+        while index > 0 {
+            let ancestor = Self::ancestor(&self.reverse, index);
+            if self.join(ancestor, index) {
+                break
+            }
+            index = ancestor;
+        }
+    }
+
+    /// Get the top priority element from `WeakHeap`.
+    ///
+    /// # Time Complexity
+    ///
+    /// Worst case is ***O(log(n))***.
+    pub fn pop(&mut self) -> Option<(S, T)> {
+        if self.data.is_empty() {
+            return None
+        }
+        let top = self.data.swap_remove(0);
+        self.reverse.pop();
+        if self.data.len() > 1 { self.sift_down(); }
+        Some(top)
+    }
+
+    /// Check what is a top element in `WeakHeap`, by getting the reference.
+    ///
+    /// # Time Complexity
+    ///
+    /// ***O(1)***
+    pub fn peek(&self) -> Option<&(S, T)> {
+        self.data.first()
+    }
+
+    /// Returns the number of elements in the `WeakHeap`
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` is there are no elements in `WeakHeap`
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Remove all the elements from `WeakHeap`
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.reverse.clear();
+    }
+
+    /// Checks if `lhs` should be placed closer to the root than `rhs`.
+    /// Scores that can't be compared even with themselves sink to the back.
+    #[inline]
+    fn less(&self, lhs: usize, rhs: usize) -> bool {
+        let (lhs, rhs) = (&self.data[lhs].0, &self.data[rhs].0);
+        sift::precedes::<S, O>(lhs, rhs)
+    }
+
+    /// Finds the distinguished ancestor of a node, the one which the node
+    /// is ordered against: the parent of the first node on the way up
+    /// which is a right child.
+    #[inline]
+    fn ancestor(reverse: &[bool], mut index: usize) -> usize {
+        while (index & 1 == 1) == reverse[index / 2] {
+            index /= 2;
+        }
+        index / 2
+    }
+
+    /// Orders a node against its distinguished ancestor, swapping the
+    /// elements and the children of the node if needed. Returns `true` if
+    /// they were already in order.
+    #[inline]
+    fn join(&mut self, ancestor: usize, index: usize) -> bool {
+        if self.less(index, ancestor) {
+            self.data.swap(ancestor, index);
+            self.reverse[index] = !self.reverse[index];
+            return false
+        }
+        true
+    }
+
+    /// Restores the order after the root is replaced, by joining it with
+    /// the nodes on the leftmost path of its right subtree, bottom up.
+    fn sift_down(&mut self) {
+        let len = self.data.len();
+        let mut index = 1;
+        loop {
+            let _left = 2 * index + self.reverse[index] as usize;
+            if _left >= len {
+                break
+            }
+            index = _left;
+        }
+        while index > 0 {
+            self.join(0, index);
+            index /= 2;
+        }
+    }
+}
+
+impl<S, T, O> Default for WeakHeap<S, T, O> {
+    fn default() -> Self {
+        WeakHeap { data: Vec::new(), reverse: Vec::new(), _order: PhantomData }
+    }
+}
+
+impl<S, T> FromIterator<(S, T)> for WeakHeap<S, T>
+where
+    S: PartialOrd,
+{
+    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
+        WeakHeap::from_vec(iter.into_iter().collect())
+    }
+}
+
+impl<S, T, O> From<Vec<(S, T)>> for WeakHeap<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn from(data: Vec<(S, T)>) -> Self {
+        WeakHeap::from_vec(data)
+    }
+}
+
+impl<S, T, O> Extend<(S, T)> for WeakHeap<S, T, O>
+where
+    S: PartialOrd,
+    O: Order,
+{
+    fn extend<I: IntoIterator<Item = (S, T)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        self.data.reserve(lower);
+        self.reverse.reserve(lower);
+        iter.for_each(|(s, e)| self.put(s, e));
+    }
+}
+
+impl<S, T, O> fmt::Debug for WeakHeap<S, T, O>
+where
+    S: fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeakHeap")
+         .field("data", &self.data)
+         .finish()
+    }
+}
@@ -1,4 +1,4 @@
-#![feature(test)]
+#![cfg_attr(feature = "std", feature(allocator_api))]
 
 use priq::PriorityQueue;
 
@@ -367,3 +367,3620 @@ fn pq_points_as_scores() {
     pq.put((6, 7), ());
     println!("{:?}", pq.into_sorted_vec());
 }
+
+#[test]
+fn pq_drain_to_with_predicate() {
+    let mut pq1 = PriorityQueue::from([(5, 55), (1, 11), (4, 44), (2, 22)]);
+    let mut pq2 = PriorityQueue::from([(3, 33), (6, 66)]);
+    pq1.drain_to(&mut pq2, |(s, _): &(usize, usize)| *s > 3);
+
+    assert_eq!(2, pq1.len());
+    assert_eq!(4, pq2.len());
+    assert_eq!(11, pq1.pop().unwrap().1);
+    assert_eq!(22, pq1.pop().unwrap().1);
+    (3..=6).for_each(|i| assert_eq!(i * 11, pq2.pop().unwrap().1));
+}
+
+#[test]
+fn pq_drain_to_with_selector() {
+    let mut pq1 = PriorityQueue::from([(5, "e"), (1, "a"), (4, "d"), (2, "b")]);
+    let mut pq2 = PriorityQueue::new();
+    pq1.drain_to(&mut pq2, priq::selector(|(_, e)| *e < "c"));
+
+    assert_eq!(vec![(4, "d"), (5, "e")], pq1.into_sorted_vec());
+    assert_eq!(vec![(1, "a"), (2, "b")], pq2.into_sorted_vec());
+}
+
+#[test]
+fn pq_drain_to_with_range() {
+    let mut pq1: PriorityQueue<usize, String> = PriorityQueue::new();
+    let mut pq2 = PriorityQueue::new();
+    (0..10).for_each(|i| pq1.put(i, i.to_string()));
+    pq1.drain_to(&mut pq2, 3..7);
+
+    assert_eq!(6, pq1.len());
+    assert_eq!(4, pq2.len());
+    let mut scores: Vec<usize> = Vec::new();
+    while let Some((s, _)) = pq1.pop() { scores.push(s); }
+    while let Some((s, _)) = pq2.pop() { scores.push(s); }
+    scores.sort();
+    assert_eq!((0..10).collect::<Vec<_>>(), scores);
+}
+
+#[test]
+fn pq_map_scores_into_same_layout() {
+    let mut pq: PriorityQueue<f64, String> = PriorityQueue::new();
+    [3.5, -1.25, 8.0, 0.5].iter().for_each(|s| pq.put(*s, s.to_string()));
+    let mut res: PriorityQueue<i64, String> = pq.map_scores_into(|s| {
+        -(s * 4.0) as i64
+    });
+
+    assert_eq!(4, res.len());
+    assert_eq!((-32, "8".to_string()), res.pop().unwrap());
+    assert_eq!((-14, "3.5".to_string()), res.pop().unwrap());
+    assert_eq!((-2, "0.5".to_string()), res.pop().unwrap());
+    assert_eq!((5, "-1.25".to_string()), res.pop().unwrap());
+}
+
+#[test]
+fn pq_map_scores_into_other_layout() {
+    let pq = PriorityQueue::from([(3u8, "c"), (1u8, "a"), (2u8, "b")]);
+    let mut res = pq.map_scores_into(|s| (u64::MAX - s as u64, s));
+
+    assert_eq!(3, res.len());
+    assert_eq!("c", res.pop().unwrap().1);
+    assert_eq!("b", res.pop().unwrap().1);
+    assert_eq!("a", res.pop().unwrap().1);
+}
+
+#[test]
+fn pq_split_off() {
+    let mut pq1: PriorityQueue<usize, String> = PriorityQueue::new();
+    (0..20).rev().for_each(|i| pq1.put(i, i.to_string()));
+    let mut pq2 = pq1.split_off(5);
+
+    assert_eq!(5, pq1.len());
+    assert_eq!(15, pq2.len());
+    assert_eq!(0, pq1.peek().unwrap().0);
+    let mut prev = pq2.pop().unwrap().0;
+    while let Some((s, _)) = pq2.pop() {
+        assert!(prev <= s);
+        prev = s;
+    }
+}
+
+#[test]
+fn pq_split_off_at_ends() {
+    let mut pq = PriorityQueue::from([(5, 55), (1, 11), (4, 44)]);
+    assert!(pq.split_off(3).is_empty());
+    assert_eq!(3, pq.len());
+
+    let other = pq.split_off(0);
+    assert!(pq.is_empty());
+    assert_eq!(3, other.len());
+}
+
+#[test]
+#[should_panic]
+fn pq_split_off_out_of_bounds() {
+    let mut pq = PriorityQueue::from([(5, 55), (1, 11)]);
+    let _ = pq.split_off(3);
+}
+
+#[test]
+fn pq_partition_by_score() {
+    let mut pq: PriorityQueue<f32, usize> = PriorityQueue::new();
+    (0..10).for_each(|i| pq.put(i as f32, i));
+    pq.put(f32::NAN, 100);
+    let (mut due, mut rest) = pq.partition_by_score(&4.0);
+
+    assert_eq!(5, due.len());
+    assert_eq!(6, rest.len());
+    (0..5).for_each(|i| assert_eq!(i, due.pop().unwrap().1));
+    (5..10).for_each(|i| assert_eq!(i, rest.pop().unwrap().1));
+    assert_eq!(100, rest.pop().unwrap().1);
+}
+
+#[test]
+fn pq_truncate_best() {
+    let mut pq: PriorityQueue<usize, String> = PriorityQueue::new();
+    let mut scores: Vec<usize> = (0..1000).collect();
+    scores.shuffle(&mut thread_rng());
+    scores.iter().for_each(|s| pq.put(*s, s.to_string()));
+
+    pq.truncate_best(10);
+    assert_eq!(10, pq.len());
+    (0..10).for_each(|i| assert_eq!(i.to_string(), pq.pop().unwrap().1));
+}
+
+#[test]
+fn pq_truncate_best_keeps_nan_last() {
+    let mut pq: PriorityQueue<f64, usize> = PriorityQueue::new();
+    pq.put(f64::NAN, 0);
+    pq.put(2.0, 2);
+    pq.put(f64::NAN, 0);
+    pq.put(1.0, 1);
+
+    pq.truncate_best(2);
+    assert_eq!(1, pq.pop().unwrap().1);
+    assert_eq!(2, pq.pop().unwrap().1);
+
+    pq.truncate_best(5);
+    assert!(pq.is_empty());
+}
+
+#[test]
+fn pq_into_sorted_vec_large() {
+    let mut pq: PriorityQueue<usize, String> = PriorityQueue::new();
+    let mut scores: Vec<usize> = (0..10000).collect();
+    scores.shuffle(&mut thread_rng());
+    scores.iter().for_each(|s| pq.put(*s, s.to_string()));
+
+    let res = pq.into_sorted_vec();
+    assert_eq!(10000, res.len());
+    res.iter()
+       .enumerate()
+       .for_each(|(i, (s, e))| { assert_eq!(i, *s); assert_eq!(i.to_string(), *e); });
+}
+
+#[test]
+fn pq_into_sorted_vec_empty() {
+    let pq: PriorityQueue<usize, String> = PriorityQueue::new();
+    assert!(pq.into_sorted_vec().is_empty());
+    let mut pq: PriorityQueue<(), ()> = PriorityQueue::new();
+    pq.put((), ());
+    pq.put((), ());
+    assert_eq!(2, pq.into_sorted_vec().len());
+}
+
+#[test]
+fn pq_into_vec() {
+    let mut pq: PriorityQueue<usize, String> = PriorityQueue::with_capacity(64);
+    (0..50).rev().for_each(|i| pq.put(i, i.to_string()));
+
+    let mut res = pq.into_vec();
+    assert_eq!(50, res.len());
+    assert!(res.capacity() >= 64);
+    assert_eq!("0", res[0].1);
+
+    res.sort();
+    res.iter()
+       .enumerate()
+       .for_each(|(i, (s, e))| { assert_eq!(i, *s); assert_eq!(i.to_string(), *e); });
+}
+
+#[test]
+fn pq_from_vec_heapify() {
+    let mut scores: Vec<usize> = (0..10000).collect();
+    scores.shuffle(&mut thread_rng());
+    let vec: Vec<(usize, String)> = scores.into_iter()
+                                          .map(|s| (s, s.to_string()))
+                                          .collect();
+    let mut pq = PriorityQueue::from(vec);
+
+    assert_eq!(10000, pq.len());
+    (0..10000).for_each(|i| assert_eq!(i.to_string(), pq.pop().unwrap().1));
+}
+
+#[test]
+fn pq_from_empty_and_zst() {
+    let mut pq: PriorityQueue<usize, String> = PriorityQueue::from(Vec::new());
+    assert!(pq.is_empty());
+    pq.put(1, String::from("Erti"));
+    assert_eq!(1, pq.len());
+
+    let pq: PriorityQueue<usize, usize> = PriorityQueue::from([]);
+    assert!(pq.is_empty());
+
+    let pq = PriorityQueue::from([((), ()), ((), ()), ((), ())]);
+    assert_eq!(3, pq.len());
+}
+
+#[test]
+fn pq_from_sorted_vec() {
+    let vec: Vec<(usize, String)> = (0..100).map(|i| (i, i.to_string()))
+                                            .collect();
+    let mut pq = PriorityQueue::from_sorted_vec(vec);
+    pq.put(50, String::from("Erti"));
+    pq.put(0, String::from("Ori"));
+
+    assert_eq!(102, pq.len());
+    assert_eq!(0, pq.pop().unwrap().0);
+    assert_eq!(0, pq.pop().unwrap().0);
+    (1..=50).for_each(|i| assert_eq!(i, pq.pop().unwrap().0));
+    assert_eq!(50, pq.pop().unwrap().0);
+}
+
+#[test]
+fn pq_from_sorted_iter_with_nan() {
+    let iter = [(1.0, 1), (2.0, 2), (f64::NAN, 3), (f64::NAN, 4)].into_iter();
+    let mut pq = PriorityQueue::from_sorted_iter(iter);
+    assert_eq!(1, pq.pop().unwrap().1);
+    assert_eq!(2, pq.pop().unwrap().1);
+    assert!(pq.pop().unwrap().0.is_nan());
+}
+
+#[test]
+#[should_panic]
+#[cfg(debug_assertions)]
+fn pq_from_unsorted_vec() {
+    let _ = PriorityQueue::from_sorted_vec(vec![(2, 22), (1, 11)]);
+}
+
+#[test]
+fn pq_put_many() {
+    let mut scores: Vec<usize> = (0..1000).collect();
+    scores.shuffle(&mut thread_rng());
+
+    // few elements to a large queue are percolated up one by one
+    let mut pq: PriorityQueue<usize, String> = PriorityQueue::new();
+    pq.put_many(scores[..990].iter().map(|s| (*s, s.to_string())));
+    pq.put_many(scores[990..].iter().map(|s| (*s, s.to_string())));
+    assert_eq!(1000, pq.len());
+    (0..1000).for_each(|i| assert_eq!(i.to_string(), pq.pop().unwrap().1));
+
+    // many elements to a small queue rebuild the whole heap
+    let mut pq: PriorityQueue<usize, String> = PriorityQueue::new();
+    pq.put_many(scores[..10].iter().map(|s| (*s, s.to_string())));
+    pq.put_many(scores[10..].iter().map(|s| (*s, s.to_string())));
+    assert_eq!(1000, pq.len());
+    (0..1000).for_each(|i| assert_eq!(i.to_string(), pq.pop().unwrap().1));
+}
+
+#[test]
+fn pq_put_many_without_size_hint() {
+    let mut pq = PriorityQueue::from([(5, 55), (4, 44)]);
+    pq.put_many((0..100).filter(|i| i % 10 == 0).map(|i| (i, i + 1)));
+    assert_eq!(12, pq.len());
+    assert_eq!(1, pq.pop().unwrap().1);
+    assert_eq!(4, pq.pop().unwrap().0);
+}
+
+#[test]
+fn pq_extend() {
+    let mut pq: PriorityQueue<usize, String> = PriorityQueue::new();
+    pq.extend((0..10).rev().map(|i| (i, i.to_string())));
+    pq.extend(vec![(3, String::from("Sami"))]);
+
+    assert_eq!(11, pq.len());
+    assert_eq!("0", pq.pop().unwrap().1);
+    assert_eq!("1", pq.pop().unwrap().1);
+    assert_eq!("2", pq.pop().unwrap().1);
+    assert_eq!(3, pq.pop().unwrap().0);
+    assert_eq!(3, pq.pop().unwrap().0);
+}
+
+#[test]
+fn pq_extend_by_ref() {
+    let elems = [(2, String::from("Ori")), (1, String::from("Erti"))];
+    let mut pq = PriorityQueue::from([(3, String::from("Sami"))]);
+    pq.extend(&elems);
+    pq.extend(elems.iter());
+
+    assert_eq!(5, pq.len());
+    assert_eq!("Erti", pq.pop().unwrap().1);
+    assert_eq!("Erti", pq.pop().unwrap().1);
+    assert_eq!("Ori", pq.pop().unwrap().1);
+    assert_eq!(2, elems.len());
+}
+
+#[test]
+fn pq_extend_from_slice() {
+    let elems: Vec<(usize, String)> = (0..100).rev()
+                                              .map(|i| (i, i.to_string()))
+                                              .collect();
+    let mut pq = PriorityQueue::from([(50, String::from("Erti"))]);
+    pq.extend_from_slice(&elems[..50]);
+    pq.extend_from_slice(&elems[50..]);
+    pq.extend_from_slice(&[]);
+
+    assert_eq!(101, pq.len());
+    (0..50).for_each(|i| assert_eq!(i.to_string(), pq.pop().unwrap().1));
+    assert_eq!(50, pq.pop().unwrap().0);
+    assert_eq!(50, pq.pop().unwrap().0);
+    assert_eq!(100, elems.len());
+}
+
+#[test]
+fn pq_sum() {
+    let queues: Vec<PriorityQueue<usize, String>> = (0..10)
+        .map(|i| (0..10).map(|j| (j * 10 + i, (j * 10 + i).to_string()))
+                        .collect())
+        .collect();
+
+    let mut pq: PriorityQueue<usize, String> = queues.into_iter().sum();
+    assert_eq!(100, pq.len());
+    (0..100).for_each(|i| assert_eq!(i.to_string(), pq.pop().unwrap().1));
+
+    let empty: PriorityQueue<usize, String> = Vec::new().into_iter().sum();
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn pq_clone_owned_items() {
+    let mut pq1: PriorityQueue<usize, String> = PriorityQueue::new();
+    (0..10).rev().for_each(|i| pq1.put(i, i.to_string()));
+    let mut pq2 = pq1.clone();
+    drop(pq1);
+
+    assert_eq!(10, pq2.len());
+    (0..10).for_each(|i| assert_eq!(i.to_string(), pq2.pop().unwrap().1));
+}
+
+#[test]
+fn pq_add_refs() {
+    let pq1: PriorityQueue<usize, String> = (0..10).filter(|i| i % 2 == 0)
+                                                   .map(|i| (i, i.to_string()))
+                                                   .collect();
+    let pq2: PriorityQueue<usize, String> = (0..10).filter(|i| i % 2 == 1)
+                                                   .map(|i| (i, i.to_string()))
+                                                   .collect();
+    let mut res = &pq1 + &pq2;
+
+    assert_eq!(5, pq1.len());
+    assert_eq!(5, pq2.len());
+    assert_eq!(10, res.len());
+    (0..10).for_each(|i| assert_eq!(i.to_string(), res.pop().unwrap().1));
+}
+
+#[test]
+fn pq_iter_sorted() {
+    let mut pq: PriorityQueue<usize, String> = PriorityQueue::new();
+    let mut scores: Vec<usize> = (0..1000).collect();
+    scores.shuffle(&mut thread_rng());
+    scores.iter().for_each(|s| pq.put(*s, s.to_string()));
+
+    let mut iter = pq.iter_sorted();
+    assert_eq!(1000, iter.len());
+    (0..1000).for_each(|i| assert_eq!(i.to_string(), iter.next().unwrap().1));
+    assert!(iter.next().is_none());
+    assert_eq!(1000, pq.len());
+
+    let top: Vec<usize> = pq.iter_sorted().take(3).map(|(s, _)| *s).collect();
+    assert_eq!(vec![0, 1, 2], top);
+}
+
+#[test]
+fn pq_iter_sorted_empty_and_nan() {
+    let pq: PriorityQueue<f32, isize> = PriorityQueue::new();
+    assert!(pq.iter_sorted().next().is_none());
+
+    let pq = PriorityQueue::from([(2.0, 2), (f32::NAN, -1), (1.0, 1)]);
+    let items: Vec<_> = pq.iter_sorted().map(|(_, e)| *e).collect();
+    assert_eq!(vec![1, 2, -1], items);
+}
+
+#[test]
+fn pq_into_iter_sorted() {
+    let mut pq: PriorityQueue<usize, String> = PriorityQueue::new();
+    (0..100).rev().for_each(|i| pq.put(i, i.to_string()));
+
+    let mut iter = pq.into_iter_sorted();
+    assert_eq!(100, iter.len());
+    (0..10).for_each(|i| assert_eq!(i.to_string(), iter.next().unwrap().1));
+    assert_eq!(90, iter.len());
+
+    let rest: Vec<usize> = iter.map(|(s, _)| s).collect();
+    assert_eq!((10..100).collect::<Vec<_>>(), rest);
+}
+
+#[test]
+fn pq_drain_keeps_rest() {
+    let mut pq: PriorityQueue<usize, String> = PriorityQueue::new();
+    (0..100).rev().for_each(|i| pq.put(i, i.to_string()));
+
+    let drained: Vec<(usize, String)> = pq.drain(10..30).collect();
+    assert_eq!(20, drained.len());
+    assert_eq!(80, pq.len());
+
+    let mut scores: Vec<usize> = drained.into_iter().map(|(s, _)| s).collect();
+    let mut prev = pq.peek().unwrap().0;
+    while let Some((s, e)) = pq.pop() {
+        assert!(prev <= s);
+        assert_eq!(s.to_string(), e);
+        prev = s;
+        scores.push(s);
+    }
+    scores.sort();
+    assert_eq!((0..100).collect::<Vec<_>>(), scores);
+}
+
+#[test]
+fn pq_drain_partially_consumed() {
+    let mut pq: PriorityQueue<usize, String> = PriorityQueue::new();
+    (0..10).rev().for_each(|i| pq.put(i, i.to_string()));
+
+    let mut drain = pq.drain(..5);
+    assert!(drain.next().is_some());
+    drop(drain);
+
+    assert_eq!(5, pq.len());
+    let mut prev = 0;
+    while let Some((s, _)) = pq.pop() {
+        assert!(prev <= s);
+        prev = s;
+    }
+}
+
+#[test]
+fn pq_into_iter_double_ended() {
+    let pq: PriorityQueue<usize, String> = (0..10).map(|i| (i, i.to_string()))
+                                                  .collect();
+    let mut iter = pq.into_iter();
+    assert_eq!(10, iter.len());
+
+    let first = iter.next().unwrap();
+    let last = iter.next_back().unwrap();
+    assert_eq!(0, first.0);
+    assert_eq!(8, iter.len());
+    assert_ne!(first, last);
+
+    let rest: Vec<(usize, String)> = iter.rev().collect();
+    assert_eq!(8, rest.len());
+    assert!(!rest.contains(&last));
+}
+
+#[test]
+fn pq_drain_double_ended() {
+    let mut pq: PriorityQueue<usize, String> = (0..10).map(|i| (i, i.to_string()))
+                                                      .collect();
+    let mut drain = pq.drain(2..6);
+    assert_eq!(4, drain.len());
+    assert!(drain.next_back().is_some());
+    assert_eq!(3, drain.len());
+    assert_eq!(3, drain.rev().count());
+    assert_eq!(6, pq.len());
+
+    let mut zst = PriorityQueue::from([((), ()), ((), ()), ((), ())]);
+    let mut drain = zst.drain(..);
+    assert_eq!(3, drain.len());
+    assert!(drain.next_back().is_some());
+    assert_eq!(2, drain.count());
+    assert!(zst.is_empty());
+}
+
+#[test]
+fn pq_into_iter_non_clone_score() {
+    #[derive(Debug, PartialEq, PartialOrd)]
+    struct Deadline(u64);
+
+    let mut pq = PriorityQueue::new();
+    pq.put(Deadline(3), "c");
+    pq.put(Deadline(1), "a");
+    pq.put(Deadline(2), "b");
+
+    let mut items: Vec<(Deadline, &str)> = pq.into_iter().collect();
+    items.sort_by_key(|(d, _)| d.0);
+    assert_eq!(vec![(Deadline(1), "a"), (Deadline(2), "b"), (Deadline(3), "c")],
+               items);
+}
+
+#[test]
+fn pq_map_scores() {
+    let mut v: Vec<usize> = (0..100).collect();
+    v.shuffle(&mut thread_rng());
+    let mut pq: PriorityQueue<usize, usize> = v.into_iter().map(|i| (i, i))
+                                               .collect();
+    pq.map_scores(|s| 1000 - s);
+
+    for i in (0..100).rev() {
+        assert_eq!(Some((1000 - i, i)), pq.pop());
+    }
+    assert!(pq.is_empty());
+
+    let mut empty: PriorityQueue<f64, ()> = PriorityQueue::new();
+    empty.map_scores(|s| s * 2.0);
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn pq_map_items() {
+    let mut v: Vec<usize> = (0..100).collect();
+    v.shuffle(&mut thread_rng());
+    let pq: PriorityQueue<usize, usize> = v.into_iter().map(|i| (i, i))
+                                           .collect();
+    let before: Vec<usize> = pq.iter().map(|(s, _)| *s).collect();
+
+    // same layout, converted in place
+    let pq = pq.map_items(|e| -(e as isize));
+    let after: Vec<usize> = pq.iter().map(|(s, _)| *s).collect();
+    assert_eq!(before, after);
+
+    // different layout, new array is allocated
+    let mut pq = pq.map_items(|e| e.to_string());
+    let after: Vec<usize> = pq.iter().map(|(s, _)| *s).collect();
+    assert_eq!(before, after);
+
+    for i in 0..100 {
+        assert_eq!(Some((i, (-(i as isize)).to_string())), pq.pop());
+    }
+}
+
+#[test]
+fn pq_to_sorted_vec() {
+    let mut v: Vec<usize> = (0..100).collect();
+    v.shuffle(&mut thread_rng());
+    let pq: PriorityQueue<usize, String> = v.into_iter()
+                                            .map(|i| (i, i.to_string()))
+                                            .collect();
+    let sorted = pq.to_sorted_vec();
+    assert_eq!(100, pq.len());
+    assert_eq!(pq.into_sorted_vec(), sorted);
+    for (i, (s, e)) in sorted.into_iter().enumerate() {
+        assert_eq!(i, s);
+        assert_eq!(i.to_string(), e);
+    }
+}
+
+#[test]
+fn pq_drop_elements() {
+    use std::rc::Rc;
+
+    let token = Rc::new(());
+    let mut pq = PriorityQueue::new();
+    for i in 0..100 {
+        pq.put(i, Rc::clone(&token));
+    }
+    assert_eq!(101, Rc::strong_count(&token));
+    drop(pq);
+    assert_eq!(1, Rc::strong_count(&token));
+}
+
+#[test]
+fn pq_reserve() {
+    let mut pq: PriorityQueue<usize, usize> = PriorityQueue::new();
+    assert_eq!(0, pq.capacity());
+
+    pq.reserve_exact(5);
+    assert_eq!(5, pq.capacity());
+    (0..5).for_each(|i| pq.put(i, i));
+    assert_eq!(5, pq.capacity());
+
+    pq.reserve(1);
+    assert!(pq.capacity() >= 10);
+    let cap = pq.capacity();
+    pq.reserve(cap - pq.len());
+    assert_eq!(cap, pq.capacity());
+
+    pq.reserve_exact(100);
+    assert_eq!(105, pq.capacity());
+    (5..105).for_each(|i| pq.put(i, i));
+    assert_eq!(105, pq.capacity());
+    assert_eq!(Some((0, 0)), pq.pop());
+}
+
+#[test]
+#[should_panic]
+fn pq_reserve_overflow() {
+    let mut pq = PriorityQueue::from([(1, 11)]);
+    pq.reserve(usize::MAX);
+}
+
+#[test]
+fn pq_shrink_to() {
+    let mut pq: PriorityQueue<usize, String> = (0..1000).map(|i| (i, i.to_string()))
+                                                        .collect();
+    pq.drain(10..);
+    assert!(pq.capacity() >= 1000);
+
+    pq.shrink_to(100);
+    assert_eq!(100, pq.capacity());
+    pq.shrink_to(1000);
+    assert_eq!(100, pq.capacity());
+
+    pq.shrink_to_fit();
+    assert_eq!(10, pq.capacity());
+    for i in 0..10 {
+        assert_eq!(Some((i, i.to_string())), pq.pop());
+    }
+
+    pq.shrink_to_fit();
+    assert_eq!(0, pq.capacity());
+    pq.put(1, String::from("1"));
+    assert_eq!(Some((1, String::from("1"))), pq.pop());
+
+    let mut zst = PriorityQueue::from([((), ()), ((), ())]);
+    zst.shrink_to_fit();
+    assert_eq!(2, zst.len());
+}
+
+#[test]
+fn pq_try_reserve() {
+    use priq::TryReserveError;
+
+    let mut pq: PriorityQueue<u64, u64> = PriorityQueue::new();
+    assert_eq!(Ok(()), pq.try_reserve(10));
+    assert!(pq.capacity() >= 10);
+
+    assert_eq!(Err(TryReserveError::CapacityOverflow),
+               pq.try_reserve(usize::MAX));
+    assert_eq!(Err(TryReserveError::CapacityOverflow),
+               pq.try_reserve(usize::MAX / 4));
+    assert_eq!(Err(TryReserveError::CapacityOverflow),
+               pq.try_reserve(usize::MAX - pq.len()));
+
+    // failed reservations leave the queue intact
+    for i in (0..10).rev() {
+        assert_eq!(Ok(()), pq.try_put(i, i * 10));
+    }
+    assert_eq!(10, pq.len());
+    assert_eq!(Some((0, 0)), pq.pop());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_try_reserve_alloc_error() {
+    use priq::TryReserveError;
+    use std::alloc::{AllocError, Allocator, Layout};
+    use std::ptr::NonNull;
+
+    struct Exhausted;
+
+    unsafe impl Allocator for Exhausted {
+        fn allocate(&self, _: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            Err(AllocError)
+        }
+
+        unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {
+            unreachable!("nothing is ever allocated")
+        }
+    }
+
+    let mut pq: PriorityQueue<u64, u64, _, _> = PriorityQueue::new_in(Exhausted);
+    match pq.try_reserve(10) {
+        Err(TryReserveError::AllocError { layout }) => assert_eq!(160, layout.size()),
+        res => panic!("unexpected result {:?}", res),
+    }
+    let err = pq.try_put(1, 11).unwrap_err();
+    assert!(matches!(err.error(), Some(TryReserveError::AllocError { .. })));
+    assert_eq!((1, 11), err.into_inner());
+    assert!(pq.is_empty());
+}
+
+#[test]
+fn pq_growth_policy() {
+    use priq::GrowthPolicy;
+
+    let mut pq = PriorityQueue::with_growth_policy(GrowthPolicy::new(2, 1.5));
+    let mut caps = vec![];
+    for i in 0..20 {
+        pq.put(i, i);
+        if caps.last() != Some(&pq.capacity()) {
+            caps.push(pq.capacity());
+        }
+    }
+    assert_eq!(vec![2, 3, 4, 6, 9, 13, 19, 28], caps);
+
+    let mut other = pq.split_off(10);
+    assert_eq!(pq.growth_policy(), other.growth_policy());
+    assert_eq!(pq.growth_policy(), pq.clone().growth_policy());
+    other.shrink_to_fit();
+    other.reserve(1);
+    assert_eq!(15, other.capacity());
+
+    for i in 0..10 {
+        assert_eq!(Some((i, i)), pq.pop());
+    }
+}
+
+#[test]
+#[should_panic]
+fn pq_growth_policy_invalid_factor() {
+    use priq::GrowthPolicy;
+    GrowthPolicy::new(8, 1.0);
+}
+
+#[test]
+fn pq_spare_capacity_bulk_load() {
+    let mut v: Vec<usize> = (0..100).collect();
+    v.shuffle(&mut thread_rng());
+
+    let mut pq: PriorityQueue<usize, String> = PriorityQueue::new();
+    pq.put(100, String::from("100"));
+    pq.reserve_exact(v.len());
+
+    let spare = pq.spare_capacity_mut();
+    assert_eq!(100, spare.len());
+    for (slot, i) in spare.iter_mut().zip(v) {
+        slot.write((i, i.to_string()));
+    }
+    unsafe { pq.set_len(101) };
+    pq.rebuild();
+
+    assert!(pq.spare_capacity_mut().is_empty());
+    for i in 0..=100 {
+        assert_eq!(Some((i, i.to_string())), pq.pop());
+    }
+}
+
+#[test]
+fn pq_builder() {
+    use priq::{GrowthPolicy, NanPolicy, ShrinkPolicy};
+
+    let mut pq: PriorityQueue<usize, usize> = PriorityQueue::builder()
+        .initial_capacity(3)
+        .growth_factor(3.0)
+        .build();
+    assert_eq!(0, pq.capacity());
+    assert_eq!(GrowthPolicy::new(3, 3.0), pq.growth_policy());
+
+    (0..4).for_each(|i| pq.put(i, i));
+    assert_eq!(9, pq.capacity());
+
+    let pq: PriorityQueue<usize, usize> = PriorityQueue::builder()
+        .capacity(10)
+        .build();
+    assert_eq!(10, pq.capacity());
+    assert_eq!(GrowthPolicy::default(), pq.growth_policy());
+    assert_eq!(ShrinkPolicy::default(), pq.shrink_policy());
+
+    let mut pq: PriorityQueue<f64, usize, _> = PriorityQueue::builder()
+        .capacity(2000)
+        .shrink_policy(ShrinkPolicy::new(100, 0.5))
+        .nan_policy(NanPolicy::First)
+        .max()
+        .build();
+    assert!(pq.is_max());
+    assert_eq!(NanPolicy::First, pq.nan_policy());
+    (0..1000).for_each(|i| pq.put(i as f64, i));
+    pq.put(f64::NAN, 1000);
+    assert_eq!(1000, pq.pop().unwrap().1);
+    assert_eq!(999, pq.pop().unwrap().1);
+    assert_eq!(1000, pq.capacity());
+    assert_eq!(ShrinkPolicy::new(100, 0.5), pq.clone().shrink_policy());
+
+    pq.set_shrink_policy(ShrinkPolicy::never());
+    pq.clear();
+    (0..10).for_each(|i| pq.put(i as f64, i));
+    (0..10).for_each(|_| { pq.pop(); });
+    assert_eq!(1000, pq.capacity());
+}
+
+#[test]
+fn pq_with_capacity_exact() {
+    for cap in [0, 1, 7, 100, 1023] {
+        let mut pq: PriorityQueue<usize, usize> = PriorityQueue::with_capacity(cap);
+        assert_eq!(cap, pq.capacity());
+        (0..cap).for_each(|i| pq.put(i, i));
+        assert_eq!(cap, pq.capacity());
+        assert_eq!(pq.len(), pq.clone().capacity());
+    }
+
+    let zst: PriorityQueue<(), ()> = PriorityQueue::with_capacity(0);
+    assert!(zst.capacity() > 0);
+}
+
+#[test]
+fn pq_peek_and_pop_halves() {
+    let mut pq: PriorityQueue<f32, String> = PriorityQueue::new();
+    assert!(pq.peek_score().is_none());
+    assert!(pq.peek_item().is_none());
+    assert!(pq.pop_item().is_none());
+
+    pq.put(2.2, String::from("b"));
+    pq.put(f32::NAN, String::from("nan"));
+    pq.put(1.1, String::from("a"));
+
+    assert_eq!(Some(&1.1), pq.peek_score());
+    assert_eq!("a", pq.peek_item().unwrap());
+    assert_eq!("a", pq.pop_item().unwrap());
+    assert_eq!("b", pq.pop_item().unwrap());
+    assert!(pq.peek_score().unwrap().is_nan());
+    assert_eq!("nan", pq.pop_item().unwrap());
+    assert!(pq.is_empty());
+}
+
+#[test]
+fn pq_peek_bottom() {
+    fn check(pq: &PriorityQueue<usize, usize>) {
+        assert_eq!(pq.iter().map(|(s, _)| s).max(), pq.bottom_score());
+    }
+
+    let mut rng = thread_rng();
+    let mut v: Vec<usize> = (0..200).map(|i| i % 50).collect();
+    v.shuffle(&mut rng);
+
+    let mut pq: PriorityQueue<usize, usize> = PriorityQueue::new();
+    assert!(pq.peek_bottom().is_none());
+    for &i in &v {
+        pq.put(i, i);
+        check(&pq);
+    }
+    for _ in 0..50 {
+        pq.pop();
+        check(&pq);
+    }
+
+    pq.extend(v.iter().map(|&i| (i + 100, i)));
+    check(&pq);
+    pq.truncate(150);
+    check(&pq);
+    pq.drain(20..40);
+    check(&pq);
+    drop(pq.drain(100..));
+    check(&pq);
+    let other = pq.split_off(30);
+    check(&pq);
+    check(&other);
+    pq.truncate_best(10);
+    check(&pq);
+    pq.map_scores(|s| 1000 - s);
+    check(&pq);
+    let pq = pq.map_items(|e| e * 2);
+    check(&pq);
+    check(&pq.clone());
+
+    // all the scores are equal
+    let mut pq: PriorityQueue<usize, usize> = (0..20).map(|i| (1, i)).collect();
+    while !pq.is_empty() {
+        assert_eq!(Some(&1), pq.bottom_score());
+        pq.pop();
+    }
+    assert!(pq.bottom_score().is_none());
+
+    // incomparable scores are the highest
+    let mut pq = PriorityQueue::from([(1.0, 1), (f64::NAN, 2), (3.0, 3)]);
+    assert_eq!(2, pq.peek_bottom().unwrap().1);
+    pq.pop();
+    pq.pop();
+    assert_eq!(2, pq.peek_bottom().unwrap().1);
+}
+
+#[test]
+fn pq_new_max() {
+    let mut v: Vec<usize> = (0..100).collect();
+    v.shuffle(&mut thread_rng());
+
+    let mut pq = PriorityQueue::new_max();
+    v.iter().for_each(|&i| pq.put(i, i.to_string()));
+    assert_eq!(Some(&99), pq.peek_score());
+    assert_eq!(Some(&0), pq.bottom_score());
+
+    let other = pq.split_off(50);
+    assert!(other.is_max());
+    assert!(pq.clone().is_max());
+    assert!((&pq + &other).is_max());
+    pq.merge(&mut other.map_items(|e| e + "!"));
+
+    let sorted = pq.to_sorted_vec();
+    assert_eq!(100, sorted.len());
+    assert!(sorted.windows(2).all(|w| w[0].0 > w[1].0));
+    for i in (0..100).rev() {
+        assert_eq!(i, pq.pop().unwrap().0);
+    }
+}
+
+#[test]
+fn pq_max_order_type() {
+    use priq::{Drain, Max, MaxPriorityQueue};
+
+    let mut pq: MaxPriorityQueue<usize, usize> = PriorityQueue::builder()
+        .max()
+        .capacity(10)
+        .build();
+    assert_eq!(10, pq.capacity());
+    pq.extend((0..10).map(|i| (i, i)));
+
+    let mut other: PriorityQueue<usize, usize, Max> = PriorityQueue::default();
+    other.extend((10..20).map(|i| (i, i)));
+    let drain: Drain<'_, usize, usize, Max> = other.drain(..5);
+    assert_eq!(5, drain.count());
+
+    let pq: MaxPriorityQueue<usize, usize> = vec![pq, other].into_iter().sum();
+    let pq = pq.map_scores_into(|s| s as f64);
+    let top = pq.iter().map(|(s, _)| *s).fold(f64::MIN, f64::max);
+    assert_eq!(Some(&top), pq.peek_score());
+    assert_eq!(Some(&0.0), pq.bottom_score());
+    let sorted: Vec<f64> = pq.into_iter_sorted().map(|(s, _)| s).collect();
+    assert_eq!(15, sorted.len());
+    assert!(sorted.windows(2).all(|w| w[0] > w[1]));
+}
+
+#[test]
+fn pq_with_comparator() {
+    // order by the second field, then by the first one in reverse
+    let cmp = |a: &(u32, u32), b: &(u32, u32)| {
+        Some(a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+    };
+    let mut v: Vec<(u32, u32)> = (0..100).map(|i| (i, i % 10)).collect();
+    v.shuffle(&mut thread_rng());
+
+    let mut pq = PriorityQueue::with_comparator(cmp);
+    v.iter().for_each(|&s| pq.put(s, s.0));
+    assert_eq!(Some(&(90, 0)), pq.peek_score());
+    assert_eq!(Some(&(9, 9)), pq.bottom_score());
+
+    let other = pq.split_off(50);
+    let mut pq = (&pq + &other).map_items(|e| e.to_string());
+    assert_eq!(Some(&(9, 9)), pq.bottom_score());
+
+    let sorted = pq.clone().into_sorted_vec();
+    assert!(sorted.windows(2).all(|w| cmp(&w[0].0, &w[1].0) == Some(std::cmp::Ordering::Less)));
+    for (s, e) in sorted {
+        assert_eq!(Some((s, e)), pq.pop());
+    }
+
+    let mut pq: PriorityQueue<u32, (), priq::Max> = PriorityQueue::builder()
+        .max()
+        .comparator(|a: &u32, b: &u32| (a % 7).partial_cmp(&(b % 7)))
+        .build();
+    pq.extend((0..20).map(|i| (i, ())));
+    assert_eq!(6, pq.pop().unwrap().0 % 7);
+    let (low, high) = pq.partition_by_score(&3);
+    assert!(low.iter().all(|(s, _)| s % 7 <= 3));
+    assert!(high.iter().all(|(s, _)| s % 7 > 3));
+}
+
+#[test]
+fn pq_by_key() {
+    use priq::PriorityQueueBy;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Job { id: usize, cost: f64 }
+
+    let mut v: Vec<usize> = (0..100).collect();
+    v.shuffle(&mut thread_rng());
+
+    let mut pq = PriorityQueueBy::with_capacity(100, |j: &Job| j.cost);
+    pq.extend(v.iter().map(|&id| Job { id, cost: id as f64 / 2.0 }));
+    pq.put(Job { id: 100, cost: f64::NAN });
+    assert_eq!(101, pq.len());
+    assert_eq!(Some(0.0), pq.peek_key());
+
+    let copy = pq.clone();
+    for i in 0..100 {
+        assert_eq!(i, pq.pop().unwrap().id);
+    }
+    assert_eq!(100, pq.pop().unwrap().id);
+    assert!(pq.pop().is_none());
+
+    let sorted = copy.into_sorted_vec();
+    assert_eq!(101, sorted.len());
+    assert_eq!(99, sorted[99].id);
+
+    let mut pq = PriorityQueueBy::new_max(|j: &Job| j.id);
+    pq.extend(v.iter().map(|&id| Job { id, cost: 0.0 }));
+    assert_eq!(99, pq.peek().unwrap().id);
+    pq.clear();
+    assert!(pq.is_empty());
+}
+
+#[test]
+fn pq_with_tiebreak() {
+    let mut ids: Vec<usize> = (0..200).collect();
+    ids.shuffle(&mut thread_rng());
+
+    let mut pq = PriorityQueue::with_tiebreak(|a: &usize, b: &usize| a.cmp(b));
+    ids.iter().for_each(|&id| pq.put(id % 4, id));
+
+    let popped: Vec<(usize, usize)> = std::iter::from_fn(|| pq.pop()).collect();
+    let mut expected: Vec<(usize, usize)> = ids.iter().map(|&id| (id % 4, id)).collect();
+    expected.sort();
+    assert_eq!(expected, popped);
+
+    let mut pq: PriorityQueue<usize, usize, priq::Max> = PriorityQueue::builder()
+        .max()
+        .tiebreak(|a: &usize, b: &usize| b.cmp(a))
+        .build();
+    ids.iter().for_each(|&id| pq.put(id % 4, id));
+    assert_eq!(Some(&(3, 199)), pq.peek());
+    assert_eq!(Some(&(0, 0)), pq.peek_bottom());
+
+    let sorted: Vec<_> = pq.iter_sorted().cloned().collect();
+    let mut rest = pq.clone().split_off(100);
+    assert_eq!(sorted, pq.into_sorted_vec());
+    let top = rest.pop().unwrap();
+    assert!(rest.into_sorted_vec().iter().all(|e| *e < top));
+}
+
+#[test]
+fn pq_stable() {
+    let mut elems: Vec<(usize, usize)> = (0..500).map(|i| (i % 7, i)).collect();
+    elems.shuffle(&mut thread_rng());
+
+    let mut pq: priq::StablePriorityQueue<usize, usize> = elems.iter().copied().collect();
+    assert_eq!(500, pq.len());
+    let popped: Vec<_> = std::iter::from_fn(|| pq.pop()).collect();
+    let mut expected = elems.clone();
+    expected.sort_by_key(|e| e.0);
+    assert_eq!(expected, popped);
+
+    let mut pq = priq::StablePriorityQueue::new_max();
+    pq.extend(elems.iter().copied());
+    assert_eq!(Some((&6, &elems.iter().find(|e| e.0 == 6).unwrap().1)), pq.peek());
+    expected.sort_by_key(|e| Reverse(e.0));
+    assert_eq!(expected, pq.clone().into_sorted_vec());
+
+    pq.clear();
+    pq.put(1, 1);
+    pq.put(1, 2);
+    assert_eq!(Some((1, 1)), pq.pop());
+    assert_eq!(Some((1, 2)), pq.pop());
+    assert!(pq.is_empty());
+}
+
+#[test]
+fn pq_nan_policy() {
+    use priq::{NanPolicy, PutErrorKind};
+
+    let mut scores: Vec<f64> = (0..50).map(|i| i as f64).collect();
+    scores.extend([f64::NAN; 5]);
+    scores.shuffle(&mut thread_rng());
+
+    let mut pq: PriorityQueue<f64, usize> = PriorityQueue::builder()
+        .nan_policy(NanPolicy::First)
+        .build();
+    scores.iter().for_each(|&s| pq.put(s, 0));
+    (0..5).for_each(|_| assert!(pq.pop().unwrap().0.is_nan()));
+    assert_eq!(Some(0.0), pq.pop().map(|e| e.0));
+    assert!(!pq.peek_bottom().unwrap().0.is_nan());
+
+    pq.put(f64::NAN, 1);
+    pq.set_nan_policy(NanPolicy::Last);
+    assert_eq!(Some(1.0), pq.peek().map(|e| e.0));
+    assert!(pq.peek_bottom().unwrap().0.is_nan());
+
+    pq.set_nan_policy(NanPolicy::Reject);
+    let err = pq.try_put(f64::NAN, 2).unwrap_err();
+    assert_eq!(&PutErrorKind::Incomparable, err.kind());
+    assert!(err.error().is_none());
+    assert_eq!(2, err.into_inner().1);
+    assert!(pq.try_put(100.0, 3).is_ok());
+    assert_eq!(51, pq.len());
+
+    let res = std::panic::catch_unwind(move || {
+        let mut pq = PriorityQueue::new();
+        pq.set_nan_policy(NanPolicy::Panic);
+        pq.put(1.0, 1);
+        pq.put(f64::NAN, 2);
+    });
+    assert!(res.is_err());
+}
+
+#[test]
+fn pq_quarantine_incomparable() {
+    use priq::NanPolicy;
+
+    let mut scores: Vec<f64> = (0..100).map(|i| i as f64).collect();
+    scores.extend([f64::NAN; 10]);
+    scores.shuffle(&mut thread_rng());
+
+    let mut pq = PriorityQueue::new();
+    scores.iter().for_each(|&s| pq.put(s, s));
+    assert_eq!(110, pq.len());
+    assert_eq!(10, pq.nan_count());
+    assert!(pq.incomparable().iter().all(|e| e.0.is_nan()));
+    assert!(pq.peek_bottom().unwrap().0.is_nan());
+
+    let sorted: Vec<f64> = pq.iter_sorted().map(|e| e.0).collect();
+    assert!(sorted[..100].iter().enumerate().all(|(i, &s)| s == i as f64));
+    assert!(sorted[100..].iter().all(|s| s.is_nan()));
+
+    let mut first = pq.clone();
+    first.set_nan_policy(NanPolicy::First);
+    let sorted = first.to_sorted_vec();
+    assert!(sorted[..10].iter().all(|e| e.0.is_nan()));
+    assert_eq!(0.0, sorted[10].0);
+    assert_eq!(Some(99.0), first.peek_bottom().map(|e| e.0));
+    (0..10).for_each(|_| assert!(first.pop().unwrap().0.is_nan()));
+    assert_eq!(0, first.nan_count());
+    assert_eq!(Some(0.0), first.pop().map(|e| e.0));
+
+    for i in 0..100 {
+        assert_eq!(Some(i as f64), pq.pop().map(|e| e.0));
+        assert_eq!(110 - i - 1, pq.len());
+        assert_eq!(10, pq.nan_count());
+    }
+    pq.put(5.0, 5.0);
+    assert_eq!(Some(5.0), pq.pop().map(|e| e.0));
+    while let Some((s, _)) = pq.pop() {
+        assert!(s.is_nan());
+    }
+    assert_eq!(0, pq.nan_count());
+
+    let mut pq = PriorityQueue::from(
+        scores.iter().map(|&s| (s, ())).collect::<Vec<_>>());
+    assert_eq!(10, pq.nan_count());
+    pq.truncate(95);
+    assert_eq!(0, pq.nan_count());
+    let small = pq.iter().filter(|e| e.0 < 10.0).count();
+    pq.map_scores(|&s| if s < 10.0 { f64::NAN } else { s });
+    assert_eq!(small, pq.nan_count());
+    let rest = pq.split_off(50);
+    assert_eq!(small, rest.nan_count());
+    assert!(pq.into_sorted_vec().windows(2).all(|w| w[0].0 <= w[1].0));
+}
+
+#[test]
+fn pq_float_total_order() {
+    let mut scores: Vec<f64> = (0..100).map(|i| (i as f64 - 50.0) / 3.0).collect();
+    scores.extend([f64::NAN, -f64::NAN, f64::INFINITY, f64::NEG_INFINITY, -0.0]);
+    scores.shuffle(&mut thread_rng());
+
+    let pq: priq::FloatPriorityQueue<f64, usize> =
+        scores.iter().map(|&s| (s, 0)).collect();
+    assert_eq!(105, pq.len());
+    let sorted: Vec<f64> = pq.clone().into_sorted_vec().into_iter().map(|e| e.0).collect();
+    assert_eq!(f64::NEG_INFINITY, sorted[0]);
+    assert_eq!(f64::INFINITY, sorted[102]);
+    assert!(sorted[103].is_nan() && sorted[104].is_nan());
+    assert!(sorted[..103].windows(2).all(|w| w[0] <= w[1]));
+
+    let mut max = priq::FloatPriorityQueue::new_max();
+    max.extend(scores.iter().map(|&s| (s as f32, ())));
+    assert!(max.pop().unwrap().0.is_nan());
+    assert!(max.pop().unwrap().0.is_nan());
+    assert_eq!(Some(f32::INFINITY), max.peek().map(|e| e.0));
+}
+
+#[cfg(feature = "ordered-float")]
+#[test]
+fn pq_ordered_float() {
+    use ordered_float::{NotNan, OrderedFloat};
+
+    let mut scores: Vec<f64> = (0..50).map(|i| i as f64 / 4.0).collect();
+    scores.shuffle(&mut thread_rng());
+
+    let mut pq = PriorityQueue::from_floats(scores.iter().map(|&s| (s, s)));
+    pq.put_float(f64::NAN, -1.0);
+    assert_eq!(0, pq.nan_count());
+    (0..50).for_each(|i| assert_eq!(Some((i as f64 / 4.0, i as f64 / 4.0)), pq.pop_float()));
+    assert!(pq.pop_float().unwrap().0.is_nan());
+
+    let mut pq: PriorityQueue<NotNan<f64>, f64> =
+        PriorityQueue::try_from_floats(scores.iter().map(|&s| (s, s))).unwrap();
+    assert!(pq.try_put_float(f64::NAN, 0.0).is_err());
+    assert_eq!(50, pq.len());
+    assert_eq!(Some((0.0, 0.0)), pq.pop_float());
+
+    let fpq: priq::FloatPriorityQueue<f64, f64, priq::Max> = {
+        let mut fpq = priq::FloatPriorityQueue::new_max();
+        fpq.extend(scores.iter().map(|&s| (s, s)));
+        fpq
+    };
+    let mut pq = PriorityQueue::<OrderedFloat<f64>, f64, priq::Max>::from(fpq);
+    assert_eq!(Some((49.0 / 4.0, 49.0 / 4.0)), pq.pop_float());
+}
+
+#[test]
+fn pq_epsilon_ties() {
+    let mut times: Vec<f64> = (0..60).map(|i| (i / 3) as f64 + (i % 3) as f64 * 1e-12).collect();
+    times.shuffle(&mut thread_rng());
+
+    let mut pq = PriorityQueue::with_epsilon(1e-9);
+    times.iter().for_each(|&t| pq.put(t, t));
+    for i in 0..20 {
+        let ties = pq.pop_ties();
+        assert_eq!(3, ties.len());
+        assert!(ties.iter().all(|e| (e.0 - i as f64).abs() < 1e-9));
+    }
+    assert!(pq.pop_ties().is_empty());
+
+    let mut pq: PriorityQueue<f64, usize> = PriorityQueue::builder()
+        .epsilon(1e-9)
+        .tiebreak(|a: &usize, b: &usize| a.cmp(b))
+        .build();
+    times.iter().enumerate().for_each(|(i, &t)| pq.put(t, i));
+    pq.put(f64::NAN, 100);
+    pq.put(f64::NAN, 101);
+    pq.dedup();
+    assert_eq!(22, pq.len());
+    assert_eq!(2, pq.nan_count());
+    let top = times.iter().enumerate().filter(|e| *e.1 < 0.5).map(|e| e.0).min();
+    assert_eq!(top, pq.pop().map(|e| e.1));
+
+    let mut pq = PriorityQueue::from([(1, 'a'), (1, 'b'), (2, 'c')]);
+    pq.dedup();
+    assert_eq!(2, pq.len());
+}
+
+#[test]
+fn pq_monotone() {
+    let mut pq = priq::MonotonePriorityQueue::new();
+    let mut visited = [false; 50];
+    pq.put(0, 0);
+    let mut popped = Vec::new();
+    while let Some((dist, node)) = pq.pop() {
+        if node >= 50 || std::mem::replace(&mut visited[node], true) {
+            continue
+        }
+        popped.push(dist);
+        pq.put(dist + 3, node + 1);
+        pq.put(dist + 1, node + 2);
+    }
+    assert!(visited.iter().all(|&v| v));
+    assert!(popped.windows(2).all(|w| w[0] <= w[1]));
+
+    let mut pq = priq::MonotonePriorityQueue::new_max();
+    pq.extend([(5, 'a'), (3, 'b')]);
+    assert_eq!(Some((5, 'a')), pq.pop());
+    pq.put(5, 'c');
+    assert_eq!(2, pq.len());
+    assert_eq!(Some(&(5, 'c')), pq.peek());
+
+    #[cfg(debug_assertions)]
+    {
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            pq.put(6, 'd');
+        }));
+        assert!(res.is_err());
+    }
+}
+
+#[test]
+fn pq_decay() {
+    // linear aging with the same rate keeps the order of stored elements
+    let aging = |&(base, at): &(i64, i64), now: &i64| base - (now - at);
+    let mut pq = priq::DecayPriorityQueue::new(0, aging);
+
+    let mut expected = Vec::new();
+    for now in 0..20 {
+        pq.set_now(now);
+        let mut bases: Vec<i64> = (0..10).collect();
+        bases.shuffle(&mut thread_rng());
+        for base in bases {
+            pq.put((base * 3, now), base * 3 + now);
+            expected.push(base * 3 + now);
+        }
+        assert_eq!(Some(expected.iter().min().unwrap() - now), pq.peek_score());
+    }
+    expected.sort();
+    let popped: Vec<i64> = std::iter::from_fn(|| pq.pop()).map(|e| e.1).collect();
+    assert_eq!(expected, popped);
+
+    // boosts which reorder elements need a rebuild
+    let mut pq = priq::DecayPriorityQueue::new_max(false, |&(s, boost): &(i32, bool), late: &bool| {
+        if boost && *late { s + 100 } else { s }
+    });
+    pq.extend([((5, false), 'a'), ((1, true), 'b'), ((3, false), 'c')]);
+    assert_eq!('a', pq.peek().unwrap().1);
+    pq.set_now(true);
+    pq.rebuild();
+    assert_eq!(Some(101), pq.peek_score());
+    assert_eq!('b', pq.pop().unwrap().1);
+    assert_eq!('a', pq.pop().unwrap().1);
+}
+
+#[test]
+fn pq_banded() {
+    use priq::{BandedPriorityQueue, ServicePolicy};
+
+    let mut pq = BandedPriorityQueue::new(3, ServicePolicy::Strict);
+    let mut elems: Vec<(usize, usize)> = (0..90).map(|i| (i % 3, i)).collect();
+    elems.shuffle(&mut thread_rng());
+    elems.iter().for_each(|&(band, i)| pq.put(band, i, i));
+    assert_eq!(90, pq.len());
+    assert_eq!(Some((0, &(0, 0))), pq.peek());
+    let popped: Vec<usize> = std::iter::from_fn(|| pq.pop()).map(|e| e.1).collect();
+    let mut expected: Vec<usize> = (0..90).collect();
+    expected.sort_by_key(|&i| (i % 3, i));
+    assert_eq!(expected, popped);
+
+    let mut pq = BandedPriorityQueue::new_max(3, ServicePolicy::Weighted(vec![3, 2, 1]));
+    elems.iter().for_each(|&(band, i)| pq.put(band, i, band));
+    let bands: Vec<usize> = (0..12).map(|_| {
+        let (band, _) = pq.peek().unwrap();
+        let (_, b) = pq.pop().unwrap();
+        assert_eq!(band, b);
+        b
+    }).collect();
+    assert_eq!(vec![0, 0, 0, 1, 1, 2, 0, 0, 0, 1, 1, 2], bands);
+    assert_eq!(Some(&69), pq.band(0).peek_score());
+
+    pq.clear();
+    assert!(pq.is_empty());
+    pq.put(2, 1, 2);
+    pq.put(1, 1, 1);
+    assert_eq!(Some((1, 1)), pq.pop());
+    assert_eq!(Some((1, 2)), pq.pop());
+    assert_eq!(None, pq.pop());
+}
+
+#[test]
+fn pq_lex_scores() {
+    use priq::{Lex2, Lex3, Max, Min};
+
+    let mut elems: Vec<(u8, u8, u8)> = (0..125).map(|i| (i / 25, i / 5 % 5, i % 5)).collect();
+    elems.shuffle(&mut thread_rng());
+
+    type Score = Lex3<u8, u8, u8, Min, Max, Min>;
+    let mut pq: PriorityQueue<Score, (u8, u8, u8)> =
+        elems.iter().map(|&(a, b, c)| (Lex3::new(a, b, c), (a, b, c))).collect();
+    let popped: Vec<_> = std::iter::from_fn(|| pq.pop_item()).collect();
+    let mut expected = elems.clone();
+    expected.sort_by_key(|&(a, b, c)| (a, Reverse(b), c));
+    assert_eq!(expected, popped);
+
+    let mut sorted: Vec<Lex2<u8, u8, Max>> =
+        elems.iter().map(|&(a, b, _)| Lex2::new(a, b)).collect();
+    sorted.sort();
+    assert_eq!(Lex2::new(4, 0), sorted[0]);
+    assert_eq!(Lex2::new(0, 4), sorted[124]);
+
+    let nan = Lex2::<f64, f64>::new(f64::NAN, 1.0);
+    assert_eq!(None, nan.partial_cmp(&Lex2::new(1.0, 1.0)));
+    assert!(Lex2::<f64, f64>::new(1.0, f64::NAN) > Lex2::new(0.0, f64::NAN));
+}
+
+#[test]
+fn pq_dary_heap() {
+    use priq::{DaryHeap, HeapBackend};
+
+    let mut elems: Vec<usize> = (0..1000).collect();
+    elems.shuffle(&mut thread_rng());
+
+    let mut pq: DaryHeap<usize, usize, 4> = DaryHeap::new();
+    elems.iter().for_each(|&i| pq.put(i, i * 2));
+    assert_eq!(1000, pq.len());
+    assert_eq!(Some(&(0, 0)), pq.peek());
+    let popped: Vec<_> = std::iter::from_fn(|| pq.pop()).map(|(s, _)| s).collect();
+    assert_eq!((0..1000).collect::<Vec<_>>(), popped);
+
+    let mut pq: DaryHeap<usize, (), 3, _> = DaryHeap::new_max();
+    pq.extend(elems.iter().map(|&i| (i, ())));
+    assert_eq!(Some(&(999, ())), pq.peek());
+    let sorted: Vec<_> = pq.into_sorted_vec().into_iter().map(|(s, _)| s).collect();
+    assert_eq!((0..1000).rev().collect::<Vec<_>>(), sorted);
+
+    let pq: DaryHeap<f64, usize, 8> =
+        elems.iter().map(|&i| (if i % 10 == 0 { f64::NAN } else { i as f64 }, i)).collect();
+    let popped: Vec<_> = pq.into_sorted_vec().into_iter().map(|(_, i)| i).collect();
+    assert_eq!(1000, popped.len());
+    assert!(popped[..900].windows(2).all(|w| w[0] < w[1]));
+    assert!(popped[900..].iter().all(|i| i % 10 == 0));
+
+    let pq: DaryHeap<usize, usize, 2> = DaryHeap::from(elems.iter().map(|&i| (i, i)).collect::<Vec<_>>());
+    assert_eq!(Some(&(0, 0)), pq.peek());
+}
+
+#[test]
+fn pq_pairing_heap() {
+    use priq::{HeapBackend, PairingHeap};
+
+    let mut elems: Vec<usize> = (0..1000).collect();
+    elems.shuffle(&mut thread_rng());
+
+    let mut pq = PairingHeap::new();
+    let handles: Vec<_> = elems.iter().map(|&i| (i, pq.put(i + 1000, i))).collect();
+    assert_eq!(1000, pq.len());
+    for &(i, h) in handles.iter().filter(|(i, _)| i % 2 == 0) {
+        pq.decrease_key(h, i);
+        assert_eq!(Some(&(i, i)), pq.get(h));
+    }
+    assert_eq!(Some(&(0, 0)), pq.peek());
+    let popped: Vec<_> = std::iter::from_fn(|| pq.pop()).map(|(_, i)| i).collect();
+    let mut expected: Vec<_> = (0..1000).step_by(2).collect();
+    expected.extend((1..1000).step_by(2));
+    assert_eq!(expected, popped);
+    assert!(handles.iter().all(|&(_, h)| pq.get(h).is_none()));
+
+    let h = pq.put(5, 5);
+    assert!(handles.iter().all(|&(_, old)| old != h));
+    assert_eq!(Some(&(5, 5)), pq.get(h));
+
+    let mut pq1 = PairingHeap::new_max();
+    let mut pq2 = PairingHeap::new_max();
+    elems.iter().for_each(|&i| { pq1.put(i, i); });
+    let hs: Vec<_> = elems.iter().map(|&i| pq2.put(i + 1000, i)).collect();
+    pq2.pop();
+    let moved = pq1.merge(&mut pq2);
+    assert!(pq2.is_empty());
+    assert_eq!(1999, pq1.len());
+    pq1.decrease_key(moved(hs[0]), 5000);
+    let popped: Vec<_> = pq1.clone().into_sorted_vec().into_iter().map(|(s, _)| s).collect();
+    assert!(popped.windows(2).all(|w| w[0] >= w[1]));
+    assert_eq!(Some(&5000), popped.first());
+
+    pq1.clear();
+    assert!(pq1.is_empty());
+    assert_eq!(None, pq1.pop());
+}
+
+#[test]
+#[should_panic(expected = "new score goes after the old one")]
+fn pq_pairing_heap_increase_key() {
+    let mut pq = priq::PairingHeap::new();
+    let h = pq.put(1, ());
+    pq.decrease_key(h, 2);
+}
+
+#[test]
+fn pq_fibonacci_heap() {
+    use priq::{FibonacciHeap, HeapBackend};
+
+    let mut elems: Vec<usize> = (0..1000).collect();
+    elems.shuffle(&mut thread_rng());
+
+    let mut pq = FibonacciHeap::new();
+    let handles: Vec<_> = elems.iter().map(|&i| (i, pq.put(i + 1000, i))).collect();
+    assert_eq!(Some(1000), pq.pop().map(|(s, _)| s));
+    let mut order: Vec<_> = handles.iter().filter(|(i, _)| *i != 0).collect();
+    order.shuffle(&mut thread_rng());
+    for &&(i, h) in order.iter().filter(|(i, _)| i % 3 == 0) {
+        pq.decrease_key(h, i);
+        assert_eq!(Some(&(i, i)), pq.get(h));
+        if i % 2 == 0 {
+            pq.decrease_key(h, i);
+        }
+    }
+    let popped: Vec<_> = std::iter::from_fn(|| pq.pop()).map(|(_, i)| i).collect();
+    let mut expected: Vec<_> = (3..1000).step_by(3).collect();
+    expected.extend((1..1000).filter(|i| i % 3 != 0));
+    assert_eq!(expected, popped);
+    assert!(handles.iter().all(|&(_, h)| pq.get(h).is_none()));
+
+    let mut pq1 = FibonacciHeap::new_max();
+    let mut pq2 = FibonacciHeap::new_max();
+    elems.iter().for_each(|&i| { pq1.put(i, i); });
+    let hs: Vec<_> = elems.iter().map(|&i| pq2.put(i + 1000, i)).collect();
+    pq1.pop();
+    pq2.pop();
+    let moved = pq1.merge(&mut pq2);
+    assert!(pq2.is_empty());
+    assert_eq!(1998, pq1.len());
+    let h = hs.iter().copied().find(|&h| pq1.get(moved(h)).is_some()).unwrap();
+    pq1.decrease_key(moved(h), 5000);
+    let popped: Vec<_> = pq1.clone().into_sorted_vec().into_iter().map(|(s, _)| s).collect();
+    assert!(popped.windows(2).all(|w| w[0] >= w[1]));
+    assert_eq!(Some(&5000), popped.first());
+
+    let pq: FibonacciHeap<f64, usize> =
+        elems.iter().map(|&i| (if i % 10 == 0 { f64::NAN } else { i as f64 }, i)).collect();
+    let popped: Vec<_> = pq.into_sorted_vec().into_iter().map(|(_, i)| i).collect();
+    assert!(popped[..900].windows(2).all(|w| w[0] < w[1]));
+    assert!(popped[900..].iter().all(|i| i % 10 == 0));
+}
+
+#[test]
+fn pq_binomial_heap() {
+    use priq::{BinomialHeap, HeapBackend};
+
+    let mut elems: Vec<usize> = (0..1000).collect();
+    elems.shuffle(&mut thread_rng());
+
+    let pq = elems.chunks(37)
+        .map(|chunk| chunk.iter().map(|&i| (i, i * 2)).collect::<BinomialHeap<_, _>>())
+        .fold(BinomialHeap::new(), BinomialHeap::meld);
+    assert_eq!(1000, pq.len());
+    assert_eq!(Some(&(0, 0)), pq.peek());
+    let sorted: Vec<_> = pq.into_sorted_vec().into_iter().map(|(s, _)| s).collect();
+    assert_eq!((0..1000).collect::<Vec<_>>(), sorted);
+
+    let mut pq = BinomialHeap::new_max();
+    for (n, &i) in elems.iter().enumerate() {
+        pq.put(i, ());
+        if n % 3 == 0 {
+            let top = pq.pop().unwrap().0;
+            assert!(pq.peek().is_none_or(|&(s, _)| s <= top));
+        }
+    }
+    assert_eq!(666, pq.len());
+    let popped: Vec<_> = std::iter::from_fn(|| pq.pop()).map(|(s, _)| s).collect();
+    assert!(popped.windows(2).all(|w| w[0] > w[1]));
+    assert!(pq.is_empty());
+
+    let pq: BinomialHeap<f64, usize> =
+        elems.iter().map(|&i| (if i % 10 == 0 { f64::NAN } else { i as f64 }, i)).collect();
+    let popped: Vec<_> = pq.into_sorted_vec().into_iter().map(|(_, i)| i).collect();
+    assert!(popped[..900].windows(2).all(|w| w[0] < w[1]));
+    assert!(popped[900..].iter().all(|i| i % 10 == 0));
+}
+
+#[test]
+fn pq_leftist_heap() {
+    use priq::{HeapBackend, LeftistHeap};
+
+    let mut elems: Vec<usize> = (0..1000).collect();
+    elems.shuffle(&mut thread_rng());
+
+    let mut pq: LeftistHeap<usize, usize> = elems[..500].iter().map(|&i| (i, i * 2)).collect();
+    let other: LeftistHeap<usize, usize> = elems[500..].iter().map(|&i| (i, i * 2)).collect();
+    let snapshot = pq.clone();
+    pq.merge(&other);
+    assert_eq!(1000, pq.len());
+    assert_eq!(500, other.len());
+    assert_eq!(Some(&(0, 0)), pq.peek());
+
+    let sorted: Vec<_> = pq.into_sorted_vec().into_iter().map(|(s, _)| s).collect();
+    assert_eq!((0..1000).collect::<Vec<_>>(), sorted);
+    let mut expected = elems[..500].to_vec();
+    expected.sort();
+    let sorted: Vec<_> = snapshot.into_sorted_vec().into_iter().map(|(s, _)| s).collect();
+    assert_eq!(expected, sorted);
+    assert_eq!(500, other.clone().into_sorted_vec().len());
+
+    let mut pq = LeftistHeap::new_max();
+    (0..100_000).for_each(|i| pq.put(i, ()));
+    assert_eq!(Some((99_999, ())), pq.pop());
+    pq.clear();
+    assert!(pq.is_empty());
+    (0..100_000).rev().for_each(|i| pq.put(i, ()));
+}
+
+#[test]
+fn pq_radix_heap() {
+    use priq::{HeapBackend, RadixHeap};
+
+    let mut elems: Vec<u64> = (0..1000).map(|i| i * 7919 % 100_003).collect();
+    elems.shuffle(&mut thread_rng());
+
+    let mut pq: RadixHeap<u64, u64> = elems.iter().map(|&i| (i, i)).collect();
+    assert_eq!(1000, pq.len());
+    assert_eq!(Some(&(0, 0)), pq.peek());
+    let mut popped = Vec::new();
+    while let Some((s, i)) = pq.pop() {
+        assert_eq!(s, pq.last());
+        if s % 2 == 1 && s < 50_000 {
+            pq.put(s * 2, s * 2);
+        }
+        popped.push(i);
+    }
+    assert!(popped.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(1000 + elems.iter().filter(|&&s| s % 2 == 1 && s < 50_000).count(), popped.len());
+
+    let mut pq = RadixHeap::starting_at(u8::MAX - 1);
+    pq.put(u8::MAX, 'b');
+    pq.put(u8::MAX - 1, 'a');
+    assert_eq!(vec![(254, 'a'), (255, 'b')], pq.into_sorted_vec());
+}
+
+#[test]
+#[should_panic(expected = "score is lower than the last popped one")]
+fn pq_radix_heap_non_monotone() {
+    let mut pq = priq::RadixHeap::new();
+    pq.put(5usize, ());
+    pq.pop();
+    pq.put(4, ());
+}
+
+#[test]
+fn pq_bucket_queue() {
+    use priq::BucketQueue;
+
+    let mut elems: Vec<usize> = (0..1000).collect();
+    elems.shuffle(&mut thread_rng());
+
+    let mut pq = BucketQueue::new(10);
+    pq.extend(elems.iter().map(|&i| (i % 10, i)));
+    assert_eq!(1000, pq.len());
+    assert_eq!(100, pq.level_len(3));
+    assert_eq!(Some((0, &elems.iter().copied().find(|i| i % 10 == 0).unwrap())), pq.peek());
+    let popped: Vec<_> = std::iter::from_fn(|| pq.pop()).collect();
+    let mut expected: Vec<_> = elems.iter().map(|&i| (i % 10, i)).collect();
+    expected.sort_by_key(|&(s, _)| s);
+    assert_eq!(expected, popped);
+
+    let mut pq = BucketQueue::new_max(3);
+    pq.put(0, 'c');
+    pq.put(2, 'a');
+    assert_eq!(Some((2, 'a')), pq.pop());
+    pq.put(1, 'b');
+    assert_eq!(Some((1, &'b')), pq.peek());
+    assert_eq!(Some((1, 'b')), pq.pop());
+    assert_eq!(Some((0, 'c')), pq.pop());
+    assert_eq!(None, pq.pop());
+    pq.put(2, 'd');
+    pq.clear();
+    assert!(pq.is_empty());
+    assert_eq!(None, pq.peek());
+}
+
+#[test]
+fn pq_calendar_queue() {
+    use priq::CalendarQueue;
+    use rand::Rng;
+
+    let mut rng = thread_rng();
+    let mut events = CalendarQueue::new();
+    let mut now = 0.0;
+    let mut popped = Vec::new();
+    for i in 0..5000 {
+        events.put(now + rng.gen_range(0.0..100.0), i);
+        if i % 3 == 0 {
+            let (t, _) = events.pop().unwrap();
+            now = t;
+            popped.push(t);
+        }
+    }
+    assert!(events.bucket_count() >= events.len() / 2);
+    assert!(events.bucket_width() < 1.0);
+    let peeked = events.peek().map(|(t, _)| t);
+    assert_eq!(peeked, events.pop().map(|(t, _)| t));
+    popped.extend(peeked);
+    while let Some((t, _)) = events.pop() {
+        popped.push(t);
+    }
+    assert_eq!(5000, popped.len());
+    assert!(popped.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(2, events.bucket_count());
+
+    let mut events: CalendarQueue<usize> = (0..100).map(|i| ((i % 4) as f64 * 1e6, i)).collect();
+    events.put(-5.0, 100);
+    events.put(f64::INFINITY, 101);
+    assert_eq!(Some((-5.0, 100)), events.pop());
+    let ties: Vec<_> = std::iter::from_fn(|| events.pop()).take(25).map(|(_, i)| i).collect();
+    assert_eq!((0..100).step_by(4).collect::<Vec<_>>(), ties);
+    assert_eq!(Some((f64::INFINITY, 101)), std::iter::from_fn(|| events.pop()).last());
+    assert!(events.is_empty());
+}
+
+#[test]
+fn pq_double_ended() {
+    use priq::DoubleEndedPriorityQueue;
+    use rand::Rng;
+
+    let mut rng = thread_rng();
+    let mut pq = DoubleEndedPriorityQueue::new();
+    let mut model: Vec<u32> = Vec::new();
+    for i in 0..5000 {
+        match rng.gen_range(0..4) {
+            0 if !model.is_empty() => assert_eq!(Some(model.remove(0)), pq.pop_min().map(|(s, _)| s)),
+            1 => assert_eq!(model.pop(), pq.pop_max().map(|(s, _)| s)),
+            _ => {
+                let s = rng.gen_range(0..1000);
+                pq.put(s, i);
+                let pos = model.partition_point(|&m| m < s);
+                model.insert(pos, s);
+            },
+        }
+        assert_eq!(model.first(), pq.peek_min().map(|(s, _)| s));
+        assert_eq!(model.last(), pq.peek_max().map(|(s, _)| s));
+        assert_eq!(model.len(), pq.len());
+    }
+    let sorted: Vec<_> = pq.into_sorted_vec().into_iter().map(|(s, _)| s).collect();
+    assert_eq!(model, sorted);
+
+    let mut pq: DoubleEndedPriorityQueue<f64, ()> =
+        [2.0, f64::NAN, 1.0, 3.0].into_iter().map(|s| (s, ())).collect();
+    assert!(pq.pop_max().unwrap().0.is_nan());
+    assert_eq!(Some((3.0, ())), pq.pop_max());
+    assert_eq!(Some((1.0, ())), pq.pop_min());
+}
+
+#[test]
+fn pq_median_heap() {
+    use priq::MedianHeap;
+
+    let mut elems: Vec<usize> = (0..1000).collect();
+    elems.shuffle(&mut thread_rng());
+
+    for q in [0.5, 0.01, 0.9, 1.0] {
+        let mut pq = MedianHeap::with_quantile(q);
+        let mut seen = Vec::new();
+        for &i in &elems {
+            pq.put(i, i);
+            let pos = seen.partition_point(|&s| s < i);
+            seen.insert(pos, i);
+            let rank = ((q * seen.len() as f64).ceil() as usize).max(1);
+            assert_eq!(Some(&seen[rank - 1]), pq.peek_score());
+        }
+        assert_eq!(1000, pq.len());
+        let top = pq.pop().unwrap().0;
+        assert_eq!(999, pq.len());
+        assert_ne!(Some(&top), pq.peek_score());
+    }
+
+    let mut pq = MedianHeap::new();
+    pq.extend([(1.0, ()), (f64::NAN, ()), (f64::NAN, ()), (3.0, ())]);
+    assert_eq!(Some(&3.0), pq.peek_score());
+    pq.clear();
+    assert!(pq.is_empty());
+    assert_eq!(None, pq.pop());
+}
+
+#[test]
+fn pq_top_k() {
+    use priq::TopK;
+
+    let mut elems: Vec<usize> = (0..1000).collect();
+    elems.shuffle(&mut thread_rng());
+
+    let mut top = TopK::new(10);
+    let rejected = elems.iter().filter_map(|&i| top.offer(i, i * 2)).count();
+    assert_eq!(990, rejected);
+    assert!(top.is_full());
+    assert_eq!(Some(&(9, 18)), top.peek_worst());
+    let best: Vec<_> = top.into_sorted_vec().into_iter().map(|(s, _)| s).collect();
+    assert_eq!((0..10).collect::<Vec<_>>(), best);
+
+    let mut top = TopK::new_max(3);
+    top.extend([(1.0, 'a'), (f64::NAN, 'n'), (3.0, 'c')]);
+    assert_eq!('n', top.peek_worst().unwrap().1);
+    assert_eq!(Some('n'), top.offer(2.0, 'b').map(|(_, i)| i));
+    assert_eq!(Some((0.5, 'd')), top.offer(0.5, 'd'));
+    assert_eq!(Some((1.0, 'a')), top.clone().offer(1.5, 'e'));
+    assert_eq!(vec![(3.0, 'c'), (2.0, 'b'), (1.0, 'a')], top.clone().into_sorted_vec());
+    top.clear();
+    assert!(top.is_empty());
+
+    let mut none: TopK<u8, ()> = TopK::new(0);
+    assert_eq!(Some((1, ())), none.offer(1, ()));
+    assert!(none.into_vec().is_empty());
+}
+
+#[test]
+fn pq_bheap() {
+    use priq::{BHeap, HeapBackend};
+
+    let mut elems: Vec<u32> = (0..3000).collect();
+    elems.shuffle(&mut thread_rng());
+
+    let mut small: BHeap<u8, ()> = BHeap::new();
+    let mut wide: BHeap<u32, [u64; 4], _> = BHeap::new_max();
+    let mut strings: BHeap<u32, String> = BHeap::with_capacity(10);
+    for &i in &elems {
+        small.put((i % 256) as u8, ());
+        wide.put(i, [i as u64; 4]);
+        strings.put(i, i.to_string());
+    }
+    assert_eq!(Some(&(0, ())), small.peek());
+    let popped: Vec<_> = std::iter::from_fn(|| small.pop()).map(|(s, _)| s).collect();
+    assert!(popped.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(3000, popped.len());
+
+    let sorted = wide.clone().into_sorted_vec();
+    assert!(sorted.iter().rev().map(|(s, e)| (*s, e[3] as u32)).eq((0..3000).map(|i| (i, i))));
+    assert_eq!(3000, wide.len());
+
+    for i in 0..1000 {
+        assert_eq!(Some(i.to_string()), strings.pop().map(|(_, e)| e));
+    }
+    let copy = strings.clone();
+    strings.clear();
+    assert!(strings.is_empty());
+    assert_eq!(Some(&(1000, "1000".to_string())), copy.peek());
+
+    let nans: BHeap<f64, u32> =
+        elems.iter().map(|&i| (if i % 10 == 0 { f64::NAN } else { i as f64 }, i)).collect();
+    let popped: Vec<_> = nans.into_sorted_vec().into_iter().map(|(_, i)| i).collect();
+    assert!(popped[..2700].windows(2).all(|w| w[0] < w[1]));
+    assert!(popped[2700..].iter().all(|i| i % 10 == 0));
+}
+
+#[test]
+fn pq_soa() {
+    use priq::{HeapBackend, SoaPriorityQueue};
+
+    let mut elems: Vec<u32> = (0..2000).collect();
+    elems.shuffle(&mut thread_rng());
+
+    let mut pq: SoaPriorityQueue<u32, [u32; 64]> = SoaPriorityQueue::new();
+    let mut max = SoaPriorityQueue::new_max();
+    for &i in &elems {
+        pq.put(i, [i; 64]);
+        max.put(i, i.to_string());
+    }
+    assert_eq!(2000, pq.len());
+    assert_eq!(Some(&0), pq.peek_score());
+    assert_eq!(Some((&1999, &"1999".to_string())), max.peek());
+    for i in 0..1000 {
+        let (score, item) = pq.pop().unwrap();
+        assert_eq!((i, [i; 64]), (score, item));
+    }
+
+    // slots of the popped items are reused
+    for &i in elems.iter().filter(|&&i| i < 1000) {
+        pq.put(i, [i; 64]);
+    }
+    let copy = pq.clone();
+    assert!(copy.into_sorted_vec().into_iter().map(|(s, e)| (s, e[63])).eq((0..2000).map(|i| (i, i))));
+    pq.clear();
+    assert!(pq.is_empty());
+    assert_eq!(None, pq.pop());
+
+    let sorted: Vec<_> = max.into_sorted_vec().into_iter().map(|(s, e)| (s, e.parse().unwrap())).collect();
+    assert!(sorted.into_iter().eq((0..2000).rev().map(|i| (i, i))));
+
+    let nans: SoaPriorityQueue<f64, u32> =
+        elems.iter().map(|&i| (if i % 10 == 0 { f64::NAN } else { i as f64 }, i)).collect();
+    assert_eq!(2000, nans.scores().len());
+    let popped: Vec<_> = nans.into_sorted_vec().into_iter().map(|(_, i)| i).collect();
+    assert!(popped[..1800].windows(2).all(|w| w[0] < w[1]));
+    assert!(popped[1800..].iter().all(|i| i % 10 == 0));
+}
+
+#[test]
+fn pq_weak_heap() {
+    use priq::{HeapBackend, WeakHeap};
+    use std::cell::Cell;
+    use std::cmp::Ordering;
+
+    #[derive(Debug)]
+    struct Counted<'a>(u32, &'a Cell<usize>);
+
+    impl PartialEq for Counted<'_> {
+        fn eq(&self, other: &Self) -> bool {
+            self.partial_cmp(other) == Some(Ordering::Equal)
+        }
+    }
+
+    impl PartialOrd for Counted<'_> {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            self.1.set(self.1.get() + 1);
+            self.0.partial_cmp(&other.0)
+        }
+    }
+
+    let mut elems: Vec<u32> = (0..4096).collect();
+    elems.shuffle(&mut thread_rng());
+
+    let count = Cell::new(0);
+    let mut pq: WeakHeap<_, _> = elems.iter().map(|&i| (Counted(i, &count), i)).collect();
+    assert_eq!(4095, count.get());
+    count.set(0);
+    for i in 0..4096 {
+        assert_eq!(Some(i), pq.pop().map(|(_, e)| e));
+    }
+    assert!(count.get() <= 4096 * 12);
+
+    let mut pq = WeakHeap::new_max();
+    for &i in &elems {
+        pq.put(i, i.to_string());
+    }
+    assert_eq!(Some(&(4095, "4095".to_string())), pq.peek());
+    for i in (2048..4096).rev() {
+        assert_eq!(Some((i, i.to_string())), pq.pop());
+    }
+    pq.extend(elems.iter().filter(|&&i| i >= 2048).map(|&i| (i, i.to_string())));
+    assert_eq!(4096, pq.len());
+    let sorted: Vec<_> = pq.clone().into_sorted_vec().into_iter().map(|(s, _)| s).collect();
+    assert!(sorted.into_iter().eq((0..4096).rev()));
+    pq.clear();
+    assert!(pq.is_empty());
+    assert_eq!(None, pq.pop());
+
+    let nans: WeakHeap<f64, u32> =
+        elems.iter().map(|&i| (if i % 10 == 0 { f64::NAN } else { i as f64 }, i)).collect();
+    let popped: Vec<_> = nans.into_sorted_vec().into_iter().map(|(_, i)| i).collect();
+    assert!(popped[..3686].windows(2).all(|w| w[0] < w[1]));
+    assert!(popped[3686..].iter().all(|i| i % 10 == 0));
+}
+
+#[test]
+fn pq_soft_heap() {
+    use priq::SoftHeap;
+    use std::collections::BTreeSet;
+
+    let mut elems: Vec<u32> = (0..5000).collect();
+    elems.shuffle(&mut thread_rng());
+
+    // at most `ε * n` elements are popped later than they should
+    let mut pq = SoftHeap::new(0.1);
+    pq.extend(elems.iter().map(|&i| (i, i.to_string())));
+    assert_eq!(5000, pq.len());
+    let mut left: BTreeSet<u32> = (0..5000).collect();
+    while let Some((s, e)) = pq.pop() {
+        assert_eq!(s.to_string(), e);
+        assert!(left.remove(&s));
+        assert!(left.range(..s).count() <= 500);
+    }
+    assert!(left.is_empty() && pq.is_empty());
+
+    // with a low enough rate no element is corrupted
+    let (lower, upper) = elems.split_at(2500);
+    let mut pq = SoftHeap::new_max(0.001);
+    lower.iter().for_each(|&i| pq.put(i, ()));
+    let mut other = SoftHeap::new_max(0.001);
+    upper.iter().for_each(|&i| other.put(i, ()));
+    let mut pq = pq.meld(other);
+    assert_eq!(5000, pq.len());
+    assert_eq!(Some(&(4999, ())), pq.peek());
+    for i in (0..5000).rev() {
+        assert_eq!(Some((i, ())), pq.pop());
+    }
+    assert_eq!(None, pq.pop());
+
+    let mut nans: SoftHeap<f64, u32> = SoftHeap::new(0.001);
+    elems.iter().for_each(|&i| nans.put(if i % 10 == 0 { f64::NAN } else { i as f64 }, i));
+    let copy = nans.clone();
+    let popped: Vec<_> = std::iter::from_fn(|| nans.pop()).map(|(_, i)| i).collect();
+    assert!(popped[..4500].windows(2).all(|w| w[0] < w[1]));
+    assert!(popped[4500..].iter().all(|i| i % 10 == 0));
+    assert_eq!(5000, copy.len());
+    assert_eq!(0.001, copy.epsilon());
+}
+
+#[test]
+fn pq_heap_backend() {
+    use priq::{BHeap, BTreePriorityQueue, BinomialHeap, DaryHeap, FibonacciHeap, HeapBackend,
+               LeftistHeap, PairingHeap, RadixHeap, SoaPriorityQueue, StablePriorityQueue,
+               WeakHeap};
+
+    fn check<B: HeapBackend<u32, String> + Default>(mut pq: B, elems: &[u32]) {
+        assert!(pq.is_empty());
+        elems.iter().for_each(|&i| pq.put(i, i.to_string()));
+        assert_eq!(elems.len(), pq.len());
+        assert_eq!(Some(&0), pq.peek_score());
+        assert_eq!(Some(&"0".to_string()), pq.peek_item());
+        assert_eq!(Some("0".to_string()), pq.pop_item());
+
+        let mut drain = pq.drain_sorted();
+        assert_eq!(elems.len() - 1, drain.len());
+        assert!(drain.by_ref().take(100).map(|(s, _)| s).eq(1..101));
+        drop(drain);
+        assert!(pq.is_empty());
+
+        elems.iter().for_each(|&i| pq.put(i, i.to_string()));
+        pq.pop();
+        pq.clear();
+        assert_eq!(None, pq.pop());
+        elems.iter().for_each(|&i| pq.put(i, i.to_string()));
+        let sorted = pq.into_sorted_iter();
+        assert_eq!(elems.len(), sorted.len());
+        assert!(sorted.map(|(s, e)| (s, e.parse().unwrap())).eq((0..1000).map(|i| (i, i))));
+
+        let mut pq = B::default();
+        elems.iter().for_each(|&i| pq.put(i, i.to_string()));
+        let sorted = pq.into_sorted_vec();
+        assert!(sorted.into_iter().map(|(s, _)| s).eq(0..1000));
+    }
+
+    let mut elems: Vec<u32> = (0..1000).collect();
+    elems.shuffle(&mut thread_rng());
+
+    check(PriorityQueue::new(), &elems);
+    check(BTreePriorityQueue::new(), &elems);
+    check(StablePriorityQueue::new(), &elems);
+    check(DaryHeap::<_, _, 4>::new(), &elems);
+    check(BHeap::new(), &elems);
+    check(WeakHeap::new(), &elems);
+    check(SoaPriorityQueue::new(), &elems);
+    check(BinomialHeap::new(), &elems);
+    check(PairingHeap::new(), &elems);
+    check(FibonacciHeap::new(), &elems);
+    check(LeftistHeap::new(), &elems);
+
+    // radix heap doesn't accept scores lower than the popped ones
+    let mut radix = RadixHeap::new();
+    elems.iter().for_each(|&i| HeapBackend::put(&mut radix, i, i));
+    assert_eq!(Some((&0, &0)), HeapBackend::peek(&radix));
+    assert!(radix.into_sorted_iter().map(|(s, _)| s).eq(0..1000));
+}
+
+#[test]
+fn pq_heap_backend_nan() {
+    use priq::{BHeap, BTreePriorityQueue, BinomialHeap, DaryHeap, FibonacciHeap, HeapBackend,
+               LeftistHeap, PairingHeap, SegmentedPriorityQueue, SmallPriorityQueue,
+               SoaPriorityQueue, WeakHeap};
+
+    // every backend orders scores the same way, incomparable ones last
+    fn check<B: HeapBackend<f64, usize>>(mut pq: B) {
+        [2.0, f64::NAN, 1.0, f64::NAN, 3.0, 0.5].into_iter()
+            .enumerate()
+            .for_each(|(i, s)| pq.put(s, i));
+        let sorted: Vec<f64> = pq.into_sorted_iter().map(|(s, _)| s).collect();
+        assert_eq!(vec![0.5, 1.0, 2.0, 3.0], sorted[..4]);
+        assert!(sorted[4..].iter().all(|s| s.is_nan()));
+    }
+
+    check(PriorityQueue::new());
+    check(BTreePriorityQueue::new());
+    check(DaryHeap::<_, _, 3>::new());
+    check(BHeap::new());
+    check(WeakHeap::new());
+    check(SegmentedPriorityQueue::new());
+    check(SmallPriorityQueue::<_, _, 4>::new());
+    check(SoaPriorityQueue::new());
+    check(BinomialHeap::new());
+    check(PairingHeap::new());
+    check(FibonacciHeap::new());
+    check(LeftistHeap::new());
+}
+
+#[test]
+fn pq_btree() {
+    use priq::{BTreePriorityQueue, HeapBackend};
+
+    let mut elems: Vec<u32> = (0..3000).collect();
+    elems.shuffle(&mut thread_rng());
+
+    // equal scores come out in the order they were put
+    let mut pq = BTreePriorityQueue::new();
+    for &i in &elems {
+        pq.put(i % 100, i);
+    }
+    assert_eq!(3000, pq.len());
+    assert_eq!(100, pq.score_count());
+    let firsts: Vec<_> = elems.iter().filter(|&&i| i % 100 == 0).collect();
+    assert_eq!(Some((&0, firsts[0])), pq.peek());
+    let in_range: Vec<_> = pq.range(10..=11).map(|(s, e)| (*s, *e)).collect();
+    assert_eq!(60, in_range.len());
+    assert!(in_range[..30].iter().all(|&(s, e)| s == 10 && e % 100 == 10));
+    assert_eq!(3000, pq.iter().count());
+    assert_eq!(3000, pq.len());
+    for &i in firsts {
+        assert_eq!(Some((0, i)), pq.pop());
+    }
+    let sorted = pq.clone().into_sorted_vec();
+    assert!(sorted.windows(2).all(|w| w[0].0 <= w[1].0));
+    assert!(sorted.iter().map(|(s, _)| s).eq(pq.iter().map(|(s, _)| s)));
+    assert_eq!(2970, pq.drain_sorted().count());
+    assert!(pq.is_empty());
+
+    let mut max = BTreePriorityQueue::new_max();
+    max.extend(elems.iter().map(|&i| (if i % 10 == 0 { f64::NAN } else { i as f64 }, i)));
+    let top: Vec<_> = max.range(2990.0..2995.0).map(|(_, e)| *e).collect();
+    assert_eq!(vec![2994, 2993, 2992, 2991], top);
+    assert_eq!(Some((2999.0, 2999)), max.pop());
+    let popped: Vec<_> = max.into_sorted_vec().into_iter().map(|(_, i)| i).collect();
+    assert!(popped[..2699].windows(2).all(|w| w[0] > w[1]));
+    assert!(popped[2699..].iter().all(|i| i % 10 == 0));
+}
+
+#[test]
+fn pq_segmented() {
+    use priq::{HeapBackend, SegmentedPriorityQueue};
+
+    let mut elems: Vec<u32> = (0..3000).collect();
+    elems.shuffle(&mut thread_rng());
+
+    let mut pq = SegmentedPriorityQueue::with_segment_len(100);
+    assert_eq!(128, pq.segment_len());
+    for &i in &elems {
+        pq.put(i, i.to_string());
+    }
+    assert_eq!(3072, pq.capacity());
+    assert_eq!(Some(&(0, "0".to_string())), pq.peek());
+    for i in 0..1000 {
+        assert_eq!(Some((i, i.to_string())), pq.pop());
+    }
+    // a spare segment is kept after the ones in use
+    assert_eq!(2000, pq.len());
+    assert_eq!(17 * 128, pq.capacity());
+    let copy = pq.clone();
+    assert!(copy.into_sorted_vec().into_iter().map(|(s, _)| s).eq(1000..3000));
+    pq.clear();
+    assert!(pq.is_empty());
+    assert_eq!(0, pq.capacity());
+
+    let mut max = SegmentedPriorityQueue::with_segment_len_max(1);
+    max.extend(elems.iter().map(|&i| (i, ())));
+    assert!(max.into_sorted_vec().into_iter().map(|(s, _)| s).eq((0..3000).rev()));
+
+    let nans: SegmentedPriorityQueue<f64, u32> =
+        elems.iter().map(|&i| (if i % 10 == 0 { f64::NAN } else { i as f64 }, i)).collect();
+    let popped: Vec<_> = nans.into_sorted_vec().into_iter().map(|(_, i)| i).collect();
+    assert!(popped[..2700].windows(2).all(|w| w[0] < w[1]));
+    assert!(popped[2700..].iter().all(|i| i % 10 == 0));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_blocking() {
+    use priq::sync::BlockingPriorityQueue;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    let pq = Arc::new(BlockingPriorityQueue::new_max());
+    let consumers: Vec<_> = (0..4).map(|_| {
+        let pq = Arc::clone(&pq);
+        thread::spawn(move || (0..250).map(|_| pq.pop_wait().1).collect::<Vec<u32>>())
+    }).collect();
+    let producers: Vec<_> = (0..4).map(|p| {
+        let pq = Arc::clone(&pq);
+        thread::spawn(move || (0..250).for_each(|i| pq.put(i, p * 250 + i)))
+    }).collect();
+    producers.into_iter().for_each(|p| p.join().unwrap());
+    let mut popped: Vec<_> = consumers.into_iter().flat_map(|c| c.join().unwrap()).collect();
+    popped.sort_unstable();
+    assert!(popped.into_iter().eq(0..1000));
+    assert!(pq.is_empty());
+    assert_eq!(None, pq.pop_timeout(Duration::from_millis(5)));
+
+    let waiter = {
+        let pq = Arc::clone(&pq);
+        thread::spawn(move || {
+            pq.wait_until_nonempty();
+            pq.pop_timeout(Duration::from_secs(10))
+        })
+    };
+    pq.put(7, 70);
+    assert_eq!(Some((7, 70)), waiter.join().unwrap());
+
+    pq.put(1, 10);
+    pq.put(2, 20);
+    assert_eq!(2, pq.len());
+    assert_eq!(Some((2, 20)), pq.pop());
+    let pq = Arc::try_unwrap(pq).unwrap().into_inner();
+    assert_eq!(Some(&(1, 10)), pq.peek());
+}
+
+#[test]
+#[cfg(feature = "concurrent")]
+fn pq_concurrent() {
+    use priq::concurrent::ConcurrentPriorityQueue;
+    use std::sync::Arc;
+    use std::thread;
+
+    let mut elems: Vec<u32> = (0..2000).collect();
+    elems.shuffle(&mut thread_rng());
+    let pq = ConcurrentPriorityQueue::new();
+    elems.iter().for_each(|&i| pq.put(i / 2, i));
+    assert_eq!(2000, pq.len());
+    let popped: Vec<_> = std::iter::from_fn(|| pq.pop()).collect();
+    assert!(popped.iter().map(|&(s, _)| s).eq((0..1000).flat_map(|s| [s, s])));
+    assert!(pq.is_empty());
+    assert_eq!(None, pq.pop());
+
+    let pq = Arc::new(ConcurrentPriorityQueue::new_max());
+    let workers: Vec<_> = (0..4).map(|t| {
+        let pq = Arc::clone(&pq);
+        thread::spawn(move || {
+            let mut popped = Vec::new();
+            for i in 0..2000 {
+                pq.put(i, t * 2000 + i);
+                if i % 2 == 0 {
+                    popped.extend(pq.pop().map(|(_, e)| e));
+                }
+            }
+            popped
+        })
+    }).collect();
+    let mut popped: Vec<_> = workers.into_iter().flat_map(|w| w.join().unwrap()).collect();
+    assert_eq!(8000 - popped.len(), pq.len());
+    let rest: Vec<_> = std::iter::from_fn(|| pq.pop()).collect();
+    assert!(rest.windows(2).all(|w| w[0].0 >= w[1].0));
+    popped.extend(rest.into_iter().map(|(_, e)| e));
+    popped.sort_unstable();
+    assert!(popped.into_iter().eq(0..8000));
+
+    let nans = ConcurrentPriorityQueue::new();
+    (0..100).for_each(|i| nans.put(if i % 10 == 0 { f64::NAN } else { i as f64 }, i));
+    let popped: Vec<_> = std::iter::from_fn(|| nans.pop()).map(|(_, i)| i).collect();
+    assert!(popped[..90].windows(2).all(|w| w[0] < w[1]));
+    assert!(popped[90..].windows(2).all(|w| w[0] < w[1] && w[0] % 10 == 0));
+
+    let leftovers = ConcurrentPriorityQueue::new();
+    (0..100).for_each(|i| leftovers.put(i, vec![i; 3]));
+    (0..50).for_each(|_| { leftovers.pop(); });
+    drop(leftovers);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_relaxed() {
+    use priq::sync::RelaxedPriorityQueue;
+    use std::sync::Arc;
+    use std::thread;
+
+    let pq = Arc::new(RelaxedPriorityQueue::new(4));
+    let workers: Vec<_> = (0..4).map(|t| {
+        let pq = Arc::clone(&pq);
+        thread::spawn(move || {
+            let worker = pq.worker();
+            let mut elems: Vec<u32> = (0..1000).map(|i| t * 1000 + i).collect();
+            elems.shuffle(&mut thread_rng());
+            elems.iter().for_each(|&i| worker.put(i, i));
+            // every shard is still sorted on its own
+            let local: Vec<_> = (0..500).filter_map(|_| worker.pop_local()).map(|(_, e)| e).collect();
+            assert!(local.windows(2).all(|w| w[0] < w[1]));
+            local
+        })
+    }).collect();
+    let mut popped: Vec<_> = workers.into_iter().flat_map(|w| w.join().unwrap()).collect();
+    assert_eq!(4, pq.shards());
+    assert_eq!(2000, pq.len());
+
+    let worker = pq.worker();
+    // shards are handed out in turns, so this one is shared with the first
+    assert_eq!(0, worker.shard());
+    while let Some((_, e)) = worker.pop_local() {
+        popped.push(e);
+    }
+    assert_eq!(1500, pq.len());
+    while let Some((_, e)) = worker.pop() {
+        popped.push(e);
+    }
+    popped.sort_unstable();
+    assert!(popped.into_iter().eq(0..4000));
+    assert!(pq.is_empty());
+
+    let pq = RelaxedPriorityQueue::new_max(3);
+    (0..30).for_each(|i| pq.worker().put(i, i));
+    assert_eq!(30, pq.len());
+    let sorted: Vec<_> = pq.into_sorted_vec().into_iter().map(|(_, e)| e).collect();
+    assert!(sorted.into_iter().eq((0..30).rev()));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_channel() {
+    use priq::channel::{self, RecvTimeoutError, SendError, TryRecvError};
+    use std::thread;
+    use std::time::Duration;
+
+    let (tx, rx) = channel::channel();
+    let producers: Vec<_> = (0..4).map(|p| {
+        let tx = tx.clone();
+        thread::spawn(move || {
+            let mut elems: Vec<u32> = (0..250).map(|i| p * 250 + i).collect();
+            elems.shuffle(&mut thread_rng());
+            elems.into_iter().for_each(|i| tx.send(i, i).unwrap());
+        })
+    }).collect();
+    producers.into_iter().for_each(|p| p.join().unwrap());
+    assert_eq!(1000, rx.len());
+    assert!(rx.try_iter().map(|(_, e)| e).eq(0..1000));
+    assert_eq!(Err(TryRecvError::Empty), rx.try_recv());
+    assert_eq!(Err(RecvTimeoutError::Timeout), rx.recv_timeout(Duration::from_millis(5)));
+
+    let consumer = thread::spawn(move || rx.into_iter().map(|(_, e)| e).collect::<Vec<_>>());
+    tx.send(7, 7).unwrap();
+    drop(tx);
+    assert_eq!(vec![7], consumer.join().unwrap());
+
+    let (tx, rx) = channel::channel_max();
+    (0..10).for_each(|i| tx.send(i, i * 10).unwrap());
+    drop(tx);
+    let received: Vec<_> = rx.iter().map(|(s, _)| s).collect();
+    assert!(received.into_iter().eq((0..10).rev()));
+    assert_eq!(Err(TryRecvError::Disconnected), rx.try_recv());
+    assert_eq!(Err(RecvTimeoutError::Disconnected), rx.recv_timeout(Duration::from_secs(10)));
+
+    let (tx, rx) = channel::channel::<u32, &str>();
+    drop(rx);
+    assert_eq!(Err(SendError((1, "lost"))), tx.send(1, "lost"));
+
+    // timeout past the representable instants waits like `recv`
+    let (tx, rx) = channel::channel();
+    tx.send(1, 'a').unwrap();
+    assert_eq!(Ok((1, 'a')), rx.recv_timeout(Duration::MAX));
+    drop(tx);
+    assert_eq!(Err(RecvTimeoutError::Disconnected), rx.recv_timeout(Duration::MAX));
+
+    // pending messages owning a sender are dropped without a deadlock
+    struct Carrier { _tx: Option<channel::Sender<u32, Carrier>> }
+    let (tx, rx) = channel::channel();
+    assert!(tx.send(1, Carrier { _tx: None }).is_ok());
+    assert!(tx.send(2, Carrier { _tx: Some(tx.clone()) }).is_ok());
+    drop(rx);
+    assert!(tx.send(3, Carrier { _tx: None }).is_err());
+}
+
+/// Wakes up a thread parked by `block_on`.
+#[cfg(feature = "std")]
+struct Unpark(std::thread::Thread);
+
+#[cfg(feature = "std")]
+impl std::task::Wake for Unpark {
+    fn wake(self: std::sync::Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs a future to completion on the current thread.
+#[cfg(feature = "std")]
+fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+    use std::task::{Context, Poll};
+
+    let waker = std::sync::Arc::new(Unpark(std::thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = std::pin::pin!(fut);
+    loop {
+        match fut.as_mut().poll(&mut cx) {
+            Poll::Ready(out) => return out,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_channel_async() {
+    use priq::channel::{self, RecvError};
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+    use std::thread;
+
+    let (tx, rx) = channel::channel();
+    let consumer = thread::spawn(move || block_on(async {
+        let mut received = Vec::new();
+        while let Ok((_, e)) = rx.recv_async().await {
+            received.push(e);
+        }
+        received
+    }));
+    let producers: Vec<_> = (0..4).map(|p| {
+        let tx = tx.clone();
+        thread::spawn(move || (0..250).for_each(|i| tx.send(i, p * 250 + i).unwrap()))
+    }).collect();
+    producers.into_iter().for_each(|p| p.join().unwrap());
+    drop(tx);
+    let mut received = consumer.join().unwrap();
+    received.sort_unstable();
+    assert!(received.into_iter().eq(0..1000));
+
+    let (tx, rx) = channel::channel_max();
+    let waker = Arc::new(Unpark(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    assert_eq!(Poll::Pending, rx.poll_recv(&mut cx));
+    tx.send(1, 'a').unwrap();
+    tx.send(3, 'c').unwrap();
+    assert_eq!(Poll::Ready(Ok((3, 'c'))), rx.poll_recv(&mut cx));
+    drop(tx);
+    assert_eq!(Ok((1, 'a')), block_on(rx.recv_async()));
+    assert_eq!(Err(RecvError), block_on(rx.recv_async()));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_async_queue() {
+    use priq::sync::AsyncPriorityQueue;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+    use std::thread;
+
+    let pq = Arc::new(AsyncPriorityQueue::new());
+    let consumers: Vec<_> = (0..4).map(|_| {
+        let pq = Arc::clone(&pq);
+        thread::spawn(move || block_on(async {
+            let mut popped = Vec::new();
+            for _ in 0..250 {
+                popped.push(pq.pop().await.1);
+            }
+            popped
+        }))
+    }).collect();
+    let producers: Vec<_> = (0..4).map(|p| {
+        let pq = Arc::clone(&pq);
+        thread::spawn(move || (0..250).for_each(|i| pq.put(i, p * 250 + i)))
+    }).collect();
+    producers.into_iter().for_each(|p| p.join().unwrap());
+    let mut popped: Vec<_> = consumers.into_iter().flat_map(|c| c.join().unwrap()).collect();
+    popped.sort_unstable();
+    assert!(popped.into_iter().eq(0..1000));
+    assert_eq!(0, pq.waiting());
+
+    // consumers are served in the order they started to wait
+    let pq = AsyncPriorityQueue::new_max();
+    let waker = Arc::new(Unpark(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let (mut first, mut second) = (pin!(pq.pop()), pin!(pq.pop()));
+    assert!(first.as_mut().poll(&mut cx).is_pending());
+    assert!(second.as_mut().poll(&mut cx).is_pending());
+    let mut late = pq.stream();
+    pq.put(1, 'a');
+    assert!(late.poll_next_elem(&mut cx).is_pending());
+    assert!(second.as_mut().poll(&mut cx).is_pending());
+    pq.put(2, 'b');
+    assert_eq!(Poll::Ready((2, 'b')), first.poll(&mut cx));
+    assert_eq!(Poll::Ready((1, 'a')), second.poll(&mut cx));
+    assert_eq!(1, pq.waiting());
+    drop(late);
+
+    // a cancelled consumer leaves its place to the next one
+    let mut next = pin!(pq.pop());
+    {
+        let mut cancelled = pin!(pq.pop());
+        assert!(cancelled.as_mut().poll(&mut cx).is_pending());
+        assert!(next.as_mut().poll(&mut cx).is_pending());
+        pq.put(3, 'c');
+    }
+    assert_eq!(Poll::Ready((3, 'c')), next.poll(&mut cx));
+    assert_eq!(0, pq.waiting());
+    assert_eq!(None, pq.try_pop());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_delay_queue() {
+    use priq::time::DelayQueue;
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    let start = Instant::now();
+    let mut delays: Vec<u64> = (0..20).collect();
+    delays.shuffle(&mut thread_rng());
+    let mut timeouts: DelayQueue<_> =
+        delays.into_iter().map(|ms| (ms, start + Duration::from_millis(ms * 2 + 1))).collect();
+    timeouts.insert_at(100, start);
+    timeouts.insert_at(101, start);
+    assert_eq!(Some(start), timeouts.next_deadline());
+
+    let expired = block_on(async {
+        let mut expired = Vec::new();
+        while let Some(ms) = timeouts.next_expired().await {
+            assert!(ms >= 100 || start.elapsed() > Duration::from_millis(ms * 2));
+            expired.push(ms);
+        }
+        expired
+    });
+    assert_eq!(vec![100, 101], expired[..2]);
+    assert!(expired[2..].iter().copied().eq(0..20));
+    assert!(timeouts.is_empty());
+
+    timeouts.insert(1, Duration::from_secs(60));
+    let waker = Arc::new(Unpark(thread::current())).into();
+    assert_eq!(Poll::Pending, timeouts.poll_expired(&mut Context::from_waker(&waker)));
+    assert_eq!(None, timeouts.pop_expired());
+    assert_eq!(1, timeouts.len());
+
+    // repeated polls keep a single registration of the waker in the timer
+    let unpark = Arc::new(Unpark(thread::current()));
+    let waker = Arc::clone(&unpark).into();
+    let mut cx = Context::from_waker(&waker);
+    for _ in 0..100 {
+        assert_eq!(Poll::Pending, timeouts.poll_expired(&mut cx));
+    }
+    assert_eq!(3, Arc::strong_count(&unpark));
+    timeouts.insert(2, Duration::from_secs(30));
+    assert_eq!(Poll::Pending, timeouts.poll_expired(&mut cx));
+    assert_eq!(3, Arc::strong_count(&unpark));
+    drop(timeouts);
+    assert_eq!(2, Arc::strong_count(&unpark));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_timer_queue() {
+    use priq::time::{Clock, ManualClock, TimerQueue};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let clock = Arc::new(ManualClock::new());
+    let mut timers = TimerQueue::with_clock(Arc::clone(&clock));
+    let mut delays: Vec<u64> = (0..100).collect();
+    delays.shuffle(&mut thread_rng());
+    delays.iter().for_each(|&s| timers.schedule_in(Duration::from_secs(s / 2), s));
+    assert_eq!(Some(Duration::ZERO), timers.time_until_next());
+
+    let mut expired = Vec::new();
+    for _ in 0..50 {
+        expired.extend(timers.expired().map(|s| s / 2));
+        clock.advance(Duration::from_secs(1));
+    }
+    assert!(expired.into_iter().eq((0..50).flat_map(|s| [s, s])));
+    assert!(timers.is_empty());
+
+    let start = clock.now();
+    timers.schedule_at(start + Duration::from_secs(3), 1);
+    timers.schedule_at(start + Duration::from_secs(3), 2);
+    timers.schedule_at(start + Duration::from_secs(9), 3);
+    assert_eq!(Some(Duration::from_secs(3)), timers.time_until_next());
+    assert_eq!(Some(1), timers.pop_expired(start + Duration::from_secs(4)).next());
+    assert_eq!(2, timers.len());
+    let later: Vec<_> = timers.pop_expired(start + Duration::from_secs(9)).collect();
+    assert_eq!(vec![2, 3], later);
+    assert_eq!(None, timers.next_deadline());
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn pq_par_collect() {
+    use rayon::prelude::*;
+
+    let mut elems: Vec<u32> = (0..100_000).collect();
+    elems.shuffle(&mut thread_rng());
+    let pq: PriorityQueue<_, _> = elems.par_iter().map(|&i| (i, i * 2)).collect();
+    assert_eq!(100_000, pq.len());
+    assert!(pq.into_sorted_vec().into_iter().map(|(s, _)| s).eq(0..100_000));
+
+    let pq: PriorityQueue<u32, u32> = Vec::new().into_par_iter().collect();
+    assert!(pq.is_empty());
+    let pq: PriorityQueue<_, _> = (0..10_000).into_par_iter()
+        .map(|i| (if i % 100 == 0 { f64::NAN } else { f64::from(i) }, i))
+        .collect();
+    assert_eq!(100, pq.nan_count());
+    assert_eq!(Some(&(1.0, 1)), pq.peek());
+
+    let mut pq = PriorityQueue::new_max();
+    pq.put(1_000_000, 0);
+    pq.par_extend(elems.par_iter().map(|&i| (i, i)));
+    assert_eq!(100_001, pq.len());
+    assert_eq!(Some((1_000_000, 0)), pq.pop());
+    assert_eq!(Some((99_999, 99_999)), pq.pop());
+
+    let mut pq = PriorityQueue::with_capacity(10);
+    pq.par_extend((0..5).into_par_iter().map(|i| (f64::from(i), i)));
+    pq.par_extend(vec![(f64::NAN, 5)]);
+    assert_eq!(1, pq.nan_count());
+    assert_eq!(Some(0), pq.pop_item());
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn pq_par_iter() {
+    use rayon::prelude::*;
+
+    let mut elems: Vec<u64> = (0..100_000).collect();
+    elems.shuffle(&mut thread_rng());
+    let mut pq: PriorityQueue<_, _> = elems.into_iter().map(|i| (i, i * 2)).collect();
+    assert_eq!(99_999 * 100_000, pq.par_iter().map(|(_, e)| e).sum::<u64>());
+    assert_eq!(Some(&(0, 0)), pq.par_iter().find_first(|_| true));
+
+    let mut drained: Vec<_> = pq.par_drain().map(|(s, _)| s).collect();
+    assert!(pq.is_empty());
+    drained.par_sort_unstable();
+    assert!(drained.into_iter().eq(0..100_000));
+
+    let mut pq = PriorityQueue::with_comparator(|a: &u64, b: &u64| b.partial_cmp(a));
+    pq.extend((0..10).map(|i| (i, i)));
+    assert_eq!(10, pq.par_drain().count());
+    pq.extend((0..10).map(|i| (i, i)));
+    assert_eq!(Some(9), pq.pop_item());
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn pq_par_into_sorted_vec() {
+    use priq::NanPolicy;
+
+    let mut elems: Vec<u32> = (0..100_000).collect();
+    elems.shuffle(&mut thread_rng());
+    let pq: PriorityQueue<_, _> = elems.iter().map(|&i| (i / 2, i)).collect();
+    let expected = pq.clone().into_sorted_vec();
+    let sorted = pq.par_into_sorted_vec();
+    assert!(sorted.iter().map(|(s, _)| *s).eq(expected.iter().map(|(s, _)| *s)));
+    assert!(sorted.windows(2).all(|w| w[0].0 <= w[1].0));
+
+    let mut pq = PriorityQueue::new_max();
+    pq.extend(elems.iter().map(|&i| (i, i)));
+    assert!(pq.par_into_sorted_vec().into_iter().map(|(s, _)| s).eq((0..100_000).rev()));
+
+    for nan in [NanPolicy::Last, NanPolicy::First] {
+        let mut pq: PriorityQueue<f64, u32> = PriorityQueue::builder().nan_policy(nan).build();
+        pq.extend(elems.iter().map(|&i| (if i % 10 == 0 { f64::NAN } else { f64::from(i) }, i)));
+        let expected: Vec<_> = pq.clone().into_sorted_vec().into_iter().map(|(_, e)| e).collect();
+        let sorted: Vec<_> = pq.par_into_sorted_vec().into_iter().map(|(_, e)| e).collect();
+        assert_eq!(expected, sorted);
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_combining() {
+    use priq::sync::CombiningPriorityQueue;
+    use std::sync::Arc;
+    use std::thread;
+
+    // fewer slots than threads, so some of them share a slot
+    let pq = Arc::new(CombiningPriorityQueue::with_slots(3));
+    let workers: Vec<_> = (0..6).map(|t| {
+        let pq = Arc::clone(&pq);
+        thread::spawn(move || {
+            let mut popped = Vec::new();
+            for i in 0..1000 {
+                pq.put(i, t * 1000 + i);
+                if i % 3 == 0 {
+                    popped.extend(pq.pop().map(|(_, e)| e));
+                }
+            }
+            popped
+        })
+    }).collect();
+    let mut popped: Vec<_> = workers.into_iter().flat_map(|w| w.join().unwrap()).collect();
+    assert_eq!(3, pq.slots());
+    assert_eq!(6000 - popped.len(), pq.len());
+
+    let pq = Arc::try_unwrap(pq).unwrap().into_inner();
+    let rest = pq.into_sorted_vec();
+    assert!(rest.windows(2).all(|w| w[0].0 <= w[1].0));
+    popped.extend(rest.into_iter().map(|(_, e)| e));
+    popped.sort_unstable();
+    assert!(popped.into_iter().eq(0..6000));
+
+    let pq = CombiningPriorityQueue::new_max();
+    let mut elems: Vec<u32> = (0..100).collect();
+    elems.shuffle(&mut thread_rng());
+    elems.into_iter().for_each(|i| pq.put(i, i));
+    assert!(std::iter::from_fn(|| pq.pop()).map(|(s, _)| s).eq((0..100).rev()));
+    assert!(pq.is_empty());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_combining_panic() {
+    use priq::sync::CombiningPriorityQueue;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    static ARMED: AtomicBool = AtomicBool::new(false);
+
+    #[derive(PartialEq)]
+    struct Bomb(u32);
+
+    impl PartialOrd for Bomb {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            assert!(!ARMED.load(Ordering::SeqCst), "armed score compared");
+            self.0.partial_cmp(&other.0)
+        }
+    }
+
+    // a panic is resumed by the thread which requested the operation, even
+    // if another thread applied it
+    let pq = Arc::new(CombiningPriorityQueue::with_slots(2));
+    (0..100).for_each(|i| pq.put(Bomb(i), i));
+    ARMED.store(true, Ordering::SeqCst);
+    let workers: Vec<_> = (0..3).map(|_| {
+        let pq = Arc::clone(&pq);
+        thread::spawn(move || {
+            (0..2).filter(|_| panic::catch_unwind(AssertUnwindSafe(|| pq.pop())).is_err()).count()
+        })
+    }).collect();
+    let panicked: usize = workers.into_iter().map(|w| w.join().unwrap()).sum();
+    assert_eq!(6, panicked);
+
+    ARMED.store(false, Ordering::SeqCst);
+    pq.put(Bomb(0), 0);
+    assert!(pq.pop().is_some());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_put_batch() {
+    use priq::channel;
+    use priq::sync::{
+        AsyncPriorityQueue, BlockingPriorityQueue, CombiningPriorityQueue, RelaxedPriorityQueue,
+    };
+    use std::sync::Arc;
+    use std::thread;
+
+    let mut elems: Vec<(u32, u32)> = (0..1024).map(|i| (i, i * 10)).collect();
+    elems.shuffle(&mut thread_rng());
+    let sorted: Vec<_> = (0..1024).map(|i| (i, i * 10)).collect();
+
+    let blocking = Arc::new(BlockingPriorityQueue::new());
+    let consumers: Vec<_> = (0..4).map(|_| {
+        let pq = Arc::clone(&blocking);
+        thread::spawn(move || pq.pop_wait())
+    }).collect();
+    blocking.put_batch(&elems[..64]);
+    consumers.into_iter().for_each(|c| { c.join().unwrap(); });
+    assert_eq!(60, blocking.len());
+
+    let relaxed = RelaxedPriorityQueue::new(2);
+    relaxed.worker().put_batch(&elems);
+    assert_eq!(sorted, relaxed.into_sorted_vec());
+
+    let fair = AsyncPriorityQueue::new();
+    fair.put_batch(&elems);
+    assert_eq!(sorted, fair.into_inner().into_sorted_vec());
+
+    let combining = CombiningPriorityQueue::with_slots(2);
+    combining.put_batch(&elems);
+    combining.put_batch(&[]);
+    assert_eq!(sorted, combining.into_inner().into_sorted_vec());
+
+    let (tx, rx) = channel::channel();
+    tx.send_batch(&elems).unwrap();
+    drop(tx);
+    assert_eq!(sorted, rx.iter().collect::<Vec<_>>());
+    let (tx, rx) = channel::channel::<u32, u32>();
+    drop(rx);
+    assert!(tx.send_batch(&elems).is_err());
+
+    #[cfg(feature = "concurrent")]
+    {
+        let lock_free = priq::concurrent::ConcurrentPriorityQueue::new();
+        lock_free.put_batch(&elems);
+        assert_eq!(sorted, std::iter::from_fn(|| lock_free.pop()).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_queue_pool() {
+    use priq::sync::{QueuePool, Routing};
+    use std::sync::Arc;
+    use std::thread;
+
+    for routing in [Routing::Thread, Routing::LeastLoaded] {
+        let pool = Arc::new(QueuePool::new(4, routing));
+        let workers: Vec<_> = (0..4).map(|t| {
+            let pool = Arc::clone(&pool);
+            thread::spawn(move || {
+                let mut popped = Vec::new();
+                for i in 0..1000 {
+                    pool.put(i, t * 1000 + i);
+                    if i % 2 == 0 {
+                        popped.extend(pool.pop().map(|(_, e)| e));
+                    }
+                }
+                popped
+            })
+        }).collect();
+        let mut popped: Vec<_> = workers.into_iter().flat_map(|w| w.join().unwrap()).collect();
+        let stats = pool.stats();
+        assert_eq!(4000, stats.iter().map(|s| s.puts).sum::<u64>());
+        assert_eq!(popped.len() as u64, stats.iter().map(|s| s.pops).sum::<u64>());
+        assert_eq!(4000 - popped.len(), pool.len());
+        assert_eq!(routing, pool.routing());
+
+        let pool = Arc::try_unwrap(pool).unwrap();
+        popped.extend(pool.into_sorted_vec().into_iter().map(|(_, e)| e));
+        popped.sort_unstable();
+        assert!(popped.into_iter().eq(0..4000));
+    }
+
+    let pool = QueuePool::new_max(3, Routing::LeastLoaded);
+    pool.put_batch(&[(1, 'a'), (2, 'b')]);
+    pool.put(3, 'c');
+    pool.put(4, 'd');
+    assert_eq!(vec![2, 1, 1], pool.stats().iter().map(|s| s.len).collect::<Vec<_>>());
+    let mut popped: Vec<_> = std::iter::from_fn(|| pool.pop()).map(|(_, e)| e).collect();
+    assert!(pool.is_empty());
+    popped.sort_unstable();
+    assert_eq!(vec!['a', 'b', 'c', 'd'], popped);
+
+    // NaN scores go after the comparable ones, whichever queue they are in
+    let pool = QueuePool::new(2, Routing::LeastLoaded);
+    pool.put(f64::NAN, 'n');
+    pool.put(1.0, 'a');
+    assert_eq!(Some('a'), pool.pop().map(|(_, e)| e));
+    assert_eq!(Some('n'), pool.pop().map(|(_, e)| e));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_bounded() {
+    use priq::sync::{BoundedPriorityQueue, Full};
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+    use std::thread;
+    use std::time::Duration;
+
+    let pq = Arc::new(BoundedPriorityQueue::new(8));
+    let producers: Vec<_> = (0..4).map(|p| {
+        let pq = Arc::clone(&pq);
+        thread::spawn(move || (0..250).for_each(|i| {
+            pq.put(i, p * 250 + i);
+            assert!(pq.len() <= 8);
+        }))
+    }).collect();
+    let consumer = {
+        let pq = Arc::clone(&pq);
+        thread::spawn(move || block_on(async {
+            let mut popped = Vec::new();
+            for _ in 0..1000 {
+                popped.push(pq.pop_async().await.1);
+            }
+            popped
+        }))
+    };
+    producers.into_iter().for_each(|p| p.join().unwrap());
+    let mut popped = consumer.join().unwrap();
+    popped.sort_unstable();
+    assert!(popped.into_iter().eq(0..1000));
+    assert_eq!(None, pq.pop_timeout(Duration::from_millis(5)));
+
+    let pq = BoundedPriorityQueue::new_max(2);
+    assert_eq!(2, pq.capacity());
+    pq.put(1, 'a');
+    assert_eq!(Ok(()), pq.try_put(2, 'b'));
+    assert!(pq.is_full());
+    assert_eq!(Err(Full((3, 'c'))), pq.try_put(3, 'c'));
+    let err = pq.put_timeout(3, 'c', Duration::from_millis(5)).unwrap_err();
+    assert_eq!((3, 'c'), err.into_inner());
+
+    let waker = Arc::new(Unpark(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut put = pin!(pq.put_async(3, 'c'));
+    assert_eq!(Poll::Pending, put.as_mut().poll(&mut cx));
+    assert_eq!(Some((2, 'b')), pq.pop());
+    assert_eq!(Poll::Ready(()), put.poll(&mut cx));
+    assert_eq!((3, 'c'), pq.pop_wait());
+    pq.clear();
+    assert!(pq.is_empty());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_watch_top() {
+    use priq::sync::{AsyncPriorityQueue, BlockingPriorityQueue};
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    // timer loop sleeping until the deadline of the top, woken up early by
+    // an earlier deadline
+    let timers = Arc::new(BlockingPriorityQueue::new());
+    let start = Instant::now();
+    timers.put(start + Duration::from_secs(60), "late");
+    let mut top = timers.watch_top();
+    let fired = {
+        let timers = Arc::clone(&timers);
+        thread::spawn(move || loop {
+            let deadline = timers.peek_score().unwrap();
+            let now = Instant::now();
+            if deadline <= now {
+                return timers.pop().unwrap().1
+            }
+            top.wait_timeout(deadline - now);
+        })
+    };
+    thread::sleep(Duration::from_millis(10));
+    timers.put(start + Duration::from_millis(20), "early");
+    assert_eq!("early", fired.join().unwrap());
+    assert!(start.elapsed() < Duration::from_secs(60));
+
+    let pq = BlockingPriorityQueue::new_max();
+    let mut top = pq.watch_top();
+    assert!(!top.has_changed());
+    pq.put_batch(&[(1, 'a'), (2, 'b')]);
+    assert!(top.has_changed());
+    top.mark_seen();
+    pq.put(2, 'c');
+    pq.pop();
+    assert!(!top.wait_timeout(Duration::from_millis(1)));
+
+    let pq = AsyncPriorityQueue::new();
+    let mut top = pq.watch_top();
+    let waker = Arc::new(Unpark(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    {
+        let mut changed = pin!(top.changed());
+        assert_eq!(Poll::Pending, changed.as_mut().poll(&mut cx));
+        pq.put(5, 'e');
+        assert_eq!(Poll::Ready(()), changed.poll(&mut cx));
+    }
+    pq.put(6, 'f');
+    assert_eq!(Poll::Pending, top.poll_changed(&mut cx));
+    pq.put_batch(&[(9, 'i'), (1, 'a')]);
+    assert_eq!(Poll::Ready(()), top.poll_changed(&mut cx));
+}
+
+#[test]
+#[cfg(feature = "rkyv")]
+fn pq_rkyv() {
+    use priq::{ArchivedPriorityQueue, Max};
+    use rkyv::rancor::Error;
+
+    let mut data: Vec<(u32, String)> = (0..200).map(|i| (i, i.to_string())).collect();
+    data.shuffle(&mut thread_rng());
+    let mut pq: PriorityQueue<u32, String, Max> = PriorityQueue::new_max();
+    pq.put_many(data);
+    let bytes = rkyv::to_bytes::<Error>(&pq).unwrap();
+
+    let archived = rkyv::access::<ArchivedPriorityQueue<u32, String>, Error>(&bytes).unwrap();
+    assert_eq!(200, archived.len());
+    assert_eq!(199, archived.peek().unwrap().0);
+    assert_eq!("199", archived.peek().unwrap().1.as_str());
+    assert_eq!(archived.as_slice().len(), archived.iter().count());
+
+    let back: PriorityQueue<u32, String, Max> =
+        rkyv::deserialize::<_, Error>(archived).unwrap();
+    assert_eq!(pq.into_sorted_vec(), back.into_sorted_vec());
+
+    // Loading into the opposite order rebuilds the heap.
+    let mut min: PriorityQueue<u32, String> = rkyv::deserialize::<_, Error>(archived).unwrap();
+    assert_eq!(Some((0, "0".to_string())), min.pop());
+
+    let empty: PriorityQueue<u32, String> = PriorityQueue::new();
+    let bytes = rkyv::to_bytes::<Error>(&empty).unwrap();
+    let archived = rkyv::access::<ArchivedPriorityQueue<u32, String>, Error>(&bytes).unwrap();
+    assert!(archived.is_empty());
+    assert!(archived.peek().is_none());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_snapshot() {
+    use std::io::ErrorKind;
+    use priq::Max;
+
+    let mut data: Vec<(i64, String)> = (0..500).map(|i| (i, format!("job-{}", i))).collect();
+    data.shuffle(&mut thread_rng());
+    let mut pq: PriorityQueue<i64, String, Max> = PriorityQueue::new_max();
+    pq.put_many(data);
+
+    let mut buf = Vec::new();
+    pq.save_to(&mut buf).unwrap();
+    let back: PriorityQueue<i64, String, Max> = PriorityQueue::load_from(&buf[..]).unwrap();
+    assert_eq!(pq.clone().into_sorted_vec(), back.into_sorted_vec());
+
+    let mut min: PriorityQueue<i64, String> = PriorityQueue::load_from(&buf[..]).unwrap();
+    assert_eq!(Some((0, "job-0".to_string())), min.pop());
+
+    let err = PriorityQueue::<i64, String>::load_from(&buf[..buf.len() - 1]).unwrap_err();
+    assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+
+    let mut bad = buf.clone();
+    bad[4] = 99;
+    let err = PriorityQueue::<i64, String>::load_from(&bad[..]).unwrap_err();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+    bad[0] = b'X';
+    let err = PriorityQueue::<i64, String>::load_from(&bad[..]).unwrap_err();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+
+    let mut pq: PriorityQueue<f64, char> = PriorityQueue::new();
+    pq.put(1.5, 'b');
+    pq.put(0.5, 'a');
+    let mut buf = Vec::new();
+    pq.save_to(&mut buf).unwrap();
+    let mut back: PriorityQueue<f64, char> = PriorityQueue::load_from(&buf[..]).unwrap();
+    assert_eq!(Some((0.5, 'a')), back.pop());
+    assert_eq!(Some((1.5, 'b')), back.pop());
+
+    // popped in the same order as from the saved queue, NaN scores included
+    let mut pq: PriorityQueue<f64, usize> = PriorityQueue::new();
+    (0..40).for_each(|i| pq.put(if i % 3 == 0 { f64::NAN } else { (i % 7) as f64 }, i));
+    (0..5).for_each(|_| { pq.pop(); });
+    let mut buf = Vec::new();
+    pq.save_to(&mut buf).unwrap();
+    let mut back: PriorityQueue<f64, usize> = PriorityQueue::load_from(&buf[..]).unwrap();
+    assert_eq!(pq.nan_count(), back.nan_count());
+    while let Some((_, e)) = pq.pop() {
+        assert_eq!(Some(e), back.pop().map(|(_, e)| e));
+    }
+    assert!(back.is_empty());
+}
+
+#[test]
+#[cfg(feature = "mmap")]
+fn pq_mmap() {
+    use priq::persistent::MmapPriorityQueue;
+    use std::io::ErrorKind;
+
+    let path = std::env::temp_dir().join(format!("priq-test-mmap-{}.pq", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut data: Vec<(f64, u32)> = (0..1000).map(|i| (i as f64, i)).collect();
+    data.shuffle(&mut thread_rng());
+    {
+        let mut pq: MmapPriorityQueue<f64, u32> = MmapPriorityQueue::open(&path).unwrap();
+        assert!(pq.is_empty());
+        for &(score, item) in &data {
+            pq.put(score, item).unwrap();
+        }
+        pq.put(f64::NAN, 9999).unwrap();
+        assert_eq!(1001, pq.len());
+        assert!(pq.capacity() >= 1001);
+        assert_eq!(Some((0.0, 0)), pq.peek());
+        assert_eq!(Some((0.0, 0)), pq.pop());
+        pq.flush().unwrap();
+    }
+
+    let err = MmapPriorityQueue::<f64, u32, _>::open_max(&path).unwrap_err();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+    let err = MmapPriorityQueue::<f64, u64>::open(&path).unwrap_err();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+
+    let mut pq: MmapPriorityQueue<f64, u32> = MmapPriorityQueue::open(&path).unwrap();
+    assert_eq!(1000, pq.len());
+    assert_eq!(1000, pq.iter().count());
+    for i in 1..1000 {
+        assert_eq!(Some((i as f64, i)), pq.pop());
+    }
+    let (nan, item) = pq.pop().unwrap();
+    assert!(nan.is_nan());
+    assert_eq!(9999, item);
+    assert_eq!(None, pq.pop());
+
+    pq.shrink_to_fit().unwrap();
+    assert_eq!(16, pq.capacity());
+    pq.put(1.0, 1).unwrap();
+    pq.clear();
+    assert!(pq.is_empty());
+    drop(pq);
+
+    std::fs::write(&path, b"not a queue at all, definitely not a queue").unwrap();
+    let err = MmapPriorityQueue::<f64, u32>::open(&path).unwrap_err();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_wal() {
+    use priq::persistent::WalPriorityQueue;
+    use std::io::Write;
+
+    let dir = std::env::temp_dir().join(format!("priq-test-wal-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let mut pq: WalPriorityQueue<u32, String> = WalPriorityQueue::open(&dir).unwrap();
+    for i in [5, 3, 3, 8, 1, 9, 2] {
+        pq.put(i, format!("task-{}", i)).unwrap();
+    }
+    assert_eq!(Some((1, "task-1".to_string())), pq.pop().unwrap());
+    assert_eq!(8, pq.journal_len());
+    let expected = pq.queue().clone().into_sorted_vec();
+    drop(pq);
+
+    // A record torn by a crash is dropped on replay.
+    let log = dir.join("queue.wal");
+    std::fs::OpenOptions::new().append(true).open(&log).unwrap()
+        .write_all(&[1, 7, 0]).unwrap();
+
+    let mut pq: WalPriorityQueue<u32, String> = WalPriorityQueue::open(&dir).unwrap();
+    assert_eq!(6, pq.len());
+    assert_eq!(8, pq.journal_len());
+    assert_eq!(expected, pq.queue().clone().into_sorted_vec());
+
+    // A checkpoint interrupted before the log is emptied doesn't replay twice.
+    let stale = std::fs::read(&log).unwrap();
+    pq.checkpoint().unwrap();
+    assert_eq!(0, pq.journal_len());
+    drop(pq);
+    std::fs::write(&log, stale).unwrap();
+
+    let mut pq: WalPriorityQueue<u32, String> = WalPriorityQueue::open(&dir).unwrap();
+    assert_eq!(expected, pq.queue().clone().into_sorted_vec());
+    assert_eq!(0, pq.journal_len());
+
+    pq.set_sync(false);
+    pq.put(0, "urgent".to_string()).unwrap();
+    pq.sync().unwrap();
+    assert_eq!(Some(&(0, "urgent".to_string())), pq.peek());
+    while pq.pop().unwrap().is_some() {}
+    assert!(pq.is_empty());
+    drop(pq);
+
+    let pq: WalPriorityQueue<u32, String> = WalPriorityQueue::open(&dir).unwrap();
+    assert!(pq.is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_wal_replay() {
+    use priq::persistent::WalPriorityQueue;
+    use std::io::{ErrorKind, Write};
+
+    let dir = std::env::temp_dir().join(format!("priq-test-wal-replay-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let log = dir.join("queue.wal");
+
+    // Popping after a checkpoint replays against the same elements, NaN
+    // scores included.
+    let mut pq: WalPriorityQueue<f64, u32> = WalPriorityQueue::open(&dir).unwrap();
+    for i in 0..30 {
+        pq.put(if i % 4 == 0 { f64::NAN } else { (i % 5) as f64 }, i).unwrap();
+    }
+    pq.checkpoint().unwrap();
+    for _ in 0..5 {
+        pq.pop().unwrap();
+    }
+    let mut live = pq.queue().clone();
+    drop(pq);
+
+    let mut pq: WalPriorityQueue<f64, u32> = WalPriorityQueue::open(&dir).unwrap();
+    assert_eq!(5, pq.journal_len());
+    while let Some((_, e)) = live.pop() {
+        assert_eq!(Some(e), pq.pop().unwrap().map(|(_, e)| e));
+    }
+    assert!(pq.is_empty());
+    pq.put(1.0, 1).unwrap();
+    drop(pq);
+    let len = std::fs::metadata(&log).unwrap().len();
+
+    // Zero-filled and garbage tails are dropped like a torn record.
+    for tail in [[0; 64], [0xa5; 64]] {
+        std::fs::OpenOptions::new().append(true).open(&log).unwrap()
+            .write_all(&tail).unwrap();
+        let pq: WalPriorityQueue<f64, u32> = WalPriorityQueue::open(&dir).unwrap();
+        assert_eq!(Some(&(1.0, 1)), pq.peek());
+        assert_eq!(len, std::fs::metadata(&log).unwrap().len());
+    }
+
+    // A bad record followed by a valid one is corruption, not a torn tail.
+    let mut pq: WalPriorityQueue<f64, u32> = WalPriorityQueue::open(&dir).unwrap();
+    pq.put(2.0, 2).unwrap();
+    drop(pq);
+    // both puts are 21 bytes long: 8 of the frame, the tag, score and item
+    let mut bytes = std::fs::read(&log).unwrap();
+    let at = bytes.len() - 21 - 5;
+    bytes[at] ^= 1;
+    std::fs::write(&log, bytes).unwrap();
+    let err = WalPriorityQueue::<f64, u32>::open(&dir).unwrap_err();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn pq_binary_heap() {
+    use priq::Max;
+    use std::collections::BinaryHeap;
+
+    let mut values: Vec<u32> = (0..300).collect();
+    values.shuffle(&mut thread_rng());
+
+    let heap = BinaryHeap::from(values.clone());
+    let mut pq: PriorityQueue<u32, u32, Max> = PriorityQueue::from(heap);
+    assert_eq!(300, pq.len());
+    for i in (0..300).rev() {
+        assert_eq!(Some((i, i)), pq.pop());
+    }
+
+    let heap: BinaryHeap<_> = values.iter().copied().map(Reverse).collect();
+    let pq = PriorityQueue::from(heap);
+    assert_eq!((0..300).map(|i| (i, i)).collect::<Vec<_>>(), pq.into_sorted_vec());
+
+    let pq: PriorityQueue<u32, u32> = PriorityQueue::from_values(values.clone());
+    let mut heap = pq.into_binary_heap();
+    for i in 0..300 {
+        assert_eq!(Some(Reverse((i, i))), heap.pop());
+    }
+
+    let mut pq = PriorityQueue::new_max();
+    pq.extend([(2, 'b'), (2, 'a'), (9, 'z'), (1, 'x')]);
+    let heap = pq.into_binary_heap();
+    assert_eq!(vec![(1, 'x'), (2, 'a'), (2, 'b'), (9, 'z')], heap.into_sorted_vec());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_from_maps() {
+    use std::collections::{BTreeMap, HashMap};
+
+    let mut keys: Vec<u32> = (0..200).collect();
+    keys.shuffle(&mut thread_rng());
+
+    let map: BTreeMap<u32, String> = keys.iter().map(|&k| (k, k.to_string())).collect();
+    let pq = PriorityQueue::from(map);
+    assert_eq!(200, pq.len());
+    assert_eq!(Some(&(0, "0".to_string())), pq.peek());
+    let sorted = pq.into_sorted_vec();
+    assert!(sorted.windows(2).all(|w| w[0].0 < w[1].0));
+
+    let map: HashMap<String, u32> = keys.iter().map(|&k| (format!("task-{}", k), k % 50)).collect();
+    let mut pq = PriorityQueue::from(map);
+    assert_eq!(200, pq.len());
+    let mut last = 0;
+    while let Some((score, item)) = pq.pop() {
+        assert!(score >= last);
+        assert_eq!(score, item[5..].parse::<u32>().unwrap() % 50);
+        last = score;
+    }
+
+    let empty: HashMap<&str, f64> = HashMap::new();
+    assert!(PriorityQueue::from(empty).is_empty());
+}
+
+#[test]
+fn pq_into_groups() {
+    use priq::Max;
+    use std::collections::BTreeMap;
+
+    let mut data: Vec<(u32, u32)> = (0..300).map(|i| (i % 7, i)).collect();
+    data.shuffle(&mut thread_rng());
+
+    let pq = PriorityQueue::from(data.clone());
+    let groups = pq.into_grouped_vec();
+    assert_eq!((0..7).collect::<Vec<_>>(), groups.iter().map(|g| g.0).collect::<Vec<_>>());
+    for (score, items) in &groups {
+        assert!(items.iter().all(|i| i % 7 == *score));
+    }
+    assert_eq!(300, groups.iter().map(|g| g.1.len()).sum::<usize>());
+
+    let mut pq: PriorityQueue<u32, u32, Max> = PriorityQueue::new_max();
+    pq.put_many(data.clone());
+    let groups = pq.clone().into_grouped_vec();
+    assert_eq!((0..7).rev().collect::<Vec<_>>(), groups.iter().map(|g| g.0).collect::<Vec<_>>());
+
+    let map: BTreeMap<u32, Vec<u32>> = pq.into();
+    assert_eq!((0..7).collect::<Vec<_>>(), map.keys().copied().collect::<Vec<_>>());
+    assert_eq!(43, map[&0].len());
+
+    let empty: PriorityQueue<u32, u32> = PriorityQueue::new();
+    assert!(empty.clone().into_grouped_vec().is_empty());
+    assert!(empty.into_btree_map().is_empty());
+}
+
+#[test]
+#[cfg(feature = "arbitrary")]
+fn pq_arbitrary() {
+    use arbitrary::{Arbitrary, Unstructured};
+    use priq::Max;
+    use rand::RngCore;
+
+    let mut bytes = vec![0u8; 4096];
+    thread_rng().fill_bytes(&mut bytes);
+
+    let mut u = Unstructured::new(&bytes);
+    for _ in 0..20 {
+        let pq = PriorityQueue::<f32, u16, Max>::arbitrary(&mut u).unwrap();
+        let sorted = pq.into_sorted_vec();
+        let scores: Vec<f32> = sorted.iter().map(|e| e.0).filter(|s| !s.is_nan()).collect();
+        assert!(scores.windows(2).all(|w| w[0] >= w[1]));
+    }
+
+    let pq = PriorityQueue::<u32, u8>::arbitrary_take_rest(Unstructured::new(&bytes)).unwrap();
+    let sorted = pq.into_sorted_vec();
+    assert!(sorted.windows(2).all(|w| w[0].0 <= w[1].0));
+}
+
+#[cfg(feature = "testing")]
+mod pq_testing {
+    use priq::testing::{assert_heap_invariant, check_model, ops, queue, queue_max, Op};
+    use priq::{BinomialHeap, Max, Min, PriorityQueue};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn queues_are_heaps(pq in queue(any::<f32>(), any::<u8>(), 0..200)) {
+            assert_heap_invariant(&pq);
+        }
+
+        #[test]
+        fn max_queues_are_heaps(pq in queue_max(any::<i64>(), any::<u8>(), 0..200)) {
+            assert_heap_invariant(&pq);
+        }
+
+        #[test]
+        fn queue_matches_model(ops in ops(0..50u32, any::<u8>(), 0..300)) {
+            check_model(&mut PriorityQueue::new(), Min, &ops);
+            check_model(&mut PriorityQueue::new_max(), Max, &ops);
+            check_model(&mut BinomialHeap::new(), Min, &ops);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "popped a wrong score")]
+    fn model_catches_wrong_order() {
+        let ops = [Op::Put(1, 'a'), Op::Put(2, 'b'), Op::Pop];
+        check_model(&mut PriorityQueue::new(), Max, &ops);
+    }
+}
+
+#[test]
+#[cfg(feature = "ffi")]
+fn pq_ffi() {
+    use priq::ffi::*;
+    use std::ffi::c_void;
+    use std::ptr;
+
+    let mut items = [10u32, 20, 30];
+    unsafe {
+        let pq = priq_new();
+        assert_eq!(PRIQ_OK, priq_put(pq, 2.0, &mut items[1] as *mut u32 as *mut c_void));
+        assert_eq!(PRIQ_OK, priq_put(pq, f64::NAN, ptr::null_mut()));
+        assert_eq!(PRIQ_OK, priq_put(pq, 1.0, &mut items[0] as *mut u32 as *mut c_void));
+        assert_eq!(3, priq_len(pq));
+
+        let (mut score, mut item) = (0.0, ptr::null_mut());
+        assert_eq!(PRIQ_OK, priq_peek(pq, &mut score, &mut item));
+        assert_eq!(1.0, score);
+        assert_eq!(10, *(item as *const u32));
+
+        assert_eq!(PRIQ_OK, priq_pop(pq, &mut score, &mut item));
+        assert_eq!(10, *(item as *const u32));
+        assert_eq!(PRIQ_OK, priq_pop(pq, ptr::null_mut(), &mut item));
+        assert_eq!(20, *(item as *const u32));
+        assert_eq!(PRIQ_OK, priq_pop(pq, &mut score, ptr::null_mut()));
+        assert!(score.is_nan());
+        assert_eq!(PRIQ_EMPTY, priq_pop(pq, &mut score, &mut item));
+        assert_eq!(PRIQ_EMPTY, priq_peek(pq, &mut score, &mut item));
+        priq_free(pq);
+
+        let pq = priq_new_max();
+        for (i, item) in items.iter_mut().enumerate() {
+            priq_put(pq, i as f64, item as *mut u32 as *mut c_void);
+        }
+        assert_eq!(PRIQ_OK, priq_pop(pq, &mut score, &mut item));
+        assert_eq!(30, *(item as *const u32));
+        priq_clear(pq);
+        assert_eq!(0, priq_len(pq));
+        priq_free(pq);
+
+        assert_eq!(PRIQ_ERROR, priq_put(ptr::null_mut(), 1.0, ptr::null_mut()));
+        assert_eq!(PRIQ_ERROR, priq_pop(ptr::null_mut(), &mut score, &mut item));
+        assert_eq!(0, priq_len(ptr::null()));
+        priq_free(ptr::null_mut());
+    }
+}
+
+#[test]
+#[cfg(feature = "ffi")]
+fn pq_ffi_header() {
+    use priq::ffi::*;
+
+    let header = include_str!("../include/priq.h");
+    for (name, value) in [("PRIQ_OK", PRIQ_OK), ("PRIQ_EMPTY", PRIQ_EMPTY),
+                          ("PRIQ_ERROR", PRIQ_ERROR)] {
+        let define = header.lines().find(|l| l.starts_with(&format!("#define {name} ")));
+        let value = value.to_string().replace('-', "(-") + if value < 0 { ")" } else { "" };
+        assert!(define.unwrap().ends_with(&format!(" {value}")));
+    }
+    for func in ["priq_new(", "priq_new_max(", "priq_put(", "priq_pop(", "priq_peek(",
+                 "priq_len(", "priq_clear(", "priq_free("] {
+        assert!(header.contains(func), "{func} is not declared");
+    }
+}
+
+#[test]
+#[cfg(feature = "python")]
+fn pq_python() {
+    use pyo3::prelude::*;
+    use pyo3::types::PyDict;
+
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let module = PyModule::new(py, "priq").unwrap();
+        priq::python::priq(&module).unwrap();
+        let locals = PyDict::new(py);
+        locals.set_item("priq", module).unwrap();
+        py.run(c"
+pq = priq.PriorityQueue()
+assert not pq
+pq.put(2.5, {'name': 'backup'})
+pq.put(float('nan'), 'later')
+pq.put(0.5, ['deploy'])
+assert len(pq) == 3
+assert pq.peek() == (0.5, ['deploy'])
+assert pq.pop() == (0.5, ['deploy'])
+assert pq.pop() == (2.5, {'name': 'backup'})
+assert pq.pop()[1] == 'later'
+assert pq.peek() is None
+try:
+    pq.pop()
+    raise AssertionError('popped from an empty queue')
+except IndexError:
+    pass
+pq.put(1.0, None)
+pq.clear()
+assert len(pq) == 0
+", None, Some(&locals)).unwrap();
+    });
+}
+
+#[test]
+fn pq_small() {
+    use priq::{HeapBackend, SmallPriorityQueue};
+    use std::rc::Rc;
+
+    let mut pq: SmallPriorityQueue<usize, usize, 8> = SmallPriorityQueue::new();
+    [5, 2, 7, 1].iter().for_each(|&i| pq.put(i, i * 2));
+    assert!(!pq.spilled());
+    assert_eq!(8, pq.capacity());
+    assert_eq!(Some(&(1, 2)), pq.peek());
+
+    let mut elems: Vec<usize> = (0..100).collect();
+    elems.shuffle(&mut thread_rng());
+    pq.clear();
+    pq.extend(elems.iter().map(|&i| (i, i)));
+    assert!(pq.spilled());
+    assert_eq!(100, pq.len());
+    let popped: Vec<_> = (0..95).filter_map(|_| pq.pop()).map(|(s, _)| s).collect();
+    assert_eq!((0..95).collect::<Vec<_>>(), popped);
+    assert!(pq.spilled());
+    pq.shrink_to_fit();
+    assert!(!pq.spilled());
+    assert_eq!(vec![(95, 95), (96, 96), (97, 97), (98, 98), (99, 99)], pq.clone().into_sorted_vec());
+
+    let mut pq: SmallPriorityQueue<f64, usize, 4, _> = SmallPriorityQueue::new_max();
+    pq.extend([(1.0, 1), (f64::NAN, 0), (3.0, 3), (2.0, 2)]);
+    let popped: Vec<_> = pq.into_sorted_vec().into_iter().map(|(_, i)| i).collect();
+    assert_eq!(vec![3, 2, 1, 0], popped);
+
+    // Inline elements are dropped exactly once.
+    let rc = Rc::new(());
+    let mut pq: SmallPriorityQueue<u8, Rc<()>, 4> = SmallPriorityQueue::new();
+    (0..3).for_each(|i| pq.put(i, rc.clone()));
+    let cloned = pq.clone();
+    assert_eq!(7, Rc::strong_count(&rc));
+    assert!(pq.pop().is_some());
+    drop(pq);
+    assert_eq!(4, Rc::strong_count(&rc));
+    assert_eq!(3, cloned.into_vec().len());
+    assert_eq!(1, Rc::strong_count(&rc));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn pq_allocator() {
+    use std::alloc::{AllocError, Allocator, Global, Layout};
+    use std::cell::Cell;
+    use std::ptr::NonNull;
+
+    #[derive(Clone, Copy)]
+    struct Counting<'a>(&'a Cell<usize>);
+
+    unsafe impl Allocator for Counting<'_> {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.0.set(self.0.get() + layout.size());
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.0.set(self.0.get() - layout.size());
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    let used = Cell::new(0);
+    let mut pq = PriorityQueue::with_capacity_in(4, Counting(&used));
+    assert_eq!(4 * std::mem::size_of::<(u32, u32)>(), used.get());
+    (0..100u32).rev().for_each(|i| pq.put(i, i * 2));
+    assert!(used.get() >= 100 * std::mem::size_of::<(u32, u32)>());
+    assert_eq!(Some((0, 0)), pq.pop());
+
+    let other = pq.split_off(50);
+    assert_eq!(99, pq.len() + other.len());
+    let mut sorted = pq.into_sorted_vec();
+    assert_eq!(Some(&(1, 2)), sorted.first());
+    sorted.shrink_to_fit();
+    drop(sorted);
+    drop(other);
+    assert_eq!(0, used.get());
+
+    let mut pq = PriorityQueue::new_max_in(Counting(&used));
+    pq.extend([(1.0, 'a'), (f64::NAN, '?'), (3.0, 'c')]);
+    let items: Vec<_> = pq.into_iter_sorted().map(|(_, e)| e).collect();
+    assert_eq!(vec!['c', 'a', '?'], items);
+    assert_eq!(0, used.get());
+}
+
+#[test]
+#[cfg(feature = "bumpalo")]
+fn pq_bump() {
+    use bumpalo::Bump;
+    use priq::BumpPriorityQueue;
+    use std::rc::Rc;
+
+    let mut arena = Bump::new();
+    let rc = Rc::new(());
+    for round in 0..5 {
+        let mut pq: BumpPriorityQueue<'_, usize, Rc<()>> = BumpPriorityQueue::new_in(&arena);
+        (0..100).rev().for_each(|i| pq.put(i + round, rc.clone()));
+        assert_eq!(Some(round), pq.pop().map(|(s, _)| s));
+        assert!(arena.allocated_bytes() > 0);
+
+        let mut max = BumpPriorityQueue::new_max_in(&arena);
+        max.extend(pq.drain(..10));
+        assert_eq!(10, max.len());
+        drop(max);
+        drop(pq);
+        assert_eq!(1, Rc::strong_count(&rc));
+        arena.reset();
+    }
+}
+
+#[test]
+#[cfg(feature = "defmt")]
+fn pq_defmt() {
+    use priq::MaxPriorityQueue;
+
+    // Logging needs a global logger of the target, so only the bounds are
+    // checked here.
+    fn assert_format<F: defmt::Format>(_: &F) {}
+
+    let mut pq = PriorityQueue::new();
+    pq.put(1u8, 'a');
+    assert_format(&pq);
+    let pq: MaxPriorityQueue<i32, (u16, bool)> = MaxPriorityQueue::new_max();
+    assert_format(&pq);
+}
+
+#[test]
+fn pq_debug() {
+    let pq: PriorityQueue<u8, &str> = PriorityQueue::new();
+    assert_eq!("PriorityQueue { len: 0, data: [] }", format!("{:?}", pq));
+
+    let mut pq = PriorityQueue::new_max();
+    pq.put(1.5, "a");
+    pq.put(f64::NAN, "?");
+    pq.put(3.0, "c");
+    assert_eq!(r#"PriorityQueue { len: 3, data: [(3.0, "c"), (1.5, "a"), (NaN, "?")] }"#,
+               format!("{:?}", pq));
+
+    let pq: PriorityQueue<usize, usize> = (0..1000).rev().map(|i| (i, i)).collect();
+    let printed = format!("{:#?}", pq);
+    assert!(printed.contains("len: 1000"));
+    assert!(printed.contains("\n        ..,\n"));
+    assert_eq!(32, printed.matches("    (\n").count());
+}
+
+#[test]
+fn pq_display() {
+    let mut pq = PriorityQueue::new_max();
+    assert_eq!("[]", pq.to_string());
+    pq.extend([(1.5, "a"), (f64::NAN, "?"), (3.0, "c"), (2.0, "b")]);
+    assert_eq!("[3: c, 2: b, 1.5: a, NaN: ?]", pq.to_string());
+    assert_eq!("[3.00: c, 2.00: b, 1.50: a, NaN: ?]", format!("{:.2}", pq));
+    assert_eq!(4, pq.len());
+    assert_eq!(Some((3.0, "c")), pq.pop());
+}
+
+#[test]
+fn pq_to_dot() {
+    let pq: PriorityQueue<u8, ()> = PriorityQueue::new();
+    assert_eq!("digraph PriorityQueue {\n    node [shape=circle];\n}\n", pq.to_dot());
+
+    let mut pq = PriorityQueue::new_max();
+    pq.extend([(1.5, 'a'), (f64::NAN, '?'), (3.0, 'c'), (2.0, 'b'), (0.5, 'z')]);
+    let dot = pq.to_dot();
+    assert_eq!(3, dot.matches(" -> ").count());
+    assert!(dot.contains("n0 [label=\"3\"];"));
+    assert!(dot.contains("n0 -> n1;\n"));
+    assert!(dot.contains("n1 -> n3;\n"));
+    assert!(dot.contains("n4 [label=\"NaN\", style=dashed];"));
+    assert!(!dot.contains("-> n4"));
+
+    let pq = PriorityQueue::from([("say \"hi\"", 1)]);
+    assert!(pq.to_dot().contains(r#"n0 [label="say \"hi\""];"#));
+}
+
+#[test]
+fn pq_consume_drops_comparators() {
+    use std::sync::Arc;
+
+    let token = Arc::new(());
+    let by_cmp = |token: &Arc<()>| {
+        let token = Arc::clone(token);
+        let mut pq = PriorityQueue::with_comparator(move |a: &u32, b: &u32| {
+            let _ = &token;
+            b.partial_cmp(a)
+        });
+        pq.extend([(1, 1u32), (3, 3), (2, 2)]);
+        pq
+    };
+    let by_tiebreak = |token: &Arc<()>| {
+        let token = Arc::clone(token);
+        let mut pq = PriorityQueue::with_tiebreak(move |a: &u32, b: &u32| {
+            let _ = &token;
+            a.cmp(b)
+        });
+        pq.extend([(1, 1u32), (3, 3), (1, 2)]);
+        pq
+    };
+
+    let sorted: Vec<u32> = by_cmp(&token).into_sorted_vec().into_iter().map(|e| e.1).collect();
+    assert_eq!(vec![3, 2, 1], sorted);
+    assert_eq!(1, Arc::strong_count(&token));
+    assert_eq!(3, by_cmp(&token).into_vec().len());
+    assert_eq!(1, Arc::strong_count(&token));
+    assert_eq!(3, by_tiebreak(&token).into_vec().len());
+    assert_eq!(1, Arc::strong_count(&token));
+    assert_eq!(3, by_cmp(&token).into_iter().count());
+    assert_eq!(1, Arc::strong_count(&token));
+    assert_eq!(3, by_tiebreak(&token).into_iter().count());
+    assert_eq!(1, Arc::strong_count(&token));
+
+    let pq = by_cmp(&token).map_items(|e| e as u64);
+    assert_eq!(2, Arc::strong_count(&token));
+    drop(pq);
+    assert_eq!(1, Arc::strong_count(&token));
+    let pq = by_cmp(&token).map_items(|e| e.to_string());
+    assert_eq!(Some(&(3, "3".to_string())), pq.peek());
+    drop(pq);
+    assert_eq!(1, Arc::strong_count(&token));
+
+    let pq = by_tiebreak(&token).map_scores_into(|s| s * 2);
+    assert_eq!(Some(&(2, 1)), pq.peek());
+    assert_eq!(2, Arc::strong_count(&token));
+    drop(pq);
+    assert_eq!(1, Arc::strong_count(&token));
+}
+
+#[test]
+fn pq_comparator_unordered_scores() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct Version(&'static str);
+
+    fn parse(v: &Version) -> Option<Vec<u32>> {
+        v.0.split('.').map(|n| n.parse().ok()).collect()
+    }
+
+    let mut pq = PriorityQueue::with_comparator(|a: &Version, b: &Version| {
+        parse(a)?.partial_cmp(&parse(b)?)
+    });
+    pq.extend([(Version("1.10"), 'c'), (Version("1.2"), 'b'), (Version("x"), '?')]);
+    pq.put(Version("0.9"), 'a');
+    pq.put(Version("1.3"), 'd');
+
+    assert_eq!(Some(&(Version("0.9"), 'a')), pq.peek());
+    let sorted: String = pq.iter_sorted().map(|e| e.1).collect();
+    assert_eq!("abdc?", sorted);
+
+    let copy = pq.clone();
+    let items: String = pq.drain(..).map(|e| e.1).collect();
+    assert_eq!(5, items.len());
+    let mut merged = copy.clone() + copy;
+    assert_eq!(10, merged.len());
+    assert_eq!(Some((Version("0.9"), 'a')), merged.pop());
+    let sorted: String = merged.into_sorted_vec().into_iter().map(|e| e.1).collect();
+    assert_eq!("abbddcc??", sorted);
+}